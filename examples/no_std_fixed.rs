@@ -1,7 +1,7 @@
 // examples/fixed.rs
 
-#![feature(start)]
 #![no_std]
+#![no_main]
 
 extern crate libc;
 use core::fmt::Write;
@@ -32,8 +32,8 @@ impl Write for Stdout {
     }
 }
 
-#[start]
-fn _start(_: isize, _: *const *const u8) -> isize {
+#[no_mangle]
+pub extern "C" fn main(_argc: libc::c_int, _argv: *const *const libc::c_char) -> libc::c_int {
     let mut config = FlightStabilizerConfig::<I16F16>::new();
 
     // Set the PID gains for roll, pitch, and yaw.
@@ -6,12 +6,18 @@
 //! (Proportional, Integral, Derivative) control functions from dRehmFlight
 //! version 1.3, an Arduino-based flight controller software. These functions
 //! are used to stabilize unmanned aerial vehicles (UAVs).
+//!
+//! The crate is `no_std` unless the `std` feature is enabled,
+//! and no-alloc unless the `alloc` feature is enabled.
 
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 #![deny(rustdoc::broken_intra_doc_links)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod pid;
 pub mod stabilizer;
 
@@ -13,6 +13,22 @@ mod cascade_angle;
 #[doc(inline)]
 pub use cascade_angle::*;
 
+mod derivative;
+#[doc(inline)]
+pub use derivative::*;
+
+mod notch;
+#[doc(inline)]
+pub use notch::*;
+
 mod rate;
 #[doc(inline)]
 pub use rate::*;
+
+mod single_axis;
+#[doc(inline)]
+pub use single_axis::*;
+
+mod terms;
+#[doc(inline)]
+pub use terms::*;
@@ -6,43 +6,260 @@
 //! to perform angle-based PID (Proportional-Integral-Derivative) control
 //! calculations.
 
-use crate::Number;
+use crate::pid::limit_derivative;
+use crate::{DerivativeSource, IntegrationMethod, Number, PidForm, SaturationState};
 use piddiy::PidController;
 
 /// Control data for angle-based PID stabilization callback.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct AngleControlData<T> {
     /// The current measured angle, calculated from sensors.
     pub measurement: T,
     /// The current rate of change, typically reported by a gyro.
-    pub rate: T,
+    /// If `None`, as on a platform without a gyro, the rate is derived from the change
+    /// in measurement, `(measurement - prev_measurement) / dt`. The previous measurement
+    /// is recovered from the stored proportional error and `prev_set_point`.
+    /// The stored error is zero after a reset, so the first tick differentiates from
+    /// `b_weight * prev_set_point`. Hold the derivative with `measurement_updated` or
+    /// prime the controller with a tick to avoid the resulting spike.
+    pub rate: Option<T>,
+    /// Source of the derivative when a rate is supplied. `DerivativeSource::Measurement`
+    /// ignores the rate and differentiates the measurement as if `rate` were `None`,
+    /// and `DerivativeSource::Blend` combines the two. Without a rate, the measurement
+    /// is always differentiated. Ignored with `derivative_on_error`.
+    pub derivative_source: DerivativeSource<T>,
     /// The time delta since the last computation.
+    /// A non-positive value skips integral accumulation and holds the previous
+    /// derivative for the tick, rather than producing runaway values or `NaN`.
     pub dt: T,
-    /// The maximum allowed value for the integral term, used to prevent integral windup.
+    /// Flag indicating the measurement is a fresh sample. Clear it when the attitude
+    /// estimate updates slower than the control loop and the previous measurement is
+    /// repeated. On a stale tick the derivative is held, rather than differentiating
+    /// a repeated sample, which reads as zero motion followed by a spike on the next
+    /// fresh sample. The integral still accumulates the error against the last
+    /// measurement with the full `dt`. Stabilizers always pass fresh samples.
+    pub measurement_updated: bool,
+    /// The maximum allowed value for the raw accumulated integral, before `ki` is applied.
+    /// Used to prevent integral windup.
     pub integral_limit: T,
     /// Flag to reset the integral term, typically used when the controller is inactive.
     pub reset_integral: bool,
+    /// Fraction of the error accumulated into the integral this tick.
+    /// Stabilizers lower it below the integral throttle threshold so the integrator
+    /// fades out smoothly instead of resetting. A value of one accumulates fully.
+    /// With `integral_divisor`, it is zero between integral updates, which skips the
+    /// accumulation, and the summed time steps over `dt` on each update.
+    pub integral_scale: T,
+    /// Maximum rate of change of the raw accumulated integral, in integral units per second.
+    /// Each tick adds at most `max_integral_rate * dt` to the integral, so it ramps
+    /// smoothly under a large error. A value of zero disables the limit.
+    pub max_integral_rate: T,
+    /// Flag to compute the derivative from the change in error instead of the rate.
+    /// Derivative-on-error responds to set point changes with a "derivative kick".
+    /// The change in error is negated, so it matches the sign of the rate for a fixed
+    /// set point, and the same `kd` damps in both modes.
+    pub derivative_on_error: bool,
+    /// Flag to zero the derivative on a tick where the set point changed from
+    /// `prev_set_point`, removing the derivative kick of a set point step instead of
+    /// filtering it. The derivative is computed normally from the next tick.
+    /// It only applies while the derivative depends on the set point, with
+    /// `derivative_on_error` or a nonzero `c_weight`, so the damping of a derivative
+    /// on the measurement alone is never dropped.
+    pub suppress_derivative_on_setpoint_change: bool,
+    /// Smoothing coefficient of the first-order low-pass filter applied to the derivative.
+    /// The filtered derivative is `alpha * derivative + (1 - alpha) * prev_derivative`.
+    /// A value of one disables filtering.
+    pub derivative_lpf_alpha: T,
+    /// Maximum magnitude of the derivative term, `kd * derivative`, after filtering.
+    /// Caps the derivative contribution of a sudden rate spike without limiting
+    /// the proportional and integral terms. A value of zero or infinity disables the limit.
+    pub d_term_limit: T,
+    /// Saturation state of the previous output, used for conditional anti-windup.
+    /// While saturated, the integral does not accumulate errors that would push the
+    /// output further into saturation, assuming a positive output scale.
+    /// Stabilizers clamp to `output_min` and `output_max` after scaling, so callers
+    /// must report the clamped state of the previous output here.
+    /// `SaturationState::None` always accumulates, relying on the hard clamp alone.
+    pub saturation: SaturationState,
+    /// Optional half range for wrapping the error, like 180 for degrees or π for radians.
+    /// The error is wrapped into `[-wrap, wrap]` before forming the proportional and
+    /// integral terms, so the controller takes the short way around the boundary.
+    /// Typically only used for yaw. The error is not wrapped if `None`.
+    pub wrap: Option<T>,
+    /// Leak coefficient that decays the stored integral toward zero, in units per second.
+    /// Each tick the integral is multiplied by `1 - integral_leak * dt`, clamped to
+    /// `[0, 1]`, before the error is accumulated. A value of zero disables the leak.
+    pub integral_leak: T,
+    /// Numerical integration method of the integral. Trapezoidal integration averages
+    /// the error with the previous error, which is recovered from the stored proportional
+    /// error and `prev_set_point`. The stored error is zero after a reset, so the first
+    /// tick averages with `(1 - b_weight) * prev_set_point`, zero for the default weight.
+    pub integration_method: IntegrationMethod,
+    /// Structure of the PID. The setpoint weights only apply to
+    /// `PidForm::TwoDegreesOfFreedom`, and `PidForm::IPd` also ignores `derivative_on_error`.
+    pub form: PidForm,
+    /// Setpoint weight of the proportional term for two-degree-of-freedom control.
+    /// The proportional term acts on `b_weight * set_point - measurement`, while the
+    /// integral always acts on the full error. A value below one reduces the
+    /// proportional kick on setpoint steps. A value of one is standard PID.
+    pub b_weight: T,
+    /// Setpoint weight of the derivative term for two-degree-of-freedom control.
+    /// The derivative acts on `measurement - c_weight * set_point`, matching the sign
+    /// of the rate, so the raw derivative is `rate - c_weight * Δset_point / dt`.
+    /// A value of zero differentiates the measurement alone, avoiding derivative kick.
+    /// Ignored if `derivative_on_error` is set.
+    pub c_weight: T,
+    /// The set point of the previous computation, used by a nonzero `c_weight`.
+    pub prev_set_point: T,
+}
+
+impl<T: Number> Default for AngleControlData<T> {
+    fn default() -> Self {
+        Self {
+            measurement: T::zero(),
+            rate: Some(T::zero()),
+            derivative_source: DerivativeSource::Rate,
+            dt: T::zero(),
+            measurement_updated: true,
+            integral_limit: T::zero(),
+            reset_integral: false,
+            integral_scale: T::one(),
+            max_integral_rate: T::zero(),
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: T::one(),
+            d_term_limit: T::zero(),
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: T::zero(),
+            integration_method: IntegrationMethod::Rectangular,
+            form: PidForm::TwoDegreesOfFreedom,
+            b_weight: T::one(),
+            c_weight: T::zero(),
+            prev_set_point: T::zero(),
+        }
+    }
 }
 
 /// Angle-based PID stabilization compute callback.
+///
+/// With a zero derivative gain, `kd`, the derivative is not evaluated,
+/// so a PI controller skips the derivative and its filter entirely.
+/// The previous derivative is held for when the gain becomes nonzero.
+///
+/// The returned error is the proportional error of the form, like
+/// `b_weight * set_point - measurement` for two degrees of freedom or `-measurement`
+/// for I-PD, which is the full error for the standard form and the default `b_weight`.
 pub fn compute_angle<T: Number>(
     pid: &mut PidController<T, AngleControlData<T>>,
     data: AngleControlData<T>,
 ) -> (T, T, T) {
-    let error = pid.set_point - data.measurement;
-    let integral = if !data.reset_integral {
-        (pid.integral + error * data.dt).clamp(-data.integral_limit, data.integral_limit)
+    let wrap = |angle: T| match data.wrap {
+        Some(half_range) => angle.wrap(half_range),
+        None => angle,
+    };
+    let error = wrap(pid.set_point - data.measurement);
+    let windup = match data.saturation {
+        SaturationState::None => false,
+        SaturationState::Upper => T::zero() < error.saturating_mul(pid.ki),
+        SaturationState::Lower => error.saturating_mul(pid.ki) < T::zero(),
+    };
+    let valid_dt = T::zero() < data.dt;
+    let (b_weight, c_weight, derivative_on_error) = match data.form {
+        PidForm::Standard => (T::one(), T::zero(), data.derivative_on_error),
+        PidForm::TwoDegreesOfFreedom => (data.b_weight, data.c_weight, data.derivative_on_error),
+        PidForm::IPd => (T::zero(), T::zero(), false),
+    };
+    let proportional_error = if b_weight == T::one() {
+        error
     } else {
+        wrap(b_weight * pid.set_point - data.measurement)
+    };
+    let leaked_integral = if valid_dt && data.integral_leak != T::zero() {
+        // Decay the stored integral toward zero before accumulating the error.
+        let decay = (T::one() - data.integral_leak * data.dt).clamp(T::zero(), T::one());
+        pid.integral * decay
+    } else {
+        pid.integral
+    };
+    let integral = if data.reset_integral {
         T::zero()
+    } else if !valid_dt {
+        pid.integral
+    } else if windup {
+        leaked_integral
+    } else if data.integral_scale == T::zero() {
+        // Skip the accumulation between integral updates and on frozen axes.
+        leaked_integral.clamp(-data.integral_limit, data.integral_limit)
+    } else {
+        let step_error = match data.integration_method {
+            IntegrationMethod::Rectangular => error,
+            IntegrationMethod::Trapezoidal => {
+                // Average with the previous error, the short way around a wrap boundary.
+                let prev_error = wrap(pid.error + (T::one() - b_weight) * data.prev_set_point);
+                wrap(error + wrap(prev_error - error) / (T::one() + T::one()))
+            }
+        };
+        let step = step_error * data.dt * data.integral_scale;
+        let step = if T::zero() < data.max_integral_rate {
+            // Limit how fast the integral can change.
+            let max_step = data.max_integral_rate * data.dt;
+            step.clamp(-max_step, max_step)
+        } else {
+            step
+        };
+        (leaked_integral + step).clamp(-data.integral_limit, data.integral_limit)
     };
-    let derivative = data.rate;
+    let derivative = if pid.kd == T::zero() {
+        // Skip the unused derivative, leaving the filter state untouched.
+        pid.derivative
+    } else if !valid_dt || !data.measurement_updated {
+        // Hold the previous derivative rather than dividing by a non-positive dt
+        // or differentiating a stale measurement.
+        pid.derivative
+    } else if data.suppress_derivative_on_setpoint_change
+        && (derivative_on_error || c_weight != T::zero())
+        && pid.set_point != data.prev_set_point
+    {
+        // Drop the derivative kick of a set point step for this tick.
+        T::zero()
+    } else {
+        let measurement_rate = || {
+            // Differentiate the measurement, recovering the previous one from the error.
+            let prev_measurement = b_weight * data.prev_set_point - pid.error;
+            wrap(data.measurement - prev_measurement) / data.dt
+        };
+        let rate = || match (data.rate, data.derivative_source) {
+            (Some(rate), DerivativeSource::Rate) => rate,
+            (Some(rate), DerivativeSource::Blend(alpha)) => {
+                alpha * rate + (T::one() - alpha) * measurement_rate()
+            }
+            (Some(_), DerivativeSource::Measurement) | (None, _) => measurement_rate(),
+        };
+        let raw_derivative = if derivative_on_error {
+            // Negate the change in error, matching the sign of the rate.
+            wrap(pid.error - proportional_error) / data.dt
+        } else if c_weight == T::zero() {
+            rate()
+        } else {
+            let set_point_rate = wrap(pid.set_point - data.prev_set_point) / data.dt;
+            rate() - c_weight * set_point_rate
+        };
+        let alpha = data.derivative_lpf_alpha;
+        let derivative = alpha * raw_derivative + (T::one() - alpha) * pid.derivative;
+        limit_derivative(derivative, pid.kd, data.d_term_limit)
+    };
+    // Hold the previous state rather than storing a non-finite intermediate.
+    let integral = integral.finite_or(pid.integral);
+    let derivative = derivative.finite_or(pid.derivative);
 
-    (error, integral, derivative)
+    (proportional_error, integral, derivative)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pid::PidTerms;
     use crate::test_utils::*;
 
     /// Test that the integral term is clamped to the specified limit.
@@ -56,10 +273,26 @@ mod tests {
             .kd(0.1);
         let data = AngleControlData {
             measurement: 0.0,
-            rate: 0.0,
+            rate: Some(0.0),
+            derivative_source: DerivativeSource::Rate,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 100.0, // Integral should not exceed this value.
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: 0.0,
+            integration_method: IntegrationMethod::Rectangular,
+            form: PidForm::TwoDegreesOfFreedom,
+            b_weight: 1.0,
+            c_weight: 0.0,
+            prev_set_point: 0.0,
         };
 
         // This would normally push integral way over 100 if not clamped
@@ -85,10 +318,26 @@ mod tests {
             .kd(0.1);
         let data = AngleControlData {
             measurement: 0.0,
-            rate: 0.0,
+            rate: Some(0.0),
+            derivative_source: DerivativeSource::Rate,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 100.0,
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: 0.0,
+            integration_method: IntegrationMethod::Rectangular,
+            form: PidForm::TwoDegreesOfFreedom,
+            b_weight: 1.0,
+            c_weight: 0.0,
+            prev_set_point: 0.0,
         };
 
         // First compute without reset to build up the integral.
@@ -98,6 +347,7 @@ mod tests {
         // Now compute with reset.
         let data_reset = AngleControlData {
             reset_integral: true,
+            integral_scale: 1.0,
             ..data
         };
         let (_, integral_reset, _) = compute_angle(&mut pid, data_reset);
@@ -124,10 +374,26 @@ mod tests {
             .kd(0.1);
         let data = AngleControlData {
             measurement: 0.0,
-            rate: 0.0,
+            rate: Some(0.0),
+            derivative_source: DerivativeSource::Rate,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 100.0,
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: 0.0,
+            integration_method: IntegrationMethod::Rectangular,
+            form: PidForm::TwoDegreesOfFreedom,
+            b_weight: 1.0,
+            c_weight: 0.0,
+            prev_set_point: 0.0,
         };
 
         let (error, integral, derivative) = compute_angle(&mut pid, data);
@@ -164,10 +430,26 @@ mod tests {
             .kd(1.0);
         let data = AngleControlData {
             measurement: 5.0,
-            rate: 7.0,
+            rate: Some(7.0),
+            derivative_source: DerivativeSource::Rate,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 100.0,
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: 0.0,
+            integration_method: IntegrationMethod::Rectangular,
+            form: PidForm::TwoDegreesOfFreedom,
+            b_weight: 1.0,
+            c_weight: 0.0,
+            prev_set_point: 0.0,
         };
 
         let (error, integral, derivative) = compute_angle(&mut pid, data);
@@ -204,10 +486,26 @@ mod tests {
             .kd(0.0);
         let data = AngleControlData {
             measurement: 0.0,
-            rate: 0.0,
+            rate: Some(0.0),
+            derivative_source: DerivativeSource::Rate,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 10.0,
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: 0.0,
+            integration_method: IntegrationMethod::Rectangular,
+            form: PidForm::TwoDegreesOfFreedom,
+            b_weight: 1.0,
+            c_weight: 0.0,
+            prev_set_point: 0.0,
         };
         let (error, integral, derivative) = compute_angle(&mut pid, data);
         let output = pid.compute(data);
@@ -217,4 +515,1185 @@ mod tests {
         assert!(value_close(0.0, derivative), "Derivative should be zero.");
         assert!(value_close(0.0, output), "Output should be zero.");
     }
+
+    /// Test that derivative-on-error diverges from the rate on a set point step.
+    #[test]
+    fn test_pid_angle_derivative_on_error_set_point_step() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(0.0)
+            .kp(1.0)
+            .ki(0.0)
+            .kd(1.0);
+        let data = AngleControlData {
+            measurement: 0.0,
+            rate: Some(0.0),
+            derivative_source: DerivativeSource::Rate,
+            dt: 0.5,
+            measurement_updated: true,
+            integral_limit: 100.0,
+            reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_on_error: true,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: 0.0,
+            integration_method: IntegrationMethod::Rectangular,
+            form: PidForm::TwoDegreesOfFreedom,
+            b_weight: 1.0,
+            c_weight: 0.0,
+            prev_set_point: 0.0,
+        };
+
+        // Settle with zero error.
+        let _ = pid.compute(data);
+
+        // Step the set point.
+        pid.set_point(10.0);
+        let (_, _, derivative_on_error) = compute_angle(&mut pid, data);
+        let data_rate = AngleControlData {
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            ..data
+        };
+        let (_, _, derivative_on_rate) = compute_angle(&mut pid, data_rate);
+
+        assert!(
+            value_close(-20.0, derivative_on_error),
+            "Derivative-on-error should kick against a set point step."
+        );
+        assert!(
+            value_close(0.0, derivative_on_rate),
+            "Derivative-on-measurement should not kick on a set point step."
+        );
+
+        // The kick only lasts one step.
+        let _ = pid.compute(data);
+        let (_, _, derivative) = compute_angle(&mut pid, data);
+        assert!(
+            value_close(0.0, derivative),
+            "Derivative-on-error should be zero for a constant error."
+        );
+    }
+
+    /// Test that derivative-on-error has the same sign as the rate for a steady
+    /// rotation with a fixed set point, so the same `kd` damps in both modes.
+    #[test]
+    fn test_pid_angle_derivative_on_error_sign() {
+        let derivative_term = |derivative_on_error: bool| {
+            let mut pid = PidController::new();
+            pid.compute_fn(compute_angle)
+                .set_point(0.0)
+                .kp(0.0)
+                .ki(0.0)
+                .kd(-0.5);
+            let data = AngleControlData {
+                dt: 0.1,
+                integral_limit: 100.0,
+                derivative_on_error,
+                ..Default::default()
+            };
+
+            // Rotate steadily at 20 units per second.
+            let mut output = 0.0;
+            for tick in 0..5 {
+                output = pid.compute(AngleControlData {
+                    measurement: 2.0 * tick as f32,
+                    rate: Some(20.0),
+                    ..data
+                });
+            }
+            output
+        };
+        let on_rate = derivative_term(false);
+        let on_error = derivative_term(true);
+        assert!(value_close(-10.0, on_rate), "D should damp the rotation.");
+        assert!(
+            value_close(on_rate, on_error),
+            "Derivative-on-error should match the rate."
+        );
+    }
+
+    /// Test that a suppressed set point step has no derivative on the change tick only.
+    #[test]
+    fn test_pid_angle_suppress_derivative_on_setpoint_change() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(0.0)
+            .kp(1.0)
+            .ki(0.0)
+            .kd(1.0);
+        let data = AngleControlData {
+            measurement: 0.0,
+            rate: Some(0.0),
+            dt: 0.5,
+            integral_limit: 100.0,
+            derivative_on_error: true,
+            suppress_derivative_on_setpoint_change: true,
+            ..AngleControlData::default()
+        };
+
+        // Settle with zero error.
+        let _ = pid.compute(data);
+
+        // Step the set point, with no derivative on the change tick.
+        pid.set_point(10.0);
+        let output = pid.compute(data);
+        assert_eq!(0.0, pid.derivative, "The kick should be suppressed.");
+        assert!(
+            value_close(10.0, output),
+            "Only the proportional term remains."
+        );
+
+        // The derivative is normal afterward, here from a measurement step.
+        let data = AngleControlData {
+            measurement: 2.0,
+            prev_set_point: 10.0,
+            ..data
+        };
+        let _ = pid.compute(data);
+        assert!(value_close(4.0, pid.derivative));
+
+        // A set point weighted derivative is suppressed too.
+        let data = AngleControlData {
+            rate: Some(1.0),
+            derivative_on_error: false,
+            c_weight: 1.0,
+            ..data
+        };
+        pid.set_point(20.0);
+        let _ = pid.compute(data);
+        assert_eq!(
+            0.0, pid.derivative,
+            "The weighted kick should be suppressed."
+        );
+        let data = AngleControlData {
+            prev_set_point: 20.0,
+            ..data
+        };
+        let _ = pid.compute(data);
+        assert!(value_close(1.0, pid.derivative));
+
+        // A derivative on the measurement alone keeps its damping on a step.
+        let data = AngleControlData {
+            c_weight: 0.0,
+            ..data
+        };
+        pid.set_point(30.0);
+        let _ = pid.compute(data);
+        assert!(value_close(1.0, pid.derivative));
+    }
+
+    /// Test that derivative-on-error holds the previous derivative when dt is zero.
+    #[test]
+    fn test_pid_angle_derivative_on_error_zero_dt() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(0.0)
+            .kp(1.0)
+            .ki(0.0)
+            .kd(1.0);
+        let data = AngleControlData {
+            measurement: 0.0,
+            rate: Some(0.0),
+            derivative_source: DerivativeSource::Rate,
+            dt: 1.0,
+            measurement_updated: true,
+            integral_limit: 100.0,
+            reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_on_error: true,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: 0.0,
+            integration_method: IntegrationMethod::Rectangular,
+            form: PidForm::TwoDegreesOfFreedom,
+            b_weight: 1.0,
+            c_weight: 0.0,
+            prev_set_point: 0.0,
+        };
+        let _ = pid.compute(data);
+        pid.set_point(5.0);
+        let _ = pid.compute(data);
+
+        let data_zero_dt = AngleControlData { dt: 0.0, ..data };
+        let (_, _, derivative) = compute_angle(&mut pid, data_zero_dt);
+        assert!(
+            value_close(-5.0, derivative),
+            "Derivative should hold the previous value when dt is zero."
+        );
+        assert!(derivative.is_finite(), "Derivative should be finite.");
+    }
+
+    /// Test that the derivative low-pass filter reduces the variance of a noisy rate.
+    #[test]
+    fn test_pid_angle_derivative_lpf_noise() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(0.0)
+            .kp(1.0)
+            .ki(0.0)
+            .kd(1.0);
+        let data = AngleControlData {
+            measurement: 0.0,
+            rate: Some(0.0),
+            derivative_source: DerivativeSource::Rate,
+            dt: 0.01,
+            measurement_updated: true,
+            integral_limit: 100.0,
+            reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: 0.2,
+            d_term_limit: 0.0,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: 0.0,
+            integration_method: IntegrationMethod::Rectangular,
+            form: PidForm::TwoDegreesOfFreedom,
+            b_weight: 1.0,
+            c_weight: 0.0,
+            prev_set_point: 0.0,
+        };
+
+        // Noisy rate signal around a mean of 2.
+        const SAMPLES: usize = 100;
+        let noise = [1.5, -0.5, 2.0, -1.75, 0.25, -1.0, 1.0, -1.5];
+        let mut raw_variance = 0.0;
+        let mut filtered_variance = 0.0;
+        for i in 0..SAMPLES {
+            let rate = 2.0 + noise[i % noise.len()];
+            let _ = pid.compute(AngleControlData {
+                rate: Some(rate),
+                ..data
+            });
+            // Skip the filter warm up.
+            if 20 <= i {
+                raw_variance += (rate - 2.0) * (rate - 2.0);
+                filtered_variance += (pid.derivative - 2.0) * (pid.derivative - 2.0);
+            }
+        }
+
+        assert!(
+            filtered_variance < 0.25 * raw_variance,
+            "Filtered derivative should have lower variance."
+        );
+    }
+
+    /// Test that a derivative filter coefficient of one reproduces the unfiltered output.
+    #[test]
+    fn test_pid_angle_derivative_lpf_unity() {
+        let mut pid_unfiltered = PidController::new();
+        pid_unfiltered
+            .compute_fn(compute_angle)
+            .set_point(10.0)
+            .kp(0.2)
+            .ki(0.3)
+            .kd(-0.05);
+        let mut pid_filtered = PidController::new();
+        pid_filtered
+            .compute_fn(compute_angle)
+            .set_point(10.0)
+            .kp(0.2)
+            .ki(0.3)
+            .kd(-0.05);
+        let data = AngleControlData {
+            measurement: 5.0,
+            rate: Some(0.0),
+            derivative_source: DerivativeSource::Rate,
+            dt: 0.01,
+            measurement_updated: true,
+            integral_limit: 25.0,
+            reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: 0.0,
+            integration_method: IntegrationMethod::Rectangular,
+            form: PidForm::TwoDegreesOfFreedom,
+            b_weight: 1.0,
+            c_weight: 0.0,
+            prev_set_point: 0.0,
+        };
+
+        for i in 0..10 {
+            let rate = i as f32 - 4.5;
+            let expected = pid_unfiltered.compute(AngleControlData {
+                rate: Some(rate),
+                ..data
+            });
+            let output = pid_filtered.compute(AngleControlData {
+                rate: Some(rate),
+                ..data
+            });
+            assert_eq!(
+                expected, output,
+                "Unity coefficient should not change the output."
+            );
+            assert_eq!(rate, pid_filtered.derivative);
+        }
+    }
+
+    /// Run a saturating step response, returning the number of steps needed to
+    /// leave upper saturation after the set point drops.
+    fn saturation_recovery_steps(conditional: bool) -> usize {
+        const OUTPUT_LIMIT: f32 = 10.0;
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(20.0)
+            .kp(1.0)
+            .ki(1.0)
+            .kd(0.0);
+        let mut data = AngleControlData {
+            measurement: 0.0,
+            rate: Some(0.0),
+            derivative_source: DerivativeSource::Rate,
+            dt: 0.1,
+            measurement_updated: true,
+            integral_limit: 100.0,
+            reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: 0.0,
+            integration_method: IntegrationMethod::Rectangular,
+            form: PidForm::TwoDegreesOfFreedom,
+            b_weight: 1.0,
+            c_weight: 0.0,
+            prev_set_point: 0.0,
+        };
+        let step = |pid: &mut PidController<f32, AngleControlData<f32>>,
+                    data: &mut AngleControlData<f32>| {
+            let output = pid.compute(*data);
+            let saturation = if OUTPUT_LIMIT < output {
+                SaturationState::Upper
+            } else if output < -OUTPUT_LIMIT {
+                SaturationState::Lower
+            } else {
+                SaturationState::None
+            };
+            if conditional {
+                data.saturation = saturation;
+            }
+            saturation
+        };
+
+        // Hold the output in saturation
+        for _ in 0..50 {
+            let _ = step(&mut pid, &mut data);
+        }
+
+        // Drop the set point and wait for the output to leave saturation
+        pid.set_point(-5.0);
+        let mut steps = 1;
+        while step(&mut pid, &mut data) == SaturationState::Upper {
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Test that conditional anti-windup recovers from saturation faster than the hard clamp.
+    #[test]
+    fn test_pid_angle_conditional_anti_windup_recovery() {
+        let conditional_steps = saturation_recovery_steps(true);
+        let clamped_steps = saturation_recovery_steps(false);
+
+        assert_eq!(
+            1, conditional_steps,
+            "Conditional should recover immediately."
+        );
+        assert!(
+            conditional_steps < clamped_steps,
+            "Conditional anti-windup should recover faster."
+        );
+    }
+
+    /// Test that saturation blocks only the integration that deepens saturation.
+    #[test]
+    fn test_pid_angle_conditional_anti_windup_direction() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(10.0)
+            .kp(1.0)
+            .ki(1.0)
+            .kd(0.0);
+        pid.integral = 5.0;
+        let data = AngleControlData {
+            measurement: 0.0,
+            rate: Some(0.0),
+            derivative_source: DerivativeSource::Rate,
+            dt: 1.0,
+            measurement_updated: true,
+            integral_limit: 100.0,
+            reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
+            saturation: SaturationState::Upper,
+            wrap: None,
+            integral_leak: 0.0,
+            integration_method: IntegrationMethod::Rectangular,
+            form: PidForm::TwoDegreesOfFreedom,
+            b_weight: 1.0,
+            c_weight: 0.0,
+            prev_set_point: 0.0,
+        };
+
+        // Positive error deepens upper saturation.
+        let (_, integral, _) = compute_angle(&mut pid, data);
+        assert!(value_close(5.0, integral), "Integral should be held.");
+
+        // Positive error relieves lower saturation.
+        let data_lower = AngleControlData {
+            saturation: SaturationState::Lower,
+            wrap: None,
+            ..data
+        };
+        let (_, integral, _) = compute_angle(&mut pid, data_lower);
+        assert!(value_close(15.0, integral), "Integral should accumulate.");
+    }
+
+    /// Core angle PID assertions, generic over the number type.
+    fn check_pid_angle_core<T: TestNumber>() {
+        let n = <T as TestNumber>::from_f32;
+
+        // Integral clamping
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle::<T>)
+            .set_point(n(50.0))
+            .kp(n(1.0))
+            .ki(n(5.0))
+            .kd(n(0.1));
+        let data = AngleControlData {
+            measurement: n(0.0),
+            rate: Some(n(0.0)),
+            dt: n(1.0),
+            integral_limit: n(100.0),
+            ..Default::default()
+        };
+        for _ in 0..10 {
+            let _ = pid.compute(data);
+        }
+        let (_, integral, _) = compute_angle(&mut pid, data);
+        assert!(
+            number_close(100.0, integral),
+            "Integral should be clamped to 100."
+        );
+
+        // Specific output and accumulation
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle::<T>)
+            .set_point(n(10.0))
+            .kp(n(1.0))
+            .ki(n(1.0))
+            .kd(n(1.0));
+        let data = AngleControlData {
+            measurement: n(5.0),
+            rate: Some(n(7.0)),
+            ..data
+        };
+        let (error, integral, derivative) = compute_angle(&mut pid, data);
+        let output = pid.compute(data);
+        assert!(number_close(5.0, error), "Error should be 5.");
+        assert!(number_close(5.0, integral), "Integral should be 5.");
+        assert!(number_close(7.0, derivative), "Derivative should be 7.");
+        assert!(number_close(17.0, output), "Output should be 17.");
+        let _ = pid.compute(data);
+        assert!(number_close(10.0, pid.integral), "Integral should be 10.");
+
+        // Closed loop convergence on a simple integrating plant
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle::<T>)
+            .set_point(n(10.0))
+            .kp(n(2.0))
+            .ki(n(0.5))
+            .kd(n(0.0));
+        let dt = n(0.125);
+        let mut measurement = n(0.0);
+        for _ in 0..200 {
+            let data = AngleControlData {
+                measurement,
+                dt,
+                ..data
+            };
+            measurement += pid.compute(data) * dt;
+        }
+        assert!(
+            (10.0 - measurement.to_f32()).abs() < 0.05,
+            "Measurement should converge to the set point."
+        );
+    }
+
+    /// Test that the wrapped error takes the short way around the ±180 degree boundary.
+    #[test]
+    fn test_pid_angle_wrap_boundary() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(179.0)
+            .kp(1.0)
+            .ki(1.0)
+            .kd(0.0);
+        let data = AngleControlData {
+            measurement: -179.0,
+            dt: 1.0,
+            integral_limit: 100.0,
+            wrap: Some(180.0),
+            ..Default::default()
+        };
+        let (error, integral, _) = compute_angle(&mut pid, data);
+        assert!(value_close(-2.0, error), "Error should wrap to -2.");
+        assert!(
+            value_close(-2.0, integral),
+            "Integral should use the wrapped error."
+        );
+
+        // Crossing the boundary in the other direction
+        pid.set_point(-179.0);
+        let data = AngleControlData {
+            measurement: 179.0,
+            ..data
+        };
+        let (error, _, _) = compute_angle(&mut pid, data);
+        assert!(value_close(2.0, error), "Error should wrap to 2.");
+
+        // The boundary itself is inside the range
+        pid.set_point(180.0);
+        let data = AngleControlData {
+            measurement: 0.0,
+            ..data
+        };
+        let (error, _, _) = compute_angle(&mut pid, data);
+        assert!(value_close(180.0, error), "Error should stay at 180.");
+    }
+
+    /// Test that the error is not wrapped when wrapping is disabled.
+    #[test]
+    fn test_pid_angle_wrap_disabled() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(179.0)
+            .kp(1.0)
+            .ki(0.0)
+            .kd(0.0);
+        let data = AngleControlData {
+            measurement: -179.0,
+            dt: 1.0,
+            integral_limit: 100.0,
+            wrap: None,
+            ..Default::default()
+        };
+        let (error, _, _) = compute_angle(&mut pid, data);
+        assert!(value_close(358.0, error), "Error should not wrap.");
+    }
+
+    /// Test that the error wraps at ±π for radians.
+    #[test]
+    fn test_pid_angle_wrap_radians() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(3.0)
+            .kp(1.0)
+            .ki(0.0)
+            .kd(0.0);
+        let data = AngleControlData {
+            measurement: -3.0,
+            dt: 1.0,
+            integral_limit: 100.0,
+            wrap: Some(core::f32::consts::PI),
+            ..Default::default()
+        };
+        let (error, _, _) = compute_angle(&mut pid, data);
+        assert!(
+            value_close(6.0 - 2.0 * core::f32::consts::PI, error),
+            "Error should wrap to 6 - 2π."
+        );
+    }
+
+    /// Test the core angle PID behavior with `f32`.
+    #[test]
+    fn test_pid_angle_core_f32() {
+        check_pid_angle_core::<f32>();
+    }
+
+    /// Test the core angle PID behavior with `f64`.
+    #[test]
+    fn test_pid_angle_core_f64() {
+        check_pid_angle_core::<f64>();
+    }
+
+    /// Test the core angle PID behavior with a fixed-point type.
+    #[test]
+    fn test_pid_angle_core_fixed() {
+        check_pid_angle_core::<fixed::types::I16F16>();
+    }
+
+    /// Test that a non-positive dt holds the integral and derivative without producing `NaN`.
+    #[test]
+    fn test_pid_angle_non_positive_dt() {
+        for dt in [0.0, -0.01] {
+            let mut pid = PidController::new();
+            pid.compute_fn(compute_angle)
+                .set_point(10.0)
+                .kp(1.0)
+                .ki(1.0)
+                .kd(1.0);
+            pid.integral = 2.0;
+            pid.derivative = 3.0;
+            let data = AngleControlData {
+                measurement: 0.0,
+                rate: Some(5.0),
+                dt,
+                integral_limit: 100.0,
+                derivative_on_error: true,
+                suppress_derivative_on_setpoint_change: false,
+                ..Default::default()
+            };
+            let (error, integral, derivative) = compute_angle(&mut pid, data);
+            let output = pid.compute(data);
+
+            assert!(value_close(10.0, error), "Error should be 10.");
+            assert!(value_close(2.0, integral), "Integral should be unchanged.");
+            assert!(
+                value_close(3.0, derivative),
+                "Derivative should be unchanged."
+            );
+            assert!(value_close(15.0, output), "Output should be 15.");
+        }
+    }
+
+    /// Test that a zero derivative gain does not advance the derivative filter state.
+    #[test]
+    fn test_pid_angle_zero_kd_skips_derivative() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(10.0)
+            .kp(1.0)
+            .ki(0.0)
+            .kd(0.0);
+        pid.derivative = 3.0;
+        let data = AngleControlData {
+            measurement: 0.0,
+            rate: Some(5.0),
+            dt: 0.01,
+            integral_limit: 100.0,
+            derivative_lpf_alpha: 0.5,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            let output = pid.compute(data);
+            assert!(value_close(10.0, output), "Output should be 10.");
+        }
+        assert!(
+            value_close(3.0, pid.derivative),
+            "Derivative filter state should be unchanged."
+        );
+    }
+
+    /// Test that the integral leak decays the integral exponentially with zero error.
+    #[test]
+    fn test_pid_angle_integral_leak_decay() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(0.0)
+            .kp(1.0)
+            .ki(1.0)
+            .kd(0.0);
+        pid.integral = 10.0;
+        let data = AngleControlData {
+            dt: 0.1,
+            integral_limit: 100.0,
+            integral_leak: 0.5,
+            ..Default::default()
+        };
+
+        // Each tick multiplies the integral by 1 - 0.5 * 0.1 = 0.95.
+        let mut expected = 10.0;
+        for _ in 0..20 {
+            let _ = pid.compute(data);
+            expected *= 0.95;
+            assert!(
+                value_close(expected, pid.integral),
+                "Integral should decay exponentially."
+            );
+        }
+    }
+
+    /// Test that a zero integral leak leaves the integral untouched.
+    #[test]
+    fn test_pid_angle_integral_leak_disabled() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(0.0)
+            .kp(1.0)
+            .ki(1.0)
+            .kd(0.0);
+        pid.integral = 10.0;
+        let data = AngleControlData {
+            dt: 0.1,
+            integral_limit: 100.0,
+            integral_leak: 0.0,
+            b_weight: 1.0,
+            c_weight: 0.0,
+            prev_set_point: 0.0,
+            ..Default::default()
+        };
+
+        for _ in 0..20 {
+            let _ = pid.compute(data);
+        }
+        assert_eq!(10.0, pid.integral, "Integral should not decay.");
+    }
+
+    /// Test that trapezoidal integration of an error ramp matches the analytical integral,
+    /// while rectangular integration overshoots it, with and without a setpoint weight.
+    #[test]
+    fn test_pid_angle_trapezoidal_integration() {
+        let integrate = |integration_method: IntegrationMethod, b_weight: f32| {
+            let mut pid = PidController::new();
+            pid.compute_fn(compute_angle)
+                .set_point(10.0)
+                .kp(1.0)
+                .ki(1.0)
+                .kd(0.0);
+            let data = AngleControlData {
+                dt: 0.5,
+                integral_limit: 100.0,
+                integration_method,
+                b_weight,
+                prev_set_point: 10.0,
+                ..Default::default()
+            };
+
+            // Prime the previous error at zero, then ramp the error as 2 * t.
+            let _ = pid.compute(AngleControlData {
+                measurement: 10.0,
+                ..data
+            });
+            pid.integral = 0.0;
+            for step in 1..=8 {
+                let time = step as f32 * 0.5;
+                let data = AngleControlData {
+                    measurement: 10.0 - 2.0 * time,
+                    ..data
+                };
+                let _ = pid.compute(data);
+            }
+            pid.integral
+        };
+
+        // The integral of 2 * t from 0 to 4 seconds is 16.
+        for b_weight in [1.0, 0.5] {
+            assert!(
+                value_close(16.0, integrate(IntegrationMethod::Trapezoidal, b_weight)),
+                "Trapezoidal integral should be exact."
+            );
+            assert!(
+                value_close(18.0, integrate(IntegrationMethod::Rectangular, b_weight)),
+                "Rectangular integral should overshoot by half a step per tick."
+            );
+        }
+    }
+
+    /// Test that trapezoidal integration averages the errors across the wrap boundary.
+    #[test]
+    fn test_pid_angle_trapezoidal_wrap() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(172.0)
+            .kp(1.0)
+            .ki(1.0)
+            .kd(0.0);
+        let data = AngleControlData {
+            measurement: 0.0,
+            dt: 1.0,
+            integral_limit: 1000.0,
+            wrap: Some(180.0),
+            integration_method: IntegrationMethod::Trapezoidal,
+            form: PidForm::TwoDegreesOfFreedom,
+            prev_set_point: 172.0,
+            ..Default::default()
+        };
+        let _ = pid.compute(data);
+        pid.integral = 0.0;
+        let (_, integral, _) = compute_angle(
+            &mut pid,
+            AngleControlData {
+                measurement: -12.0,
+                ..data
+            },
+        );
+        assert!(
+            value_close(178.0, integral),
+            "Errors of 172 and 184 should average to 178."
+        );
+    }
+
+    /// Test that the I-PD form has no proportional or derivative kick on a setpoint step,
+    /// while the integral matches the standard form.
+    #[test]
+    fn test_pid_angle_ipd_set_point_step() {
+        let step = |form: PidForm| {
+            let mut pid = PidController::new();
+            pid.compute_fn(compute_angle)
+                .set_point(0.0)
+                .kp(1.0)
+                .ki(1.0)
+                .kd(1.0);
+            let data = AngleControlData {
+                dt: 0.1,
+                integral_limit: 100.0,
+                derivative_on_error: true,
+                suppress_derivative_on_setpoint_change: false,
+                form,
+                ..Default::default()
+            };
+            let _ = pid.compute(data);
+            pid.set_point(10.0);
+            let output = pid.compute(data);
+            (output, PidTerms::from_pid(&pid))
+        };
+
+        // P sees the step of 10 and D differentiates it over 0.1 seconds.
+        let (standard_output, standard_terms) = step(PidForm::Standard);
+        let (ipd_output, ipd_terms) = step(PidForm::IPd);
+        assert!(value_close(-89.0, standard_output), "Output should kick.");
+        assert_eq!(0.0, ipd_terms.proportional, "P should not kick.");
+        assert_eq!(0.0, ipd_terms.derivative, "D should not kick.");
+        assert_eq!(standard_terms.integral, ipd_terms.integral);
+        assert!(value_close(1.0, ipd_output), "Only I should respond.");
+
+        // P and D act on the measurement alone.
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(10.0)
+            .kp(1.0)
+            .ki(0.0)
+            .kd(1.0);
+        let data = AngleControlData {
+            measurement: 4.0,
+            rate: Some(2.0),
+            dt: 0.1,
+            integral_limit: 100.0,
+            form: PidForm::IPd,
+            ..Default::default()
+        };
+        let (error, _, derivative) = compute_angle(&mut pid, data);
+        assert_eq!(-4.0, error, "P should act on -measurement.");
+        assert_eq!(2.0, derivative, "D should act on the rate.");
+    }
+
+    /// Test that the standard form ignores the setpoint weights.
+    #[test]
+    fn test_pid_angle_standard_ignores_weights() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(10.0)
+            .kp(1.0)
+            .ki(0.0)
+            .kd(1.0);
+        let data = AngleControlData {
+            measurement: 4.0,
+            rate: Some(2.0),
+            dt: 0.1,
+            integral_limit: 100.0,
+            b_weight: 0.5,
+            c_weight: 1.0,
+            prev_set_point: 9.0,
+            ..Default::default()
+        };
+        let (error, _, derivative) = compute_angle(&mut pid, data);
+        assert!(value_close(1.0, error), "Weighted error should be 1.");
+        assert!(
+            value_close(-8.0, derivative),
+            "Weighted D should be 2 - 10."
+        );
+
+        let data = AngleControlData {
+            form: PidForm::Standard,
+            ..data
+        };
+        let (error, _, derivative) = compute_angle(&mut pid, data);
+        assert!(value_close(6.0, error), "Standard error should be 6.");
+        assert!(value_close(2.0, derivative), "Standard D should be 2.");
+    }
+
+    /// Test that a proportional setpoint weight below one reduces the kick on a setpoint step.
+    #[test]
+    fn test_pid_angle_b_weight_kick() {
+        let kick = |b_weight: f32| {
+            let mut pid = PidController::new();
+            pid.compute_fn(compute_angle)
+                .set_point(0.0)
+                .kp(1.0)
+                .ki(1.0)
+                .kd(0.0);
+            let data = AngleControlData {
+                dt: 0.1,
+                integral_limit: 100.0,
+                b_weight,
+                ..Default::default()
+            };
+            let _ = pid.compute(data);
+            pid.set_point(10.0);
+            let output = pid.compute(data);
+            (output, pid.error, pid.integral)
+        };
+
+        // The proportional term sees half the step while the integral sees all of it.
+        let (full_output, full_error, full_integral) = kick(1.0);
+        let (weighted_output, weighted_error, weighted_integral) = kick(0.5);
+        assert!(value_close(10.0, full_error), "Full error should be 10.");
+        assert!(
+            value_close(5.0, weighted_error),
+            "Weighted error should be 5."
+        );
+        assert_eq!(full_integral, weighted_integral, "Integral should match.");
+        assert!(
+            value_close(5.0, full_output - weighted_output),
+            "Proportional kick should be halved."
+        );
+    }
+
+    /// Test that the derivative setpoint weight adds the weighted setpoint rate.
+    #[test]
+    fn test_pid_angle_c_weight() {
+        let derivative = |c_weight: f32| {
+            let mut pid = PidController::new();
+            pid.compute_fn(compute_angle)
+                .set_point(10.0)
+                .kp(1.0)
+                .ki(0.0)
+                .kd(1.0);
+            let data = AngleControlData {
+                rate: Some(2.0),
+                dt: 0.1,
+                integral_limit: 100.0,
+                c_weight,
+                prev_set_point: 9.0,
+                ..Default::default()
+            };
+            let (_, _, derivative) = compute_angle(&mut pid, data);
+            derivative
+        };
+
+        // The setpoint moves 1 over 0.1 seconds, a rate of 10.
+        assert!(
+            value_close(2.0, derivative(0.0)),
+            "D should act on the rate."
+        );
+        assert!(
+            value_close(-3.0, derivative(0.5)),
+            "D should be 2 - 0.5 * 10."
+        );
+        assert!(value_close(-8.0, derivative(1.0)), "D should be 2 - 10.");
+    }
+
+    /// Test that non-finite intermediates hold the previous integral and derivative.
+    #[test]
+    fn test_pid_angle_non_finite_hold() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(10.0)
+            .kp(1.0)
+            .ki(1.0)
+            .kd(1.0);
+        pid.integral = 2.0;
+        pid.derivative = 3.0;
+
+        // An infinite error saturates the integral at its limit.
+        let data = AngleControlData {
+            measurement: f32::NEG_INFINITY,
+            rate: Some(f32::INFINITY),
+            dt: 0.01,
+            integral_limit: 100.0,
+            ..Default::default()
+        };
+        let (_, integral, derivative) = compute_angle(&mut pid, data);
+        assert!(
+            value_close(100.0, integral),
+            "Integral should saturate at the limit."
+        );
+        assert!(
+            value_close(3.0, derivative),
+            "Derivative should be unchanged."
+        );
+
+        // A NaN measurement leaves the integral unchanged.
+        let data = AngleControlData {
+            measurement: f32::NAN,
+            rate: Some(f32::NAN),
+            ..data
+        };
+        let (_, integral, derivative) = compute_angle(&mut pid, data);
+        assert!(value_close(2.0, integral), "Integral should be unchanged.");
+        assert!(
+            value_close(3.0, derivative),
+            "Derivative should be unchanged."
+        );
+    }
+
+    /// Test that the derivative only advances on fresh measurements,
+    /// while the integral accumulates on every tick.
+    #[test]
+    fn test_pid_angle_stale_measurement() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(0.0)
+            .kp(1.0)
+            .ki(1.0)
+            .kd(1.0);
+        let data = AngleControlData {
+            dt: 0.1,
+            integral_limit: 100.0,
+            derivative_on_error: true,
+            suppress_derivative_on_setpoint_change: false,
+            ..Default::default()
+        };
+
+        // (measurement, updated, expected derivative, expected integral)
+        let ticks = [
+            (0.0, true, 0.0, 0.0),
+            (1.0, true, 10.0, -0.1),
+            (1.0, false, 10.0, -0.2),
+            (1.0, false, 10.0, -0.3),
+            (4.0, true, 30.0, -0.7),
+            (4.0, false, 30.0, -1.1),
+        ];
+        for (measurement, measurement_updated, expected_derivative, expected_integral) in ticks {
+            let data = AngleControlData {
+                measurement,
+                measurement_updated,
+                ..data
+            };
+            let (_, integral, derivative) = compute_angle(&mut pid, data);
+            let _ = pid.compute(data);
+            assert!(
+                value_close(expected_derivative, derivative),
+                "Derivative should only advance on fresh samples."
+            );
+            assert!(
+                value_close(expected_integral, integral),
+                "Integral should accumulate on every tick."
+            );
+        }
+    }
+
+    /// Test that a missing rate is derived from the change in measurement,
+    /// matching an explicitly supplied rate for the same measurements.
+    #[test]
+    fn test_pid_angle_derived_rate() {
+        for (b_weight, c_weight, wrap) in
+            [(1.0, 0.0, None), (0.5, 0.5, None), (1.0, 0.0, Some(180.0))]
+        {
+            let controller = || {
+                let mut pid = PidController::new();
+                pid.compute_fn(compute_angle)
+                    .set_point(170.0)
+                    .kp(1.0)
+                    .ki(0.5)
+                    .kd(-0.2);
+                pid
+            };
+            let mut derived = controller();
+            let mut explicit = controller();
+            let data = AngleControlData {
+                dt: 0.1,
+                integral_limit: 100.0,
+                wrap,
+                b_weight,
+                c_weight,
+                prev_set_point: 170.0,
+                ..Default::default()
+            };
+
+            // Prime both controllers with a held derivative, then step through
+            // measurements that cross the wrap boundary when it is enabled.
+            let measurements: [f32; 7] = [160.0, 165.0, 172.0, 179.0, -178.0, 176.0, 174.0];
+            let mut prev_measurement = measurements[0];
+            for (tick, measurement) in measurements.into_iter().enumerate() {
+                let data = AngleControlData {
+                    measurement,
+                    measurement_updated: 0 < tick,
+                    ..data
+                };
+                let change = measurement - prev_measurement;
+                let rate = match wrap {
+                    Some(half_range) => change.wrap(half_range),
+                    None => change,
+                } / data.dt;
+                prev_measurement = measurement;
+                let output = derived.compute(AngleControlData { rate: None, ..data });
+                let expected = explicit.compute(AngleControlData {
+                    rate: Some(rate),
+                    ..data
+                });
+                assert!(value_close(explicit.derivative, derived.derivative));
+                assert!(value_close(expected, output));
+            }
+            assert!(value_not_close(0.0, derived.derivative));
+        }
+    }
+
+    /// Test that each derivative source produces its derivative when the measured
+    /// rate and the change in measurement disagree.
+    #[test]
+    fn test_pid_angle_derivative_source() {
+        // The rate reads 4 while the measurement moves 0.5 in 0.5 seconds
+        for (derivative_source, expected) in [
+            (DerivativeSource::Rate, 4.0),
+            (DerivativeSource::Measurement, 1.0),
+            (DerivativeSource::Blend(0.25), 0.25 * 4.0 + 0.75 * 1.0),
+            (DerivativeSource::Blend(1.0), 4.0),
+            (DerivativeSource::Blend(0.0), 1.0),
+        ] {
+            let mut pid = PidController::new();
+            pid.compute_fn(compute_angle).kp(1.0).ki(0.0).kd(1.0);
+            let data = AngleControlData {
+                measurement: 1.0,
+                measurement_updated: false,
+                rate: Some(4.0),
+                derivative_source,
+                dt: 0.5,
+                integral_limit: 100.0,
+                ..Default::default()
+            };
+            let _ = pid.compute(data);
+            let output = pid.compute(AngleControlData {
+                measurement: 1.5,
+                measurement_updated: true,
+                ..data
+            });
+            assert!(
+                value_close(expected, pid.derivative),
+                "{:?} derivative should be {}.",
+                derivative_source,
+                expected
+            );
+            assert!(value_close(-1.5 + expected, output));
+        }
+    }
 }
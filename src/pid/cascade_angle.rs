@@ -7,11 +7,12 @@
 //! calculations. It differs from the plain angle PID compute function
 //! in that the previous measurement is used to calculate the derivative.
 
+use crate::pid::limit_derivative;
 use crate::Number;
 use piddiy::PidController;
 
 /// Control data for angle-based PID stabilization callback.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct CascadeAngleControlData<T> {
     /// The current measured angle, calculated from sensors.
     pub measurement: T,
@@ -20,25 +21,108 @@ pub struct CascadeAngleControlData<T> {
     /// The current rate of change, typically reported by a gyro.
     pub rate: T,
     /// The time delta since the last computation.
+    /// A non-positive value skips integral accumulation and holds the previous
+    /// derivative for the tick, rather than producing runaway values or `NaN`.
     pub dt: T,
-    /// The maximum allowed value for the integral term, used to prevent integral windup.
+    /// Flag indicating the measurement is a fresh sample. Clear it when the attitude
+    /// estimate updates slower than the control loop and the previous measurement is
+    /// repeated. On a stale tick the derivative is held, rather than differentiating
+    /// a repeated sample, which reads as zero motion followed by a spike on the next
+    /// fresh sample. The integral still accumulates the error against the last
+    /// measurement with the full `dt`. Stabilizers always pass fresh samples.
+    pub measurement_updated: bool,
+    /// The maximum allowed value for the raw accumulated integral, before `ki` is applied.
+    /// Used to prevent integral windup.
     pub integral_limit: T,
     /// Flag to reset the integral term, typically used when the controller is inactive.
     pub reset_integral: bool,
+    /// Fraction of the error accumulated into the integral this tick.
+    /// Stabilizers lower it below the integral throttle threshold so the integrator
+    /// fades out smoothly instead of resetting. A value of one accumulates fully.
+    /// With `integral_divisor`, it is zero between integral updates, which skips the
+    /// accumulation, and the summed time steps over `dt` on each update.
+    pub integral_scale: T,
+    /// Maximum rate of change of the raw accumulated integral, in integral units per second.
+    /// Each tick adds at most `max_integral_rate * dt` to the integral, so it ramps
+    /// smoothly under a large error. A value of zero disables the limit.
+    pub max_integral_rate: T,
+    /// Maximum magnitude of the derivative term, `kd * derivative`.
+    /// Caps the derivative contribution of a sudden rate spike without limiting
+    /// the proportional and integral terms. A value of zero or infinity disables the limit.
+    pub d_term_limit: T,
+    /// Optional half range for wrapping the error, like 180 for degrees or π for radians.
+    /// The error and the change in measurement are wrapped into `[-wrap, wrap]`,
+    /// so the controller takes the short way around the boundary.
+    /// Typically only used for yaw. The error is not wrapped if `None`.
+    pub wrap: Option<T>,
+}
+
+impl<T: Number> Default for CascadeAngleControlData<T> {
+    fn default() -> Self {
+        Self {
+            measurement: T::zero(),
+            prev_measurement: T::zero(),
+            rate: T::zero(),
+            dt: T::zero(),
+            measurement_updated: true,
+            integral_limit: T::zero(),
+            reset_integral: false,
+            integral_scale: T::one(),
+            max_integral_rate: T::zero(),
+            d_term_limit: T::zero(),
+            wrap: None,
+        }
+    }
 }
 
 /// Angle-based PID stabilization compute callback.
+///
+/// With a zero derivative gain, `kd`, the derivative is not evaluated,
+/// so a PI controller skips the derivative entirely.
+/// The previous derivative is held for when the gain becomes nonzero.
 pub fn compute_cascade_angle<T: Number>(
     pid: &mut PidController<T, CascadeAngleControlData<T>>,
     data: CascadeAngleControlData<T>,
 ) -> (T, T, T) {
-    let error = pid.set_point - data.measurement;
-    let integral = if !data.reset_integral {
-        (pid.integral + error * data.dt).clamp(-data.integral_limit, data.integral_limit)
-    } else {
+    let wrap = |angle: T| match data.wrap {
+        Some(half_range) => angle.wrap(half_range),
+        None => angle,
+    };
+    let error = wrap(pid.set_point - data.measurement);
+    let valid_dt = T::zero() < data.dt;
+    let integral = if data.reset_integral {
         T::zero()
+    } else if !valid_dt {
+        pid.integral
+    } else if data.integral_scale == T::zero() {
+        // Skip the accumulation between integral updates and on frozen axes.
+        pid.integral
+            .clamp(-data.integral_limit, data.integral_limit)
+    } else {
+        let step = error * data.dt * data.integral_scale;
+        let step = if T::zero() < data.max_integral_rate {
+            // Limit how fast the integral can change.
+            let max_step = data.max_integral_rate * data.dt;
+            step.clamp(-max_step, max_step)
+        } else {
+            step
+        };
+        (pid.integral + step).clamp(-data.integral_limit, data.integral_limit)
     };
-    let derivative = (data.measurement - data.prev_measurement) / data.dt;
+    let derivative = if pid.kd == T::zero() {
+        // Skip the unused derivative.
+        pid.derivative
+    } else if valid_dt && data.measurement_updated {
+        let derivative = wrap(data.measurement - data.prev_measurement) / data.dt;
+        limit_derivative(derivative, pid.kd, data.d_term_limit)
+    } else {
+        // Hold the previous derivative rather than dividing by a non-positive dt
+        // or differentiating a stale measurement.
+        pid.derivative
+    };
+    // Hold the previous state rather than storing a non-finite intermediate.
+    let integral = integral.finite_or(pid.integral);
+    let derivative = derivative.finite_or(pid.derivative);
 
     (error, integral, derivative)
 }
@@ -62,8 +146,13 @@ mod tests {
             measurement: 0.0,
             prev_measurement: 0.0,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 100.0, // Integral should not exceed this value.
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            d_term_limit: 0.0,
+            wrap: None,
         };
 
         // This would normally push integral way over 100 if not clamped
@@ -92,8 +181,13 @@ mod tests {
             prev_measurement: 0.0,
             rate: 0.0,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 100.0,
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            d_term_limit: 0.0,
+            wrap: None,
         };
 
         // First compute without reset to build up the integral.
@@ -103,6 +197,8 @@ mod tests {
         // Now compute with reset.
         let data_reset = CascadeAngleControlData {
             reset_integral: true,
+            integral_scale: 1.0,
+            wrap: None,
             ..data
         };
         let (_, integral_reset, _) = compute_cascade_angle(&mut pid, data_reset);
@@ -132,8 +228,13 @@ mod tests {
             prev_measurement: 0.0,
             rate: 0.0,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 100.0,
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            d_term_limit: 0.0,
+            wrap: None,
         };
 
         let (error, integral, derivative) = compute_cascade_angle(&mut pid, data);
@@ -173,8 +274,13 @@ mod tests {
             prev_measurement: 2.0,
             rate: 7.0,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 100.0,
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            d_term_limit: 0.0,
+            wrap: None,
         };
 
         let (error, integral, derivative) = compute_cascade_angle(&mut pid, data);
@@ -214,8 +320,13 @@ mod tests {
             prev_measurement: 0.0,
             rate: 0.0,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 10.0,
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            d_term_limit: 0.0,
+            wrap: None,
         };
         let (error, integral, derivative) = compute_cascade_angle(&mut pid, data);
         let output = pid.compute(data);
@@ -225,4 +336,109 @@ mod tests {
         assert!(value_close(0.0, derivative), "Derivative should be zero.");
         assert!(value_close(0.0, output), "Output should be zero.");
     }
+
+    /// Test that the error and the change in measurement wrap at the ±180 degree boundary.
+    #[test]
+    fn test_pid_angle_wrap_boundary() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_cascade_angle)
+            .set_point(179.0)
+            .kp(1.0)
+            .ki(0.0)
+            .kd(1.0);
+        let data = CascadeAngleControlData {
+            measurement: -179.0,
+            prev_measurement: 179.0,
+            rate: 0.0,
+            dt: 1.0,
+            measurement_updated: true,
+            integral_limit: 10.0,
+            reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            d_term_limit: 0.0,
+            wrap: Some(180.0),
+        };
+        let (error, _, derivative) = compute_cascade_angle(&mut pid, data);
+
+        assert!(value_close(-2.0, error), "Error should wrap to -2.");
+        assert!(value_close(2.0, derivative), "Derivative should wrap to 2.");
+    }
+
+    /// Test that a non-positive dt holds the integral and derivative without producing `NaN`.
+    #[test]
+    fn test_pid_angle_non_positive_dt() {
+        for dt in [0.0, -0.01] {
+            let mut pid = PidController::new();
+            pid.compute_fn(compute_cascade_angle)
+                .set_point(10.0)
+                .kp(1.0)
+                .ki(1.0)
+                .kd(1.0);
+            pid.integral = 2.0;
+            pid.derivative = 3.0;
+            let data = CascadeAngleControlData {
+                measurement: 0.0,
+                prev_measurement: 1.0,
+                rate: 0.0,
+                dt,
+                measurement_updated: true,
+                integral_limit: 100.0,
+                reset_integral: false,
+                integral_scale: 1.0,
+                max_integral_rate: 0.0,
+                d_term_limit: 0.0,
+                wrap: None,
+            };
+            let (error, integral, derivative) = compute_cascade_angle(&mut pid, data);
+            let output = pid.compute(data);
+
+            assert!(value_close(10.0, error), "Error should be 10.");
+            assert!(value_close(2.0, integral), "Integral should be unchanged.");
+            assert!(
+                value_close(3.0, derivative),
+                "Derivative should be unchanged."
+            );
+            assert!(value_close(15.0, output), "Output should be 15.");
+        }
+    }
+
+    /// Test that the derivative only advances on fresh measurements,
+    /// rather than reading a repeated sample as zero motion.
+    #[test]
+    fn test_pid_angle_stale_measurement() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_cascade_angle)
+            .set_point(0.0)
+            .kp(1.0)
+            .ki(1.0)
+            .kd(1.0);
+        let data = CascadeAngleControlData {
+            dt: 0.1,
+            integral_limit: 100.0,
+            ..Default::default()
+        };
+
+        // (measurement, previous measurement, updated, expected derivative)
+        let ticks = [
+            (1.0, 0.0, true, 10.0),
+            (1.0, 1.0, false, 10.0),
+            (3.0, 1.0, true, 20.0),
+            (3.0, 3.0, false, 20.0),
+        ];
+        for (measurement, prev_measurement, measurement_updated, expected_derivative) in ticks {
+            let data = CascadeAngleControlData {
+                measurement,
+                prev_measurement,
+                measurement_updated,
+                ..data
+            };
+            let (_, _, derivative) = compute_cascade_angle(&mut pid, data);
+            let _ = pid.compute(data);
+            assert!(
+                value_close(expected_derivative, derivative),
+                "Derivative should only advance on fresh samples."
+            );
+        }
+    }
 }
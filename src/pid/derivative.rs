@@ -0,0 +1,274 @@
+// src/pid/derivative.rs
+
+//! # Derivative Estimation Module
+//!
+//! This module provides a fixed-size history of measurements for estimating
+//! the derivative from the samples themselves, instead of trusting the rate
+//! reported by a sensor. The estimate is the least-squares slope over the
+//! window, which smooths noise better than a two-point difference.
+//! The history is a ring buffer sized at compile time, so it is alloc-free.
+
+use crate::Number;
+
+/// Ring buffer of the last `N` measurements and time steps, with a least-squares
+/// slope estimate of their derivative.
+/// `N` must be at least two, which is checked at compile time.
+///
+/// The estimate can replace the reported rate of a control data structure,
+/// like `AngleControlData::rate`.
+///
+/// Example Usage
+/// ```
+/// use free_flight_stabilization::pid::{AngleControlData, DerivativeEstimator};
+///
+/// let mut estimator = DerivativeEstimator::<f32, 5>::new();
+/// let dt = 0.01;
+/// for measurement in [0.0, 0.02, 0.04, 0.06] {
+///     estimator.push(measurement, dt);
+/// }
+///
+/// let data = AngleControlData {
+///     measurement: 0.06,
+///     rate: Some(estimator.derivative()),
+///     dt,
+///     ..Default::default()
+/// };
+/// assert!((estimator.derivative() - 2.0).abs() < 1e-3);
+/// ```
+///
+/// A window of fewer than two samples cannot estimate a slope.
+/// ```compile_fail
+/// use free_flight_stabilization::pid::DerivativeEstimator;
+///
+/// let estimator = DerivativeEstimator::<f32, 1>::new();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DerivativeEstimator<T, const N: usize> {
+    measurements: [T; N],
+    dts: [T; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T: Number, const N: usize> Default for DerivativeEstimator<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Number, const N: usize> DerivativeEstimator<T, N> {
+    /// Fails to compile for a window of fewer than two samples.
+    const VALID_WINDOW: () = assert!(2 <= N, "DerivativeEstimator needs at least two samples");
+
+    /// Creates a new estimator with an empty history.
+    pub fn new() -> Self {
+        let () = Self::VALID_WINDOW;
+        Self {
+            measurements: [T::zero(); N],
+            dts: [T::zero(); N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of stored samples, up to `N`.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no samples are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Clears the history.
+    pub fn reset(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// Stores a measurement taken `dt` after the previous one, replacing the oldest
+    /// sample once the window is full. The `dt` of the first sample is ignored.
+    /// A sample with a non-positive `dt` is skipped, as it has no place in time.
+    pub fn push(&mut self, measurement: T, dt: T) {
+        if !self.is_empty() && dt <= T::zero() {
+            return;
+        }
+        self.measurements[self.head] = measurement;
+        self.dts[self.head] = dt;
+        self.head = (self.head + 1) % N;
+        if self.len < N {
+            self.len += 1;
+        }
+    }
+
+    /// Stores a measurement like `push` and returns the updated derivative estimate.
+    pub fn update(&mut self, measurement: T, dt: T) -> T {
+        self.push(measurement, dt);
+        self.derivative()
+    }
+
+    /// Returns the least-squares slope of the stored measurements over time.
+    /// Zero is returned until two samples are stored.
+    pub fn derivative(&self) -> T {
+        if self.len < 2 {
+            return T::zero();
+        }
+
+        // Means of the sample times, relative to the newest sample, and measurements
+        let mut count = T::zero();
+        let mut sum_time = T::zero();
+        let mut sum_measurement = T::zero();
+        self.for_each_sample(|time, measurement| {
+            count += T::one();
+            sum_time += time;
+            sum_measurement += measurement;
+        });
+        let mean_time = sum_time / count;
+        let mean_measurement = sum_measurement / count;
+
+        // Slope of the centered samples
+        let mut covariance = T::zero();
+        let mut variance = T::zero();
+        self.for_each_sample(|time, measurement| {
+            let time = time - mean_time;
+            covariance += time * (measurement - mean_measurement);
+            variance += time * time;
+        });
+        if variance <= T::zero() {
+            // Time steps too small for the number type
+            return T::zero();
+        }
+        covariance / variance
+    }
+
+    /// Visits the stored (time, measurement) samples from newest to oldest,
+    /// with times relative to the newest sample.
+    fn for_each_sample<F: FnMut(T, T)>(&self, mut visit: F) {
+        let mut time = T::zero();
+        for age in 0..self.len {
+            let index = (self.head + N - 1 - age) % N;
+            visit(time, self.measurements[index]);
+            time -= self.dts[index];
+        }
+    }
+}
+
+/// Clamps a derivative so the derivative term, `kd * derivative`, stays within
+/// `±d_term_limit`. The derivative is returned unchanged if the limit is not positive
+/// or `kd` is zero. An infinite limit divides to an infinite bound, so it never clamps.
+pub(crate) fn limit_derivative<T: Number>(derivative: T, kd: T, d_term_limit: T) -> T {
+    if d_term_limit <= T::zero() || kd == T::zero() {
+        return derivative;
+    }
+    let max_derivative = d_term_limit / kd.abs();
+    derivative.clamp(-max_derivative, max_derivative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use fixed::types::I16F16;
+
+    /// Feed a linear ramp through the estimator and check the slope.
+    fn assert_ramp<T: TestNumber, const N: usize>(slope: f32, dt: f32, samples: usize) {
+        let n = <T as TestNumber>::from_f32;
+        let mut estimator = DerivativeEstimator::<T, N>::new();
+        let mut measurement = 1.0;
+        for _ in 0..samples {
+            estimator.push(n(measurement), n(dt));
+            measurement += slope * dt;
+        }
+        assert!(
+            number_close(slope, estimator.derivative()),
+            "Estimate should match the slope of the ramp."
+        );
+    }
+
+    /// Test that a three-point window recovers the slope of a linear ramp.
+    #[test]
+    fn test_pid_derivative_ramp_three_point() {
+        assert_ramp::<f32, 3>(3.0, 0.01, 3);
+        assert_ramp::<f32, 3>(-2.0, 0.01, 20);
+    }
+
+    /// Test that a five-point window recovers the slope once the buffer wraps.
+    #[test]
+    fn test_pid_derivative_ramp_five_point() {
+        assert_ramp::<f32, 5>(3.0, 0.01, 2);
+        assert_ramp::<f32, 5>(3.0, 0.01, 5);
+        assert_ramp::<f32, 5>(3.0, 0.01, 12);
+    }
+
+    /// Test the estimator with every supported number type.
+    #[test]
+    fn test_pid_derivative_ramp_core() {
+        assert_ramp::<f32, 5>(1.5, 0.25, 8);
+        assert_ramp::<f64, 5>(1.5, 0.25, 8);
+        assert_ramp::<I16F16, 5>(1.5, 0.25, 8);
+    }
+
+    /// Test that uneven time steps still recover the slope.
+    #[test]
+    fn test_pid_derivative_uneven_dt() {
+        let mut estimator = DerivativeEstimator::<f32, 4>::new();
+        let mut time = 0.0;
+        for dt in [0.0, 0.01, 0.03, 0.02] {
+            time += dt;
+            estimator.push(2.0 * time - 1.0, dt);
+        }
+        assert!(
+            value_close(2.0, estimator.derivative()),
+            "Slope should be 2."
+        );
+    }
+
+    /// Test that the least-squares slope averages out alternating noise.
+    #[test]
+    fn test_pid_derivative_noise() {
+        let mut estimator = DerivativeEstimator::<f32, 5>::new();
+        let noise = [0.01, -0.01, 0.01, -0.01, 0.01];
+        for (step, noise) in noise.iter().enumerate() {
+            estimator.push(step as f32 * 0.1 + noise, 0.1);
+        }
+        let two_point: f32 = (0.4 + 0.01 - (0.3 - 0.01)) / 0.1;
+        let estimate = estimator.derivative();
+        assert!(
+            (estimate - 1.0).abs() < (two_point - 1.0).abs(),
+            "Estimate should be closer to the true slope than a two-point difference."
+        );
+    }
+
+    /// Test that fewer than two samples estimate zero, and reset clears the history.
+    #[test]
+    fn test_pid_derivative_insufficient_samples() {
+        let mut estimator = DerivativeEstimator::<f32, 3>::new();
+        assert!(estimator.is_empty());
+        assert_eq!(0.0, estimator.derivative());
+        assert_eq!(0.0, estimator.update(5.0, 0.1));
+
+        let derivative = estimator.update(6.0, 0.1);
+        assert!(value_close(10.0, derivative), "Slope should be 10.");
+        assert_eq!(2, estimator.len());
+
+        estimator.reset();
+        assert!(estimator.is_empty());
+        assert_eq!(0.0, estimator.update(6.0, 0.1));
+    }
+
+    /// Test that samples with a non-positive dt are skipped.
+    #[test]
+    fn test_pid_derivative_non_positive_dt() {
+        let mut estimator = DerivativeEstimator::<f32, 3>::new();
+        estimator.push(0.0, 0.1);
+        estimator.push(1.0, 0.1);
+        estimator.push(100.0, 0.0);
+        estimator.push(-100.0, -0.1);
+        assert_eq!(2, estimator.len());
+        assert!(
+            value_close(10.0, estimator.derivative()),
+            "Slope should be 10."
+        );
+    }
+}
@@ -0,0 +1,303 @@
+// src/pid/notch.rs
+
+//! # Notch Filter Module
+//!
+//! This module provides a second-order (biquad) notch filter for removing a
+//! narrow band of vibration, like the motor and propeller frequency of a
+//! multirotor, from the derivative input. A first-order low-pass filter
+//! strong enough to suppress that band adds a lot of phase lag, while the notch
+//! leaves frequencies away from its center nearly untouched.
+//! The coefficients follow the RBJ audio EQ cookbook, with the sine and cosine
+//! evaluated by polynomial, so the filter is fixed-point safe.
+
+use crate::Number;
+
+/// Biquad notch filter with a center frequency in hertz and a quality factor.
+/// The sample rate is derived from the `dt` of each sample, and the coefficients
+/// are recomputed whenever `dt`, `center_hz`, or `q` changes.
+///
+/// The filter passes samples through unchanged if `center_hz` or `q` is not positive,
+/// `dt` is not positive, or the center is at or above the Nyquist frequency, `0.5 / dt`.
+/// A higher `q` makes a narrower notch.
+///
+/// Example Usage
+/// ```
+/// use free_flight_stabilization::pid::NotchFilter;
+///
+/// // Remove 100 Hz vibration from a gyro sampled at 1 kHz.
+/// let mut notch = NotchFilter::<f32>::new(100.0, 1.0);
+/// let dt = 0.001;
+/// let mut filtered = 0.0;
+/// for step in 0..1000 {
+///     let time = step as f32 * dt;
+///     let vibration = (2.0 * core::f32::consts::PI * 100.0 * time).sin();
+///     filtered = notch.apply(2.0 + vibration, dt);
+/// }
+/// assert!((filtered - 2.0).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NotchFilter<T> {
+    /// Center frequency of the notch, in hertz. Zero disables the filter.
+    pub center_hz: T,
+    /// Quality factor, the center frequency over the bandwidth of the notch.
+    pub q: T,
+    coefficients: Option<NotchCoefficients<T>>,
+    inputs: [T; 2],
+    outputs: [T; 2],
+    primed: bool,
+}
+
+/// Normalized biquad coefficients and the parameters they were computed for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct NotchCoefficients<T> {
+    center_hz: T,
+    q: T,
+    dt: T,
+    b0: T,
+    b1: T,
+    a2: T,
+}
+
+impl<T: Number> Default for NotchFilter<T> {
+    fn default() -> Self {
+        Self::new(T::zero(), T::one())
+    }
+}
+
+impl<T: Number> NotchFilter<T> {
+    /// Creates a new filter with an empty history.
+    pub fn new(center_hz: T, q: T) -> Self {
+        Self {
+            center_hz,
+            q,
+            coefficients: None,
+            inputs: [T::zero(); 2],
+            outputs: [T::zero(); 2],
+            primed: false,
+        }
+    }
+
+    /// Returns `true` if the filter modifies samples at the given `dt`.
+    pub fn is_active(&self, dt: T) -> bool {
+        let half = match T::from_f32(0.5) {
+            Some(half) => half,
+            None => return false,
+        };
+        T::zero() < self.center_hz
+            && T::zero() < self.q
+            && T::zero() < dt
+            && self.center_hz * dt < half
+    }
+
+    /// Clears the history, so the next sample restarts the filter.
+    pub fn reset(&mut self) {
+        self.inputs = [T::zero(); 2];
+        self.outputs = [T::zero(); 2];
+        self.primed = false;
+    }
+
+    /// Filters a sample taken `dt` after the previous one and returns the result.
+    /// The first sample after a reset primes the history as a steady state,
+    /// so a constant input passes without a startup transient.
+    /// A non-finite result restarts the filter from the sample.
+    pub fn apply(&mut self, input: T, dt: T) -> T {
+        if !self.is_active(dt) {
+            return input;
+        }
+        let coefficients = match self.coefficients(dt) {
+            Some(coefficients) => coefficients,
+            None => return input,
+        };
+        if !self.primed {
+            self.inputs = [input; 2];
+            self.outputs = [input; 2];
+            self.primed = true;
+        }
+
+        // The notch is symmetric, so b2 equals b0 and a1 equals b1.
+        let [input_1, input_2] = self.inputs;
+        let [output_1, output_2] = self.outputs;
+        let output = coefficients.b0 * (input + input_2) + coefficients.b1 * (input_1 - output_1)
+            - coefficients.a2 * output_2;
+        if !output.is_finite() {
+            self.reset();
+            return input;
+        }
+        self.inputs = [input, input_1];
+        self.outputs = [output, output_1];
+        output
+    }
+
+    /// Returns the coefficients for `dt`, recomputing them if a parameter changed.
+    fn coefficients(&mut self, dt: T) -> Option<NotchCoefficients<T>> {
+        match self.coefficients {
+            Some(coefficients)
+                if coefficients.center_hz == self.center_hz
+                    && coefficients.q == self.q
+                    && coefficients.dt == dt =>
+            {
+                return Some(coefficients);
+            }
+            _ => {}
+        }
+
+        // Angular frequency of the center, in radians per sample
+        let two = T::one() + T::one();
+        let pi = T::from_f64(core::f64::consts::PI)?;
+        let omega = two * pi * self.center_hz * dt;
+        let (sin, cos) = sin_cos(omega);
+        let alpha = sin / (two * self.q);
+        let a0 = T::one() + alpha;
+        let coefficients = NotchCoefficients {
+            center_hz: self.center_hz,
+            q: self.q,
+            dt,
+            b0: T::one() / a0,
+            b1: -two * cos / a0,
+            a2: (T::one() - alpha) / a0,
+        };
+        self.coefficients = Some(coefficients);
+        Some(coefficients)
+    }
+}
+
+/// Returns the sine and cosine of an angle in `[0, π]`.
+/// The Taylor series of the half angle, at most π/2, is evaluated in Horner form
+/// with integer divisors, then doubled with the double-angle identities.
+fn sin_cos<T: Number>(angle: T) -> (T, T) {
+    let divide = |value: T, divisor: u8| match T::from_u8(divisor) {
+        Some(divisor) => value / divisor,
+        None => T::zero(),
+    };
+    let half = divide(angle, 2);
+    let square = half * half;
+    let mut sin = T::one();
+    for divisor in [110, 72, 42, 20, 6] {
+        sin = T::one() - divide(square * sin, divisor);
+    }
+    let sin = half * sin;
+    let mut cos = T::one();
+    for divisor in [132, 90, 56, 30, 12, 2] {
+        cos = T::one() - divide(square * cos, divisor);
+    }
+    let two = T::one() + T::one();
+    (two * sin * cos, T::one() - two * sin * sin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use core::f32::consts::PI;
+    use fixed::types::I16F16;
+
+    /// Returns the peak magnitude of the filtered sinusoid after the transient settles.
+    fn filtered_amplitude(notch: &mut NotchFilter<f32>, frequency: f32, dt: f32) -> f32 {
+        let mut peak: f32 = 0.0;
+        for step in 0..2000 {
+            let time = step as f32 * dt;
+            let output = notch.apply((2.0 * PI * frequency * time).sin(), dt);
+            if 1000 <= step {
+                peak = peak.max(output.abs());
+            }
+        }
+        peak
+    }
+
+    /// Test that the polynomial sine and cosine match the standard library.
+    #[test]
+    fn test_pid_notch_sin_cos() {
+        for step in 0..=32 {
+            let angle = PI * step as f32 / 32.0;
+            let (sin, cos) = sin_cos(angle);
+            assert!((angle.sin() - sin).abs() < 1e-5, "Sine of {}.", angle);
+            assert!((angle.cos() - cos).abs() < 1e-5, "Cosine of {}.", angle);
+        }
+    }
+
+    /// Test that a sinusoid at the center frequency is strongly attenuated.
+    #[test]
+    fn test_pid_notch_center_attenuated() {
+        let mut notch = NotchFilter::new(100.0, 2.0);
+        let amplitude = filtered_amplitude(&mut notch, 100.0, 0.001);
+        assert!(
+            amplitude < 0.01,
+            "Amplitude {} should be removed.",
+            amplitude
+        );
+    }
+
+    /// Test that frequencies away from the center pass nearly unchanged.
+    #[test]
+    fn test_pid_notch_off_center_passes() {
+        for frequency in [10.0, 400.0] {
+            let mut notch = NotchFilter::new(100.0, 2.0);
+            let amplitude = filtered_amplitude(&mut notch, frequency, 0.001);
+            assert!(
+                (amplitude - 1.0).abs() < 0.05,
+                "Amplitude {} at {} Hz should pass.",
+                amplitude,
+                frequency
+            );
+        }
+    }
+
+    /// Test that a constant input passes without a startup transient.
+    #[test]
+    fn test_pid_notch_dc_passes() {
+        let mut notch = NotchFilter::new(100.0, 2.0);
+        for _ in 0..100 {
+            assert!(value_close(3.0, notch.apply(3.0, 0.001)));
+        }
+    }
+
+    /// Test that the filter is fixed-point safe.
+    #[test]
+    fn test_pid_notch_fixed_point() {
+        let n = <I16F16 as TestNumber>::from_f32;
+        let mut notch = NotchFilter::new(n(100.0), n(2.0));
+        let dt = n(0.001);
+        let mut peak: f32 = 0.0;
+        for step in 0..2000 {
+            let time = step as f32 * 0.001;
+            let input = 1.0 + (2.0 * PI * 100.0 * time).sin();
+            let output: f32 = notch.apply(n(input), dt).to_num();
+            if 1000 <= step {
+                peak = peak.max((output - 1.0).abs());
+            }
+        }
+        assert!(peak < 0.05, "Amplitude {} should be removed.", peak);
+    }
+
+    /// Test that a disabled or invalid filter passes samples through.
+    #[test]
+    fn test_pid_notch_pass_through() {
+        let mut disabled = NotchFilter::<f32>::default();
+        assert!(!disabled.is_active(0.001));
+        assert_eq!(0.7, disabled.apply(0.7, 0.001));
+
+        // The center is above the Nyquist frequency of 50 Hz.
+        let mut aliased = NotchFilter::new(100.0, 2.0);
+        assert!(!aliased.is_active(0.01));
+        assert_eq!(0.7, aliased.apply(0.7, 0.01));
+
+        let mut notch = NotchFilter::new(100.0, 2.0);
+        assert_eq!(0.7, notch.apply(0.7, 0.0));
+        assert_eq!(0.7, notch.apply(0.7, -0.001));
+    }
+
+    /// Test that reset clears the history and a non-finite sample restarts the filter.
+    #[test]
+    fn test_pid_notch_reset() {
+        let mut notch = NotchFilter::new(100.0, 2.0);
+        let mut fresh = notch;
+        for step in 0..10 {
+            notch.apply(step as f32, 0.001);
+        }
+        notch.reset();
+        assert_eq!(fresh.apply(0.5, 0.001), notch.apply(0.5, 0.001));
+
+        assert!(notch.apply(f32::NAN, 0.001).is_nan());
+        assert!(value_close(0.5, notch.apply(0.5, 0.001)));
+    }
+}
@@ -6,34 +6,114 @@
 //! to perform rate-based PID (Proportional-Integral-Derivative) control
 //! calculations.
 
+use crate::pid::limit_derivative;
 use crate::Number;
 use piddiy::PidController;
 
 /// Control data for rate-based PID stabilization callback.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct RateControlData<T> {
     /// The current rate of change, typically reported by a gyro.
     pub rate: T,
     /// The time delta since the last computation.
+    /// A non-positive value skips integral accumulation and holds the previous
+    /// derivative for the tick, rather than producing runaway values or `NaN`.
     pub dt: T,
-    /// The maximum allowed value for the integral term, used to prevent integral windup.
+    /// Flag indicating the measurement is a fresh sample. Clear it when the attitude
+    /// estimate updates slower than the control loop and the previous measurement is
+    /// repeated. On a stale tick the derivative is held, rather than differentiating
+    /// a repeated sample, which reads as zero motion followed by a spike on the next
+    /// fresh sample. The integral still accumulates the error against the last
+    /// measurement with the full `dt`. Stabilizers always pass fresh samples.
+    pub measurement_updated: bool,
+    /// The maximum allowed value for the raw accumulated integral, before `ki` is applied.
+    /// Used to prevent integral windup.
     pub integral_limit: T,
     /// Flag to reset the integral term, typically used when the controller is inactive.
     pub reset_integral: bool,
+    /// Fraction of the error accumulated into the integral this tick.
+    /// Stabilizers lower it below the integral throttle threshold so the integrator
+    /// fades out smoothly instead of resetting. A value of one accumulates fully.
+    /// With `integral_divisor`, it is zero between integral updates, which skips the
+    /// accumulation, and the summed time steps over `dt` on each update.
+    pub integral_scale: T,
+    /// Maximum rate of change of the raw accumulated integral, in integral units per second.
+    /// Each tick adds at most `max_integral_rate * dt` to the integral, so it ramps
+    /// smoothly under a large error. A value of zero disables the limit.
+    pub max_integral_rate: T,
+    /// Smoothing coefficient of the first-order low-pass filter applied to the derivative.
+    /// The derivative of the rate is effectively an angular acceleration, so it is noisy.
+    /// The filtered derivative is `alpha * derivative + (1 - alpha) * prev_derivative`.
+    /// A value of one disables filtering.
+    pub derivative_lpf_alpha: T,
+    /// Maximum magnitude of the derivative term, `kd * derivative`, after filtering.
+    /// Caps the derivative contribution of a sudden rate spike without limiting
+    /// the proportional and integral terms. A value of zero or infinity disables the limit.
+    pub d_term_limit: T,
+}
+
+impl<T: Number> Default for RateControlData<T> {
+    fn default() -> Self {
+        Self {
+            rate: T::zero(),
+            dt: T::zero(),
+            measurement_updated: true,
+            integral_limit: T::zero(),
+            reset_integral: false,
+            integral_scale: T::one(),
+            max_integral_rate: T::zero(),
+            derivative_lpf_alpha: T::one(),
+            d_term_limit: T::zero(),
+        }
+    }
 }
 
 /// Rate-based PID stabilization compute callback.
+///
+/// With a zero derivative gain, `kd`, the derivative is not evaluated,
+/// so a PI controller skips the derivative and its filter entirely.
+/// The previous derivative is held for when the gain becomes nonzero.
 pub fn compute_rate<T: Number>(
     pid: &mut PidController<T, RateControlData<T>>,
     data: RateControlData<T>,
 ) -> (T, T, T) {
     let error = pid.set_point - data.rate;
-    let integral = if !data.reset_integral {
-        (pid.integral + error * data.dt).clamp(-data.integral_limit, data.integral_limit)
-    } else {
+    let valid_dt = T::zero() < data.dt;
+    let integral = if data.reset_integral {
         T::zero()
+    } else if !valid_dt {
+        pid.integral
+    } else if data.integral_scale == T::zero() {
+        // Skip the accumulation between integral updates and on frozen axes.
+        pid.integral
+            .clamp(-data.integral_limit, data.integral_limit)
+    } else {
+        let step = error * data.dt * data.integral_scale;
+        let step = if T::zero() < data.max_integral_rate {
+            // Limit how fast the integral can change.
+            let max_step = data.max_integral_rate * data.dt;
+            step.clamp(-max_step, max_step)
+        } else {
+            step
+        };
+        (pid.integral + step).clamp(-data.integral_limit, data.integral_limit)
     };
-    let derivative = (error - pid.error) / data.dt;
+    let derivative = if pid.kd == T::zero() {
+        // Skip the unused derivative, leaving the filter state untouched.
+        pid.derivative
+    } else if valid_dt && data.measurement_updated {
+        let raw_derivative = (error - pid.error) / data.dt;
+        let alpha = data.derivative_lpf_alpha;
+        let derivative = alpha * raw_derivative + (T::one() - alpha) * pid.derivative;
+        limit_derivative(derivative, pid.kd, data.d_term_limit)
+    } else {
+        // Hold the previous derivative rather than dividing by a non-positive dt
+        // or differentiating a stale measurement.
+        pid.derivative
+    };
+    // Hold the previous state rather than storing a non-finite intermediate.
+    let integral = integral.finite_or(pid.integral);
+    let derivative = derivative.finite_or(pid.derivative);
 
     (error, integral, derivative)
 }
@@ -55,8 +135,13 @@ mod tests {
         let data = RateControlData {
             rate: 0.0,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 100.0, // Integral should not exceed this value.
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
         };
 
         // This would normally push integral way over 100 if not clamped
@@ -83,8 +168,13 @@ mod tests {
         let data = RateControlData {
             rate: 0.0,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 100.0,
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
         };
 
         // First compute without reset to build up the integral.
@@ -94,6 +184,8 @@ mod tests {
         // Now compute with reset.
         let data_reset = RateControlData {
             reset_integral: true,
+            integral_scale: 1.0,
+            derivative_lpf_alpha: 1.0,
             ..data
         };
         let (_, integral_reset, _) = compute_rate(&mut pid, data_reset);
@@ -121,8 +213,13 @@ mod tests {
         let data = RateControlData {
             rate: 0.0,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 100.0,
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
         };
 
         let (mut error, mut integral, mut derivative) = compute_rate(&mut pid, data);
@@ -166,8 +263,13 @@ mod tests {
         let data = RateControlData {
             rate: 7.0,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 100.0,
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
         };
 
         let (error, integral, derivative) = compute_rate(&mut pid, data);
@@ -205,8 +307,13 @@ mod tests {
         let data = RateControlData {
             rate: 0.0,
             dt: 1.0,
+            measurement_updated: true,
             integral_limit: 10.0,
             reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
         };
         let (error, integral, derivative) = compute_rate(&mut pid, data);
         let output = pid.compute(data);
@@ -216,4 +323,273 @@ mod tests {
         assert!(value_close(0.0, derivative), "Derivative should be zero.");
         assert!(value_close(0.0, output), "Output should be zero.");
     }
+
+    /// Test that a non-positive dt holds the integral and derivative without producing `NaN`.
+    #[test]
+    fn test_pid_rate_non_positive_dt() {
+        for dt in [0.0, -0.01] {
+            let mut pid = PidController::new();
+            pid.compute_fn(compute_rate)
+                .set_point(10.0)
+                .kp(1.0)
+                .ki(1.0)
+                .kd(1.0);
+            pid.integral = 2.0;
+            pid.derivative = 3.0;
+            let data = RateControlData {
+                rate: 0.0,
+                dt,
+                measurement_updated: true,
+                integral_limit: 100.0,
+                reset_integral: false,
+                integral_scale: 1.0,
+                max_integral_rate: 0.0,
+                derivative_lpf_alpha: 1.0,
+                d_term_limit: 0.0,
+            };
+            let (error, integral, derivative) = compute_rate(&mut pid, data);
+            let output = pid.compute(data);
+
+            assert!(value_close(10.0, error), "Error should be 10.");
+            assert!(value_close(2.0, integral), "Integral should be unchanged.");
+            assert!(
+                value_close(3.0, derivative),
+                "Derivative should be unchanged."
+            );
+            assert!(value_close(15.0, output), "Output should be 15.");
+        }
+    }
+
+    /// Test that the derivative low-pass filter attenuates the noise of a rate derivative.
+    #[test]
+    fn test_pid_rate_derivative_lpf_noise() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_rate)
+            .set_point(0.0)
+            .kp(1.0)
+            .ki(0.0)
+            .kd(1.0);
+        let data = RateControlData {
+            rate: 0.0,
+            dt: 0.01,
+            measurement_updated: true,
+            integral_limit: 100.0,
+            reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_lpf_alpha: 0.2,
+            d_term_limit: 0.0,
+        };
+
+        // Noisy rate ramp with a derivative of 2, so the error derivative is -2.
+        const SAMPLES: usize = 100;
+        let noise = [1.5, -0.5, 2.0, -1.75, 0.25, -1.0, 1.0, -1.5];
+        let mut raw_variance = 0.0;
+        let mut filtered_variance = 0.0;
+        for i in 0..SAMPLES {
+            let rate = 2.0 * i as f32 * data.dt + 0.01 * noise[i % noise.len()];
+            let raw_derivative = (-rate - pid.error) / data.dt;
+            let _ = pid.compute(RateControlData { rate, ..data });
+            // Skip the filter warm up.
+            if 20 <= i {
+                raw_variance += (raw_derivative + 2.0) * (raw_derivative + 2.0);
+                filtered_variance += (pid.derivative + 2.0) * (pid.derivative + 2.0);
+            }
+        }
+
+        assert!(
+            filtered_variance < 0.05 * raw_variance,
+            "Filtered derivative should have lower variance."
+        );
+    }
+
+    /// Test that a derivative filter coefficient of one reproduces the unfiltered derivative.
+    #[test]
+    fn test_pid_rate_derivative_lpf_unity() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_rate)
+            .set_point(10.0)
+            .kp(0.2)
+            .ki(0.3)
+            .kd(-0.05);
+        let data = RateControlData {
+            rate: 0.0,
+            dt: 0.01,
+            measurement_updated: true,
+            integral_limit: 25.0,
+            reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
+        };
+
+        for i in 0..10 {
+            let rate = i as f32 * i as f32 - 4.5;
+            let expected = (10.0 - rate - pid.error) / data.dt;
+            let (_, _, derivative) = compute_rate(&mut pid, RateControlData { rate, ..data });
+            let _ = pid.compute(RateControlData { rate, ..data });
+            assert!(
+                value_close(expected, derivative),
+                "Unity coefficient should not filter the derivative."
+            );
+        }
+    }
+
+    /// Test that a zero derivative gain does not advance the derivative filter state.
+    #[test]
+    fn test_pid_rate_zero_kd_skips_derivative() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_rate)
+            .set_point(10.0)
+            .kp(1.0)
+            .ki(0.0)
+            .kd(0.0);
+        pid.derivative = 3.0;
+        let data = RateControlData {
+            rate: 0.0,
+            dt: 0.01,
+            measurement_updated: true,
+            integral_limit: 100.0,
+            reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_lpf_alpha: 0.5,
+            d_term_limit: 0.0,
+        };
+
+        for i in 0..10 {
+            let output = pid.compute(RateControlData {
+                rate: i as f32,
+                ..data
+            });
+            assert!(
+                value_close(10.0 - i as f32, output),
+                "Output should only have a proportional term."
+            );
+        }
+        assert!(
+            value_close(3.0, pid.derivative),
+            "Derivative filter state should be unchanged."
+        );
+    }
+
+    /// Test that the integral scale reduces accumulation without resetting.
+    #[test]
+    fn test_pid_rate_integral_scale() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_rate)
+            .set_point(10.0)
+            .kp(1.0)
+            .ki(1.0)
+            .kd(0.0);
+        pid.integral = 2.0;
+        let data = RateControlData {
+            rate: 0.0,
+            dt: 0.1,
+            measurement_updated: true,
+            integral_limit: 100.0,
+            reset_integral: false,
+            integral_scale: 0.5,
+            max_integral_rate: 0.0,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
+        };
+
+        let (_, integral, _) = compute_rate(&mut pid, data);
+        assert!(value_close(2.5, integral), "Integral should grow by half.");
+
+        let data = RateControlData {
+            integral_scale: 0.0,
+            ..data
+        };
+        let (_, integral, _) = compute_rate(&mut pid, data);
+        assert!(value_close(2.0, integral), "Integral should be held.");
+
+        // A skipped accumulation still respects the integral limit
+        pid.integral = 200.0;
+        let (_, integral, _) = compute_rate(&mut pid, data);
+        assert!(value_close(100.0, integral), "Integral should be clamped.");
+    }
+
+    /// Test that the integral ramps no faster than the maximum integral rate.
+    #[test]
+    fn test_pid_rate_max_integral_rate() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_rate)
+            .set_point(100.0)
+            .kp(1.0)
+            .ki(1.0)
+            .kd(0.0);
+        let data = RateControlData {
+            rate: 0.0,
+            dt: 0.1,
+            measurement_updated: true,
+            integral_limit: 100.0,
+            reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 5.0,
+            derivative_lpf_alpha: 1.0,
+            d_term_limit: 0.0,
+        };
+
+        // A large constant error would add 10 per tick without the limit
+        for step in 1..=10 {
+            let (_, integral, _) = compute_rate(&mut pid, data);
+            let _ = pid.compute(data);
+            assert!(
+                value_close(0.5 * step as f32, integral),
+                "Integral should grow by 0.5 per tick."
+            );
+        }
+
+        // Errors below the limit accumulate normally in either direction
+        pid.set_point(-2.0);
+        let (_, integral, _) = compute_rate(&mut pid, data);
+        assert!(value_close(4.8, integral), "Integral should shrink by 0.2.");
+    }
+
+    /// Test that the derivative term is clipped to the limit after filtering.
+    #[test]
+    fn test_pid_rate_d_term_limit() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_rate)
+            .set_point(0.0)
+            .kp(1.0)
+            .ki(0.0)
+            .kd(-0.5);
+        let data = RateControlData {
+            rate: -100.0,
+            dt: 0.1,
+            measurement_updated: true,
+            integral_limit: 100.0,
+            reset_integral: false,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            derivative_lpf_alpha: 0.5,
+            d_term_limit: 20.0,
+        };
+
+        // The filtered derivative is 0.5 * 1000 = 500, so the term would be -250
+        let (error, _, derivative) = compute_rate(&mut pid, data);
+        assert!(value_close(100.0, error), "Error should pass.");
+        assert!(
+            value_close(40.0, derivative),
+            "Derivative should be clipped."
+        );
+        let output = pid.compute(data);
+        assert!(
+            value_close(100.0 - 20.0, output),
+            "Output should carry the clipped term."
+        );
+
+        // A disabled limit passes the filtered derivative
+        pid.reset();
+        let data = RateControlData {
+            d_term_limit: 0.0,
+            ..data
+        };
+        let (_, _, derivative) = compute_rate(&mut pid, data);
+        assert!(value_close(500.0, derivative), "Derivative should pass.");
+    }
 }
@@ -0,0 +1,326 @@
+// src/pid/single_axis.rs
+
+//! # Single-Axis PID Module
+//!
+//! This module provides a self-contained single-axis PID controller for uses
+//! outside of flight stabilization, like a motor speed or temperature loop.
+//! It runs the angle-based compute function, so it shares the integral limit,
+//! conditional anti-windup, and `dt` handling of the stabilizers, without
+//! requiring the caller to build a `PidController` or control data.
+
+use crate::pid::{compute_angle, compute_terms, AngleControlData, PidTerms};
+use crate::{Number, SaturationState};
+use piddiy::PidController;
+
+/// Struct representing a single-axis PID controller.
+///
+/// The output is `kp * error + ki * integral + kd * rate`, where the error is
+/// `set_point - measurement` and the integral accumulates `error * dt`, clamped to
+/// `±integral_limit`. As with `compute_angle`, the derivative is the measured rate,
+/// so damping needs a negative `kd`.
+///
+/// With output limits, the output is clamped, and while it is saturated the integral
+/// does not accumulate error that would push it further into saturation.
+pub struct SingleAxisPid<T: Number> {
+    pid: PidController<T, AngleControlData<T>>,
+    integral_limit: T,
+    output_min: Option<T>,
+    output_max: Option<T>,
+    terms: PidTerms<T>,
+    saturation: SaturationState,
+}
+
+impl<T: Number> SingleAxisPid<T> {
+    /// Creates a new controller with the given gains, no integral limit,
+    /// and no output limits.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::pid::SingleAxisPid;
+    ///
+    /// let mut pid = SingleAxisPid::new(2.0, 0.5, -0.25).with_output_limits(Some(-10.0), Some(10.0));
+    /// // Error of 4 for 0.5 seconds, with a rate of 2
+    /// let output = pid.step(4.0, 0.0, 2.0, 0.5);
+    /// assert_eq!(2.0 * 4.0 + 0.5 * 2.0 - 0.25 * 2.0, output);
+    /// ```
+    pub fn new(kp: T, ki: T, kd: T) -> Self {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle).kp(kp).ki(ki).kd(kd);
+        Self {
+            pid,
+            integral_limit: T::max_value(),
+            output_min: None,
+            output_max: None,
+            terms: PidTerms::new(),
+            saturation: SaturationState::None,
+        }
+    }
+
+    /// Sets the maximum magnitude of the raw accumulated integral, before `ki` is applied.
+    pub fn with_integral_limit(mut self, integral_limit: T) -> Self {
+        self.integral_limit = integral_limit;
+        self
+    }
+
+    /// Sets the optional lower and upper bounds of the output.
+    pub fn with_output_limits(mut self, output_min: Option<T>, output_max: Option<T>) -> Self {
+        self.output_min = output_min;
+        self.output_max = output_max;
+        self
+    }
+
+    /// Returns the (kp, ki, kd) gains.
+    pub fn gains(&self) -> (T, T, T) {
+        (self.pid.kp, self.pid.ki, self.pid.kd)
+    }
+
+    /// Updates the gains, keeping the integral and derivative state.
+    pub fn set_gains(&mut self, kp: T, ki: T, kd: T) {
+        self.pid.kp(kp).ki(ki).kd(kd);
+    }
+
+    /// Returns the raw accumulated integral, before `ki` is applied.
+    pub fn integral(&self) -> T {
+        self.pid.integral
+    }
+
+    /// Sets the raw accumulated integral, clamped to the integral limit,
+    /// like seeding a hover trim.
+    pub fn set_integral(&mut self, integral: T) {
+        self.pid.integral = integral.clamp(-self.integral_limit, self.integral_limit);
+    }
+
+    /// Returns the gain-multiplied terms of the most recent step. Their sum
+    /// is the output before clamping.
+    pub fn last_terms(&self) -> PidTerms<T> {
+        self.terms
+    }
+
+    /// Returns which output limit clamped the most recent step, if any.
+    pub fn saturation(&self) -> SaturationState {
+        self.saturation
+    }
+
+    /// Computes the output for a setpoint, measurement, measured rate, and time step.
+    /// A non-positive `dt` holds the integral and derivative for the step.
+    pub fn step(&mut self, set_point: T, measurement: T, rate: T, dt: T) -> T {
+        self.pid.set_point(set_point);
+        let data = AngleControlData {
+            measurement,
+            rate: Some(rate),
+            dt,
+            integral_limit: self.integral_limit,
+            saturation: self.saturation,
+            ..Default::default()
+        };
+        self.terms = compute_terms(&mut self.pid, compute_angle, data, T::zero());
+        let output = self.terms.sum();
+        let clamped = match (self.output_min, self.output_max) {
+            (Some(min), _) if output < min => min,
+            (_, Some(max)) if max < output => max,
+            _ => output,
+        };
+        self.saturation = if clamped < output {
+            SaturationState::Upper
+        } else if output < clamped {
+            SaturationState::Lower
+        } else {
+            SaturationState::None
+        };
+        clamped
+    }
+
+    /// Resets the integral, the previous error and derivative, and the saturation state.
+    pub fn reset(&mut self) {
+        self.pid.reset();
+        self.terms = PidTerms::new();
+        self.saturation = SaturationState::None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    /// Test that the integral term is clamped to the specified limit.
+    #[test]
+    fn test_single_axis_pid_integral_clamping() {
+        let mut pid = SingleAxisPid::new(1.0, 5.0, 0.1).with_integral_limit(100.0);
+
+        // This would normally push integral way over 100 if not clamped
+        for _ in 0..10 {
+            let _ = pid.step(50.0, 0.0, 0.0, 1.0);
+        }
+        assert!(
+            value_close(100.0, pid.integral()),
+            "Integral should be clamped to 100."
+        );
+    }
+
+    /// Test that a reset clears the accumulated integral.
+    #[test]
+    fn test_single_axis_pid_integral_reset() {
+        let mut pid = SingleAxisPid::new(1.0, 1.0, 0.1).with_integral_limit(100.0);
+        let _ = pid.step(10.0, 0.0, 0.0, 1.0);
+        assert!(
+            value_close(10.0, pid.integral()),
+            "Integral before reset should accumulate."
+        );
+
+        pid.reset();
+        assert!(
+            value_close(0.0, pid.integral()),
+            "Integral after reset should be zero."
+        );
+        assert_eq!(PidTerms::new(), pid.last_terms());
+    }
+
+    /// Test the response with a non-zero set point and zero measurement.
+    #[test]
+    fn test_single_axis_pid_response() {
+        let mut pid = SingleAxisPid::new(1.0, 1.0, 0.1).with_integral_limit(100.0);
+        let output = pid.step(10.0, 0.0, 0.0, 1.0);
+        let terms = pid.last_terms();
+        assert!(value_close(10.0, terms.proportional), "P should be 10.");
+        assert!(value_close(10.0, terms.integral), "I should be 10.");
+        assert!(value_close(0.0, terms.derivative), "D should be zero.");
+        assert!(
+            value_close(20.0, output),
+            "Output should be the sum of terms."
+        );
+
+        // Call again to test accumulation
+        let _ = pid.step(10.0, 0.0, 0.0, 1.0);
+        assert!(
+            value_close(20.0, pid.integral()),
+            "Integral should accumulate to 20."
+        );
+    }
+
+    /// Test a specific response with non-zero values.
+    #[test]
+    fn test_single_axis_pid_specific_output() {
+        let mut pid = SingleAxisPid::new(1.0, 1.0, 1.0).with_integral_limit(100.0);
+        let output = pid.step(10.0, 5.0, 7.0, 1.0);
+        assert!(value_close(5.0, pid.integral()), "Integral should be 5.");
+        assert!(
+            value_close(7.0, pid.last_terms().derivative),
+            "Derivative should be 7."
+        );
+        assert!(
+            value_close(17.0, output),
+            "Output should be the sum of terms."
+        );
+
+        let _ = pid.step(10.0, 5.0, 7.0, 1.0);
+        assert!(value_close(10.0, pid.integral()), "Integral should be 10.");
+    }
+
+    /// Test that the output is zero for zero error with zero initial conditions.
+    #[test]
+    fn test_single_axis_pid_zero_conditions() {
+        let mut pid = SingleAxisPid::new(1.0, 0.0, 0.0);
+        let output = pid.step(0.0, 0.0, 0.0, 1.0);
+        assert!(value_close(0.0, pid.integral()), "Integral should be zero.");
+        assert!(value_close(0.0, output), "Output should be zero.");
+    }
+
+    /// Test that a non-positive dt holds the integral without producing `NaN`.
+    #[test]
+    fn test_single_axis_pid_non_positive_dt() {
+        for dt in [0.0, -0.01] {
+            let mut pid = SingleAxisPid::new(1.0, 1.0, 1.0).with_integral_limit(100.0);
+            pid.set_integral(2.0);
+            let output = pid.step(10.0, 0.0, 5.0, dt);
+            assert!(
+                value_close(2.0, pid.integral()),
+                "Integral should be unchanged."
+            );
+            assert!(value_close(12.0, output), "Output should be 12.");
+        }
+    }
+
+    /// Test that saturation blocks only the integration that deepens saturation.
+    #[test]
+    fn test_single_axis_pid_conditional_anti_windup_direction() {
+        let mut pid = SingleAxisPid::new(1.0, 1.0, 0.0).with_output_limits(Some(-10.0), Some(10.0));
+        pid.set_integral(5.0);
+
+        // The first step saturates, so the next positive error is held
+        let output = pid.step(10.0, 0.0, 0.0, 1.0);
+        assert_eq!(10.0, output, "Output should be clamped.");
+        assert_eq!(SaturationState::Upper, pid.saturation());
+        assert!(value_close(15.0, pid.integral()));
+        let _ = pid.step(10.0, 0.0, 0.0, 1.0);
+        assert!(
+            value_close(15.0, pid.integral()),
+            "Integral should be held."
+        );
+
+        // A negative error relieves upper saturation
+        let _ = pid.step(-10.0, 0.0, 0.0, 1.0);
+        assert!(value_close(5.0, pid.integral()), "Integral should unwind.");
+    }
+
+    /// Test that conditional anti-windup recovers from saturation faster than
+    /// an unlimited output, which lets the integral wind up.
+    #[test]
+    fn test_single_axis_pid_anti_windup_recovery() {
+        let recovery_steps = |mut pid: SingleAxisPid<f32>| {
+            for _ in 0..50 {
+                let _ = pid.step(20.0, 0.0, 0.0, 0.1);
+            }
+            let mut steps = 1;
+            while 10.0 <= pid.step(-5.0, 0.0, 0.0, 0.1) {
+                steps += 1;
+            }
+            steps
+        };
+        let limited = SingleAxisPid::new(1.0, 1.0, 0.0)
+            .with_integral_limit(100.0)
+            .with_output_limits(Some(-10.0), Some(10.0));
+        let unlimited = SingleAxisPid::new(1.0, 1.0, 0.0).with_integral_limit(100.0);
+        assert_eq!(1, recovery_steps(limited), "Should recover immediately.");
+        assert!(1 < recovery_steps(unlimited));
+    }
+
+    /// Core single-axis PID assertions, generic over the number type.
+    fn check_single_axis_pid_core<T: TestNumber>() {
+        let n = <T as TestNumber>::from_f32;
+
+        // Specific output and accumulation
+        let mut pid = SingleAxisPid::new(n(1.0), n(1.0), n(1.0)).with_integral_limit(n(100.0));
+        let output = pid.step(n(10.0), n(5.0), n(7.0), n(1.0));
+        assert!(number_close(17.0, output), "Output should be 17.");
+        let _ = pid.step(n(10.0), n(5.0), n(7.0), n(1.0));
+        assert!(number_close(10.0, pid.integral()), "Integral should be 10.");
+
+        // Closed loop convergence on a simple integrating plant
+        let mut pid = SingleAxisPid::new(n(2.0), n(0.5), n(0.0));
+        let dt = n(0.125);
+        let mut measurement = n(0.0);
+        for _ in 0..200 {
+            measurement += pid.step(n(10.0), measurement, n(0.0), dt) * dt;
+        }
+        assert!(
+            (10.0 - measurement.to_f32()).abs() < 0.05,
+            "Measurement should converge to the set point."
+        );
+    }
+
+    #[test]
+    fn test_single_axis_pid_core_f32() {
+        check_single_axis_pid_core::<f32>();
+    }
+
+    #[test]
+    fn test_single_axis_pid_core_f64() {
+        check_single_axis_pid_core::<f64>();
+    }
+
+    #[test]
+    fn test_single_axis_pid_core_fixed() {
+        check_single_axis_pid_core::<fixed::types::I16F16>();
+    }
+}
@@ -0,0 +1,200 @@
+// src/pid/terms.rs
+
+//! # PID Terms Module
+//!
+//! This module provides a data structure holding the individual
+//! gain-multiplied PID (Proportional-Integral-Derivative) terms,
+//! used for telemetry and tuning.
+
+use crate::Number;
+use piddiy::PidController;
+
+/// Gain-multiplied proportional, integral, and derivative contributions of a PID.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PidTerms<T> {
+    /// The proportional contribution, `kp * error`.
+    pub proportional: T,
+    /// The integral contribution, `ki * integral`.
+    pub integral: T,
+    /// The derivative contribution, `kd * derivative`.
+    pub derivative: T,
+    /// The feed-forward contribution, which bypasses the PID.
+    /// Includes both setpoint and disturbance feed-forward.
+    pub feed_forward: T,
+}
+
+impl<T: Number> Default for PidTerms<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Number> PidTerms<T> {
+    /// Creates a new set of terms with all contributions set to zero.
+    pub fn new() -> Self {
+        Self {
+            proportional: T::zero(),
+            integral: T::zero(),
+            derivative: T::zero(),
+            feed_forward: T::zero(),
+        }
+    }
+
+    /// Reads the terms of the most recent computation from a PID controller.
+    /// The feed-forward contribution is not known to the PID, so it is zero.
+    /// The products saturate, so a fixed-point term clamps at the extremes of the type.
+    pub fn from_pid<U>(pid: &PidController<T, U>) -> Self {
+        Self {
+            proportional: pid.kp.saturating_mul(pid.error),
+            integral: pid.ki.saturating_mul(pid.integral),
+            derivative: pid.kd.saturating_mul(pid.derivative),
+            feed_forward: T::zero(),
+        }
+    }
+
+    /// Sets the feed-forward contribution.
+    pub fn with_feed_forward(mut self, feed_forward: T) -> Self {
+        self.feed_forward = feed_forward;
+        self
+    }
+
+    /// Returns the sum of the terms, which is the unscaled output.
+    /// The sum saturates, so a fixed-point output clamps at the extremes of the type.
+    pub fn sum(&self) -> T {
+        self.proportional
+            .saturating_add(self.integral)
+            .saturating_add(self.derivative)
+            .saturating_add(self.feed_forward)
+    }
+}
+
+/// Compute function of a PID, returning the error, integral, and derivative.
+pub(crate) type ComputeFn<T, U> = fn(&mut PidController<T, U>, U) -> (T, T, T);
+
+/// Runs the compute function of a PID and returns its terms, with the proportional
+/// term clamped to `±p_term_limit`. The error, integral, and derivative are stored on
+/// the PID as by `PidController::compute`, but the terms are formed with saturating
+/// products, so a fixed-point gain times a large error clamps instead of wrapping.
+/// The stored error is left unclipped, so the integral, the derivative, and the next
+/// computation are unaffected by the limit.
+/// The proportional term is not limited if the limit is not positive or is infinite.
+pub(crate) fn compute_terms<T: Number, U>(
+    pid: &mut PidController<T, U>,
+    compute: ComputeFn<T, U>,
+    data: U,
+    p_term_limit: T,
+) -> PidTerms<T> {
+    let (error, integral, derivative) = compute(pid, data);
+    pid.error = error;
+    pid.integral = integral;
+    pid.derivative = derivative;
+    let mut terms = PidTerms::from_pid(pid);
+    if T::zero() < p_term_limit {
+        terms.proportional = terms.proportional.clamp(-p_term_limit, p_term_limit);
+    }
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pid::{compute_angle, AngleControlData};
+    use crate::test_utils::*;
+
+    /// Test that the terms read from a PID sum to its output.
+    #[test]
+    fn test_pid_terms_from_pid() {
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(10.0)
+            .kp(2.0)
+            .ki(3.0)
+            .kd(4.0);
+        let data = AngleControlData {
+            measurement: 5.0,
+            rate: Some(7.0),
+            dt: 1.0,
+            integral_limit: 100.0,
+            ..Default::default()
+        };
+        let output = pid.compute(data);
+        let terms = PidTerms::from_pid(&pid);
+
+        assert!(value_close(10.0, terms.proportional), "P should be 10.");
+        assert!(value_close(15.0, terms.integral), "I should be 15.");
+        assert!(value_close(28.0, terms.derivative), "D should be 28.");
+        assert!(
+            value_close(output, terms.sum()),
+            "Terms should sum to output."
+        );
+    }
+
+    /// Test that the proportional term limit clips P without touching the stored error.
+    #[test]
+    fn test_pid_terms_compute_terms() {
+        let new_pid = || {
+            let mut pid = PidController::new();
+            pid.compute_fn(compute_angle)
+                .set_point(10.0)
+                .kp(2.0)
+                .ki(3.0)
+                .kd(4.0);
+            pid
+        };
+        let data = AngleControlData {
+            measurement: 5.0,
+            rate: Some(7.0),
+            dt: 1.0,
+            integral_limit: 100.0,
+            ..Default::default()
+        };
+        let mut pid = new_pid();
+        let mut unlimited_pid = new_pid();
+        let unlimited = compute_terms(&mut unlimited_pid, compute_angle, data, 0.0);
+        assert_eq!(PidTerms::from_pid(&unlimited_pid), unlimited);
+
+        let terms = compute_terms(&mut pid, compute_angle, data, 4.0);
+        assert!(value_close(4.0, terms.proportional), "P should be clipped.");
+        assert!(
+            value_close(5.0, pid.error),
+            "The error should not be clipped."
+        );
+        assert_eq!(unlimited.integral, terms.integral);
+        assert_eq!(unlimited.derivative, terms.derivative);
+        assert!(value_close(47.0, terms.sum()));
+    }
+
+    /// Test that fixed-point terms saturate at the extremes instead of wrapping.
+    #[test]
+    fn test_pid_terms_saturate_fixed() {
+        use fixed::types::I16F16;
+        let n = <I16F16 as TestNumber>::from_f32;
+        let mut pid = PidController::new();
+        pid.compute_fn(compute_angle)
+            .set_point(n(1000.0))
+            .kp(n(100.0))
+            .ki(n(-100.0))
+            .kd(n(0.0));
+        let data = AngleControlData {
+            measurement: n(0.0),
+            dt: n(1.0),
+            integral_limit: n(20000.0),
+            ..Default::default()
+        };
+        let terms = compute_terms(&mut pid, compute_angle, data, n(0.0));
+        assert_eq!(n(1000.0), pid.error);
+        assert_eq!(I16F16::MAX, terms.proportional, "P should saturate.");
+        assert_eq!(I16F16::MIN, terms.integral, "I should saturate.");
+        assert_eq!(-I16F16::DELTA, terms.sum());
+
+        // A large feed-forward pushes the sum to the extreme.
+        let terms = PidTerms {
+            integral: n(1.0),
+            feed_forward: n(1.0),
+            ..terms
+        };
+        assert_eq!(I16F16::MAX, terms.sum(), "The sum should saturate.");
+    }
+}
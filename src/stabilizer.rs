@@ -49,6 +49,18 @@ mod flight_stabilizer;
 #[doc(inline)]
 pub use flight_stabilizer::*;
 
+mod state;
+#[doc(inline)]
+pub use state::*;
+
+mod timing;
+#[doc(inline)]
+pub use timing::*;
+
+mod legacy;
+#[doc(inline)]
+pub use legacy::*;
+
 // flight stabilizer implementations
 mod angle;
 #[doc(inline)]
@@ -66,6 +78,72 @@ mod angle2_full;
 #[doc(inline)]
 pub use angle2_full::*;
 
+mod angle3;
+#[doc(inline)]
+pub use angle3::*;
+
 mod rate;
 #[doc(inline)]
 pub use rate::*;
+
+mod quat;
+#[doc(inline)]
+pub use quat::*;
+
+mod naxis;
+#[doc(inline)]
+pub use naxis::*;
+
+// flight stabilizer wrappers
+mod scheduled;
+#[doc(inline)]
+pub use scheduled::*;
+
+mod ramped;
+#[doc(inline)]
+pub use ramped::*;
+
+mod arming;
+#[doc(inline)]
+pub use arming::*;
+
+mod dropout;
+#[doc(inline)]
+pub use dropout::*;
+
+mod split;
+#[doc(inline)]
+pub use split::*;
+
+mod timestamped;
+#[doc(inline)]
+pub use timestamped::*;
+
+mod mixer;
+#[doc(inline)]
+pub use mixer::*;
+
+mod telemetry;
+#[doc(inline)]
+pub use telemetry::*;
+
+mod oscillation;
+#[doc(inline)]
+pub use oscillation::*;
+
+mod settling;
+#[doc(inline)]
+pub use settling::*;
+
+mod smith;
+#[doc(inline)]
+pub use smith::*;
+
+// offline tools
+mod replay;
+#[doc(inline)]
+pub use replay::*;
+
+mod autotune;
+#[doc(inline)]
+pub use autotune::*;
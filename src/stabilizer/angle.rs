@@ -5,8 +5,14 @@
 //! This is an angle-based PID flight stabilization controller.
 //! Note that yaw relies on rate-based stabilization.
 
+use super::state::PidState;
 use crate::pid::{compute_angle, compute_rate, AngleControlData, RateControlData};
-use crate::{FlightStabilizer, FlightStabilizerConfig, Number};
+use crate::pid::{compute_terms, NotchFilter, PidTerms};
+use crate::{
+    bumpless_integral, freeze_axes, integral_axes, select_axes, track_integrals, FixedRate,
+    FlightStabilizer, FlightStabilizerConfig, IntegralDivider, Number, SaturationState,
+    StabilizerError, StabilizerState, Telemetry, TimingStats,
+};
 use piddiy::PidController;
 
 /// Struct representing the Angle PID Flight Stabilization Controller.
@@ -14,8 +20,22 @@ pub struct AngleStabilizer<T: Number> {
     roll_pid: PidController<T, AngleControlData<T>>,
     pitch_pid: PidController<T, AngleControlData<T>>,
     yaw_pid: PidController<T, RateControlData<T>>,
-    i_limit: T,
-    scale: T,
+    config: FlightStabilizerConfig<T>,
+    set_point: (T, T, T),
+    stored_set_point: (T, T, T),
+    terms: [PidTerms<T>; 3],
+    telemetry: [Telemetry<T>; 3],
+    saturation: [SaturationState; 3],
+    prev_output: (T, T, T),
+    quantization_error: (T, T, T),
+    timing: TimingStats<T>,
+    integral_divider: IntegralDivider<T>,
+    notch: [NotchFilter<T>; 3],
+    enabled_axes: [bool; 3],
+    frozen_integrals: [bool; 3],
+    disturbance: (T, T, T),
+    filtered_attitude: Option<(T, T, T)>,
+    fixed_rate: Option<FixedRate<T>>,
 }
 
 impl<T: Number> Default for AngleStabilizer<T> {
@@ -55,8 +75,22 @@ impl<T: Number> AngleStabilizer<T> {
             roll_pid,
             pitch_pid,
             yaw_pid,
-            i_limit: config.i_limit,
-            scale: config.scale,
+            config,
+            set_point: config.set_points(),
+            stored_set_point: config.set_points(),
+            terms: [PidTerms::new(); 3],
+            telemetry: [Telemetry::new(); 3],
+            saturation: [SaturationState::None; 3],
+            prev_output: (T::zero(), T::zero(), T::zero()),
+            quantization_error: (T::zero(), T::zero(), T::zero()),
+            timing: TimingStats::new(),
+            integral_divider: IntegralDivider::new(),
+            notch: [NotchFilter::default(); 3],
+            enabled_axes: [true; 3],
+            frozen_integrals: [false; 3],
+            disturbance: (T::zero(), T::zero(), T::zero()),
+            filtered_attitude: None,
+            fixed_rate: None,
         }
     }
 
@@ -64,55 +98,551 @@ impl<T: Number> AngleStabilizer<T> {
     pub fn new() -> Self {
         Self::with_config(FlightStabilizerConfig::new())
     }
-}
 
-impl<T: Number> FlightStabilizer<T> for AngleStabilizer<T> {
-    fn control(
+    /// Returns the configuration.
+    pub fn config(&self) -> &FlightStabilizerConfig<T> {
+        &self.config
+    }
+
+    /// Returns the gain-multiplied PID terms of the most recent control step
+    /// for roll, pitch, and yaw. The terms are unscaled, so their sum times
+    /// the axis scale is the output before clamping.
+    pub fn last_terms(&self) -> [PidTerms<T>; 3] {
+        self.terms
+    }
+
+    /// Returns the raw output of the most recent control step for roll, pitch, and yaw,
+    /// the sum of the `last_terms` before the axis scale is applied. The raw output times
+    /// the scale is the output before clamping, rate limiting, and the deadband.
+    pub fn last_raw_output(&self) -> (T, T, T) {
+        let [roll, pitch, yaw] = self.terms;
+        (roll.sum(), pitch.sum(), yaw.sum())
+    }
+
+    /// Returns a copy of the internal state, like the integrals, previous errors
+    /// and filter histories, to `restore` later. The configuration and gains are not included.
+    pub fn snapshot(&self) -> StabilizerState<T> {
+        let mut state = StabilizerState::new();
+        state.pids[0] = [
+            PidState::from_pid(&self.roll_pid),
+            PidState::from_pid(&self.pitch_pid),
+            PidState::from_pid(&self.yaw_pid),
+        ];
+        state.set_point = self.set_point;
+        state.stored_set_point = self.stored_set_point;
+        state.terms = self.terms;
+        state.saturation = self.saturation;
+        state.prev_output = self.prev_output;
+        state.quantization_error = self.quantization_error;
+        state.notch = self.notch;
+        state.disturbance = self.disturbance;
+        state.filtered_attitude = self.filtered_attitude;
+        state.integral_dividers[0] = self.integral_divider;
+        state
+    }
+
+    /// Restores the internal state from a `snapshot` of this controller.
+    pub fn restore(&mut self, state: &StabilizerState<T>) {
+        state.pids[0][0].restore(&mut self.roll_pid);
+        state.pids[0][1].restore(&mut self.pitch_pid);
+        state.pids[0][2].restore(&mut self.yaw_pid);
+        self.set_point = state.set_point;
+        self.stored_set_point = state.stored_set_point;
+        self.terms = state.terms;
+        self.saturation = state.saturation;
+        self.prev_output = state.prev_output;
+        self.quantization_error = state.quantization_error;
+        self.notch = state.notch;
+        self.disturbance = state.disturbance;
+        self.filtered_attitude = state.filtered_attitude;
+        self.integral_divider = state.integral_dividers[0];
+    }
+
+    /// Returns the configuration with the gains currently applied by the PID controllers.
+    /// Output scales are not folded in; the gains in actuator units are the reported
+    /// gains times the configured `scales()`.
+    pub fn effective_gains(&self) -> FlightStabilizerConfig<T> {
+        let mut config = self.config;
+        config.kp_roll = self.roll_pid.kp;
+        config.ki_roll = self.roll_pid.ki;
+        config.kd_roll = self.roll_pid.kd;
+        config.kp_pitch = self.pitch_pid.kp;
+        config.ki_pitch = self.pitch_pid.ki;
+        config.kd_pitch = self.pitch_pid.kd;
+        config.kp_yaw = self.yaw_pid.kp;
+        config.ki_yaw = self.yaw_pid.ki;
+        config.kd_yaw = self.yaw_pid.kd;
+        config
+    }
+
+    /// Replaces the PID gains with the gains from the provided configuration without
+    /// a bump in the integral terms, for live tuning. Each stored integral is rescaled
+    /// so `ki * integral` is unchanged, then clamped to the integral limits for the
+    /// new gains. An axis with a new `ki` of zero keeps its integral.
+    /// Other settings and the remaining internal state are left untouched.
+    pub fn update_gains(&mut self, config: &FlightStabilizerConfig<T>) {
+        let integrals = (
+            bumpless_integral(self.roll_pid.integral, self.roll_pid.ki, config.ki_roll),
+            bumpless_integral(self.pitch_pid.integral, self.pitch_pid.ki, config.ki_pitch),
+            bumpless_integral(self.yaw_pid.integral, self.yaw_pid.ki, config.ki_yaw),
+        );
+        self.set_gains(config);
+        let (roll, pitch, yaw) = integrals;
+        self.set_integral(roll, pitch, yaw);
+    }
+
+    /// Replaces the configuration with a reloaded one, like a tuning stored in flash,
+    /// applying only what changed. Gains are applied like `update_gains`, so only an
+    /// axis with a changed `ki` rescales its integral, and the integrals are clamped
+    /// to the new limits. An axis with a changed initial setpoint stores the new
+    /// setpoint. Other settings, like limits and filters, apply from the next step.
+    /// The remaining internal state is left untouched.
+    pub fn apply_config(&mut self, config: &FlightStabilizerConfig<T>) {
+        let (roll, pitch, yaw) = self.config.set_points();
+        let unchanged = [
+            roll == config.set_point_roll,
+            pitch == config.set_point_pitch,
+            yaw == config.set_point_yaw,
+        ];
+        self.config = *config;
+        if let Some(fixed_rate) = self.fixed_rate {
+            self.fixed_rate = Some(config.fixed_rate(fixed_rate.dt));
+        }
+        self.update_gains(config);
+        let (roll, pitch, yaw) = self.stored_set_point;
+        let (set_point_roll, set_point_pitch, set_point_yaw) = config.set_points();
+        let [roll, pitch, yaw] = select_axes(
+            unchanged,
+            [roll, pitch, yaw],
+            [set_point_roll, set_point_pitch, set_point_yaw],
+        );
+        self.stored_set_point = (roll, pitch, yaw);
+    }
+
+    /// Replaces the PID gains with the gains from the provided configuration.
+    /// Other settings and the internal state are left untouched.
+    pub(crate) fn set_gains(&mut self, config: &FlightStabilizerConfig<T>) {
+        self.roll_pid
+            .kp(config.kp_roll)
+            .ki(config.ki_roll)
+            .kd(config.kd_roll);
+        self.pitch_pid
+            .kp(config.kp_pitch)
+            .ki(config.ki_pitch)
+            .kd(config.kd_pitch);
+        self.yaw_pid
+            .kp(config.kp_yaw)
+            .ki(config.ki_yaw)
+            .kd(config.kd_yaw);
+    }
+
+    /// Sets a constant loop period for `control_fixed_rate`, precomputing the
+    /// coefficients that depend on it. The coefficients are refreshed by `apply_config`.
+    /// Returns an error, leaving the loop period unchanged, if `dt` is not positive
+    /// and finite.
+    pub fn set_fixed_rate(&mut self, dt: T) -> Result<(), StabilizerError> {
+        if !dt.is_finite() {
+            return Err(StabilizerError::NotFinite("dt"));
+        }
+        if dt <= T::zero() {
+            return Err(StabilizerError::NonPositiveDt);
+        }
+        self.fixed_rate = Some(self.config.fixed_rate(dt));
+        Ok(())
+    }
+
+    /// Returns the precomputed coefficients of the loop period set by `set_fixed_rate`,
+    /// or `None` if no loop period is set.
+    pub fn fixed_rate(&self) -> Option<&FixedRate<T>> {
+        self.fixed_rate.as_ref()
+    }
+
+    /// Computes the control outputs like `control`, with the loop period and the
+    /// coefficients precomputed by `set_fixed_rate` instead of a `dt` argument.
+    /// The outputs match `control` with the same constant `dt`.
+    /// Returns `StabilizerError::NoLoopPeriod` if no loop period is set.
+    pub fn control_fixed_rate(
         &mut self,
         set_point: (T, T, T),
         imu_attitude: (T, T, T),
         gyro_rate: (T, T, T),
-        dt: T,
         low_throttle: bool,
+    ) -> Result<(T, T, T), StabilizerError> {
+        let fixed_rate = self.fixed_rate.ok_or(StabilizerError::NoLoopPeriod)?;
+        Ok(self.control_with(
+            set_point,
+            imu_attitude,
+            gyro_rate,
+            fixed_rate,
+            low_throttle,
+            T::one(),
+        ))
+    }
+
+    /// Computes the control outputs for `control` and `control_throttle`.
+    fn control_with(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        fixed_rate: FixedRate<T>,
+        low_throttle: bool,
+        throttle: T,
     ) -> (T, T, T) {
+        let dt = fixed_rate.dt;
+        self.timing.record(dt);
+
+        // Reset the integrals like a low throttle while idling
+        let low_throttle = low_throttle || self.config.is_idle(throttle);
+
+        // Hold the integrals of disabled and frozen axes
+        let held_integrals = [
+            self.roll_pid.integral,
+            self.pitch_pid.integral,
+            self.yaw_pid.integral,
+        ];
+
+        // Smooth the attitude measurements, restarting the filter at low throttle
+        let previous_attitude = if low_throttle {
+            None
+        } else {
+            self.filtered_attitude
+        };
+        let imu_attitude = self.config.filter_attitude(previous_attitude, imu_attitude);
+        self.filtered_attitude = Some(imu_attitude);
+
+        // Map the measurements and setpoints onto the reversed and coupled axes
+        let imu_attitude = self.config.transform_axes(imu_attitude);
+        let gyro_rate = self.config.transform_axes(gyro_rate);
+
+        // Update the integral every `integral_divisor` ticks over the summed time steps
+        let integral_divider = self.integral_divider.step(self.config.integral_divisor, dt);
+        let max_integral_rate = self.config.max_integral_rate * integral_divider;
+
+        // Scale integral accumulation by the throttle fraction, stopping it on frozen axes
+        let integral_scale = self.config.integral_throttle_scale(throttle) * integral_divider;
+        let integral_scales = freeze_axes(self.frozen_integrals, integral_scale);
+        let integral_leaks = freeze_axes(self.frozen_integrals, self.config.integral_leak);
+
+        // Keep the previous setpoints for the derivative setpoint weight
+        let (prev_set_point_roll, prev_set_point_pitch, _) =
+            self.config.transform_axes(self.set_point);
+
+        // Limit the rate of change of the setpoints
+        self.set_point = fixed_rate.slew_set_points(self.set_point, set_point);
+
         // Set the setpoints for roll, pitch, and yaw
-        let (set_point_roll, set_point_pitch, set_point_yaw) = set_point;
+        let (set_point_roll, set_point_pitch, set_point_yaw) =
+            self.config.transform_axes(self.set_point);
         self.roll_pid.set_point(set_point_roll);
         self.pitch_pid.set_point(set_point_pitch);
         self.yaw_pid.set_point(set_point_yaw);
 
         // Prepare control data for roll and pitch
+        let ki = (self.roll_pid.ki, self.pitch_pid.ki, self.yaw_pid.ki);
+        let (i_limit_roll, i_limit_pitch, i_limit_yaw) = self.config.integral_limits(ki);
         let (imu_roll, imu_pitch, _) = imu_attitude;
+        let (raw_roll, raw_pitch, raw_yaw) = gyro_rate;
+        // Remove motor vibration from the gyro rates feeding the derivative
+        let gyro_rate = self.config.notch_rates(&mut self.notch, gyro_rate, dt);
         let (gyro_roll, gyro_pitch, gyro_yaw) = gyro_rate;
         let roll_data = AngleControlData {
             measurement: imu_roll,
-            rate: gyro_roll,
+            rate: Some(gyro_roll),
+            derivative_source: self.config.derivative_source,
             dt,
-            integral_limit: self.i_limit,
+            measurement_updated: true,
+            integral_limit: i_limit_roll,
             reset_integral: low_throttle,
+            integral_scale: integral_scales[0],
+            max_integral_rate,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: self.config.derivative_alpha_roll,
+            d_term_limit: self.config.d_term_limit_roll,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: integral_leaks[0],
+            integration_method: self.config.integration_method,
+            form: self.config.pid_form,
+            b_weight: self.config.b_weight,
+            c_weight: self.config.c_weight,
+            prev_set_point: prev_set_point_roll,
         };
         let pitch_data = AngleControlData {
             measurement: imu_pitch,
-            rate: gyro_pitch,
+            rate: Some(gyro_pitch),
+            derivative_source: self.config.derivative_source,
             dt,
-            integral_limit: self.i_limit,
+            measurement_updated: true,
+            integral_limit: i_limit_pitch,
             reset_integral: low_throttle,
+            integral_scale: integral_scales[1],
+            max_integral_rate,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: self.config.derivative_alpha_pitch,
+            d_term_limit: self.config.d_term_limit_pitch,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: integral_leaks[1],
+            integration_method: self.config.integration_method,
+            form: self.config.pid_form,
+            b_weight: self.config.b_weight,
+            c_weight: self.config.c_weight,
+            prev_set_point: prev_set_point_pitch,
         };
 
         // Prepare control data for yaw
         let yaw_data = RateControlData {
             rate: gyro_yaw,
             dt,
-            integral_limit: self.i_limit,
+            measurement_updated: true,
+            integral_limit: i_limit_yaw,
             reset_integral: low_throttle,
+            integral_scale: integral_scales[2],
+            max_integral_rate,
+            derivative_lpf_alpha: self.config.derivative_alpha_yaw,
+            d_term_limit: self.config.d_term_limit_yaw,
         };
 
+        // Feed-forward the setpoints and measured disturbance, bypassing the PID
+        let (disturbance_roll, disturbance_pitch, disturbance_yaw) =
+            self.config.transform_axes(self.disturbance);
+        let roll_ff =
+            self.config.kff_roll * set_point_roll + self.config.kdist_roll * disturbance_roll;
+        let pitch_ff =
+            self.config.kff_pitch * set_point_pitch + self.config.kdist_pitch * disturbance_pitch;
+        let yaw_ff = self.config.kff_yaw * set_point_yaw + self.config.kdist_yaw * disturbance_yaw;
+
         // Compute outputs for roll, pitch, and yaw
-        let roll_output = self.scale * self.roll_pid.compute(roll_data);
-        let pitch_output = self.scale * self.pitch_pid.compute(pitch_data);
-        let yaw_output = self.scale * self.yaw_pid.compute(yaw_data);
+        let (scale_roll, scale_pitch, scale_yaw) = self.config.scales();
+        let roll_terms = compute_terms(
+            &mut self.roll_pid,
+            compute_angle,
+            roll_data,
+            self.config.p_term_limit_roll,
+        )
+        .with_feed_forward(roll_ff);
+        let pitch_terms = compute_terms(
+            &mut self.pitch_pid,
+            compute_angle,
+            pitch_data,
+            self.config.p_term_limit_pitch,
+        )
+        .with_feed_forward(pitch_ff);
+        let yaw_terms = compute_terms(
+            &mut self.yaw_pid,
+            compute_rate,
+            yaw_data,
+            self.config.p_term_limit_yaw,
+        )
+        .with_feed_forward(yaw_ff);
+        let roll_output = scale_roll * roll_terms.sum();
+        let pitch_output = scale_pitch * pitch_terms.sum();
+        let yaw_output = scale_yaw * yaw_terms.sum();
+
+        // Record the PID terms for telemetry
+        self.terms = [roll_terms, pitch_terms, yaw_terms];
+
+        // Clamp outputs to the actuator range
+        let roll_clamped = self.config.clamp_output(roll_output);
+        let pitch_clamped = self.config.clamp_output(pitch_output);
+        let yaw_clamped = self.config.clamp_output(yaw_output);
+        self.saturation = [
+            self.config.saturation(roll_output),
+            self.config.saturation(pitch_output),
+            self.config.saturation(yaw_output),
+        ];
+
+        // Back-calculation anti-windup feeds the saturation back into the integral
+        let tracking = fixed_rate.back_calculation(
+            (roll_clamped, pitch_clamped, yaw_clamped),
+            (roll_output, pitch_output, yaw_output),
+        );
+        [
+            self.roll_pid.integral,
+            self.pitch_pid.integral,
+            self.yaw_pid.integral,
+        ] = track_integrals(
+            [
+                self.roll_pid.integral,
+                self.pitch_pid.integral,
+                self.yaw_pid.integral,
+            ],
+            tracking,
+            (i_limit_roll, i_limit_pitch, i_limit_yaw),
+            low_throttle,
+        );
+
+        // Limit the rate of change of the outputs
+        self.prev_output =
+            fixed_rate.slew_outputs(self.prev_output, (roll_clamped, pitch_clamped, yaw_clamped));
+
+        // Disabled axes hold their integrals and output zero, and frozen axes
+        // hold their integrals unless reset at low throttle
+        let enabled = self.enabled_axes;
+        let integrals = integral_axes(enabled, self.frozen_integrals, low_throttle);
+        [
+            self.roll_pid.integral,
+            self.pitch_pid.integral,
+            self.yaw_pid.integral,
+        ] = select_axes(
+            integrals,
+            [
+                self.roll_pid.integral,
+                self.pitch_pid.integral,
+                self.yaw_pid.integral,
+            ],
+            held_integrals,
+        );
+        self.terms = select_axes(enabled, self.terms, [PidTerms::new(); 3]);
+        self.saturation = select_axes(enabled, self.saturation, [SaturationState::None; 3]);
+        let (roll, pitch, yaw) = self.prev_output;
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+        self.prev_output = (roll, pitch, yaw);
+        let (roll, pitch, yaw) = self.quantization_error;
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+
+        // Round the outputs to the actuator grid, carrying the rounding error forward
+        let (quantized, quantization_error) = self
+            .config
+            .quantize_outputs(self.prev_output, (roll, pitch, yaw));
+        self.quantization_error = quantization_error;
+        let (roll_clamped, pitch_clamped, yaw_clamped) = quantized;
+
+        // Suppress jitter near zero after the integral has seen the clamped output
+        let output = (
+            self.config.apply_deadband(roll_clamped),
+            self.config.apply_deadband(pitch_clamped),
+            self.config.apply_deadband(yaw_clamped),
+        );
+
+        // Hold the idle output below the idle throttle, handing off to the PID above it
+        let (roll, pitch, yaw) = self.config.idle_outputs(throttle, output);
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+        let output = (roll, pitch, yaw);
+
+        // Record the output loop for telemetry
+        let [roll_terms, pitch_terms, yaw_terms] = self.terms;
+        self.telemetry = [
+            Telemetry::from_pid(&self.roll_pid, roll_terms, raw_roll, gyro_roll, output.0),
+            Telemetry::from_pid(
+                &self.pitch_pid,
+                pitch_terms,
+                raw_pitch,
+                gyro_pitch,
+                output.1,
+            ),
+            Telemetry::from_pid(&self.yaw_pid, yaw_terms, raw_yaw, gyro_yaw, output.2),
+        ];
+        output
+    }
+}
+
+impl<T: Number> FlightStabilizer<T> for AngleStabilizer<T> {
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        let fixed_rate = self.config.fixed_rate(dt);
+        self.control_with(
+            set_point,
+            imu_attitude,
+            gyro_rate,
+            fixed_rate,
+            low_throttle,
+            T::one(),
+        )
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        let fixed_rate = self.config.fixed_rate(dt);
+        self.control_with(
+            set_point,
+            imu_attitude,
+            gyro_rate,
+            fixed_rate,
+            false,
+            throttle,
+        )
+    }
+
+    fn reset(&mut self) {
+        self.roll_pid.reset();
+        self.pitch_pid.reset();
+        self.yaw_pid.reset();
+        self.set_point = self.config.set_points();
+        self.stored_set_point = self.config.set_points();
+        self.terms = [PidTerms::new(); 3];
+        self.telemetry = [Telemetry::new(); 3];
+        self.saturation = [SaturationState::None; 3];
+        self.prev_output = (T::zero(), T::zero(), T::zero());
+        self.quantization_error = (T::zero(), T::zero(), T::zero());
+        self.timing = TimingStats::new();
+        self.integral_divider = IntegralDivider::new();
+        self.notch = [NotchFilter::default(); 3];
+        self.disturbance = (T::zero(), T::zero(), T::zero());
+        self.filtered_attitude = None;
+    }
 
-        (roll_output, pitch_output, yaw_output)
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stored_set_point = (roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.disturbance = (roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stored_set_point
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.saturation
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        let ki = (self.roll_pid.ki, self.pitch_pid.ki, self.yaw_pid.ki);
+        let (i_limit_roll, i_limit_pitch, i_limit_yaw) = self.config.integral_limits(ki);
+        self.roll_pid.integral = roll.clamp(-i_limit_roll, i_limit_roll);
+        self.pitch_pid.integral = pitch.clamp(-i_limit_pitch, i_limit_pitch);
+        self.yaw_pid.integral = yaw.clamp(-i_limit_yaw, i_limit_yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.enabled_axes = [roll, pitch, yaw];
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.enabled_axes
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.frozen_integrals = [roll, pitch, yaw];
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.frozen_integrals
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        self.telemetry
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.timing
     }
 }
 
@@ -120,6 +650,7 @@ impl<T: Number> FlightStabilizer<T> for AngleStabilizer<T> {
 mod tests {
     use super::*;
     use crate::test_utils::*;
+    use crate::PlantModel;
 
     /// Default test configuration.
     fn default_config() -> FlightStabilizerConfig<f32> {
@@ -164,6 +695,34 @@ mod tests {
         assert_eq!(stabilizer.yaw_pid.kp, config.kp_yaw);
     }
 
+    /// Test that new() behaves the same as with_config() on the default configuration.
+    #[test]
+    fn test_stabilizer_angle_new_matches_default_config() {
+        let mut stabilizer = AngleStabilizer::<f32>::new();
+        let mut reference = AngleStabilizer::with_config(Default::default());
+        assert_eq!(*stabilizer.config(), *reference.config());
+
+        let set_point = (0.2, -0.1, 0.05);
+        let gyro_rate = (0.3, -0.2, 0.1);
+        let dt = 0.01;
+        for step in 0..5 {
+            let t = step as f32 * 0.1;
+            let imu_attitude = (t, -t, 0.5 * t);
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+            let expected = reference.control(set_point, imu_attitude, gyro_rate, dt, false);
+            assert_eq!(output, expected, "Outputs should match at step {}.", step);
+        }
+    }
+
+    /// Test that config() returns the configuration the stabilizer was built with.
+    #[test]
+    fn test_stabilizer_angle_config_getter() {
+        let config = default_config();
+        let stabilizer = AngleStabilizer::with_config(config);
+
+        assert_eq!(*stabilizer.config(), config);
+    }
+
     /// Test that the integrator saturation works as expected by the DEFAULT_I_LIMIT.
     #[test]
     fn test_stabilizer_angle_integrator_saturation() {
@@ -194,92 +753,2133 @@ mod tests {
         );
     }
 
-    /// Test to ensure integrators are reset when PWM is below threshold.
+    /// Test that per-axis integral limits saturate each axis independently.
     #[test]
-    fn test_stabilizer_angle_low_throttle_integral_reset() {
-        let config = default_config();
+    fn test_stabilizer_angle_per_axis_integrator_saturation() {
+        let mut config = default_config();
+        config.i_limit_pitch = Some(10.0);
         let mut stabilizer = AngleStabilizer::with_config(config);
 
         // Simulated sensor inputs and desired setpoints
-        let set_point = (10.0, 0.0, 10.0); // desired roll, pitch, yaw
-        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
-        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let set_point = (100.0, -100.0, 50.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
         let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Apply consistent error over multiple cycles to force integrator saturation
+        for _ in 0..100 {
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
 
-        // Allow integrators to build up
-        let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
         let integrals = (
             stabilizer.roll_pid.integral,
             stabilizer.pitch_pid.integral,
             stabilizer.yaw_pid.integral,
         );
-        let unexpected_integrals = (0.0, 0.0, 0.0);
+        let expected_integrals = (config.i_limit, -10.0, config.i_limit);
         assert!(
-            vector_not_close(unexpected_integrals, integrals),
-            "Integrals should not be zero."
+            vector_close(expected_integrals, integrals),
+            "Pitch should saturate at its own limit."
         );
+    }
 
-        // Apply low throttle, which should reset integrators
-        let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, true);
+    /// Run a saturating error and return the integral terms, `ki` times the integral.
+    fn saturated_integral_terms(clamp_integral_term: bool) -> (f32, f32, f32) {
+        let mut config = default_config();
+        config.clamp_integral_term = clamp_integral_term;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (100.0, -100.0, 50.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Apply consistent error over multiple cycles to force integrator saturation
+        for _ in 0..300 {
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
+
+        let [roll, pitch, yaw] = stabilizer.last_terms();
+        (roll.integral, pitch.integral, yaw.integral)
+    }
+
+    /// Test that the integral limit clamps the raw integral or the integral term.
+    #[test]
+    fn test_stabilizer_angle_clamp_integral_term() {
+        let config = default_config();
+
+        // The raw integral saturates at the limit, so the term saturates at ki times the limit
+        let expected_terms = (
+            config.ki_roll * config.i_limit,
+            -config.ki_pitch * config.i_limit,
+            config.ki_yaw * config.i_limit,
+        );
+        assert!(
+            vector_close(expected_terms, saturated_integral_terms(false)),
+            "Raw integrals should be capped."
+        );
+
+        // The term saturates at the limit, and yaw has not reached it
+        let expected_terms = (config.i_limit, -config.i_limit, config.ki_yaw * 150.0);
+        assert!(
+            vector_close(expected_terms, saturated_integral_terms(true)),
+            "Integral terms should be capped."
+        );
+    }
+
+    /// Test that an integral limit as a fraction of the output range caps the scaled
+    /// integral term at that fraction of the range.
+    #[test]
+    fn test_stabilizer_angle_integral_limit_fraction() {
+        let mut config = default_config();
+        config.output_min = Some(-1.0);
+        config.output_max = Some(1.0);
+        config.i_limit_fraction = Some(0.1);
+        let mut stabilizer = AngleStabilizer::with_config(config);
+
+        // Apply consistent error over multiple cycles to force integrator saturation
+        let zero = (0.0, 0.0, 0.0);
+        for _ in 0..300 {
+            stabilizer.control((100.0, -100.0, 50.0), zero, zero, 0.01, false);
+        }
+
+        // Roll and pitch stop at 10% of the range, and yaw has not reached it
+        let [roll, pitch, yaw] = stabilizer.last_terms();
+        let scaled_terms = (
+            config.scale * roll.integral,
+            config.scale * pitch.integral,
+            config.scale * yaw.integral,
+        );
+        assert!(
+            vector_close((0.2, -0.2, 0.01 * 0.05 * 150.0), scaled_terms),
+            "{:?}",
+            scaled_terms
+        );
+        assert!(value_close(
+            0.2 / (0.3 * 0.01),
+            stabilizer.roll_pid.integral
+        ));
+    }
+
+    /// Test that the configured integral leak decays the roll and pitch integrals.
+    #[test]
+    fn test_stabilizer_angle_integral_leak() {
+        let mut config = default_config();
+        config.integral_leak = 1.0;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        stabilizer.roll_pid.integral = 10.0;
+        stabilizer.pitch_pid.integral = -10.0;
+
+        // No error, so only the leak changes the integrals
+        let zero = (0.0, 0.0, 0.0);
+        let _ = stabilizer.control(zero, zero, zero, 0.1, false);
         let integrals = (
             stabilizer.roll_pid.integral,
             stabilizer.pitch_pid.integral,
             stabilizer.yaw_pid.integral,
         );
-        let expected_integrals = (0.0, 0.0, 0.0);
         assert!(
-            vector_close(expected_integrals, integrals),
-            "Integrals should be zero."
+            vector_close((9.0, -9.0, 0.0), integrals),
+            "Integrals should decay by 1 - leak * dt."
         );
     }
 
-    /// Test the no error contidion.
+    /// Test that the effective gains report the configured gains.
     #[test]
-    fn test_stabilizer_angle_no_error() {
+    fn test_stabilizer_angle_effective_gains() {
         let config = default_config();
         let mut stabilizer = AngleStabilizer::with_config(config);
+        assert_eq!(config, stabilizer.effective_gains());
+
+        // Replaced gains are reported with the other settings untouched
+        let mut gains = config;
+        gains.kp_roll = 0.5;
+        gains.ki_yaw = 0.25;
+        gains.scale = 1.0;
+        stabilizer.set_gains(&gains);
+        let effective = stabilizer.effective_gains();
+        assert_eq!(0.5, effective.kp_roll);
+        assert_eq!(0.25, effective.ki_yaw);
+        assert_eq!(config.scale, effective.scale);
+    }
+
+    /// Test that changing `ki` with update_gains does not bump the output for a steady error,
+    /// while replacing the gains directly does.
+    #[test]
+    fn test_stabilizer_angle_update_gains_bumpless() {
+        let mut config = default_config();
+        config.kd_roll = 0.0;
+        config.kd_pitch = 0.0;
+        config.kd_yaw = 0.0;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut bumped_stabilizer = AngleStabilizer::with_config(config);
 
         // Simulated sensor inputs and desired setpoints
-        let set_point = (0.0, 0.0, 0.0); // desired roll, pitch, yaw
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
         let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
         let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
         let dt = 0.01; // time step
         let low_throttle = false;
 
-        // Perform the control computation
-        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
-        let expected_output = (0.0, 0.0, 0.0);
+        // Build up the integrals with a steady error
+        let mut output = (0.0, 0.0, 0.0);
+        for _ in 0..100 {
+            output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let _ = bumped_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
+        let [roll_terms, pitch_terms, yaw_terms] = stabilizer.last_terms();
+
+        // Double the integral gains
+        let mut gains = config;
+        gains.ki_roll *= 2.0;
+        gains.ki_pitch *= 2.0;
+        gains.ki_yaw *= 2.0;
+        stabilizer.update_gains(&gains);
+        bumped_stabilizer.set_gains(&gains);
+        assert_eq!(gains.ki_roll, stabilizer.effective_gains().ki_roll);
+        assert!(value_close(
+            roll_terms.integral,
+            gains.ki_roll * stabilizer.roll_pid.integral
+        ));
+        assert!(value_close(
+            pitch_terms.integral,
+            gains.ki_pitch * stabilizer.pitch_pid.integral
+        ));
+        assert!(value_close(
+            yaw_terms.integral,
+            gains.ki_yaw * stabilizer.yaw_pid.integral
+        ));
 
+        // The next output only moves by the integral of a single tick
+        let bumpless_output =
+            stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let bumped_output =
+            bumped_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let tick = config.scale * 2.0 * config.ki_roll * 10.0 * dt;
         assert!(
-            vector_close(expected_output, output),
-            "Outputs should be zero as there is no error."
+            (bumpless_output.0 - output.0).abs() <= tick + TEST_TOLERANCE,
+            "Output should not jump."
+        );
+        assert!(
+            (bumpless_output.1 - output.1).abs() <= tick + TEST_TOLERANCE,
+            "Output should not jump."
         );
+        assert!(
+            10.0 * tick < (bumped_output.0 - output.0).abs(),
+            "Replacing the gains directly should bump the output."
+        );
+
+        // A zero integral gain keeps the integral
+        let integral = stabilizer.roll_pid.integral;
+        gains.ki_roll = 0.0;
+        stabilizer.update_gains(&gains);
+        assert_eq!(integral, stabilizer.roll_pid.integral);
     }
 
-    /// Test with specific inputs to calculate expected PID outputs.
+    /// Test that applying a reloaded configuration only disturbs what changed.
     #[test]
-    fn test_stabilizer_angle_specific_pid_output() {
-        // Set up stabilizer
+    fn test_stabilizer_angle_apply_config() {
         let config = default_config();
         let mut stabilizer = AngleStabilizer::with_config(config);
-        stabilizer.roll_pid.integral = 0.2; // previous integral
-        stabilizer.pitch_pid.integral = -0.2; // previous integral
-        stabilizer.yaw_pid.integral = 0.2; // previous integral
+        stabilizer.set_setpoints(1.0, 2.0, 3.0);
 
-        // Simulated sensor inputs and desired setpoints
-        let set_point = (10.0, 0.0, 10.0); // desired roll, pitch, yaw
-        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
-        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
-        let dt = 0.01; // time step
-        let low_throttle = false;
+        // Build up the integrals with a steady error, then hold at zero error
+        let set_point = (10.0, -10.0, 0.0);
+        let zero = (0.0, 0.0, 0.0);
+        for _ in 0..100 {
+            let _ = stabilizer.control(set_point, zero, zero, 0.01, false);
+        }
+        let output = stabilizer.control(set_point, set_point, zero, 0.01, false);
+        let integral = stabilizer.roll_pid.integral;
 
-        // Perform the control computation
-        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
-        let expected_output = (0.01025, -0.01025, 0.034805);
+        // A change in kp alone leaves the integral and the output at zero error untouched
+        let mut reloaded = config;
+        reloaded.kp_roll *= 2.0;
+        stabilizer.apply_config(&reloaded);
+        assert_eq!(integral, stabilizer.roll_pid.integral);
+        assert_eq!(reloaded, *stabilizer.config());
+        assert_eq!(
+            output,
+            stabilizer.control(set_point, set_point, zero, 0.01, false)
+        );
+
+        // A change in ki rescales the integral, and new setpoints and limits apply directly
+        let roll_term = stabilizer.last_terms()[0].integral;
+        reloaded.ki_roll *= 2.0;
+        reloaded.set_point_pitch = 5.0;
+        reloaded.i_limit = 0.05;
+        stabilizer.apply_config(&reloaded);
+        assert!(value_close(
+            roll_term.min(reloaded.i_limit * reloaded.ki_roll),
+            reloaded.ki_roll * stabilizer.roll_pid.integral
+        ));
+        assert!(stabilizer.pitch_pid.integral.abs() <= reloaded.i_limit);
+        assert_eq!((1.0, 5.0, 3.0), stabilizer.setpoints());
+    }
+
+    /// Test that the closed loop settles on a simulated plant without sustained oscillation.
+    #[test]
+    fn test_stabilizer_angle_closed_loop_settles() {
+        let mut config = default_config();
+        config.kp_roll = 0.5;
+        config.ki_roll = 0.2;
+        config.kd_roll = -0.02;
+        config.kp_pitch = config.kp_roll;
+        config.ki_pitch = config.ki_roll;
+        config.kd_pitch = config.kd_roll;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut plant = Plant::new(1000.0, 0.05);
+        let dt = 0.01;
+
+        // Step roll and pitch to an angle and hold a zero yaw rate,
+        // settling within 2 percent of the step
+        let set_point = (10.0, -5.0, 0.0);
+        let mut roll = StepResponse::new(0.0, set_point.0, 0.2);
+        let mut pitch = StepResponse::new(0.0, set_point.1, 0.1);
+        for _ in 0..1000 {
+            let output = stabilizer.control(set_point, plant.attitude, plant.rate, dt, false);
+            plant.step(output, dt);
+            roll.record(plant.attitude.0);
+            pitch.record(plant.attitude.1);
+        }
+        for response in [roll, pitch] {
+            assert!(response.overshoot < 0.1, "{:?}", response);
+            assert!(
+                response.settling_tick.is_some_and(|tick| tick < 600),
+                "{:?}",
+                response
+            );
+        }
+
+        // The vehicle comes to rest instead of oscillating about the setpoint
+        assert!(plant.rate.0.abs() < 0.05 && plant.rate.1.abs() < 0.05);
+        assert_eq!(0.0, plant.attitude.2);
+    }
+
+    /// Test that gains from the bandwidth constructor give the requested natural frequency
+    /// and damping on the matching simulated plant.
+    #[test]
+    fn test_stabilizer_angle_from_bandwidth() {
+        let (wn, zeta) = (20.0f32, 0.5f32);
+        let model = PlantModel {
+            gain: 1000.0,
+            time_constant: 0.05,
+        };
+        let config = FlightStabilizerConfig::from_bandwidth(wn, zeta, model).unwrap();
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut plant = Plant::new(model.gain, model.time_constant);
+        let dt = 0.0005;
+
+        // Step roll and record the response
+        let set_point = (10.0, 0.0, 0.0);
+        let mut trajectory = [0.0; 2000];
+        for value in trajectory.iter_mut() {
+            let output = stabilizer.control(set_point, plant.attitude, plant.rate, dt, false);
+            plant.step(output, dt);
+            *value = plant.attitude.0;
+        }
 
+        // The second-order overshoot and peak time, e^(-π·ζ/√(1-ζ²)) and π/(wn·√(1-ζ²))
+        let damped = (1.0 - zeta * zeta).sqrt();
+        let expected_overshoot = (-core::f32::consts::PI * zeta / damped).exp();
+        let expected_peak_time = core::f32::consts::PI / (wn * damped);
+        let (peak_tick, peak) =
+            trajectory
+                .iter()
+                .enumerate()
+                .fold(
+                    (0, 0.0),
+                    |peak, (tick, &value)| if peak.1 < value { (tick, value) } else { peak },
+                );
+        let overshoot = (peak - set_point.0) / set_point.0;
+        let peak_time = (peak_tick + 1) as f32 * dt;
         assert!(
-            vector_close(expected_output, output),
-            "PID outputs should match specific values."
+            (overshoot - expected_overshoot).abs() < 0.02,
+            "Overshoot {} should be {}.",
+            overshoot,
+            expected_overshoot
+        );
+        assert!(
+            (peak_time - expected_peak_time).abs() < 0.01,
+            "Peak time {} should be {}.",
+            peak_time,
+            expected_peak_time
         );
+        assert!((trajectory[1999] - set_point.0).abs() < 0.05);
+    }
+
+    /// Test that reversed axes negate the error, terms, and output with a symmetric integral limit.
+    #[test]
+    fn test_stabilizer_angle_reverse_axes() {
+        let config = default_config();
+        let mut reversed_config = config;
+        reversed_config.reverse_roll = true;
+        reversed_config.reverse_pitch = true;
+        reversed_config.reverse_yaw = true;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut reversed = AngleStabilizer::with_config(reversed_config);
+
+        // A large steady error saturates the integrals
+        let set_point = (30.0, -20.0, 10.0);
+        let imu_attitude = (1.0, 2.0, 0.0);
+        let gyro_rate = (0.5, -0.5, 0.25);
+        for _ in 0..200 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, 0.01, false);
+            let reversed_output = reversed.control(set_point, imu_attitude, gyro_rate, 0.01, false);
+            tuple_close((-output.0, -output.1, -output.2), reversed_output);
+        }
+        assert!(value_close(config.i_limit, stabilizer.roll_pid.integral));
+        assert!(value_close(-config.i_limit, reversed.roll_pid.integral));
+        assert!(value_close(
+            -stabilizer.pitch_pid.integral,
+            reversed.pitch_pid.integral
+        ));
+        assert!(value_close(
+            -stabilizer.yaw_pid.integral,
+            reversed.yaw_pid.integral
+        ));
+        assert!(value_close(
+            -stabilizer.last_terms()[0].proportional,
+            reversed.last_terms()[0].proportional
+        ));
+
+        // A single reversed axis leaves the others untouched
+        let mut roll_config = config;
+        roll_config.reverse_roll = true;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut roll_reversed = AngleStabilizer::with_config(roll_config);
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, 0.01, false);
+        let reversed_output =
+            roll_reversed.control(set_point, imu_attitude, gyro_rate, 0.01, false);
+        tuple_close((-output.0, output.1, output.2), reversed_output);
+    }
+
+    /// Test that the raw output times the scale is the output, unless the output is clamped.
+    #[test]
+    fn test_stabilizer_angle_last_raw_output() {
+        let mut config = default_config();
+        config.scale_yaw = Some(0.02);
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let set_point = (10.0, -5.0, 10.0);
+        let imu_attitude = (5.0, 5.0, 0.0);
+        let gyro_rate = (1.0, -1.0, -1.0);
+        for _ in 0..3 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, 0.1, false);
+            let (roll, pitch, yaw) = stabilizer.last_raw_output();
+            assert_eq!((0.01 * roll, 0.01 * pitch, 0.02 * yaw), output);
+        }
+
+        // A clamped output differs from the scaled raw output, which stays unclamped
+        config.output_max = Some(0.01);
+        config.output_min = Some(-0.01);
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, 0.1, false);
+        let (roll, pitch, yaw) = stabilizer.last_raw_output();
+        let scaled = (0.01 * roll, 0.01 * pitch, 0.02 * yaw);
+        assert!(0.01 < scaled.0 && scaled.1 < -0.01 && 0.01 < scaled.2);
+        assert_eq!((0.01, -0.01, 0.01), output);
+        assert_eq!(
+            [
+                SaturationState::Upper,
+                SaturationState::Lower,
+                SaturationState::Upper
+            ],
+            stabilizer.saturation()
+        );
+    }
+
+    /// Test that an off-diagonal coupling gain turns a pure roll error into a pitch correction.
+    #[test]
+    fn test_stabilizer_angle_coupling() {
+        let mut config = default_config();
+        config.coupling[1][0] = 0.1;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut uncoupled = AngleStabilizer::with_config(default_config());
+
+        // A pure roll error with a roll rate
+        let set_point = (10.0, 0.0, 0.0);
+        let imu_attitude = (0.0, 0.0, 0.0);
+        let gyro_rate = (2.0, 0.0, 0.0);
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, 0.01, false);
+        let uncoupled_output = uncoupled.control(set_point, imu_attitude, gyro_rate, 0.01, false);
+
+        // Roll and yaw are unchanged, and pitch sees a tenth of the roll error and rate
+        assert_eq!(uncoupled_output.0, output.0);
+        assert_eq!(uncoupled_output.2, output.2);
+        assert_eq!(0.0, uncoupled_output.1);
+        assert!(value_close(1.0, stabilizer.pitch_pid.error));
+        let pitch_terms = stabilizer.last_terms()[1];
+        assert!(value_close(config.kp_pitch * 1.0, pitch_terms.proportional));
+        assert!(value_close(config.ki_pitch * 0.01, pitch_terms.integral));
+        assert!(value_close(config.kd_pitch * 0.2, pitch_terms.derivative));
+        assert!(value_close(0.1 * uncoupled_output.0, output.1));
+    }
+
+    /// Test that integral accumulation is graduated across the throttle threshold.
+    #[test]
+    fn test_stabilizer_angle_control_throttle() {
+        let mut config = default_config();
+        config.integral_throttle_threshold = 0.2;
+
+        // A unit error on every axis accumulates 0.1 per tick at full scale
+        let set_point = (1.0, 1.0, 1.0); // desired roll, pitch, yaw
+        let zero = (0.0, 0.0, 0.0);
+        let dt = 0.1; // time step
+        let throttles = [0.0, 0.05, 0.1, 0.15, 0.2, 0.5, 1.0];
+        let expected_integrals = [0.0, 0.025, 0.05, 0.075, 0.1, 0.1, 0.1];
+
+        for (throttle, expected) in throttles.iter().zip(expected_integrals) {
+            let mut stabilizer = AngleStabilizer::with_config(config);
+            let _ = stabilizer.control_throttle(set_point, zero, zero, dt, *throttle);
+            let integrals = (
+                stabilizer.roll_pid.integral,
+                stabilizer.pitch_pid.integral,
+                stabilizer.yaw_pid.integral,
+            );
+            assert!(
+                vector_close((expected, expected, expected), integrals),
+                "Integral accumulation should scale with throttle below the threshold."
+            );
+        }
+
+        // Zero throttle holds the integral instead of resetting it
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        stabilizer.set_integral(1.0, 1.0, 1.0);
+        let _ = stabilizer.control_throttle(set_point, zero, zero, dt, 0.0);
+        assert!(
+            value_close(1.0, stabilizer.roll_pid.integral),
+            "Integral should be held at zero throttle."
+        );
+
+        // The bool path still resets the integral
+        let _ = stabilizer.control(set_point, zero, zero, dt, true);
+        assert_eq!(0.0, stabilizer.roll_pid.integral, "Integral should reset.");
+    }
+
+    /// Test that the idle throttle holds the idle output and resets the integrals,
+    /// and that the output hands off to the PID above it.
+    #[test]
+    fn test_stabilizer_angle_idle_throttle() {
+        let config = default_config();
+        let mut idle_config = config;
+        idle_config.idle_throttle = 0.1;
+        idle_config.idle_output = 0.05;
+        idle_config.idle_handoff = 0.2;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut idle_stabilizer = AngleStabilizer::with_config(idle_config);
+
+        let set_point = (1.0, -1.0, 1.0); // desired roll, pitch, yaw
+        let zero = (0.0, 0.0, 0.0);
+        let dt = 0.1; // time step
+
+        // Below the threshold the output is idle and the integrals stay zero
+        idle_stabilizer.set_integral(1.0, 1.0, 1.0);
+        for _ in 0..3 {
+            let output = idle_stabilizer.control_throttle(set_point, zero, zero, dt, 0.05);
+            assert_eq!((0.05, 0.05, 0.05), output, "Output should be idle.");
+            assert_eq!(
+                (0.0, 0.0, 0.0),
+                (
+                    idle_stabilizer.roll_pid.integral,
+                    idle_stabilizer.pitch_pid.integral,
+                    idle_stabilizer.yaw_pid.integral,
+                ),
+                "Integrals should reset while idle."
+            );
+        }
+
+        // Halfway through the handoff the output is between idle and the PID
+        let expected = stabilizer.control_throttle(set_point, zero, zero, dt, 0.2);
+        let output = idle_stabilizer.control_throttle(set_point, zero, zero, dt, 0.2);
+        assert!(vector_close(
+            (
+                0.5 * (0.05 + expected.0),
+                0.5 * (0.05 + expected.1),
+                0.5 * (0.05 + expected.2)
+            ),
+            output
+        ));
+
+        // Above the handoff the output is the PID output
+        for _ in 0..3 {
+            let expected = stabilizer.control_throttle(set_point, zero, zero, dt, 0.5);
+            let output = idle_stabilizer.control_throttle(set_point, zero, zero, dt, 0.5);
+            assert!(
+                vector_close(expected, output),
+                "Output should be normal PID."
+            );
+        }
+
+        // Disabled axes output zero instead of idling
+        idle_stabilizer.enable_axes(true, false, true);
+        let output = idle_stabilizer.control_throttle(set_point, zero, zero, dt, 0.0);
+        assert_eq!((0.05, 0.0, 0.05), output);
+
+        // The bool path does not idle
+        let output = idle_stabilizer.control(set_point, zero, zero, dt, true);
+        assert_ne!(0.05, output.0);
+    }
+
+    /// Test that the output deadband zeroes small outputs without touching the integral.
+    #[test]
+    fn test_stabilizer_angle_output_deadband() {
+        let config = default_config();
+        let mut deadband_config = config;
+        deadband_config.output_deadband = 0.05;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut deadband_stabilizer = AngleStabilizer::with_config(deadband_config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (1.0, 50.0, 0.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        for _ in 0..10 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let deadband_output =
+                deadband_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert!(output.0.abs() < 0.05, "Roll output should be small.");
+            assert_eq!(0.0, deadband_output.0, "Small output should snap to zero.");
+            assert_eq!(output.1, deadband_output.1, "Large output should pass.");
+            assert_eq!(output.2, deadband_output.2, "Zero output should pass.");
+        }
+
+        assert_eq!(
+            stabilizer.roll_pid.integral, deadband_stabilizer.roll_pid.integral,
+            "Deadband should not affect the integral."
+        );
+    }
+
+    /// Test that the proportional setpoint weight reduces the kick on a setpoint step.
+    #[test]
+    fn test_stabilizer_angle_b_weight() {
+        let mut config = default_config();
+        config.b_weight = 0.5;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+
+        // Step the roll and pitch setpoints with a level attitude
+        let zero = (0.0, 0.0, 0.0);
+        let _ = stabilizer.control((10.0, -10.0, 0.0), zero, zero, 0.01, false);
+
+        // P acts on 0.5 * 10, while the integral accumulates the full error
+        let [roll, pitch, _] = stabilizer.last_terms();
+        assert!(value_close(config.kp_roll * 5.0, roll.proportional));
+        assert!(value_close(-config.kp_pitch * 5.0, pitch.proportional));
+        assert!(value_close(config.ki_roll * 0.1, roll.integral));
+    }
+
+    /// Test that a preloaded integral contributes immediately and is clamped to the limit.
+    #[test]
+    fn test_stabilizer_angle_set_integral() {
+        let config = default_config();
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        stabilizer.set_integral(10.0, -50.0, 2.0);
+
+        // The pitch integral is clamped to the limit
+        let integrals = (
+            stabilizer.roll_pid.integral,
+            stabilizer.pitch_pid.integral,
+            stabilizer.yaw_pid.integral,
+        );
+        assert!(vector_close((10.0, -config.i_limit, 2.0), integrals));
+
+        // No error, so the first step only carries the preloaded integral terms
+        let zero = (0.0, 0.0, 0.0);
+        let output = stabilizer.control(zero, zero, zero, 0.01, false);
+        let expected_output = (
+            config.scale * config.ki_roll * 10.0,
+            config.scale * config.ki_pitch * -config.i_limit,
+            config.scale * config.ki_yaw * 2.0,
+        );
+        assert!(
+            vector_close(expected_output, output),
+            "Output should carry the preloaded integral terms."
+        );
+    }
+
+    /// Test that disabled axes output zero and hold their integrals while enabled axes work.
+    #[test]
+    fn test_stabilizer_angle_enable_axes() {
+        let config = default_config();
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut reference_stabilizer = AngleStabilizer::with_config(config);
+        assert_eq!([true; 3], stabilizer.enabled_axes());
+        stabilizer.set_integral(0.0, 5.0, -2.0);
+        stabilizer.enable_axes(true, false, false);
+        assert_eq!([true, false, false], stabilizer.enabled_axes());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+
+        for low_throttle in [false, false, true, false] {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let reference_output =
+                reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert_eq!(reference_output.0, output.0, "Roll should be unaffected.");
+            assert_eq!((0.0, 0.0), (output.1, output.2), "Outputs should be zero.");
+            assert_eq!(5.0, stabilizer.pitch_pid.integral);
+            assert_eq!(-2.0, stabilizer.yaw_pid.integral);
+            assert_eq!(PidTerms::new(), stabilizer.last_terms()[1]);
+        }
+
+        // The flags survive a reset, and reenabled axes control normally
+        stabilizer.reset();
+        assert_eq!([true, false, false], stabilizer.enabled_axes());
+        stabilizer.enable_axes(true, true, true);
+        reference_stabilizer.reset();
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+        let reference_output =
+            reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+        assert_eq!(reference_output, output);
+    }
+
+    /// Test that a frozen integral holds across ticks and resumes where it left off.
+    #[test]
+    fn test_stabilizer_angle_freeze_integral() {
+        let config = default_config();
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut reference_stabilizer = AngleStabilizer::with_config(config);
+        assert_eq!([false; 3], stabilizer.frozen_integrals());
+        stabilizer.set_integral(0.0, 5.0, -2.0);
+        stabilizer.freeze_integral(false, true, true);
+        assert_eq!([false, true, true], stabilizer.frozen_integrals());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+
+        for _ in 0..5 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+            let reference_output =
+                reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+            assert_eq!(reference_output.0, output.0, "Roll should be unaffected.");
+            assert_ne!(0.0, output.1, "Pitch should still be controlled.");
+            assert_eq!(5.0, stabilizer.pitch_pid.integral);
+            assert_eq!(-2.0, stabilizer.yaw_pid.integral);
+            let terms = stabilizer.last_terms();
+            assert_eq!(config.ki_pitch * 5.0, terms[1].integral);
+            assert_eq!(config.ki_yaw * -2.0, terms[2].integral);
+        }
+
+        // Thawed axes accumulate from the held values, like an unfrozen copy
+        let mut resumed_stabilizer = AngleStabilizer::with_config(config);
+        resumed_stabilizer.restore(&stabilizer.snapshot());
+        stabilizer.freeze_integral(false, false, false);
+        for _ in 0..5 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+            let resumed_output =
+                resumed_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+            assert_eq!(resumed_output, output);
+        }
+        assert!(stabilizer.pitch_pid.integral < 5.0);
+
+        // A low throttle reset still zeroes a frozen integral, and the flags survive a reset
+        stabilizer.freeze_integral(true, true, true);
+        let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, true);
+        assert_eq!(0.0, stabilizer.pitch_pid.integral);
+        stabilizer.reset();
+        assert_eq!([true; 3], stabilizer.frozen_integrals());
+    }
+
+    /// Test to ensure integrators are reset when PWM is below threshold.
+    #[test]
+    fn test_stabilizer_angle_low_throttle_integral_reset() {
+        let config = default_config();
+        let mut stabilizer = AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, 0.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+
+        // Allow integrators to build up
+        let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+        let integrals = (
+            stabilizer.roll_pid.integral,
+            stabilizer.pitch_pid.integral,
+            stabilizer.yaw_pid.integral,
+        );
+        let unexpected_integrals = (0.0, 0.0, 0.0);
+        assert!(
+            vector_not_close(unexpected_integrals, integrals),
+            "Integrals should not be zero."
+        );
+
+        // Apply low throttle, which should reset integrators
+        let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, true);
+        let integrals = (
+            stabilizer.roll_pid.integral,
+            stabilizer.pitch_pid.integral,
+            stabilizer.yaw_pid.integral,
+        );
+        let expected_integrals = (0.0, 0.0, 0.0);
+        assert!(
+            vector_close(expected_integrals, integrals),
+            "Integrals should be zero."
+        );
+    }
+
+    /// Test the no error contidion.
+    #[test]
+    fn test_stabilizer_angle_no_error() {
+        let config = default_config();
+        let mut stabilizer = AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (0.0, 0.0, 0.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Perform the control computation
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output = (0.0, 0.0, 0.0);
+
+        assert!(
+            vector_close(expected_output, output),
+            "Outputs should be zero as there is no error."
+        );
+    }
+
+    /// Test with specific inputs to calculate expected PID outputs.
+    #[test]
+    fn test_stabilizer_angle_specific_pid_output() {
+        // Set up stabilizer
+        let config = default_config();
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        stabilizer.roll_pid.integral = 0.2; // previous integral
+        stabilizer.pitch_pid.integral = -0.2; // previous integral
+        stabilizer.yaw_pid.integral = 0.2; // previous integral
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, 0.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Perform the control computation
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output = (0.01025, -0.01025, 0.034805);
+
+        assert!(
+            vector_close(expected_output, output),
+            "PID outputs should match specific values."
+        );
+    }
+
+    /// Test that a saturating command is clamped while other axes stay linear.
+    #[test]
+    fn test_stabilizer_angle_output_clamping() {
+        let mut config = default_config();
+        config.output_min = Some(-0.5);
+        config.output_max = Some(0.5);
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut unclamped_stabilizer = AngleStabilizer::with_config(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (500.0, 10.0, 0.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Perform the control computation
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let unclamped_output =
+            unclamped_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+
+        assert!(0.5 < unclamped_output.0, "Roll command should saturate.");
+        assert!(value_close(0.5, output.0), "Roll should be clamped.");
+        assert!(
+            value_close(unclamped_output.1, output.1),
+            "Pitch should stay linear."
+        );
+
+        // Clamp the negative direction
+        let set_point = (-500.0, -10.0, 0.0); // desired roll, pitch, yaw
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        assert!(value_close(-0.5, output.0), "Roll should be clamped.");
+    }
+
+    /// Test that overflowing intermediates saturate to the output bounds.
+    #[test]
+    fn test_stabilizer_angle_output_overflow() {
+        let mut config = default_config();
+        config.output_min = Some(-0.5);
+        config.output_max = Some(0.5);
+        config.kt_roll = 1.0;
+        config.kt_pitch = 1.0;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+
+        // The roll error overflows to infinity and the pitch attitude is NaN
+        let set_point = (f32::MAX, 0.0, 0.0); // desired roll, pitch, yaw
+        let imu_attitude = (-f32::MAX, f32::NAN, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        for _ in 0..10 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert_eq!(0.5, output.0, "Roll should saturate at the upper bound.");
+            assert_eq!(0.0, output.1, "NaN pitch should saturate to zero.");
+            assert!(output.2.is_finite(), "Yaw should be finite.");
+        }
+
+        assert!(
+            stabilizer.roll_pid.integral.is_finite(),
+            "Roll integral should be finite."
+        );
+        assert!(
+            stabilizer.pitch_pid.integral.is_finite(),
+            "Pitch integral should be finite."
+        );
+    }
+
+    /// Test that the saturation status reports outputs clipped high, clipped low, and in range.
+    #[test]
+    fn test_stabilizer_angle_control_with_status() {
+        let mut config = default_config();
+        config.output_min = Some(-0.5);
+        config.output_max = Some(0.5);
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        assert_eq!([SaturationState::None; 3], stabilizer.saturation());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (500.0, -500.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        let (output, status) =
+            stabilizer.control_with_status(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        assert_eq!(
+            [
+                SaturationState::Upper,
+                SaturationState::Lower,
+                SaturationState::None
+            ],
+            status
+        );
+        assert!(value_close(0.5, output.0), "Roll should clip high.");
+        assert!(value_close(-0.5, output.1), "Pitch should clip low.");
+        assert!(output.2.abs() < 0.5, "Yaw should be in range.");
+        assert_eq!(status, stabilizer.saturation());
+
+        stabilizer.reset();
+        assert_eq!([SaturationState::None; 3], stabilizer.saturation());
+    }
+
+    /// Test that output clamping is disabled by default.
+    #[test]
+    fn test_stabilizer_angle_output_clamping_default_disabled() {
+        let config = default_config();
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        assert_eq!(None, config.output_min);
+        assert_eq!(None, config.output_max);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (5000.0, -5000.0, 0.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Perform the control computation
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+
+        // (kp * error + ki * i_limit) * scale
+        let expected_output = (10.075, -10.075, 0.0);
+        assert!(
+            vector_close(expected_output, output),
+            "Outputs should not be clamped."
+        );
+    }
+
+    /// Run a saturating step response, returning the number of steps needed to
+    /// leave upper saturation after the set point drops.
+    fn saturation_recovery_steps(kt: f32) -> usize {
+        let mut config = default_config();
+        config.i_limit = 1000.0;
+        config.output_max = Some(0.1);
+        config.kt_roll = kt;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Hold the roll output in saturation
+        let set_point = (50.0, 0.0, 0.0); // desired roll, pitch, yaw
+        for _ in 0..200 {
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
+
+        // Drop the set point and wait for the roll output to leave saturation
+        let set_point = (-10.0, 0.0, 0.0); // desired roll, pitch, yaw
+        let mut steps = 1;
+        while value_close(
+            0.1,
+            stabilizer
+                .control(set_point, imu_attitude, gyro_rate, dt, low_throttle)
+                .0,
+        ) {
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Test that back-calculation anti-windup recovers from saturation faster than the hard clamp.
+    #[test]
+    fn test_stabilizer_angle_back_calculation_recovery() {
+        let back_calculation_steps = saturation_recovery_steps(1000.0);
+        let clamped_steps = saturation_recovery_steps(0.0);
+
+        assert!(
+            back_calculation_steps < clamped_steps,
+            "Back-calculation should recover faster."
+        );
+        assert!(
+            back_calculation_steps <= 2,
+            "Back-calculation should recover almost immediately."
+        );
+        assert!(
+            500 < clamped_steps,
+            "Hard clamp should unwind the full integral."
+        );
+    }
+
+    /// Test that back-calculation does not affect unsaturated outputs.
+    #[test]
+    fn test_stabilizer_angle_back_calculation_unsaturated() {
+        let mut config = default_config();
+        config.kt_roll = 1000.0;
+        config.kt_pitch = 1000.0;
+        config.kt_yaw = 1000.0;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut reference_stabilizer = AngleStabilizer::with_config(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, 0.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        for _ in 0..10 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let expected_output =
+                reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert_eq!(expected_output, output, "Outputs should match.");
+        }
+    }
+
+    /// Test that back-calculation leaves an integral reset at low throttle at zero,
+    /// and never pushes the integral past the integral limit.
+    #[test]
+    fn test_stabilizer_angle_back_calculation_low_throttle() {
+        let mut config = default_config();
+        config.i_limit = 1.0;
+        config.output_min = Some(-0.1);
+        config.output_max = Some(0.1);
+        config.kt_roll = 1000.0;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+
+        // Hold the roll output in saturation
+        let set_point = (100.0, 0.0, 0.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        for _ in 0..10 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+            assert_eq!(0.1, output.0, "Roll output should saturate.");
+            assert!(
+                stabilizer.roll_pid.integral.abs() <= 1.0,
+                "Tracking should not push the integral past the limit."
+            );
+        }
+
+        // The reset integral stays zero while the output is saturated
+        let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, true);
+        assert_eq!(SaturationState::Upper, stabilizer.saturation()[0]);
+        assert_eq!(0.0, stabilizer.roll_pid.integral);
+    }
+
+    /// Test that a setpoint step ramps linearly at the maximum setpoint rate.
+    #[test]
+    fn test_stabilizer_angle_set_point_slew() {
+        let mut config = default_config();
+        config.max_set_point_rate_roll = Some(100.0);
+        config.max_set_point_rate_yaw = Some(50.0);
+        let mut stabilizer = AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        for step in 1..=15 {
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let set_points = (
+                stabilizer.roll_pid.set_point,
+                stabilizer.pitch_pid.set_point,
+                stabilizer.yaw_pid.set_point,
+            );
+            let t = step as f32 * dt;
+            let expected_set_points = ((100.0 * t).min(10.0), -10.0, (50.0 * t).min(10.0));
+            assert!(
+                vector_close(expected_set_points, set_points),
+                "Setpoints should ramp at the maximum rate."
+            );
+        }
+    }
+
+    /// Test that setpoint slew limiting is disabled by default.
+    #[test]
+    fn test_stabilizer_angle_set_point_slew_default_disabled() {
+        let config = default_config();
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        assert_eq!(None, config.max_set_point_rate_roll);
+        assert_eq!(None, config.max_set_point_rate_pitch);
+        assert_eq!(None, config.max_set_point_rate_yaw);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (1000.0, -1000.0, 1000.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let set_points = (
+            stabilizer.roll_pid.set_point,
+            stabilizer.pitch_pid.set_point,
+            stabilizer.yaw_pid.set_point,
+        );
+        assert!(
+            vector_close(set_point, set_points),
+            "Setpoints should step immediately."
+        );
+    }
+
+    /// Test that a step in the attitude measurement is smoothed by the filter,
+    /// and that the filter restarts at low throttle and on reset.
+    #[test]
+    fn test_stabilizer_angle_attitude_filter_step() {
+        let mut config = default_config();
+        config.attitude_alpha_roll = 0.5;
+        config.attitude_alpha_pitch = 0.5;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut reference_stabilizer = AngleStabilizer::with_config(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (0.0, 0.0, 0.0); // desired roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // The first measurement starts the filter, then the measurement steps
+        let steps = [
+            (0.0, 0.0),
+            (10.0, 5.0),
+            (10.0, 5.0),
+            (10.0, 5.0),
+            (10.0, 5.0),
+        ];
+        let filtered = [
+            (0.0, 0.0),
+            (5.0, 2.5),
+            (7.5, 3.75),
+            (8.75, 4.375),
+            (9.375, 4.6875),
+        ];
+        for ((roll, pitch), (filtered_roll, filtered_pitch)) in steps.iter().zip(filtered.iter()) {
+            let imu_attitude = (*roll, *pitch, 0.0);
+            let filtered_attitude = (*filtered_roll, *filtered_pitch, 0.0);
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let expected_output = reference_stabilizer.control(
+                set_point,
+                filtered_attitude,
+                gyro_rate,
+                dt,
+                low_throttle,
+            );
+            assert_eq!(
+                expected_output, output,
+                "Output should follow the smoothed measurement."
+            );
+        }
+
+        // Low throttle restarts the filter from the raw measurement
+        let imu_attitude = (-10.0, -5.0, 0.0);
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, true);
+        let expected_output =
+            reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, true);
+        assert_eq!(
+            expected_output, output,
+            "Low throttle should restart the filter."
+        );
+
+        // Reset restarts the filter from the raw measurement
+        let _ = stabilizer.control(set_point, (10.0, 5.0, 0.0), gyro_rate, dt, low_throttle);
+        stabilizer.reset();
+        reference_stabilizer.reset();
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output =
+            reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        assert_eq!(expected_output, output, "Reset should restart the filter.");
+    }
+
+    /// Test that each axis is filtered independently with its own coefficient.
+    #[test]
+    fn test_stabilizer_angle_attitude_filter_per_axis() {
+        let mut config = default_config();
+        config.attitude_alpha_roll = 0.5;
+        config.attitude_alpha_pitch = 0.25;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut reference_stabilizer = AngleStabilizer::with_config(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (0.0, 0.0, 0.0); // desired roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Step roll first, then pitch, so each axis sees the other hold steady
+        let mut filtered: (f32, f32) = (0.0, 0.0);
+        for step in 0..10 {
+            let roll = if 1 <= step { 8.0 } else { 0.0 };
+            let pitch = if 5 <= step { -8.0 } else { 0.0 };
+            if 0 < step {
+                filtered.0 += 0.5 * (roll - filtered.0);
+                filtered.1 += 0.25 * (pitch - filtered.1);
+            }
+            let output =
+                stabilizer.control(set_point, (roll, pitch, 0.0), gyro_rate, dt, low_throttle);
+            let expected_output = reference_stabilizer.control(
+                set_point,
+                (filtered.0, filtered.1, 0.0),
+                gyro_rate,
+                dt,
+                low_throttle,
+            );
+            assert_eq!(
+                expected_output, output,
+                "Each axis should be smoothed by its own coefficient."
+            );
+            if step < 5 {
+                assert_eq!(
+                    0.0,
+                    stabilizer.filtered_attitude.unwrap().1,
+                    "Pitch should hold."
+                );
+            }
+        }
+    }
+
+    /// Test that the integral ramps no faster than the maximum integral rate
+    /// under a large constant error, and that infinity disables the limit.
+    #[test]
+    fn test_stabilizer_angle_max_integral_rate() {
+        let mut config = default_config();
+        config.i_limit = 1000.0;
+        config.max_integral_rate = 2.0;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        config.max_integral_rate = f32::INFINITY;
+        let mut unlimited_stabilizer = AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (90.0, -90.0, 90.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        for step in 1..=50 {
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let _ =
+                unlimited_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let max_integral = 2.0 * dt * step as f32;
+            let integrals = (
+                stabilizer.roll_pid.integral,
+                stabilizer.pitch_pid.integral,
+                stabilizer.yaw_pid.integral,
+            );
+            assert!(
+                vector_close((max_integral, -max_integral, max_integral), integrals),
+                "Integral should ramp at the maximum rate."
+            );
+            assert!(
+                value_close(
+                    1.0,
+                    unlimited_stabilizer.roll_pid.integral / (90.0 * dt * step as f32)
+                ),
+                "Infinity should disable the limit."
+            );
+        }
+    }
+
+    /// Test that a divided integral holds between updates and matches
+    /// the full-rate integral over the same interval on each update.
+    #[test]
+    fn test_stabilizer_angle_integral_divisor() {
+        let config = default_config();
+        let mut divided_config = config;
+        divided_config.integral_divisor = 2;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut divided_stabilizer = AngleStabilizer::with_config(divided_config);
+
+        // A constant error with an irregular time step
+        let set_point = (10.0, -10.0, 5.0); // desired roll, pitch, yaw rate
+        let imu_attitude = (2.0, -2.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 1.0); // current roll rate, pitch rate, yaw rate
+        let dts = [0.01, 0.02, 0.01, 0.03, 0.02, 0.01];
+        let integrals = |stabilizer: &AngleStabilizer<f32>| {
+            (
+                stabilizer.roll_pid.integral,
+                stabilizer.pitch_pid.integral,
+                stabilizer.yaw_pid.integral,
+            )
+        };
+        let mut held = (0.0, 0.0, 0.0);
+        for (tick, dt) in dts.into_iter().enumerate() {
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+            let _ = divided_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+            if tick % 2 == 0 {
+                assert_eq!(
+                    held,
+                    integrals(&divided_stabilizer),
+                    "Integral should hold between updates."
+                );
+            } else {
+                assert!(
+                    vector_close(integrals(&stabilizer), integrals(&divided_stabilizer)),
+                    "Integral should match the full-rate integral on updates."
+                );
+                held = integrals(&divided_stabilizer);
+            }
+        }
+        assert!(vector_not_close((0.0, 0.0, 0.0), held));
+    }
+
+    /// Test that a rate spike is clipped by the derivative term limit,
+    /// while the proportional and integral terms pass through unaffected.
+    #[test]
+    fn test_stabilizer_angle_d_term_limit() {
+        let mut config = default_config();
+        config.d_term_limit_roll = 2.0;
+        config.d_term_limit_pitch = 2.0;
+        config.d_term_limit_yaw = f32::INFINITY;
+        assert_eq!(Ok(()), config.validate());
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut unlimited_stabilizer = AngleStabilizer::with_config(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let spike_rate = (1000.0, -1000.0, 1000.0); // sudden rate spike
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Small rates stay within the limit
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let unlimited_output =
+            unlimited_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        assert_eq!(unlimited_output, output, "Small rates should pass.");
+
+        let _ = stabilizer.control(set_point, imu_attitude, spike_rate, dt, low_throttle);
+        let _ = unlimited_stabilizer.control(set_point, imu_attitude, spike_rate, dt, low_throttle);
+        let [roll, pitch, yaw] = stabilizer.last_terms();
+        let [unlimited_roll, unlimited_pitch, unlimited_yaw] = unlimited_stabilizer.last_terms();
+        assert!(
+            value_close(-2.0, roll.derivative),
+            "Roll D should be clipped."
+        );
+        assert!(
+            value_close(2.0, pitch.derivative),
+            "Pitch D should be clipped."
+        );
+        assert!(value_close(-50.0, unlimited_roll.derivative));
+        assert_eq!(unlimited_roll.proportional, roll.proportional);
+        assert_eq!(unlimited_roll.integral, roll.integral);
+        assert_eq!(unlimited_pitch.proportional, pitch.proportional);
+        assert_eq!(unlimited_pitch.integral, pitch.integral);
+        assert_eq!(unlimited_yaw, yaw, "Infinity should disable the limit.");
+    }
+
+    /// Test that a large error is clipped by the proportional term limit,
+    /// while the integral keeps accumulating the full error.
+    #[test]
+    fn test_stabilizer_angle_p_term_limit() {
+        let mut config = default_config();
+        config.p_term_limit_roll = 2.0;
+        config.p_term_limit_pitch = 2.0;
+        config.p_term_limit_yaw = f32::INFINITY;
+        assert_eq!(Ok(()), config.validate());
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut unlimited_stabilizer = AngleStabilizer::with_config(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (90.0, -90.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        let mut output = (0.0, 0.0, 0.0);
+        for _ in 0..10 {
+            output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let _ =
+                unlimited_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
+        let [roll, pitch, yaw] = stabilizer.last_terms();
+        let [unlimited_roll, unlimited_pitch, unlimited_yaw] = unlimited_stabilizer.last_terms();
+        assert!(value_close(18.0, unlimited_roll.proportional));
+        assert!(
+            value_close(2.0, roll.proportional),
+            "Roll P should be clipped."
+        );
+        assert!(
+            value_close(-2.0, pitch.proportional),
+            "Pitch P should be clipped."
+        );
+        assert_eq!(
+            unlimited_stabilizer.roll_pid.integral,
+            stabilizer.roll_pid.integral
+        );
+        assert_eq!(
+            unlimited_stabilizer.pitch_pid.integral,
+            stabilizer.pitch_pid.integral
+        );
+        assert!(value_close(9.0, stabilizer.roll_pid.integral));
+        assert_eq!(unlimited_roll.integral, roll.integral);
+        assert_eq!(unlimited_pitch.derivative, pitch.derivative);
+        assert_eq!(unlimited_yaw, yaw, "Infinity should disable the limit.");
+
+        // 0.01 * (2 + 0.3 * 9 + -0.05 * 1) = 0.0465
+        assert!(value_close(0.0465, output.0));
+        assert!(value_close(0.01 * roll.sum(), output.0));
+    }
+
+    /// Test that the notch filter removes gyro vibration at its center from the derivative.
+    #[test]
+    fn test_stabilizer_angle_notch() {
+        let mut config = default_config();
+        config.notch_center_hz = 100.0;
+        config.notch_q = 2.0;
+        assert_eq!(Ok(()), config.validate());
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut unfiltered_stabilizer = AngleStabilizer::with_config(default_config());
+        let mut reference_stabilizer = AngleStabilizer::with_config(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (0.0, 0.0, 0.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let dt = 0.001; // 1 kHz loop
+        let low_throttle = false;
+
+        // Compare the roll derivative once the filter settles
+        let mut max_filtered_error: f32 = 0.0;
+        let mut max_unfiltered_error: f32 = 0.0;
+        for step in 0..1000 {
+            let time = step as f32 * dt;
+            let vibration = 10.0 * (2.0 * core::f32::consts::PI * 100.0 * time).sin();
+            let vibrating_rate = (1.0 + vibration, -1.0, 0.5);
+            let _ = stabilizer.control(set_point, imu_attitude, vibrating_rate, dt, low_throttle);
+            let _ = unfiltered_stabilizer.control(
+                set_point,
+                imu_attitude,
+                vibrating_rate,
+                dt,
+                low_throttle,
+            );
+            let _ = reference_stabilizer.control(
+                set_point,
+                imu_attitude,
+                (1.0, -1.0, 0.5),
+                dt,
+                low_throttle,
+            );
+            if 500 <= step {
+                let reference = reference_stabilizer.last_terms()[0].derivative;
+                let filtered = stabilizer.last_terms()[0].derivative;
+                let unfiltered = unfiltered_stabilizer.last_terms()[0].derivative;
+                max_filtered_error = max_filtered_error.max((filtered - reference).abs());
+                max_unfiltered_error = max_unfiltered_error.max((unfiltered - reference).abs());
+            }
+        }
+        assert!(
+            max_filtered_error < 0.01 * max_unfiltered_error,
+            "Vibration should be removed from the derivative."
+        );
+
+        // Reset clears the filter history
+        stabilizer.reset();
+        let mut fresh_stabilizer = AngleStabilizer::with_config(config);
+        let gyro_rate = (3.0, -2.0, 1.0);
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let fresh_output =
+            fresh_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        assert_eq!(fresh_output, output);
+    }
+
+    /// Test that the output change per tick is bounded by the maximum output rate.
+    #[test]
+    fn test_stabilizer_angle_max_output_rate() {
+        let mut config = default_config();
+        config.max_output_rate_roll = Some(2.0);
+        config.max_output_rate_pitch = Some(1.0);
+        config.max_output_rate_yaw = Some(f32::INFINITY);
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut unlimited_stabilizer = AngleStabilizer::with_config(default_config());
+
+        // Simulated sensor inputs and a large setpoint step
+        let set_point = (90.0, -90.0, 90.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        let mut previous_output = (0.0, 0.0, 0.0);
+        for step in 1..=10 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let unlimited_output =
+                unlimited_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert!(
+                (output.0 - previous_output.0).abs() <= 2.0 * dt + TEST_TOLERANCE,
+                "Roll output change should be bounded."
+            );
+            assert!(
+                (output.1 - previous_output.1).abs() <= dt + TEST_TOLERANCE,
+                "Pitch output change should be bounded."
+            );
+            assert!(
+                value_close(-dt * step as f32, output.1),
+                "Pitch output should ramp at the maximum rate."
+            );
+            assert_eq!(
+                unlimited_output.2, output.2,
+                "Infinity should disable the limit."
+            );
+            previous_output = output;
+        }
+
+        // Reset restarts the limiter from zero
+        stabilizer.reset();
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        assert!(value_close(2.0 * dt, output.0));
+        assert!(value_close(-dt, output.1));
+    }
+
+    /// Test that the output rate limiter is disabled by default.
+    #[test]
+    fn test_stabilizer_angle_max_output_rate_default_disabled() {
+        let config = default_config();
+        assert_eq!(None, config.max_output_rate_roll);
+        assert_eq!(None, config.max_output_rate_pitch);
+        assert_eq!(None, config.max_output_rate_yaw);
+
+        // A step in the setpoint reaches the output immediately
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let set_point = (90.0, -90.0, 90.0); // desired roll, pitch, yaw
+        let zero = (0.0, 0.0, 0.0);
+        let output = stabilizer.control(set_point, zero, zero, 0.01, false);
+        assert!(
+            0.1 < output.0.abs() && 0.1 < output.1.abs(),
+            "Output should not be rate limited."
+        );
+    }
+
+    /// Test that reset returns the controller to the state of a fresh controller.
+    #[test]
+    fn test_stabilizer_angle_reset() {
+        let config = default_config();
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut fresh_stabilizer = AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 1.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Build up internal state
+        for _ in 0..10 {
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
+        stabilizer.reset();
+
+        // Perform the control computation
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output =
+            fresh_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        assert_eq!(
+            expected_output, output,
+            "Output should match a fresh controller."
+        );
+    }
+
+    /// Test that restoring a snapshot after a diverging branch reproduces the unbranched trajectory.
+    #[test]
+    fn test_stabilizer_angle_snapshot_restore() {
+        let mut config = default_config();
+        config.attitude_alpha_roll = 0.5;
+        config.max_set_point_rate_pitch = Some(100.0);
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut unbranched_stabilizer = AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs that change every step
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let inputs = |step: usize| {
+            let step = step as f32;
+            let imu_attitude = (5.0 - 0.5 * step, 5.0 + 0.25 * step, 1.0 - step); // current roll, pitch, yaw
+            let gyro_rate = (1.0 - 0.1 * step, -1.0, 0.2 * step - 1.0); // current roll rate, pitch rate, yaw rate
+            (imu_attitude, gyro_rate)
+        };
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Build up internal state
+        for step in 0..10 {
+            let (imu_attitude, gyro_rate) = inputs(step);
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let _ =
+                unbranched_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
+
+        // Diverge on a different branch, then restore
+        let state = stabilizer.snapshot();
+        stabilizer.set_disturbance(1.0, 2.0, 3.0);
+        for _ in 0..5 {
+            let _ = stabilizer.control(
+                (-20.0, 20.0, 0.0),
+                (30.0, -30.0, 90.0),
+                (5.0, 5.0, 5.0),
+                dt,
+                low_throttle,
+            );
+        }
+        stabilizer.restore(&state);
+        assert_eq!(
+            state,
+            stabilizer.snapshot(),
+            "Restored state should match the snapshot."
+        );
+
+        // Continue along the unbranched trajectory
+        for step in 10..20 {
+            let (imu_attitude, gyro_rate) = inputs(step);
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let expected_output =
+                unbranched_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert_eq!(
+                expected_output, output,
+                "Output should match the unbranched controller."
+            );
+        }
+    }
+
+    /// Test that a snapshot taken between integral updates restores the divider,
+    /// so the restored trajectory matches the unbranched one.
+    #[test]
+    fn test_stabilizer_angle_snapshot_restore_integral_divisor() {
+        let mut config = default_config();
+        config.integral_divisor = 2;
+        config.ki_roll = 1.0;
+        config.i_limit = 100.0;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut unbranched_stabilizer = AngleStabilizer::with_config(config);
+        let set_point = (10.0, 0.0, 0.0); // desired roll, pitch, yaw
+        let zero = (0.0, 0.0, 0.0);
+        let dt = 0.01;
+
+        // Snapshot one tick into the two tick period
+        let _ = stabilizer.control(set_point, zero, zero, dt, false);
+        let _ = unbranched_stabilizer.control(set_point, zero, zero, dt, false);
+        let state = stabilizer.snapshot();
+        let _ = stabilizer.control(set_point, zero, zero, dt, false);
+        stabilizer.restore(&state);
+
+        for _ in 0..5 {
+            let output = stabilizer.control(set_point, zero, zero, dt, false);
+            let expected_output = unbranched_stabilizer.control(set_point, zero, zero, dt, false);
+            assert_eq!(
+                expected_output, output,
+                "Output should match the unbranched controller."
+            );
+        }
+    }
+
+    /// Test that the reported PID terms sum to the unscaled control output.
+    #[test]
+    fn test_stabilizer_angle_last_terms() {
+        let config = default_config();
+        let mut stabilizer = AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 1.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        for _ in 0..3 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let [roll_terms, pitch_terms, yaw_terms] = stabilizer.last_terms();
+            let terms_output = (
+                config.scale * roll_terms.sum(),
+                config.scale * pitch_terms.sum(),
+                config.scale * yaw_terms.sum(),
+            );
+            assert!(
+                vector_close(output, terms_output),
+                "Terms should sum to the unscaled output."
+            );
+            assert_ne!(0.0, roll_terms.proportional);
+            assert_ne!(0.0, roll_terms.integral);
+            assert_ne!(0.0, roll_terms.derivative);
+        }
+    }
+
+    /// Test that the feed-forward contribution appears immediately on a setpoint step.
+    #[test]
+    fn test_stabilizer_angle_feed_forward_step() {
+        let mut config = default_config();
+        config.kff_roll = 0.5;
+        config.kff_pitch = 0.25;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut reference_stabilizer = AngleStabilizer::with_config(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // The first step has almost no integral
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let reference_output =
+            reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let feed_forward = (
+            output.0 - reference_output.0,
+            output.1 - reference_output.1,
+            output.2 - reference_output.2,
+        );
+        let expected_feed_forward = (0.05, -0.025, 0.0); // scale * kff * set point
+        assert!(
+            vector_close(expected_feed_forward, feed_forward),
+            "Feed-forward should appear on the first step."
+        );
+
+        // The contribution is reported with the PID terms
+        let [roll_terms, _, yaw_terms] = stabilizer.last_terms();
+        assert!(value_close(5.0, roll_terms.feed_forward));
+        assert!(value_close(0.0, yaw_terms.feed_forward));
+        assert!(value_close(output.0, config.scale * roll_terms.sum()));
+    }
+
+    /// Test that disturbance feed-forward shifts the output without changing the PID terms.
+    #[test]
+    fn test_stabilizer_angle_disturbance_feed_forward() {
+        let mut config = default_config();
+        config.kdist_roll = 2.0;
+        config.kdist_yaw = -1.0;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut reference_stabilizer = AngleStabilizer::with_config(config);
+        stabilizer.set_disturbance(0.5, 3.0, 4.0);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        for _ in 0..5 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let reference_output =
+                reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let shift = (
+                output.0 - reference_output.0,
+                output.1 - reference_output.1,
+                output.2 - reference_output.2,
+            );
+            let expected_shift = (0.01, 0.0, -0.04); // scale * kdist * disturbance
+            assert!(
+                vector_close(expected_shift, shift),
+                "Disturbance should shift the output."
+            );
+
+            // The P, I, and D terms match the reference
+            let terms = stabilizer.last_terms();
+            let reference_terms = reference_stabilizer.last_terms();
+            for (terms, reference_terms) in terms.iter().zip(reference_terms) {
+                assert_eq!(reference_terms.proportional, terms.proportional);
+                assert_eq!(reference_terms.integral, terms.integral);
+                assert_eq!(reference_terms.derivative, terms.derivative);
+            }
+        }
+
+        // Reset clears the disturbance
+        stabilizer.reset();
+        reference_stabilizer.reset();
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let reference_output =
+            reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        assert_eq!(
+            reference_output, output,
+            "Reset should clear the disturbance."
+        );
+    }
+
+    /// Test that a non-positive dt holds the integrals without producing `NaN`.
+    #[test]
+    fn test_stabilizer_angle_non_positive_dt() {
+        let mut config = default_config();
+        config.output_min = Some(-0.01);
+        config.output_max = Some(0.01);
+        config.kt_roll = 1.0;
+        config.kt_pitch = 1.0;
+        config.kt_yaw = 1.0;
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (100.0, -100.0, 50.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, 1.0); // current roll rate, pitch rate, yaw rate
+        let low_throttle = false;
+
+        for dt in [0.0, -0.01] {
+            let mut stabilizer = AngleStabilizer::with_config(config);
+            stabilizer.roll_pid.integral = 0.2; // previous integral
+            stabilizer.pitch_pid.integral = -0.2; // previous integral
+            stabilizer.yaw_pid.integral = 0.2; // previous integral
+
+            // Perform the control computation
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+
+            assert!(
+                !output.0.is_nan() && !output.1.is_nan() && !output.2.is_nan(),
+                "Outputs should not be NaN."
+            );
+            assert!(
+                vector_close(
+                    (0.2, -0.2, 0.2),
+                    (
+                        stabilizer.roll_pid.integral,
+                        stabilizer.pitch_pid.integral,
+                        stabilizer.yaw_pid.integral
+                    )
+                ),
+                "Integrals should be unchanged."
+            );
+        }
+    }
+
+    /// Test that per-axis scales scale each output independently.
+    #[test]
+    fn test_stabilizer_angle_per_axis_scale() {
+        let config = default_config();
+        let mut scaled_config = config;
+        scaled_config.scale_roll = Some(2.0 * config.scale);
+        scaled_config.scale_pitch = Some(0.5 * config.scale);
+        scaled_config.scale_yaw = Some(3.0 * config.scale);
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut scaled_stabilizer = AngleStabilizer::with_config(scaled_config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 1.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Perform the control computation
+        let (roll, pitch, yaw) =
+            stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let output =
+            scaled_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output = (2.0 * roll, 0.5 * pitch, 3.0 * yaw);
+
+        assert!(
+            vector_close(expected_output, output),
+            "Each output should use its own scale."
+        );
+    }
+
+    /// Test that a stored setpoint persists across control calls.
+    #[test]
+    fn test_stabilizer_angle_stored_setpoints() {
+        let config = default_config();
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut explicit_stabilizer = AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 1.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        stabilizer.set_setpoints(set_point.0, set_point.1, set_point.2);
+        for _ in 0..10 {
+            let output = stabilizer.control_stored(imu_attitude, gyro_rate, dt, low_throttle);
+            let expected_output =
+                explicit_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert_eq!(
+                expected_output, output,
+                "Output should match the explicit setpoint."
+            );
+        }
+        assert_eq!(set_point, stabilizer.setpoints());
+
+        // Explicit setpoints do not replace the stored setpoints
+        let _ = stabilizer.control((0.0, 0.0, 0.0), imu_attitude, gyro_rate, dt, low_throttle);
+        assert_eq!(set_point, stabilizer.setpoints());
+    }
+
+    /// Test that the fixed-rate step matches the variable-rate step with the same
+    /// constant `dt`, with the rate limits and back-calculation active.
+    #[test]
+    fn test_stabilizer_angle_fixed_rate() {
+        let mut config = default_config();
+        config.max_set_point_rate_roll = Some(100.0);
+        config.max_set_point_rate_yaw = Some(50.0);
+        config.max_output_rate_pitch = Some(2.0);
+        config.kt_roll = 5.0;
+        config.kt_yaw = 2.0;
+        config.output_min = Some(-0.05);
+        config.output_max = Some(0.05);
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut reference_stabilizer = AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (30.0, -30.0, 30.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        stabilizer.set_fixed_rate(dt).unwrap();
+
+        for step in 0..50 {
+            let low_throttle = step == 20;
+            let output = stabilizer
+                .control_fixed_rate(set_point, imu_attitude, gyro_rate, low_throttle)
+                .unwrap();
+            let expected =
+                reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert_eq!(expected, output, "Outputs should match at step {}.", step);
+        }
+        assert_eq!(reference_stabilizer.snapshot(), stabilizer.snapshot());
+
+        // The loop period must be positive and finite, and follows a new configuration
+        assert_eq!(
+            Err(StabilizerError::NoLoopPeriod),
+            AngleStabilizer::with_config(config).control_fixed_rate(
+                set_point,
+                imu_attitude,
+                gyro_rate,
+                false
+            )
+        );
+        assert_eq!(
+            Err(StabilizerError::NonPositiveDt),
+            stabilizer.set_fixed_rate(0.0)
+        );
+        assert_eq!(
+            Err(StabilizerError::NotFinite("dt")),
+            stabilizer.set_fixed_rate(f32::INFINITY)
+        );
+        assert_eq!(
+            Some(dt),
+            stabilizer.fixed_rate().map(|fixed_rate| fixed_rate.dt)
+        );
+        config.max_output_rate_roll = Some(1.0);
+        stabilizer.apply_config(&config);
+        assert_eq!(
+            Some(&config.fixed_rate(dt)),
+            stabilizer.fixed_rate(),
+            "Coefficients should follow the new configuration."
+        );
+    }
+
+    /// Proportional-only configuration for the quantization tests, with unrounded
+    /// outputs of 0.02037, -0.0146, and 0.01515 for the inputs below.
+    fn quantization_config() -> FlightStabilizerConfig<f32> {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.kp_roll = 0.2;
+        config.kp_pitch = 0.2;
+        config.kp_yaw = 0.3;
+        config.ki_roll = 0.0;
+        config.ki_pitch = 0.0;
+        config.ki_yaw = 0.0;
+        config.kd_roll = 0.0;
+        config.kd_pitch = 0.0;
+        config.kd_yaw = 0.0;
+        config.scale = 0.01;
+        config.output_quantum = 1.0 / 1024.0;
+        config
+    }
+
+    const QUANTIZATION_SET_POINT: (f32, f32, f32) = (10.185, -7.3, 5.05);
+
+    /// Test that quantized outputs land on the grid nearest the unrounded outputs.
+    #[test]
+    fn test_stabilizer_angle_output_quantization() {
+        let config = quantization_config();
+        let mut unrounded_config = config;
+        unrounded_config.output_quantum = 0.0;
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut unrounded_stabilizer = AngleStabilizer::with_config(unrounded_config);
+        let zero = (0.0, 0.0, 0.0);
+
+        for _ in 0..10 {
+            let (roll, pitch, yaw) =
+                stabilizer.control(QUANTIZATION_SET_POINT, zero, zero, 0.01, false);
+            let unrounded =
+                unrounded_stabilizer.control(QUANTIZATION_SET_POINT, zero, zero, 0.01, false);
+            for (output, unrounded) in [
+                (roll, unrounded.0),
+                (pitch, unrounded.1),
+                (yaw, unrounded.2),
+            ] {
+                assert_eq!(
+                    0.0,
+                    (output * 1024.0) % 1.0,
+                    "Output should be on the grid."
+                );
+                assert!((output - unrounded).abs() <= 0.5 / 1024.0);
+            }
+            assert_eq!(roll, stabilizer.last_telemetry()[0].output);
+        }
+        assert_eq!(unrounded_stabilizer.last_terms(), stabilizer.last_terms());
+    }
+
+    /// Test that rounding without feedback biases the outputs over many steps,
+    /// while feedback keeps the accumulated outputs within a step of unrounded.
+    #[test]
+    fn test_stabilizer_angle_quantization_feedback() {
+        let zero = (0.0, 0.0, 0.0);
+        let step = 1.0 / 1024.0;
+        let ticks = 1000;
+        let unrounded = {
+            let mut config = quantization_config();
+            config.output_quantum = 0.0;
+            AngleStabilizer::with_config(config).control(
+                QUANTIZATION_SET_POINT,
+                zero,
+                zero,
+                0.01,
+                false,
+            )
+        };
+        let accumulated_bias = |feedback: bool| {
+            let mut config = quantization_config();
+            config.quantization_feedback = feedback;
+            let mut stabilizer = AngleStabilizer::with_config(config);
+            let mut bias = (0.0, 0.0, 0.0);
+            for _ in 0..ticks {
+                let output = stabilizer.control(QUANTIZATION_SET_POINT, zero, zero, 0.01, false);
+                bias.0 += output.0 - unrounded.0;
+                bias.1 += output.1 - unrounded.1;
+                bias.2 += output.2 - unrounded.2;
+            }
+            bias
+        };
+
+        // Rounding alone is off by a constant fraction of a step every tick
+        let bias = accumulated_bias(false);
+        assert!(20.0 * step < bias.0.abs(), "{:?}", bias);
+        assert!(20.0 * step < bias.1.abs(), "{:?}", bias);
+        assert!(20.0 * step < bias.2.abs(), "{:?}", bias);
+
+        // Feedback dithers the outputs so the rounding errors cancel
+        let bias = accumulated_bias(true);
+        assert!(bias.0.abs() <= step, "{:?}", bias);
+        assert!(bias.1.abs() <= step, "{:?}", bias);
+        assert!(bias.2.abs() <= step, "{:?}", bias);
+    }
+
+    /// Test that every control method records its time step in the timing statistics.
+    #[test]
+    fn test_stabilizer_angle_timing_stats() {
+        let mut stabilizer = AngleStabilizer::with_config(default_config());
+        let zero = (0.0, 0.0, 0.0);
+        assert_eq!(TimingStats::new(), stabilizer.timing_stats());
+
+        stabilizer.control(zero, zero, zero, 0.01, false);
+        stabilizer.control(zero, zero, zero, 0.03, false);
+        stabilizer.control_throttle(zero, zero, zero, 0.01, 0.5);
+        stabilizer.set_fixed_rate(0.02).unwrap();
+        stabilizer
+            .control_fixed_rate(zero, zero, zero, false)
+            .unwrap();
+        let stats = stabilizer.timing_stats();
+        assert_eq!(4, stats.count);
+        assert_eq!((0.01, 0.03, 0.02), (stats.min, stats.max, stats.last));
+        assert!(value_close(0.0175, stats.mean));
+        // The changes are 0.02, 0.02, and 0.01
+        assert!(value_close(0.05 / 3.0, stats.jitter));
+
+        stabilizer.reset();
+        assert_eq!(TimingStats::new(), stabilizer.timing_stats());
+    }
+
+    /// Test that filtering the roll derivative and notching the roll rate leave the
+    /// pitch and yaw derivatives unfiltered.
+    #[test]
+    fn test_stabilizer_angle_per_axis_derivative_filter() {
+        let mut config = default_config();
+        config.derivative_alpha_roll = 0.25;
+        config.notch_center_hz_roll = Some(100.0);
+        config.notch_q = 2.0;
+        assert_eq!(Ok(()), config.validate());
+        assert_eq!((100.0, 0.0, 0.0), config.notch_centers());
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut unfiltered_stabilizer = AngleStabilizer::with_config(default_config());
+
+        let zero = (0.0, 0.0, 0.0);
+        let dt = 0.001; // 1 kHz loop
+        let mut prev_derivative = 0.0;
+        let mut max_notched: f32 = 0.0;
+        for step in 0..100 {
+            let time = step as f32 * dt;
+            let vibration = (2.0 * core::f32::consts::PI * 100.0 * time).sin();
+            let gyro_rate = (1.0 + vibration, -1.0 + vibration, 0.5 + vibration);
+            stabilizer.control(zero, zero, gyro_rate, dt, false);
+            unfiltered_stabilizer.control(zero, zero, gyro_rate, dt, false);
+            let [roll, pitch, yaw] = stabilizer.last_telemetry();
+            let [_, unfiltered_pitch, unfiltered_yaw] = unfiltered_stabilizer.last_telemetry();
+
+            // The roll derivative smooths the notched rate
+            let expected = 0.25 * roll.filtered_rate + 0.75 * prev_derivative;
+            assert!(value_close(expected, roll.derivative));
+            prev_derivative = roll.derivative;
+            max_notched = max_notched.max((roll.raw_rate - roll.filtered_rate).abs());
+
+            // Pitch and yaw match the stabilizer without filters
+            assert_eq!(unfiltered_pitch, pitch);
+            assert_eq!(unfiltered_yaw, yaw);
+            assert_eq!(pitch.raw_rate, pitch.filtered_rate);
+        }
+        assert!(0.5 < max_notched, "{}", max_notched);
     }
 }
@@ -7,8 +7,14 @@
 //! It requires three configuration files- one of the angle-based PID,
 //! one for the rate-based PID, and one for the blending.
 
+use super::state::PidState;
 use crate::pid::{compute_cascade_angle, compute_rate, CascadeAngleControlData, RateControlData};
-use crate::{CascadeBlendingConfig, FlightStabilizer, FlightStabilizerConfig, Number};
+use crate::pid::{compute_terms, NotchFilter, PidTerms};
+use crate::{
+    blend_cascade_with_saturation, freeze_axes, integral_axes, select_axes, track_integrals,
+    CascadeBlendingConfig, FixedRate, FlightStabilizer, FlightStabilizerConfig, IntegralDivider,
+    Number, SaturationState, StabilizerError, StabilizerState, Telemetry, TimingStats,
+};
 use piddiy::PidController;
 
 /// Struct representing the Angle2 PID Flight Stabilization Controller.
@@ -17,18 +23,28 @@ pub struct Angle2FullStabilizer<T: Number> {
     angle_roll_pid: PidController<T, CascadeAngleControlData<T>>,
     angle_pitch_pid: PidController<T, CascadeAngleControlData<T>>,
     angle_yaw_pid: PidController<T, CascadeAngleControlData<T>>,
-    angle_i_limit: T,
-    angle_scale: T,
+    angle_config: FlightStabilizerConfig<T>,
     rate_roll_pid: PidController<T, RateControlData<T>>,
     rate_pitch_pid: PidController<T, RateControlData<T>>,
     rate_yaw_pid: PidController<T, RateControlData<T>>,
-    rate_i_limit: T,
-    rate_scale: T,
-    kl: T,             // blending factor for angle and rate
-    beta_roll: T,      // blending factor for angle and rate
-    beta_pitch: T,     // blending factor for angle and rate
-    beta_yaw: T,       // blending factor for angle and rate
-    blending_limit: T, // blending factor for angle and rate
+    rate_config: FlightStabilizerConfig<T>,
+    blending_config: CascadeBlendingConfig<T, 3>,
+    set_point: (T, T, T),
+    stored_set_point: (T, T, T),
+    terms: [PidTerms<T>; 3],
+    telemetry: [Telemetry<T>; 3],
+    saturation: [SaturationState; 3],
+    prev_output: (T, T, T),
+    quantization_error: (T, T, T),
+    timing: TimingStats<T>,
+    fixed_rates: Option<[FixedRate<T>; 2]>,
+    angle_integral_divider: IntegralDivider<T>,
+    rate_integral_divider: IntegralDivider<T>,
+    notch: [NotchFilter<T>; 3],
+    enabled_axes: [bool; 3],
+    frozen_integrals: [bool; 3],
+    disturbance: (T, T, T),
+    filtered_attitude: Option<(T, T, T)>,
     prev_set_point_roll: T,
     prev_set_point_pitch: T,
     prev_set_point_yaw: T,
@@ -102,18 +118,28 @@ impl<T: Number> Angle2FullStabilizer<T> {
             angle_roll_pid,
             angle_pitch_pid,
             angle_yaw_pid,
-            angle_i_limit: angle_config.i_limit,
-            angle_scale: angle_config.scale,
+            angle_config,
             rate_roll_pid,
             rate_pitch_pid,
             rate_yaw_pid,
-            rate_i_limit: rate_config.i_limit,
-            rate_scale: rate_config.scale,
-            kl: blending_config.k,
-            beta_roll: blending_config.beta[0],
-            beta_pitch: blending_config.beta[1],
-            beta_yaw: blending_config.beta[2],
-            blending_limit: blending_config.limit,
+            rate_config,
+            blending_config,
+            set_point: angle_config.set_points(),
+            stored_set_point: angle_config.set_points(),
+            terms: [PidTerms::new(); 3],
+            telemetry: [Telemetry::new(); 3],
+            saturation: [SaturationState::None; 3],
+            prev_output: (T::zero(), T::zero(), T::zero()),
+            quantization_error: (T::zero(), T::zero(), T::zero()),
+            timing: TimingStats::new(),
+            fixed_rates: None,
+            angle_integral_divider: IntegralDivider::new(),
+            rate_integral_divider: IntegralDivider::new(),
+            notch: [NotchFilter::default(); 3],
+            enabled_axes: [true; 3],
+            frozen_integrals: [false; 3],
+            disturbance: (T::zero(), T::zero(), T::zero()),
+            filtered_attitude: None,
             prev_set_point_roll: angle_config.set_point_roll,
             prev_set_point_pitch: angle_config.set_point_pitch,
             prev_set_point_yaw: angle_config.set_point_yaw,
@@ -132,79 +158,316 @@ impl<T: Number> Angle2FullStabilizer<T> {
         )
     }
 
-    fn blend(&self, set_point: T, prev_set_point: T, beta: T) -> T {
-        let result = (set_point * self.kl).clamp(-self.blending_limit, self.blending_limit);
-        beta * result + (T::one() - beta) * prev_set_point
+    /// Returns the configuration of the outer angle loop.
+    pub fn angle_config(&self) -> &FlightStabilizerConfig<T> {
+        &self.angle_config
     }
-}
 
-impl<T: Number> FlightStabilizer<T> for Angle2FullStabilizer<T> {
-    fn control(
+    /// Returns the configuration of the inner rate loop.
+    pub fn rate_config(&self) -> &FlightStabilizerConfig<T> {
+        &self.rate_config
+    }
+
+    /// Returns the configuration of the angle to rate blending.
+    pub fn blending_config(&self) -> &CascadeBlendingConfig<T, 3> {
+        &self.blending_config
+    }
+
+    /// Returns the gain-multiplied PID terms of the inner rate loop from the
+    /// most recent control step for roll, pitch, and yaw. The terms are unscaled,
+    /// so their sum times the rate axis scale is the output before clamping.
+    pub fn last_terms(&self) -> [PidTerms<T>; 3] {
+        self.terms
+    }
+
+    /// Returns the raw output of the most recent control step for roll, pitch, and yaw,
+    /// the sum of the `last_terms` before the rate axis scale is applied. The raw output times
+    /// the scale is the output before clamping, rate limiting, and the deadband.
+    pub fn last_raw_output(&self) -> (T, T, T) {
+        let [roll, pitch, yaw] = self.terms;
+        (roll.sum(), pitch.sum(), yaw.sum())
+    }
+
+    /// Returns a copy of the internal state, like the integrals, previous errors
+    /// and filter histories, to `restore` later. The configuration and gains are not included.
+    pub fn snapshot(&self) -> StabilizerState<T> {
+        let mut state = StabilizerState::new();
+        state.pids[0] = [
+            PidState::from_pid(&self.angle_roll_pid),
+            PidState::from_pid(&self.angle_pitch_pid),
+            PidState::from_pid(&self.angle_yaw_pid),
+        ];
+        state.pids[1] = [
+            PidState::from_pid(&self.rate_roll_pid),
+            PidState::from_pid(&self.rate_pitch_pid),
+            PidState::from_pid(&self.rate_yaw_pid),
+        ];
+        state.set_point = self.set_point;
+        state.stored_set_point = self.stored_set_point;
+        state.terms = self.terms;
+        state.saturation = self.saturation;
+        state.prev_output = self.prev_output;
+        state.quantization_error = self.quantization_error;
+        state.notch = self.notch;
+        state.disturbance = self.disturbance;
+        state.filtered_attitude = self.filtered_attitude;
+        state.history[0] = (
+            self.prev_set_point_roll,
+            self.prev_set_point_pitch,
+            self.prev_set_point_yaw,
+        );
+        state.history[1] = (self.prev_imu_roll, self.prev_imu_pitch, self.prev_imu_yaw);
+        state.integral_dividers[0] = self.angle_integral_divider;
+        state.integral_dividers[1] = self.rate_integral_divider;
+        state
+    }
+
+    /// Restores the internal state from a `snapshot` of this controller.
+    pub fn restore(&mut self, state: &StabilizerState<T>) {
+        state.pids[0][0].restore(&mut self.angle_roll_pid);
+        state.pids[0][1].restore(&mut self.angle_pitch_pid);
+        state.pids[0][2].restore(&mut self.angle_yaw_pid);
+        state.pids[1][0].restore(&mut self.rate_roll_pid);
+        state.pids[1][1].restore(&mut self.rate_pitch_pid);
+        state.pids[1][2].restore(&mut self.rate_yaw_pid);
+        self.set_point = state.set_point;
+        self.stored_set_point = state.stored_set_point;
+        self.terms = state.terms;
+        self.saturation = state.saturation;
+        self.prev_output = state.prev_output;
+        self.quantization_error = state.quantization_error;
+        self.notch = state.notch;
+        self.disturbance = state.disturbance;
+        self.filtered_attitude = state.filtered_attitude;
+        (
+            self.prev_set_point_roll,
+            self.prev_set_point_pitch,
+            self.prev_set_point_yaw,
+        ) = state.history[0];
+        (self.prev_imu_roll, self.prev_imu_pitch, self.prev_imu_yaw) = state.history[1];
+        self.angle_integral_divider = state.integral_dividers[0];
+        self.rate_integral_divider = state.integral_dividers[1];
+    }
+
+    /// Sets a constant loop period for `control_fixed_rate`, precomputing the
+    /// coefficients that depend on it. The setpoint rate limits come from the angle
+    /// loop, and the output rate limits and back-calculation from the rate loop.
+    /// Returns an error, leaving the loop period unchanged, if `dt` is not positive
+    /// and finite.
+    pub fn set_fixed_rate(&mut self, dt: T) -> Result<(), StabilizerError> {
+        if !dt.is_finite() {
+            return Err(StabilizerError::NotFinite("dt"));
+        }
+        if dt <= T::zero() {
+            return Err(StabilizerError::NonPositiveDt);
+        }
+        self.fixed_rates = Some(self.fixed_rates_at(dt));
+        Ok(())
+    }
+
+    /// Returns the precomputed (angle, rate) coefficients of the loop period set by
+    /// `set_fixed_rate`, or `None` if no loop period is set.
+    pub fn fixed_rates(&self) -> Option<&[FixedRate<T>; 2]> {
+        self.fixed_rates.as_ref()
+    }
+
+    /// Computes the control outputs like `control`, with the loop period and the
+    /// coefficients precomputed by `set_fixed_rate` instead of a `dt` argument.
+    /// The outputs match `control` with the same constant `dt`.
+    /// Returns `StabilizerError::NoLoopPeriod` if no loop period is set.
+    pub fn control_fixed_rate(
         &mut self,
         set_point: (T, T, T),
         imu_attitude: (T, T, T),
         gyro_rate: (T, T, T),
-        dt: T,
         low_throttle: bool,
+    ) -> Result<(T, T, T), StabilizerError> {
+        let fixed_rates = self.fixed_rates.ok_or(StabilizerError::NoLoopPeriod)?;
+        Ok(self.control_with(
+            set_point,
+            imu_attitude,
+            gyro_rate,
+            fixed_rates,
+            low_throttle,
+            T::one(),
+        ))
+    }
+
+    /// Returns the (angle, rate) coefficients of the loop period `dt`.
+    fn fixed_rates_at(&self, dt: T) -> [FixedRate<T>; 2] {
+        [
+            self.angle_config.fixed_rate(dt),
+            self.rate_config.fixed_rate(dt),
+        ]
+    }
+
+    /// Computes the control outputs for `control` and `control_throttle`.
+    fn control_with(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        fixed_rates: [FixedRate<T>; 2],
+        low_throttle: bool,
+        throttle: T,
     ) -> (T, T, T) {
+        let [angle_fixed_rate, rate_fixed_rate] = fixed_rates;
+        let dt = angle_fixed_rate.dt;
+        self.timing.record(dt);
+
+        // Reset the integrals like a low throttle while idling
+        let low_throttle = low_throttle || self.rate_config.is_idle(throttle);
+
+        // Hold the integrals of disabled and frozen axes
+        let held_angle_integrals = [
+            self.angle_roll_pid.integral,
+            self.angle_pitch_pid.integral,
+            self.angle_yaw_pid.integral,
+        ];
+        let held_rate_integrals = [
+            self.rate_roll_pid.integral,
+            self.rate_pitch_pid.integral,
+            self.rate_yaw_pid.integral,
+        ];
+
+        // Smooth the attitude measurements, restarting the filter at low throttle
+        let previous_attitude = if low_throttle {
+            None
+        } else {
+            self.filtered_attitude
+        };
+        let imu_attitude = self
+            .angle_config
+            .filter_attitude(previous_attitude, imu_attitude);
+        self.filtered_attitude = Some(imu_attitude);
+
+        // Map the measurements and setpoints onto the reversed and coupled axes
+        let imu_attitude = self.angle_config.transform_axes(imu_attitude);
+        let gyro_rate = self.angle_config.transform_axes(gyro_rate);
+
+        // Update the integrals every `integral_divisor` ticks over the summed time steps
+        let angle_integral_divider = self
+            .angle_integral_divider
+            .step(self.angle_config.integral_divisor, dt);
+        let angle_max_integral_rate = self.angle_config.max_integral_rate * angle_integral_divider;
+        let rate_integral_divider = self
+            .rate_integral_divider
+            .step(self.rate_config.integral_divisor, dt);
+        let rate_max_integral_rate = self.rate_config.max_integral_rate * rate_integral_divider;
+
+        // Scale integral accumulation by the throttle fraction, stopping it on frozen axes
+        let angle_integral_scales = freeze_axes(
+            self.frozen_integrals,
+            self.angle_config.integral_throttle_scale(throttle) * angle_integral_divider,
+        );
+        let rate_integral_scales = freeze_axes(
+            self.frozen_integrals,
+            self.rate_config.integral_throttle_scale(throttle) * rate_integral_divider,
+        );
+
+        // Limit the rate of change of the setpoints
+        self.set_point = angle_fixed_rate.slew_set_points(self.set_point, set_point);
+
         // Set the setpoints for roll, pitch, and yaw
-        let (set_point_roll, set_point_pitch, set_point_yaw) = set_point;
+        let (set_point_roll, set_point_pitch, set_point_yaw) =
+            self.angle_config.transform_axes(self.set_point);
         self.angle_roll_pid.set_point(set_point_roll);
         self.angle_pitch_pid.set_point(set_point_pitch);
         self.angle_yaw_pid.set_point(set_point_yaw);
 
         // Prepare control data for roll and pitch
+        let angle_ki = (
+            self.angle_roll_pid.ki,
+            self.angle_pitch_pid.ki,
+            self.angle_yaw_pid.ki,
+        );
+        let (angle_i_limit_roll, angle_i_limit_pitch, angle_i_limit_yaw) =
+            self.angle_config.integral_limits(angle_ki);
         let (imu_roll, imu_pitch, imu_yaw) = imu_attitude;
+        let (raw_roll, raw_pitch, raw_yaw) = gyro_rate;
+        // Remove motor vibration from the gyro rates feeding the derivative
+        let gyro_rate = self.rate_config.notch_rates(&mut self.notch, gyro_rate, dt);
         let (gyro_roll, gyro_pitch, gyro_yaw) = gyro_rate;
         let angle_roll_data = CascadeAngleControlData {
             measurement: imu_roll,
             prev_measurement: self.prev_imu_roll,
             rate: gyro_roll,
             dt,
-            integral_limit: self.angle_i_limit,
+            measurement_updated: true,
+            integral_limit: angle_i_limit_roll,
             reset_integral: low_throttle,
+            integral_scale: angle_integral_scales[0],
+            max_integral_rate: angle_max_integral_rate,
+            d_term_limit: self.angle_config.d_term_limit_roll,
+            wrap: None,
         };
         let angle_pitch_data = CascadeAngleControlData {
             measurement: imu_pitch,
             prev_measurement: self.prev_imu_pitch,
             rate: gyro_pitch,
             dt,
-            integral_limit: self.angle_i_limit,
+            measurement_updated: true,
+            integral_limit: angle_i_limit_pitch,
             reset_integral: low_throttle,
+            integral_scale: angle_integral_scales[1],
+            max_integral_rate: angle_max_integral_rate,
+            d_term_limit: self.angle_config.d_term_limit_pitch,
+            wrap: None,
         };
         let angle_yaw_data = CascadeAngleControlData {
             measurement: imu_yaw,
             prev_measurement: self.prev_imu_yaw,
             rate: gyro_yaw,
             dt,
-            integral_limit: self.angle_i_limit,
+            measurement_updated: true,
+            integral_limit: angle_i_limit_yaw,
             reset_integral: low_throttle,
+            integral_scale: angle_integral_scales[2],
+            max_integral_rate: angle_max_integral_rate,
+            d_term_limit: self.angle_config.d_term_limit_yaw,
+            wrap: self.angle_config.yaw_wrap_range(),
         };
 
         // Compute outputs for roll, pitch, and yaw
-        let mut adjusted_set_point_roll =
-            self.angle_scale * self.angle_roll_pid.compute(angle_roll_data);
-        let mut adjusted_set_point_pitch =
-            self.angle_scale * self.angle_pitch_pid.compute(angle_pitch_data);
-        let mut adjusted_set_point_yaw =
-            self.angle_scale * self.angle_yaw_pid.compute(angle_yaw_data);
-
-        //Apply blending gain, clamp, and LP filter for artificial damping
-        adjusted_set_point_roll = self.blend(
-            adjusted_set_point_roll,
-            self.prev_set_point_roll,
-            self.beta_roll,
+        let (angle_scale_roll, angle_scale_pitch, angle_scale_yaw) = self.angle_config.scales();
+        let angle_roll_terms = compute_terms(
+            &mut self.angle_roll_pid,
+            compute_cascade_angle,
+            angle_roll_data,
+            self.angle_config.p_term_limit_roll,
         );
-        adjusted_set_point_pitch = self.blend(
-            adjusted_set_point_pitch,
-            self.prev_set_point_pitch,
-            self.beta_pitch,
+        let angle_pitch_terms = compute_terms(
+            &mut self.angle_pitch_pid,
+            compute_cascade_angle,
+            angle_pitch_data,
+            self.angle_config.p_term_limit_pitch,
         );
-        adjusted_set_point_yaw = self.blend(
-            adjusted_set_point_yaw,
-            self.prev_set_point_yaw,
-            self.beta_yaw,
+        let angle_yaw_terms = compute_terms(
+            &mut self.angle_yaw_pid,
+            compute_cascade_angle,
+            angle_yaw_data,
+            self.angle_config.p_term_limit_yaw,
         );
+        let adjusted_set_point_roll = angle_scale_roll * angle_roll_terms.sum();
+        let adjusted_set_point_pitch = angle_scale_pitch * angle_pitch_terms.sum();
+        let adjusted_set_point_yaw = angle_scale_yaw * angle_yaw_terms.sum();
+
+        //Apply blending gain, clamp, and LP filter for artificial damping
+        let [adjusted_set_point_roll, adjusted_set_point_pitch, adjusted_set_point_yaw] =
+            blend_cascade_with_saturation(
+                [
+                    adjusted_set_point_roll,
+                    adjusted_set_point_pitch,
+                    adjusted_set_point_yaw,
+                ],
+                [
+                    self.prev_set_point_roll,
+                    self.prev_set_point_pitch,
+                    self.prev_set_point_yaw,
+                ],
+                self.saturation.map(|state| state != SaturationState::None),
+                &self.blending_config,
+            );
 
         // Set the rate set points for roll, pitch, and yaw
         self.rate_roll_pid.set_point(adjusted_set_point_roll);
@@ -212,28 +475,115 @@ impl<T: Number> FlightStabilizer<T> for Angle2FullStabilizer<T> {
         self.rate_yaw_pid.set_point(adjusted_set_point_yaw);
 
         // Prepare rate control data for roll, pitch, and yaw
+        let rate_ki = (
+            self.rate_roll_pid.ki,
+            self.rate_pitch_pid.ki,
+            self.rate_yaw_pid.ki,
+        );
+        let (rate_i_limit_roll, rate_i_limit_pitch, rate_i_limit_yaw) =
+            self.rate_config.integral_limits(rate_ki);
         let rate_roll_data = RateControlData {
             rate: gyro_roll,
             dt,
-            integral_limit: self.rate_i_limit,
+            measurement_updated: true,
+            integral_limit: rate_i_limit_roll,
             reset_integral: low_throttle,
+            integral_scale: rate_integral_scales[0],
+            max_integral_rate: rate_max_integral_rate,
+            derivative_lpf_alpha: self.rate_config.derivative_alpha_roll,
+            d_term_limit: self.rate_config.d_term_limit_roll,
         };
         let rate_pitch_data = RateControlData {
             rate: gyro_pitch,
             dt,
-            integral_limit: self.rate_i_limit,
+            measurement_updated: true,
+            integral_limit: rate_i_limit_pitch,
             reset_integral: low_throttle,
+            integral_scale: rate_integral_scales[1],
+            max_integral_rate: rate_max_integral_rate,
+            derivative_lpf_alpha: self.rate_config.derivative_alpha_pitch,
+            d_term_limit: self.rate_config.d_term_limit_pitch,
         };
         let rate_yaw_data = RateControlData {
             rate: gyro_yaw,
             dt,
-            integral_limit: self.rate_i_limit,
+            measurement_updated: true,
+            integral_limit: rate_i_limit_yaw,
             reset_integral: low_throttle,
+            integral_scale: rate_integral_scales[2],
+            max_integral_rate: rate_max_integral_rate,
+            derivative_lpf_alpha: self.rate_config.derivative_alpha_yaw,
+            d_term_limit: self.rate_config.d_term_limit_yaw,
         };
+        // Feed-forward the setpoints and measured disturbance, bypassing the PID
+        let (disturbance_roll, disturbance_pitch, disturbance_yaw) =
+            self.angle_config.transform_axes(self.disturbance);
+        let roll_ff = self.rate_config.kff_roll * adjusted_set_point_roll
+            + self.rate_config.kdist_roll * disturbance_roll;
+        let pitch_ff = self.rate_config.kff_pitch * adjusted_set_point_pitch
+            + self.rate_config.kdist_pitch * disturbance_pitch;
+        let yaw_ff = self.rate_config.kff_yaw * adjusted_set_point_yaw
+            + self.rate_config.kdist_yaw * disturbance_yaw;
+
         // Compute outputs for roll, pitch, and yaw
-        let roll_output = self.rate_scale * self.rate_roll_pid.compute(rate_roll_data);
-        let pitch_output = self.rate_scale * self.rate_pitch_pid.compute(rate_pitch_data);
-        let yaw_output = self.rate_scale * self.rate_yaw_pid.compute(rate_yaw_data);
+        let (rate_scale_roll, rate_scale_pitch, rate_scale_yaw) = self.rate_config.scales();
+        let roll_terms = compute_terms(
+            &mut self.rate_roll_pid,
+            compute_rate,
+            rate_roll_data,
+            self.rate_config.p_term_limit_roll,
+        )
+        .with_feed_forward(roll_ff);
+        let pitch_terms = compute_terms(
+            &mut self.rate_pitch_pid,
+            compute_rate,
+            rate_pitch_data,
+            self.rate_config.p_term_limit_pitch,
+        )
+        .with_feed_forward(pitch_ff);
+        let yaw_terms = compute_terms(
+            &mut self.rate_yaw_pid,
+            compute_rate,
+            rate_yaw_data,
+            self.rate_config.p_term_limit_yaw,
+        )
+        .with_feed_forward(yaw_ff);
+        let roll_output = rate_scale_roll * roll_terms.sum();
+        let pitch_output = rate_scale_pitch * pitch_terms.sum();
+        let yaw_output = rate_scale_yaw * yaw_terms.sum();
+
+        // Record the PID terms for telemetry
+        self.terms = [roll_terms, pitch_terms, yaw_terms];
+
+        // Clamp outputs to the actuator range
+        let roll_clamped = self.rate_config.clamp_output(roll_output);
+        let pitch_clamped = self.rate_config.clamp_output(pitch_output);
+        let yaw_clamped = self.rate_config.clamp_output(yaw_output);
+        self.saturation = [
+            self.rate_config.saturation(roll_output),
+            self.rate_config.saturation(pitch_output),
+            self.rate_config.saturation(yaw_output),
+        ];
+
+        // Back-calculation anti-windup feeds the saturation back into the integral
+        let tracking = rate_fixed_rate.back_calculation(
+            (roll_clamped, pitch_clamped, yaw_clamped),
+            (roll_output, pitch_output, yaw_output),
+        );
+        [
+            self.rate_roll_pid.integral,
+            self.rate_pitch_pid.integral,
+            self.rate_yaw_pid.integral,
+        ] = track_integrals(
+            [
+                self.rate_roll_pid.integral,
+                self.rate_pitch_pid.integral,
+                self.rate_yaw_pid.integral,
+            ],
+            tracking,
+            (rate_i_limit_roll, rate_i_limit_pitch, rate_i_limit_yaw),
+            low_throttle,
+        );
 
         // Store prevous values
         self.prev_set_point_roll = adjusted_set_point_roll;
@@ -243,13 +593,214 @@ impl<T: Number> FlightStabilizer<T> for Angle2FullStabilizer<T> {
         self.prev_imu_pitch = imu_pitch;
         self.prev_imu_yaw = imu_yaw;
 
-        (roll_output, pitch_output, yaw_output)
+        // Limit the rate of change of the outputs
+        self.prev_output = rate_fixed_rate
+            .slew_outputs(self.prev_output, (roll_clamped, pitch_clamped, yaw_clamped));
+
+        // Disabled axes hold their integrals and output zero, and frozen axes
+        // hold their integrals unless reset at low throttle
+        let enabled = self.enabled_axes;
+        let integrals = integral_axes(enabled, self.frozen_integrals, low_throttle);
+        [
+            self.angle_roll_pid.integral,
+            self.angle_pitch_pid.integral,
+            self.angle_yaw_pid.integral,
+        ] = select_axes(
+            integrals,
+            [
+                self.angle_roll_pid.integral,
+                self.angle_pitch_pid.integral,
+                self.angle_yaw_pid.integral,
+            ],
+            held_angle_integrals,
+        );
+        [
+            self.rate_roll_pid.integral,
+            self.rate_pitch_pid.integral,
+            self.rate_yaw_pid.integral,
+        ] = select_axes(
+            integrals,
+            [
+                self.rate_roll_pid.integral,
+                self.rate_pitch_pid.integral,
+                self.rate_yaw_pid.integral,
+            ],
+            held_rate_integrals,
+        );
+        self.terms = select_axes(enabled, self.terms, [PidTerms::new(); 3]);
+        self.saturation = select_axes(enabled, self.saturation, [SaturationState::None; 3]);
+        let (roll, pitch, yaw) = self.prev_output;
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+        self.prev_output = (roll, pitch, yaw);
+        let (roll, pitch, yaw) = self.quantization_error;
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+
+        // Round the outputs to the actuator grid, carrying the rounding error forward
+        let (quantized, quantization_error) = self
+            .rate_config
+            .quantize_outputs(self.prev_output, (roll, pitch, yaw));
+        self.quantization_error = quantization_error;
+        let (roll_clamped, pitch_clamped, yaw_clamped) = quantized;
+
+        // Suppress jitter near zero after the integral has seen the clamped output
+        let output = (
+            self.rate_config.apply_deadband(roll_clamped),
+            self.rate_config.apply_deadband(pitch_clamped),
+            self.rate_config.apply_deadband(yaw_clamped),
+        );
+
+        // Hold the idle output below the idle throttle, handing off to the PID above it
+        let (roll, pitch, yaw) = self.rate_config.idle_outputs(throttle, output);
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+        let output = (roll, pitch, yaw);
+
+        // Record the output loop for telemetry
+        let [roll_terms, pitch_terms, yaw_terms] = self.terms;
+        self.telemetry = [
+            Telemetry::from_pid(
+                &self.rate_roll_pid,
+                roll_terms,
+                raw_roll,
+                gyro_roll,
+                output.0,
+            ),
+            Telemetry::from_pid(
+                &self.rate_pitch_pid,
+                pitch_terms,
+                raw_pitch,
+                gyro_pitch,
+                output.1,
+            ),
+            Telemetry::from_pid(&self.rate_yaw_pid, yaw_terms, raw_yaw, gyro_yaw, output.2),
+        ];
+        output
+    }
+}
+
+impl<T: Number> FlightStabilizer<T> for Angle2FullStabilizer<T> {
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        let fixed_rates = self.fixed_rates_at(dt);
+        self.control_with(
+            set_point,
+            imu_attitude,
+            gyro_rate,
+            fixed_rates,
+            low_throttle,
+            T::one(),
+        )
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        let fixed_rates = self.fixed_rates_at(dt);
+        self.control_with(
+            set_point,
+            imu_attitude,
+            gyro_rate,
+            fixed_rates,
+            false,
+            throttle,
+        )
+    }
+
+    fn reset(&mut self) {
+        self.angle_roll_pid.reset();
+        self.angle_pitch_pid.reset();
+        self.angle_yaw_pid.reset();
+        self.rate_roll_pid.reset();
+        self.rate_pitch_pid.reset();
+        self.rate_yaw_pid.reset();
+        self.set_point = self.angle_config.set_points();
+        self.stored_set_point = self.angle_config.set_points();
+        self.terms = [PidTerms::new(); 3];
+        self.telemetry = [Telemetry::new(); 3];
+        self.saturation = [SaturationState::None; 3];
+        self.prev_output = (T::zero(), T::zero(), T::zero());
+        self.quantization_error = (T::zero(), T::zero(), T::zero());
+        self.timing = TimingStats::new();
+        self.angle_integral_divider = IntegralDivider::new();
+        self.rate_integral_divider = IntegralDivider::new();
+        self.notch = [NotchFilter::default(); 3];
+        self.disturbance = (T::zero(), T::zero(), T::zero());
+        self.filtered_attitude = None;
+        self.prev_set_point_roll = self.angle_config.set_point_roll;
+        self.prev_set_point_pitch = self.angle_config.set_point_pitch;
+        self.prev_set_point_yaw = self.angle_config.set_point_yaw;
+        self.prev_imu_roll = T::zero();
+        self.prev_imu_pitch = T::zero();
+        self.prev_imu_yaw = T::zero();
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stored_set_point = (roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.disturbance = (roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stored_set_point
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.saturation
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        let ki = (
+            self.rate_roll_pid.ki,
+            self.rate_pitch_pid.ki,
+            self.rate_yaw_pid.ki,
+        );
+        let (i_limit_roll, i_limit_pitch, i_limit_yaw) = self.rate_config.integral_limits(ki);
+        self.rate_roll_pid.integral = roll.clamp(-i_limit_roll, i_limit_roll);
+        self.rate_pitch_pid.integral = pitch.clamp(-i_limit_pitch, i_limit_pitch);
+        self.rate_yaw_pid.integral = yaw.clamp(-i_limit_yaw, i_limit_yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.enabled_axes = [roll, pitch, yaw];
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.enabled_axes
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.frozen_integrals = [roll, pitch, yaw];
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.frozen_integrals
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        self.telemetry
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.timing
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::blend_cascade;
     use crate::test_utils::*;
 
     /// Default test configuration.
@@ -327,7 +878,7 @@ mod tests {
         assert_eq!(stabilizer.rate_roll_pid.kp, rate_config.kp_roll);
         assert_eq!(stabilizer.rate_pitch_pid.kp, rate_config.kp_pitch);
         assert_eq!(stabilizer.rate_yaw_pid.kp, rate_config.kp_yaw);
-        assert_eq!(stabilizer.beta_roll, blending_config.beta[0]);
+        assert_eq!(stabilizer.blending_config, blending_config);
     }
 
     /// Test that the integrator saturation works as expected by the DEFAULT_I_LIMIT.
@@ -502,68 +1053,84 @@ mod tests {
             measurement: imu_attitude.0,
             prev_measurement: imu_attitude.0,
             rate: gyro_rate.0,
-            dt: dt,
+            dt,
+            measurement_updated: true,
             integral_limit: angle_config.i_limit,
             reset_integral: low_throttle,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            d_term_limit: 0.0,
+            wrap: None,
         };
         let angle_pitch_data = CascadeAngleControlData {
             measurement: imu_attitude.1,
             prev_measurement: imu_attitude.1,
             rate: gyro_rate.1,
-            dt: dt,
+            dt,
+            measurement_updated: true,
             integral_limit: angle_config.i_limit,
             reset_integral: low_throttle,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            d_term_limit: 0.0,
+            wrap: None,
         };
         let angle_yaw_data = CascadeAngleControlData {
             measurement: imu_attitude.2,
             prev_measurement: imu_attitude.2,
             rate: gyro_rate.2,
-            dt: dt,
+            dt,
+            measurement_updated: true,
             integral_limit: angle_config.i_limit,
             reset_integral: low_throttle,
+            integral_scale: 1.0,
+            max_integral_rate: 0.0,
+            d_term_limit: 0.0,
+            wrap: angle_config.yaw_wrap_range(),
         };
 
         // Compute the adjusted roll setpoint and internal values
         stabilizer.angle_roll_pid.set_point(set_point.0);
         let (roll_error, roll_integral, roll_derivative) =
             compute_cascade_angle(&mut stabilizer.angle_roll_pid, angle_roll_data);
-        let mut adjusted_set_point_roll = angle_config.scale
+        let adjusted_set_point_roll = angle_config.scale
             * (angle_config.kp_roll * roll_error
                 + angle_config.ki_roll * roll_integral
                 + angle_config.kd_roll * roll_derivative);
-        adjusted_set_point_roll = stabilizer.blend(
-            adjusted_set_point_roll,
-            PREV_SET_POINT_ROLL,
-            blending_config.beta[0],
-        );
 
         // Compute the adjusted pitch setpoint and internal values
         stabilizer.angle_pitch_pid.set_point(set_point.1);
         let (pitch_error, pitch_integral, pitch_derivative) =
             compute_cascade_angle(&mut stabilizer.angle_pitch_pid, angle_pitch_data);
-        let mut adjusted_set_point_pitch = angle_config.scale
+        let adjusted_set_point_pitch = angle_config.scale
             * (angle_config.kp_pitch * pitch_error
                 + angle_config.ki_pitch * pitch_integral
                 + angle_config.kd_pitch * pitch_derivative);
-        adjusted_set_point_pitch = stabilizer.blend(
-            adjusted_set_point_pitch,
-            PREV_SET_POINT_PITCH,
-            blending_config.beta[1],
-        );
 
         // Compute the adjusted yaw setpoint and internal values
         stabilizer.angle_yaw_pid.set_point(set_point.2);
         let (yaw_error, yaw_integral, yaw_derivative) =
             compute_cascade_angle(&mut stabilizer.angle_yaw_pid, angle_yaw_data);
-        let mut adjusted_set_point_yaw = angle_config.scale
+        let adjusted_set_point_yaw = angle_config.scale
             * (angle_config.kp_yaw * yaw_error
                 + angle_config.ki_yaw * yaw_integral
                 + angle_config.kd_yaw * yaw_derivative);
-        adjusted_set_point_yaw = stabilizer.blend(
-            adjusted_set_point_yaw,
-            PREV_SET_POINT_YAW,
-            blending_config.beta[2],
-        );
+
+        // Blend the adjusted setpoints with the previous setpoints
+        let [adjusted_set_point_roll, adjusted_set_point_pitch, adjusted_set_point_yaw] =
+            blend_cascade(
+                [
+                    adjusted_set_point_roll,
+                    adjusted_set_point_pitch,
+                    adjusted_set_point_yaw,
+                ],
+                [
+                    PREV_SET_POINT_ROLL,
+                    PREV_SET_POINT_PITCH,
+                    PREV_SET_POINT_YAW,
+                ],
+                &blending_config,
+            );
 
         // Internal values should be inverted
         assert_eq!(roll_error, -pitch_error);
@@ -593,4 +1160,72 @@ mod tests {
             "PID outputs should match specific values."
         );
     }
+
+    /// Test that reset returns the controller to the state of a fresh controller.
+    #[test]
+    fn test_stabilizer_angle2_full_reset() {
+        let (angle_config, rate_config, blending_config) = default_config();
+        let mut stabilizer =
+            Angle2FullStabilizer::with_config(angle_config, rate_config, blending_config);
+        let mut fresh_stabilizer =
+            Angle2FullStabilizer::with_config(angle_config, rate_config, blending_config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 1.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Build up internal state
+        for _ in 0..10 {
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
+        stabilizer.reset();
+
+        // Perform the control computation
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output =
+            fresh_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        assert_eq!(
+            expected_output, output,
+            "Output should match a fresh controller."
+        );
+    }
+
+    /// Test that the fixed-rate step matches the variable-rate step with the same
+    /// constant `dt`, with the rate limits and back-calculation active.
+    #[test]
+    fn test_stabilizer_angle2_full_fixed_rate() {
+        let (mut angle_config, mut rate_config, blending_config) = default_config();
+        angle_config.max_set_point_rate_roll = Some(100.0);
+        angle_config.max_set_point_rate_yaw = Some(50.0);
+        rate_config.max_output_rate_pitch = Some(2.0);
+        rate_config.kt_roll = 5.0;
+        rate_config.kt_yaw = 2.0;
+        rate_config.output_min = Some(-0.05);
+        rate_config.output_max = Some(0.05);
+        let mut stabilizer =
+            Angle2FullStabilizer::with_config(angle_config, rate_config, blending_config);
+        let mut reference_stabilizer =
+            Angle2FullStabilizer::with_config(angle_config, rate_config, blending_config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (30.0, -30.0, 30.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        stabilizer.set_fixed_rate(dt).unwrap();
+
+        for step in 0..50 {
+            let low_throttle = step == 20;
+            let output = stabilizer
+                .control_fixed_rate(set_point, imu_attitude, gyro_rate, low_throttle)
+                .unwrap();
+            let expected =
+                reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert_eq!(expected, output, "Outputs should match at step {}.", step);
+        }
+        assert_eq!(reference_stabilizer.snapshot(), stabilizer.snapshot());
+    }
 }
@@ -0,0 +1,1498 @@
+// src/stabilizer/angle3.rs
+
+//! # Angle3 Cascade PID Flight Stabilization Controller
+//!
+//! This is a three stage angle, rate, and angular acceleration cascade PID flight control.
+//! Roll, pitch, and yaw all pass through every stage.
+//! It requires five configuration files- one for each of the angle, rate, and acceleration PIDs,
+//! one for the angle to rate blending, and one for the rate to acceleration blending.
+//!
+//! ## Blending
+//!
+//! A blending step sits between each pair of stages.
+//! The output of the outer stage is multiplied by `k`, clamped to `±limit`,
+//! and then low pass filtered with the previous setpoint of the inner stage
+//! as described by [`blend_cascade`].
+//! Each blending configuration has one `beta` per axis in roll, pitch, yaw order,
+//! so `beta` and `limit` are set independently for the angle to rate transition
+//! and the rate to acceleration transition.
+//! A `beta` of one disables the filter for that axis.
+//!
+//! The measured angular acceleration is the change in gyro rate over `dt`.
+
+use super::state::PidState;
+use crate::pid::{compute_cascade_angle, compute_rate, CascadeAngleControlData, RateControlData};
+use crate::pid::{compute_terms, NotchFilter, PidTerms};
+use crate::{
+    blend_cascade, blend_cascade_with_saturation, freeze_axes, integral_axes, select_axes,
+    track_integrals, CascadeBlendingConfig, FixedRate, FlightStabilizer, FlightStabilizerConfig,
+    IntegralDivider, Number, SaturationState, StabilizerError, StabilizerState, Telemetry,
+    TimingStats,
+};
+use piddiy::PidController;
+
+/// Struct representing the Angle3 PID Flight Stabilization Controller.
+/// This is a cascade PID controller that combines angle, rate, and angular acceleration.
+pub struct Angle3Stabilizer<T: Number> {
+    angle_roll_pid: PidController<T, CascadeAngleControlData<T>>,
+    angle_pitch_pid: PidController<T, CascadeAngleControlData<T>>,
+    angle_yaw_pid: PidController<T, CascadeAngleControlData<T>>,
+    angle_config: FlightStabilizerConfig<T>,
+    rate_roll_pid: PidController<T, RateControlData<T>>,
+    rate_pitch_pid: PidController<T, RateControlData<T>>,
+    rate_yaw_pid: PidController<T, RateControlData<T>>,
+    rate_config: FlightStabilizerConfig<T>,
+    accel_roll_pid: PidController<T, RateControlData<T>>,
+    accel_pitch_pid: PidController<T, RateControlData<T>>,
+    accel_yaw_pid: PidController<T, RateControlData<T>>,
+    accel_config: FlightStabilizerConfig<T>,
+    angle_blending_config: CascadeBlendingConfig<T, 3>, // angle to rate
+    rate_blending_config: CascadeBlendingConfig<T, 3>,  // rate to acceleration
+    set_point: (T, T, T),
+    stored_set_point: (T, T, T),
+    terms: [PidTerms<T>; 3],
+    telemetry: [Telemetry<T>; 3],
+    saturation: [SaturationState; 3],
+    prev_output: (T, T, T),
+    quantization_error: (T, T, T),
+    timing: TimingStats<T>,
+    fixed_rates: Option<[FixedRate<T>; 2]>,
+    angle_integral_divider: IntegralDivider<T>,
+    rate_integral_divider: IntegralDivider<T>,
+    accel_integral_divider: IntegralDivider<T>,
+    notch: [NotchFilter<T>; 3],
+    enabled_axes: [bool; 3],
+    frozen_integrals: [bool; 3],
+    disturbance: (T, T, T),
+    filtered_attitude: Option<(T, T, T)>,
+    prev_rate_set_point: (T, T, T),
+    prev_accel_set_point: (T, T, T),
+    prev_imu: (T, T, T),
+    prev_gyro: (T, T, T),
+    accel: (T, T, T),
+}
+
+impl<T: Number> Default for Angle3Stabilizer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Number> Angle3Stabilizer<T> {
+    /// Creates a new controller using the provided configuration
+    pub fn with_config(
+        angle_config: FlightStabilizerConfig<T>,
+        rate_config: FlightStabilizerConfig<T>,
+        accel_config: FlightStabilizerConfig<T>,
+        angle_blending_config: CascadeBlendingConfig<T, 3>,
+        rate_blending_config: CascadeBlendingConfig<T, 3>,
+    ) -> Self {
+        let mut angle_roll_pid = PidController::new();
+        angle_roll_pid
+            .compute_fn(compute_cascade_angle)
+            .set_point(angle_config.set_point_roll)
+            .kp(angle_config.kp_roll)
+            .ki(angle_config.ki_roll)
+            .kd(angle_config.kd_roll);
+
+        let mut angle_pitch_pid = PidController::new();
+        angle_pitch_pid
+            .compute_fn(compute_cascade_angle)
+            .set_point(angle_config.set_point_pitch)
+            .kp(angle_config.kp_pitch)
+            .ki(angle_config.ki_pitch)
+            .kd(angle_config.kd_pitch);
+
+        let mut angle_yaw_pid = PidController::new();
+        angle_yaw_pid
+            .compute_fn(compute_cascade_angle)
+            .set_point(angle_config.set_point_yaw)
+            .kp(angle_config.kp_yaw)
+            .ki(angle_config.ki_yaw)
+            .kd(angle_config.kd_yaw);
+
+        let mut rate_roll_pid = PidController::new();
+        rate_roll_pid
+            .compute_fn(compute_rate)
+            .set_point(rate_config.set_point_roll)
+            .kp(rate_config.kp_roll)
+            .ki(rate_config.ki_roll)
+            .kd(rate_config.kd_roll);
+
+        let mut rate_pitch_pid = PidController::new();
+        rate_pitch_pid
+            .compute_fn(compute_rate)
+            .set_point(rate_config.set_point_pitch)
+            .kp(rate_config.kp_pitch)
+            .ki(rate_config.ki_pitch)
+            .kd(rate_config.kd_pitch);
+
+        let mut rate_yaw_pid = PidController::new();
+        rate_yaw_pid
+            .compute_fn(compute_rate)
+            .set_point(rate_config.set_point_yaw)
+            .kp(rate_config.kp_yaw)
+            .ki(rate_config.ki_yaw)
+            .kd(rate_config.kd_yaw);
+
+        let mut accel_roll_pid = PidController::new();
+        accel_roll_pid
+            .compute_fn(compute_rate)
+            .set_point(accel_config.set_point_roll)
+            .kp(accel_config.kp_roll)
+            .ki(accel_config.ki_roll)
+            .kd(accel_config.kd_roll);
+
+        let mut accel_pitch_pid = PidController::new();
+        accel_pitch_pid
+            .compute_fn(compute_rate)
+            .set_point(accel_config.set_point_pitch)
+            .kp(accel_config.kp_pitch)
+            .ki(accel_config.ki_pitch)
+            .kd(accel_config.kd_pitch);
+
+        let mut accel_yaw_pid = PidController::new();
+        accel_yaw_pid
+            .compute_fn(compute_rate)
+            .set_point(accel_config.set_point_yaw)
+            .kp(accel_config.kp_yaw)
+            .ki(accel_config.ki_yaw)
+            .kd(accel_config.kd_yaw);
+
+        let zero = (T::zero(), T::zero(), T::zero());
+        Angle3Stabilizer {
+            angle_roll_pid,
+            angle_pitch_pid,
+            angle_yaw_pid,
+            angle_config,
+            rate_roll_pid,
+            rate_pitch_pid,
+            rate_yaw_pid,
+            rate_config,
+            accel_roll_pid,
+            accel_pitch_pid,
+            accel_yaw_pid,
+            accel_config,
+            angle_blending_config,
+            rate_blending_config,
+            set_point: angle_config.set_points(),
+            stored_set_point: angle_config.set_points(),
+            terms: [PidTerms::new(); 3],
+            telemetry: [Telemetry::new(); 3],
+            saturation: [SaturationState::None; 3],
+            prev_output: (T::zero(), T::zero(), T::zero()),
+            quantization_error: (T::zero(), T::zero(), T::zero()),
+            timing: TimingStats::new(),
+            fixed_rates: None,
+            angle_integral_divider: IntegralDivider::new(),
+            rate_integral_divider: IntegralDivider::new(),
+            accel_integral_divider: IntegralDivider::new(),
+            notch: [NotchFilter::default(); 3],
+            enabled_axes: [true; 3],
+            frozen_integrals: [false; 3],
+            disturbance: (T::zero(), T::zero(), T::zero()),
+            filtered_attitude: None,
+            prev_rate_set_point: zero,
+            prev_accel_set_point: zero,
+            prev_imu: zero,
+            prev_gyro: zero,
+            accel: zero,
+        }
+    }
+
+    /// Creates a new controller with default settings
+    pub fn new() -> Self {
+        Self::with_config(
+            FlightStabilizerConfig::new(),
+            FlightStabilizerConfig::new(),
+            FlightStabilizerConfig::new(),
+            CascadeBlendingConfig::new(),
+            CascadeBlendingConfig::new(),
+        )
+    }
+
+    /// Returns the configuration of the outer angle loop.
+    pub fn angle_config(&self) -> &FlightStabilizerConfig<T> {
+        &self.angle_config
+    }
+
+    /// Returns the configuration of the middle rate loop.
+    pub fn rate_config(&self) -> &FlightStabilizerConfig<T> {
+        &self.rate_config
+    }
+
+    /// Returns the configuration of the inner acceleration loop.
+    pub fn accel_config(&self) -> &FlightStabilizerConfig<T> {
+        &self.accel_config
+    }
+
+    /// Returns the configuration of the angle to rate blending.
+    pub fn angle_blending_config(&self) -> &CascadeBlendingConfig<T, 3> {
+        &self.angle_blending_config
+    }
+
+    /// Returns the configuration of the rate to acceleration blending.
+    pub fn rate_blending_config(&self) -> &CascadeBlendingConfig<T, 3> {
+        &self.rate_blending_config
+    }
+
+    /// Returns the gain-multiplied PID terms of the innermost acceleration loop
+    /// from the most recent control step for roll, pitch, and yaw. The terms are unscaled,
+    /// so their sum times the acceleration axis scale is the output before clamping.
+    pub fn last_terms(&self) -> [PidTerms<T>; 3] {
+        self.terms
+    }
+
+    /// Returns the raw output of the most recent control step for roll, pitch, and yaw,
+    /// the sum of the `last_terms` before the acceleration axis scale is applied. The raw output times
+    /// the scale is the output before clamping, rate limiting, and the deadband.
+    pub fn last_raw_output(&self) -> (T, T, T) {
+        let [roll, pitch, yaw] = self.terms;
+        (roll.sum(), pitch.sum(), yaw.sum())
+    }
+
+    /// Returns a copy of the internal state, like the integrals, previous errors
+    /// and filter histories, to `restore` later. The configuration and gains are not included.
+    pub fn snapshot(&self) -> StabilizerState<T> {
+        let mut state = StabilizerState::new();
+        state.pids[0] = [
+            PidState::from_pid(&self.angle_roll_pid),
+            PidState::from_pid(&self.angle_pitch_pid),
+            PidState::from_pid(&self.angle_yaw_pid),
+        ];
+        state.pids[1] = [
+            PidState::from_pid(&self.rate_roll_pid),
+            PidState::from_pid(&self.rate_pitch_pid),
+            PidState::from_pid(&self.rate_yaw_pid),
+        ];
+        state.pids[2] = [
+            PidState::from_pid(&self.accel_roll_pid),
+            PidState::from_pid(&self.accel_pitch_pid),
+            PidState::from_pid(&self.accel_yaw_pid),
+        ];
+        state.set_point = self.set_point;
+        state.stored_set_point = self.stored_set_point;
+        state.terms = self.terms;
+        state.saturation = self.saturation;
+        state.prev_output = self.prev_output;
+        state.quantization_error = self.quantization_error;
+        state.notch = self.notch;
+        state.disturbance = self.disturbance;
+        state.filtered_attitude = self.filtered_attitude;
+        state.history[0] = self.prev_rate_set_point;
+        state.history[1] = self.prev_accel_set_point;
+        state.history[2] = self.prev_imu;
+        state.history[3] = self.prev_gyro;
+        state.history[4] = self.accel;
+        state.integral_dividers[0] = self.angle_integral_divider;
+        state.integral_dividers[1] = self.rate_integral_divider;
+        state.integral_dividers[2] = self.accel_integral_divider;
+        state
+    }
+
+    /// Restores the internal state from a `snapshot` of this controller.
+    pub fn restore(&mut self, state: &StabilizerState<T>) {
+        state.pids[0][0].restore(&mut self.angle_roll_pid);
+        state.pids[0][1].restore(&mut self.angle_pitch_pid);
+        state.pids[0][2].restore(&mut self.angle_yaw_pid);
+        state.pids[1][0].restore(&mut self.rate_roll_pid);
+        state.pids[1][1].restore(&mut self.rate_pitch_pid);
+        state.pids[1][2].restore(&mut self.rate_yaw_pid);
+        state.pids[2][0].restore(&mut self.accel_roll_pid);
+        state.pids[2][1].restore(&mut self.accel_pitch_pid);
+        state.pids[2][2].restore(&mut self.accel_yaw_pid);
+        self.set_point = state.set_point;
+        self.stored_set_point = state.stored_set_point;
+        self.terms = state.terms;
+        self.saturation = state.saturation;
+        self.prev_output = state.prev_output;
+        self.quantization_error = state.quantization_error;
+        self.notch = state.notch;
+        self.disturbance = state.disturbance;
+        self.filtered_attitude = state.filtered_attitude;
+        self.prev_rate_set_point = state.history[0];
+        self.prev_accel_set_point = state.history[1];
+        self.prev_imu = state.history[2];
+        self.prev_gyro = state.history[3];
+        self.accel = state.history[4];
+        self.angle_integral_divider = state.integral_dividers[0];
+        self.rate_integral_divider = state.integral_dividers[1];
+        self.accel_integral_divider = state.integral_dividers[2];
+    }
+
+    /// Sets a constant loop period for `control_fixed_rate`, precomputing the
+    /// coefficients that depend on it. The setpoint rate limits come from the angle
+    /// loop, and the output rate limits and back-calculation from the acceleration loop.
+    /// Returns an error, leaving the loop period unchanged, if `dt` is not positive
+    /// and finite.
+    pub fn set_fixed_rate(&mut self, dt: T) -> Result<(), StabilizerError> {
+        if !dt.is_finite() {
+            return Err(StabilizerError::NotFinite("dt"));
+        }
+        if dt <= T::zero() {
+            return Err(StabilizerError::NonPositiveDt);
+        }
+        self.fixed_rates = Some(self.fixed_rates_at(dt));
+        Ok(())
+    }
+
+    /// Returns the precomputed (angle, acceleration) coefficients of the loop period set by
+    /// `set_fixed_rate`, or `None` if no loop period is set.
+    pub fn fixed_rates(&self) -> Option<&[FixedRate<T>; 2]> {
+        self.fixed_rates.as_ref()
+    }
+
+    /// Computes the control outputs like `control`, with the loop period and the
+    /// coefficients precomputed by `set_fixed_rate` instead of a `dt` argument.
+    /// The outputs match `control` with the same constant `dt`.
+    /// Returns `StabilizerError::NoLoopPeriod` if no loop period is set.
+    pub fn control_fixed_rate(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        low_throttle: bool,
+    ) -> Result<(T, T, T), StabilizerError> {
+        let fixed_rates = self.fixed_rates.ok_or(StabilizerError::NoLoopPeriod)?;
+        Ok(self.control_with(
+            set_point,
+            imu_attitude,
+            gyro_rate,
+            fixed_rates,
+            low_throttle,
+            T::one(),
+        ))
+    }
+
+    /// Returns the (angle, acceleration) coefficients of the loop period `dt`.
+    fn fixed_rates_at(&self, dt: T) -> [FixedRate<T>; 2] {
+        [
+            self.angle_config.fixed_rate(dt),
+            self.accel_config.fixed_rate(dt),
+        ]
+    }
+
+    /// Computes the control outputs for `control` and `control_throttle`.
+    fn control_with(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        fixed_rates: [FixedRate<T>; 2],
+        low_throttle: bool,
+        throttle: T,
+    ) -> (T, T, T) {
+        let [angle_fixed_rate, accel_fixed_rate] = fixed_rates;
+        let dt = angle_fixed_rate.dt;
+        self.timing.record(dt);
+
+        // Reset the integrals like a low throttle while idling
+        let low_throttle = low_throttle || self.accel_config.is_idle(throttle);
+
+        // Hold the integrals of disabled and frozen axes
+        let held_angle_integrals = [
+            self.angle_roll_pid.integral,
+            self.angle_pitch_pid.integral,
+            self.angle_yaw_pid.integral,
+        ];
+        let held_rate_integrals = [
+            self.rate_roll_pid.integral,
+            self.rate_pitch_pid.integral,
+            self.rate_yaw_pid.integral,
+        ];
+        let held_accel_integrals = [
+            self.accel_roll_pid.integral,
+            self.accel_pitch_pid.integral,
+            self.accel_yaw_pid.integral,
+        ];
+
+        // Smooth the attitude measurements, restarting the filter at low throttle
+        let previous_attitude = if low_throttle {
+            None
+        } else {
+            self.filtered_attitude
+        };
+        let imu_attitude = self
+            .angle_config
+            .filter_attitude(previous_attitude, imu_attitude);
+        self.filtered_attitude = Some(imu_attitude);
+
+        // Map the measurements and setpoints onto the reversed and coupled axes
+        let imu_attitude = self.angle_config.transform_axes(imu_attitude);
+        let gyro_rate = self.angle_config.transform_axes(gyro_rate);
+
+        // Update the integrals every `integral_divisor` ticks over the summed time steps
+        let angle_integral_divider = self
+            .angle_integral_divider
+            .step(self.angle_config.integral_divisor, dt);
+        let angle_max_integral_rate = self.angle_config.max_integral_rate * angle_integral_divider;
+        let rate_integral_divider = self
+            .rate_integral_divider
+            .step(self.rate_config.integral_divisor, dt);
+        let rate_max_integral_rate = self.rate_config.max_integral_rate * rate_integral_divider;
+        let accel_integral_divider = self
+            .accel_integral_divider
+            .step(self.accel_config.integral_divisor, dt);
+        let accel_max_integral_rate = self.accel_config.max_integral_rate * accel_integral_divider;
+
+        // Scale integral accumulation by the throttle fraction, stopping it on frozen axes
+        let angle_integral_scales = freeze_axes(
+            self.frozen_integrals,
+            self.angle_config.integral_throttle_scale(throttle) * angle_integral_divider,
+        );
+        let rate_integral_scales = freeze_axes(
+            self.frozen_integrals,
+            self.rate_config.integral_throttle_scale(throttle) * rate_integral_divider,
+        );
+        let accel_integral_scales = freeze_axes(
+            self.frozen_integrals,
+            self.accel_config.integral_throttle_scale(throttle) * accel_integral_divider,
+        );
+
+        // Limit the rate of change of the setpoints
+        self.set_point = angle_fixed_rate.slew_set_points(self.set_point, set_point);
+
+        // Set the setpoints for roll, pitch, and yaw
+        let (set_point_roll, set_point_pitch, set_point_yaw) =
+            self.angle_config.transform_axes(self.set_point);
+        self.angle_roll_pid.set_point(set_point_roll);
+        self.angle_pitch_pid.set_point(set_point_pitch);
+        self.angle_yaw_pid.set_point(set_point_yaw);
+
+        // Prepare angle control data for roll, pitch, and yaw
+        let angle_ki = (
+            self.angle_roll_pid.ki,
+            self.angle_pitch_pid.ki,
+            self.angle_yaw_pid.ki,
+        );
+        let (angle_i_limit_roll, angle_i_limit_pitch, angle_i_limit_yaw) =
+            self.angle_config.integral_limits(angle_ki);
+        let (imu_roll, imu_pitch, imu_yaw) = imu_attitude;
+        let (prev_imu_roll, prev_imu_pitch, prev_imu_yaw) = self.prev_imu;
+        let (raw_roll, raw_pitch, raw_yaw) = gyro_rate;
+        // Remove motor vibration from the gyro rates feeding the derivative
+        let gyro_rate = self.rate_config.notch_rates(&mut self.notch, gyro_rate, dt);
+        let (gyro_roll, gyro_pitch, gyro_yaw) = gyro_rate;
+        let angle_roll_data = CascadeAngleControlData {
+            measurement: imu_roll,
+            prev_measurement: prev_imu_roll,
+            rate: gyro_roll,
+            dt,
+            measurement_updated: true,
+            integral_limit: angle_i_limit_roll,
+            reset_integral: low_throttle,
+            integral_scale: angle_integral_scales[0],
+            max_integral_rate: angle_max_integral_rate,
+            d_term_limit: self.angle_config.d_term_limit_roll,
+            wrap: None,
+        };
+        let angle_pitch_data = CascadeAngleControlData {
+            measurement: imu_pitch,
+            prev_measurement: prev_imu_pitch,
+            rate: gyro_pitch,
+            dt,
+            measurement_updated: true,
+            integral_limit: angle_i_limit_pitch,
+            reset_integral: low_throttle,
+            integral_scale: angle_integral_scales[1],
+            max_integral_rate: angle_max_integral_rate,
+            d_term_limit: self.angle_config.d_term_limit_pitch,
+            wrap: None,
+        };
+        let angle_yaw_data = CascadeAngleControlData {
+            measurement: imu_yaw,
+            prev_measurement: prev_imu_yaw,
+            rate: gyro_yaw,
+            dt,
+            measurement_updated: true,
+            integral_limit: angle_i_limit_yaw,
+            reset_integral: low_throttle,
+            integral_scale: angle_integral_scales[2],
+            max_integral_rate: angle_max_integral_rate,
+            d_term_limit: self.angle_config.d_term_limit_yaw,
+            wrap: self.angle_config.yaw_wrap_range(),
+        };
+
+        // Compute angle outputs for roll, pitch, and yaw
+        let (angle_scale_roll, angle_scale_pitch, angle_scale_yaw) = self.angle_config.scales();
+        let angle_roll_terms = compute_terms(
+            &mut self.angle_roll_pid,
+            compute_cascade_angle,
+            angle_roll_data,
+            self.angle_config.p_term_limit_roll,
+        );
+        let angle_pitch_terms = compute_terms(
+            &mut self.angle_pitch_pid,
+            compute_cascade_angle,
+            angle_pitch_data,
+            self.angle_config.p_term_limit_pitch,
+        );
+        let angle_yaw_terms = compute_terms(
+            &mut self.angle_yaw_pid,
+            compute_cascade_angle,
+            angle_yaw_data,
+            self.angle_config.p_term_limit_yaw,
+        );
+        let angle_output_roll = angle_scale_roll * angle_roll_terms.sum();
+        let angle_output_pitch = angle_scale_pitch * angle_pitch_terms.sum();
+        let angle_output_yaw = angle_scale_yaw * angle_yaw_terms.sum();
+
+        // Blend the angle outputs into the rate setpoints
+        let (prev_rate_roll, prev_rate_pitch, prev_rate_yaw) = self.prev_rate_set_point;
+        let [rate_set_point_roll, rate_set_point_pitch, rate_set_point_yaw] = blend_cascade(
+            [angle_output_roll, angle_output_pitch, angle_output_yaw],
+            [prev_rate_roll, prev_rate_pitch, prev_rate_yaw],
+            &self.angle_blending_config,
+        );
+        self.rate_roll_pid.set_point(rate_set_point_roll);
+        self.rate_pitch_pid.set_point(rate_set_point_pitch);
+        self.rate_yaw_pid.set_point(rate_set_point_yaw);
+
+        // Prepare rate control data for roll, pitch, and yaw
+        let rate_ki = (
+            self.rate_roll_pid.ki,
+            self.rate_pitch_pid.ki,
+            self.rate_yaw_pid.ki,
+        );
+        let (rate_i_limit_roll, rate_i_limit_pitch, rate_i_limit_yaw) =
+            self.rate_config.integral_limits(rate_ki);
+        let rate_roll_data = RateControlData {
+            rate: gyro_roll,
+            dt,
+            measurement_updated: true,
+            integral_limit: rate_i_limit_roll,
+            reset_integral: low_throttle,
+            integral_scale: rate_integral_scales[0],
+            max_integral_rate: rate_max_integral_rate,
+            derivative_lpf_alpha: self.rate_config.derivative_alpha_roll,
+            d_term_limit: self.rate_config.d_term_limit_roll,
+        };
+        let rate_pitch_data = RateControlData {
+            rate: gyro_pitch,
+            dt,
+            measurement_updated: true,
+            integral_limit: rate_i_limit_pitch,
+            reset_integral: low_throttle,
+            integral_scale: rate_integral_scales[1],
+            max_integral_rate: rate_max_integral_rate,
+            derivative_lpf_alpha: self.rate_config.derivative_alpha_pitch,
+            d_term_limit: self.rate_config.d_term_limit_pitch,
+        };
+        let rate_yaw_data = RateControlData {
+            rate: gyro_yaw,
+            dt,
+            measurement_updated: true,
+            integral_limit: rate_i_limit_yaw,
+            reset_integral: low_throttle,
+            integral_scale: rate_integral_scales[2],
+            max_integral_rate: rate_max_integral_rate,
+            derivative_lpf_alpha: self.rate_config.derivative_alpha_yaw,
+            d_term_limit: self.rate_config.d_term_limit_yaw,
+        };
+
+        // Compute rate outputs for roll, pitch, and yaw
+        let (rate_scale_roll, rate_scale_pitch, rate_scale_yaw) = self.rate_config.scales();
+        let rate_roll_terms = compute_terms(
+            &mut self.rate_roll_pid,
+            compute_rate,
+            rate_roll_data,
+            self.rate_config.p_term_limit_roll,
+        );
+        let rate_pitch_terms = compute_terms(
+            &mut self.rate_pitch_pid,
+            compute_rate,
+            rate_pitch_data,
+            self.rate_config.p_term_limit_pitch,
+        );
+        let rate_yaw_terms = compute_terms(
+            &mut self.rate_yaw_pid,
+            compute_rate,
+            rate_yaw_data,
+            self.rate_config.p_term_limit_yaw,
+        );
+        let rate_output_roll = rate_scale_roll * rate_roll_terms.sum();
+        let rate_output_pitch = rate_scale_pitch * rate_pitch_terms.sum();
+        let rate_output_yaw = rate_scale_yaw * rate_yaw_terms.sum();
+
+        // Blend the rate outputs into the acceleration setpoints
+        let (prev_accel_roll, prev_accel_pitch, prev_accel_yaw) = self.prev_accel_set_point;
+        let [accel_set_point_roll, accel_set_point_pitch, accel_set_point_yaw] =
+            blend_cascade_with_saturation(
+                [rate_output_roll, rate_output_pitch, rate_output_yaw],
+                [prev_accel_roll, prev_accel_pitch, prev_accel_yaw],
+                self.saturation.map(|state| state != SaturationState::None),
+                &self.rate_blending_config,
+            );
+        self.accel_roll_pid.set_point(accel_set_point_roll);
+        self.accel_pitch_pid.set_point(accel_set_point_pitch);
+        self.accel_yaw_pid.set_point(accel_set_point_yaw);
+
+        // Differentiate the gyro, holding the previous acceleration for a non-positive dt
+        if T::zero() < dt {
+            let (prev_gyro_roll, prev_gyro_pitch, prev_gyro_yaw) = self.prev_gyro;
+            self.accel = (
+                (gyro_roll - prev_gyro_roll) / dt,
+                (gyro_pitch - prev_gyro_pitch) / dt,
+                (gyro_yaw - prev_gyro_yaw) / dt,
+            );
+        }
+        let (accel_roll, accel_pitch, accel_yaw) = self.accel;
+
+        // Prepare acceleration control data for roll, pitch, and yaw
+        let accel_ki = (
+            self.accel_roll_pid.ki,
+            self.accel_pitch_pid.ki,
+            self.accel_yaw_pid.ki,
+        );
+        let (accel_i_limit_roll, accel_i_limit_pitch, accel_i_limit_yaw) =
+            self.accel_config.integral_limits(accel_ki);
+        let accel_roll_data = RateControlData {
+            rate: accel_roll,
+            dt,
+            measurement_updated: true,
+            integral_limit: accel_i_limit_roll,
+            reset_integral: low_throttle,
+            integral_scale: accel_integral_scales[0],
+            max_integral_rate: accel_max_integral_rate,
+            derivative_lpf_alpha: self.accel_config.derivative_alpha_roll,
+            d_term_limit: self.accel_config.d_term_limit_roll,
+        };
+        let accel_pitch_data = RateControlData {
+            rate: accel_pitch,
+            dt,
+            measurement_updated: true,
+            integral_limit: accel_i_limit_pitch,
+            reset_integral: low_throttle,
+            integral_scale: accel_integral_scales[1],
+            max_integral_rate: accel_max_integral_rate,
+            derivative_lpf_alpha: self.accel_config.derivative_alpha_pitch,
+            d_term_limit: self.accel_config.d_term_limit_pitch,
+        };
+        let accel_yaw_data = RateControlData {
+            rate: accel_yaw,
+            dt,
+            measurement_updated: true,
+            integral_limit: accel_i_limit_yaw,
+            reset_integral: low_throttle,
+            integral_scale: accel_integral_scales[2],
+            max_integral_rate: accel_max_integral_rate,
+            derivative_lpf_alpha: self.accel_config.derivative_alpha_yaw,
+            d_term_limit: self.accel_config.d_term_limit_yaw,
+        };
+
+        // Feed-forward the setpoints and measured disturbance, bypassing the PID
+        let (disturbance_roll, disturbance_pitch, disturbance_yaw) =
+            self.angle_config.transform_axes(self.disturbance);
+        let roll_ff = self.accel_config.kff_roll * accel_set_point_roll
+            + self.accel_config.kdist_roll * disturbance_roll;
+        let pitch_ff = self.accel_config.kff_pitch * accel_set_point_pitch
+            + self.accel_config.kdist_pitch * disturbance_pitch;
+        let yaw_ff = self.accel_config.kff_yaw * accel_set_point_yaw
+            + self.accel_config.kdist_yaw * disturbance_yaw;
+
+        // Compute outputs for roll, pitch, and yaw
+        let (accel_scale_roll, accel_scale_pitch, accel_scale_yaw) = self.accel_config.scales();
+        let roll_terms = compute_terms(
+            &mut self.accel_roll_pid,
+            compute_rate,
+            accel_roll_data,
+            self.accel_config.p_term_limit_roll,
+        )
+        .with_feed_forward(roll_ff);
+        let pitch_terms = compute_terms(
+            &mut self.accel_pitch_pid,
+            compute_rate,
+            accel_pitch_data,
+            self.accel_config.p_term_limit_pitch,
+        )
+        .with_feed_forward(pitch_ff);
+        let yaw_terms = compute_terms(
+            &mut self.accel_yaw_pid,
+            compute_rate,
+            accel_yaw_data,
+            self.accel_config.p_term_limit_yaw,
+        )
+        .with_feed_forward(yaw_ff);
+        let roll_output = accel_scale_roll * roll_terms.sum();
+        let pitch_output = accel_scale_pitch * pitch_terms.sum();
+        let yaw_output = accel_scale_yaw * yaw_terms.sum();
+
+        // Record the PID terms for telemetry
+        self.terms = [roll_terms, pitch_terms, yaw_terms];
+
+        // Clamp outputs to the actuator range
+        let roll_clamped = self.accel_config.clamp_output(roll_output);
+        let pitch_clamped = self.accel_config.clamp_output(pitch_output);
+        let yaw_clamped = self.accel_config.clamp_output(yaw_output);
+        self.saturation = [
+            self.accel_config.saturation(roll_output),
+            self.accel_config.saturation(pitch_output),
+            self.accel_config.saturation(yaw_output),
+        ];
+
+        // Back-calculation anti-windup feeds the saturation back into the integral
+        let tracking = accel_fixed_rate.back_calculation(
+            (roll_clamped, pitch_clamped, yaw_clamped),
+            (roll_output, pitch_output, yaw_output),
+        );
+        [
+            self.accel_roll_pid.integral,
+            self.accel_pitch_pid.integral,
+            self.accel_yaw_pid.integral,
+        ] = track_integrals(
+            [
+                self.accel_roll_pid.integral,
+                self.accel_pitch_pid.integral,
+                self.accel_yaw_pid.integral,
+            ],
+            tracking,
+            (accel_i_limit_roll, accel_i_limit_pitch, accel_i_limit_yaw),
+            low_throttle,
+        );
+
+        // Store prevous values
+        self.prev_rate_set_point = (
+            rate_set_point_roll,
+            rate_set_point_pitch,
+            rate_set_point_yaw,
+        );
+        self.prev_accel_set_point = (
+            accel_set_point_roll,
+            accel_set_point_pitch,
+            accel_set_point_yaw,
+        );
+        self.prev_imu = imu_attitude;
+        self.prev_gyro = gyro_rate;
+
+        // Limit the rate of change of the outputs
+        self.prev_output = accel_fixed_rate
+            .slew_outputs(self.prev_output, (roll_clamped, pitch_clamped, yaw_clamped));
+
+        // Disabled axes hold their integrals and output zero, and frozen axes
+        // hold their integrals unless reset at low throttle
+        let enabled = self.enabled_axes;
+        let integrals = integral_axes(enabled, self.frozen_integrals, low_throttle);
+        [
+            self.angle_roll_pid.integral,
+            self.angle_pitch_pid.integral,
+            self.angle_yaw_pid.integral,
+        ] = select_axes(
+            integrals,
+            [
+                self.angle_roll_pid.integral,
+                self.angle_pitch_pid.integral,
+                self.angle_yaw_pid.integral,
+            ],
+            held_angle_integrals,
+        );
+        [
+            self.rate_roll_pid.integral,
+            self.rate_pitch_pid.integral,
+            self.rate_yaw_pid.integral,
+        ] = select_axes(
+            integrals,
+            [
+                self.rate_roll_pid.integral,
+                self.rate_pitch_pid.integral,
+                self.rate_yaw_pid.integral,
+            ],
+            held_rate_integrals,
+        );
+        [
+            self.accel_roll_pid.integral,
+            self.accel_pitch_pid.integral,
+            self.accel_yaw_pid.integral,
+        ] = select_axes(
+            integrals,
+            [
+                self.accel_roll_pid.integral,
+                self.accel_pitch_pid.integral,
+                self.accel_yaw_pid.integral,
+            ],
+            held_accel_integrals,
+        );
+        self.terms = select_axes(enabled, self.terms, [PidTerms::new(); 3]);
+        self.saturation = select_axes(enabled, self.saturation, [SaturationState::None; 3]);
+        let (roll, pitch, yaw) = self.prev_output;
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+        self.prev_output = (roll, pitch, yaw);
+        let (roll, pitch, yaw) = self.quantization_error;
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+
+        // Round the outputs to the actuator grid, carrying the rounding error forward
+        let (quantized, quantization_error) = self
+            .accel_config
+            .quantize_outputs(self.prev_output, (roll, pitch, yaw));
+        self.quantization_error = quantization_error;
+        let (roll_clamped, pitch_clamped, yaw_clamped) = quantized;
+
+        // Suppress jitter near zero after the integral has seen the clamped output
+        let output = (
+            self.accel_config.apply_deadband(roll_clamped),
+            self.accel_config.apply_deadband(pitch_clamped),
+            self.accel_config.apply_deadband(yaw_clamped),
+        );
+
+        // Hold the idle output below the idle throttle, handing off to the PID above it
+        let (roll, pitch, yaw) = self.accel_config.idle_outputs(throttle, output);
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+        let output = (roll, pitch, yaw);
+
+        // Record the output loop for telemetry
+        let [roll_terms, pitch_terms, yaw_terms] = self.terms;
+        self.telemetry = [
+            Telemetry::from_pid(
+                &self.accel_roll_pid,
+                roll_terms,
+                raw_roll,
+                gyro_roll,
+                output.0,
+            ),
+            Telemetry::from_pid(
+                &self.accel_pitch_pid,
+                pitch_terms,
+                raw_pitch,
+                gyro_pitch,
+                output.1,
+            ),
+            Telemetry::from_pid(&self.accel_yaw_pid, yaw_terms, raw_yaw, gyro_yaw, output.2),
+        ];
+        output
+    }
+}
+
+impl<T: Number> FlightStabilizer<T> for Angle3Stabilizer<T> {
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        let fixed_rates = self.fixed_rates_at(dt);
+        self.control_with(
+            set_point,
+            imu_attitude,
+            gyro_rate,
+            fixed_rates,
+            low_throttle,
+            T::one(),
+        )
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        let fixed_rates = self.fixed_rates_at(dt);
+        self.control_with(
+            set_point,
+            imu_attitude,
+            gyro_rate,
+            fixed_rates,
+            false,
+            throttle,
+        )
+    }
+
+    fn reset(&mut self) {
+        let zero = (T::zero(), T::zero(), T::zero());
+        self.angle_roll_pid.reset();
+        self.angle_pitch_pid.reset();
+        self.angle_yaw_pid.reset();
+        self.rate_roll_pid.reset();
+        self.rate_pitch_pid.reset();
+        self.rate_yaw_pid.reset();
+        self.accel_roll_pid.reset();
+        self.accel_pitch_pid.reset();
+        self.accel_yaw_pid.reset();
+        self.set_point = self.angle_config.set_points();
+        self.stored_set_point = self.angle_config.set_points();
+        self.terms = [PidTerms::new(); 3];
+        self.telemetry = [Telemetry::new(); 3];
+        self.saturation = [SaturationState::None; 3];
+        self.prev_output = (T::zero(), T::zero(), T::zero());
+        self.quantization_error = (T::zero(), T::zero(), T::zero());
+        self.timing = TimingStats::new();
+        self.angle_integral_divider = IntegralDivider::new();
+        self.rate_integral_divider = IntegralDivider::new();
+        self.accel_integral_divider = IntegralDivider::new();
+        self.notch = [NotchFilter::default(); 3];
+        self.disturbance = (T::zero(), T::zero(), T::zero());
+        self.filtered_attitude = None;
+        self.prev_rate_set_point = zero;
+        self.prev_accel_set_point = zero;
+        self.prev_imu = zero;
+        self.prev_gyro = zero;
+        self.accel = zero;
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stored_set_point = (roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.disturbance = (roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stored_set_point
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.saturation
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        let ki = (
+            self.accel_roll_pid.ki,
+            self.accel_pitch_pid.ki,
+            self.accel_yaw_pid.ki,
+        );
+        let (i_limit_roll, i_limit_pitch, i_limit_yaw) = self.accel_config.integral_limits(ki);
+        self.accel_roll_pid.integral = roll.clamp(-i_limit_roll, i_limit_roll);
+        self.accel_pitch_pid.integral = pitch.clamp(-i_limit_pitch, i_limit_pitch);
+        self.accel_yaw_pid.integral = yaw.clamp(-i_limit_yaw, i_limit_yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.enabled_axes = [roll, pitch, yaw];
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.enabled_axes
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.frozen_integrals = [roll, pitch, yaw];
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.frozen_integrals
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        self.telemetry
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.timing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    /// Angle, rate, and acceleration configs followed by the two blending configs.
+    type Angle3Config = (
+        FlightStabilizerConfig<f32>,
+        FlightStabilizerConfig<f32>,
+        FlightStabilizerConfig<f32>,
+        CascadeBlendingConfig<f32, 3>,
+        CascadeBlendingConfig<f32, 3>,
+    );
+
+    /// Default test configuration.
+    fn default_config() -> Angle3Config {
+        // Define angle config with shared values
+        let mut angle_config = FlightStabilizerConfig::new();
+
+        // Set the angle PID gains for roll, pitch, and yaw.
+        angle_config.kp_roll = 0.2;
+        angle_config.ki_roll = 0.3;
+        angle_config.kd_roll = 0.0; // zero out angle derivative
+
+        angle_config.kp_pitch = angle_config.kp_roll;
+        angle_config.ki_pitch = angle_config.ki_roll;
+        angle_config.kd_pitch = angle_config.kd_roll;
+
+        angle_config.kp_yaw = angle_config.kp_roll;
+        angle_config.ki_yaw = angle_config.ki_roll;
+        angle_config.kd_yaw = angle_config.kd_roll;
+
+        // Do not scale the angle and rate PID outputs.
+        angle_config.scale = 1.0;
+
+        // Set the shared upper limit for the integral term to prevent windup.
+        angle_config.i_limit = 25.0;
+
+        // Copy shared values for rate config
+        let mut rate_config = angle_config;
+
+        // Set the rate PID gains for roll, pitch, and yaw.
+        rate_config.kp_roll = 0.15;
+        rate_config.ki_roll = 0.2;
+        rate_config.kd_roll = 0.0; // zero out rate derivative
+
+        rate_config.kp_pitch = rate_config.kp_roll;
+        rate_config.ki_pitch = rate_config.ki_roll;
+        rate_config.kd_pitch = rate_config.kd_roll;
+
+        rate_config.kp_yaw = rate_config.kp_roll;
+        rate_config.ki_yaw = rate_config.ki_roll;
+        rate_config.kd_yaw = rate_config.kd_roll;
+
+        // Copy shared values for acceleration config
+        let mut accel_config = angle_config;
+
+        // Set the acceleration PID gains for roll, pitch, and yaw.
+        accel_config.kp_roll = 0.05;
+        accel_config.ki_roll = 0.1;
+        accel_config.kd_roll = 0.0; // zero out acceleration derivative
+
+        accel_config.kp_pitch = accel_config.kp_roll;
+        accel_config.ki_pitch = accel_config.ki_roll;
+        accel_config.kd_pitch = accel_config.kd_roll;
+
+        accel_config.kp_yaw = accel_config.kp_roll;
+        accel_config.ki_yaw = accel_config.ki_roll;
+        accel_config.kd_yaw = accel_config.kd_roll;
+
+        // Set the final scale to adjust the PID outputs to the actuator range.
+        accel_config.scale = 0.01;
+
+        // Define the angle to rate blending config
+        let mut angle_blending_config = CascadeBlendingConfig::new();
+        angle_blending_config.beta = [0.9; 3];
+        angle_blending_config.k = 30.0;
+        angle_blending_config.limit = 240.0;
+
+        // Define the rate to acceleration blending config
+        let mut rate_blending_config = CascadeBlendingConfig::new();
+        rate_blending_config.beta = [0.9; 3];
+        rate_blending_config.k = 1.0;
+        rate_blending_config.limit = 500.0;
+
+        (
+            angle_config,
+            rate_config,
+            accel_config,
+            angle_blending_config,
+            rate_blending_config,
+        )
+    }
+
+    /// Builds a stabilizer from a configuration tuple.
+    fn stabilizer_with(config: Angle3Config) -> Angle3Stabilizer<f32> {
+        let (angle_config, rate_config, accel_config, angle_blending, rate_blending) = config;
+        Angle3Stabilizer::with_config(
+            angle_config,
+            rate_config,
+            accel_config,
+            angle_blending,
+            rate_blending,
+        )
+    }
+
+    /// Test the initialization of the Angle3Stabilizer with a default configuration.
+    #[test]
+    fn test_stabilizer_angle3_initialization_with_default_config() {
+        let config = default_config();
+        let (_, _, _, angle_blending, rate_blending) = config;
+        let stabilizer = stabilizer_with(config);
+        assert_eq!(stabilizer.angle_blending_config.k, angle_blending.k);
+        assert_eq!(stabilizer.angle_blending_config.beta, angle_blending.beta);
+        assert_eq!(stabilizer.rate_blending_config.limit, rate_blending.limit);
+        assert_eq!(stabilizer.rate_blending_config.beta, rate_blending.beta);
+    }
+
+    /// Test that the integrator saturation works as expected in every stage.
+    #[test]
+    fn test_stabilizer_angle3_integrator_saturation() {
+        let config = default_config();
+        let (angle_config, rate_config, accel_config, _, _) = config;
+        let mut stabilizer = stabilizer_with(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (500.0, -500.0, 500.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Apply consistent error over multiple cycles to force integrator saturation
+        for _ in 0..1000 {
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
+
+        let angle_integrals = (
+            stabilizer.angle_roll_pid.integral,
+            stabilizer.angle_pitch_pid.integral,
+            stabilizer.angle_yaw_pid.integral,
+        );
+        let expected_angle_integrals = (
+            angle_config.i_limit,
+            -angle_config.i_limit,
+            angle_config.i_limit,
+        );
+        assert!(
+            vector_close(expected_angle_integrals, angle_integrals),
+            "Angle integrals should be capped."
+        );
+        let rate_integrals = (
+            stabilizer.rate_roll_pid.integral,
+            stabilizer.rate_pitch_pid.integral,
+            stabilizer.rate_yaw_pid.integral,
+        );
+        let expected_rate_integrals = (
+            rate_config.i_limit,
+            -rate_config.i_limit,
+            rate_config.i_limit,
+        );
+        assert!(
+            vector_close(expected_rate_integrals, rate_integrals),
+            "Rate integrals should be capped."
+        );
+        let accel_integrals = (
+            stabilizer.accel_roll_pid.integral,
+            stabilizer.accel_pitch_pid.integral,
+            stabilizer.accel_yaw_pid.integral,
+        );
+        let expected_accel_integrals = (
+            accel_config.i_limit,
+            -accel_config.i_limit,
+            accel_config.i_limit,
+        );
+        assert!(
+            vector_close(expected_accel_integrals, accel_integrals),
+            "Acceleration integrals should be capped."
+        );
+    }
+
+    /// Test to ensure integrators are reset when PWM is below threshold.
+    #[test]
+    fn test_stabilizer_angle3_low_throttle_integrator_reset() {
+        let mut stabilizer = stabilizer_with(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, 0.0, 5.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, 1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+
+        // Allow integrators to build up
+        let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+        let integrals = [
+            stabilizer.angle_roll_pid.integral,
+            stabilizer.rate_roll_pid.integral,
+            stabilizer.accel_roll_pid.integral,
+        ];
+        assert!(
+            integrals.iter().all(|integral| *integral != 0.0),
+            "Integrals should not be zero."
+        );
+
+        // Apply low throttle, which should reset integrators
+        let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, true);
+        let integrals = (
+            stabilizer.angle_roll_pid.integral,
+            stabilizer.rate_pitch_pid.integral,
+            stabilizer.accel_yaw_pid.integral,
+        );
+        let expected_integrals = (0.0, 0.0, 0.0);
+        assert!(
+            vector_close(expected_integrals, integrals),
+            "Integrals should be zero."
+        );
+    }
+
+    /// Test the no error contidion.
+    #[test]
+    fn test_stabilizer_angle3_no_error() {
+        let mut stabilizer = stabilizer_with(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (0.0, 0.0, 0.0); // desired roll, pitch, yaw
+        let imu_attitude = (0.0, 0.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Perform the control computation
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output = (0.0, 0.0, 0.0);
+
+        assert!(
+            vector_close(expected_output, output),
+            "Outputs should be zero as there is no error."
+        );
+    }
+
+    /// Test with specific inputs and the filtering blend configuration.
+    #[test]
+    fn test_stabilizer_angle3_specific_pid_output() {
+        let mut stabilizer = stabilizer_with(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, -5.0, 5.0); // current roll, pitch, yaw
+        let gyro_rate = (0.01, -0.01, 0.01); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // angle:        0.2 * 5 + 0.3 * 0.05 = 1.015
+        // angle blend:  0.9 * clamp(30 * 1.015) + 0.1 * 0 = 27.405
+        // rate:         0.15 * 27.395 + 0.2 * 0.27395 = 4.16404
+        // rate blend:   0.9 * clamp(1 * 4.16404) + 0.1 * 0 = 3.747636
+        // acceleration: 0.05 * 2.747636 + 0.1 * 0.02747636 = 0.1401294
+        // output:       0.01 * 0.1401294 = 0.0014013
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output = (0.0014013, -0.0014013, 0.0014013);
+        assert!(
+            vector_close(expected_output, output),
+            "PID outputs should match specific values."
+        );
+    }
+
+    /// Test with specific inputs and a pass-through blend configuration.
+    #[test]
+    fn test_stabilizer_angle3_specific_pid_output_unfiltered_blend() {
+        let mut config = default_config();
+        config.3.beta = [1.0; 3];
+        config.4.beta = [1.0; 3];
+        config.4.limit = 4.0;
+        let mut stabilizer = stabilizer_with(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, -5.0, 5.0); // current roll, pitch, yaw
+        let gyro_rate = (0.01, -0.01, 0.01); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // angle:        0.2 * 5 + 0.3 * 0.05 = 1.015
+        // angle blend:  clamp(30 * 1.015) = 30.45
+        // rate:         0.15 * 30.44 + 0.2 * 0.3044 = 4.62688
+        // rate blend:   clamp(1 * 4.62688) = 4.0
+        // acceleration: 0.05 * 3.0 + 0.1 * 0.03 = 0.153
+        // output:       0.01 * 0.153 = 0.00153
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output = (0.00153, -0.00153, 0.00153);
+        assert!(
+            vector_close(expected_output, output),
+            "PID outputs should match specific values."
+        );
+    }
+
+    /// Test that each axis uses its own blending weight in each transition.
+    #[test]
+    fn test_stabilizer_angle3_per_axis_blend() {
+        let mut config = default_config();
+        config.3.beta = [1.0, 0.5, 0.0];
+        config.4.beta = [1.0, 1.0, 1.0];
+        let mut stabilizer = stabilizer_with(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, 10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 5.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let rate_set_points = stabilizer.prev_rate_set_point;
+        let expected_rate_set_points = (30.45, 15.225, 0.0);
+        assert!(
+            vector_close(expected_rate_set_points, rate_set_points),
+            "Rate setpoints should follow the per-axis blending weights."
+        );
+    }
+
+    /// Test that a non-positive dt holds the measured acceleration.
+    #[test]
+    fn test_stabilizer_angle3_non_positive_dt() {
+        let mut stabilizer = stabilizer_with(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, -5.0, 5.0); // current roll, pitch, yaw
+        let low_throttle = false;
+
+        let _ = stabilizer.control(set_point, imu_attitude, (1.0, 1.0, 1.0), 0.01, low_throttle);
+        let accel = stabilizer.accel;
+        let output =
+            stabilizer.control(set_point, imu_attitude, (2.0, 2.0, 2.0), 0.0, low_throttle);
+        assert_eq!(accel, stabilizer.accel);
+        assert!(output.0.is_finite() && output.1.is_finite() && output.2.is_finite());
+    }
+
+    /// Test that setpoints can be stored and read back.
+    #[test]
+    fn test_stabilizer_angle3_stored_setpoints() {
+        let mut stabilizer = stabilizer_with(default_config());
+        stabilizer.set_setpoints(1.0, -2.0, 3.0);
+        assert_eq!((1.0, -2.0, 3.0), stabilizer.setpoints());
+        stabilizer.reset();
+        assert_eq!((0.0, 0.0, 0.0), stabilizer.setpoints());
+    }
+
+    /// Test that reset returns the controller to the state of a fresh controller.
+    #[test]
+    fn test_stabilizer_angle3_reset() {
+        let mut stabilizer = stabilizer_with(default_config());
+        let mut fresh_stabilizer = stabilizer_with(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 1.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Build up internal state
+        for _ in 0..10 {
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
+        stabilizer.reset();
+
+        // Perform the control computation
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output =
+            fresh_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        assert_eq!(
+            expected_output, output,
+            "Output should match a fresh controller."
+        );
+    }
+
+    /// Test that restoring a snapshot after a diverging branch reproduces the unbranched trajectory.
+    #[test]
+    fn test_stabilizer_angle3_snapshot_restore() {
+        let mut stabilizer = stabilizer_with(default_config());
+        let mut unbranched_stabilizer = stabilizer_with(default_config());
+
+        // Simulated sensor inputs that change every step
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let inputs = |step: usize| {
+            let step = step as f32;
+            let imu_attitude = (5.0 - 0.5 * step, 5.0 + 0.25 * step, 1.0 - step); // current roll, pitch, yaw
+            let gyro_rate = (1.0 - 0.1 * step, -1.0, 0.2 * step - 1.0); // current roll rate, pitch rate, yaw rate
+            (imu_attitude, gyro_rate)
+        };
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Build up internal state
+        for step in 0..10 {
+            let (imu_attitude, gyro_rate) = inputs(step);
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let _ =
+                unbranched_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
+
+        // Diverge on a different branch, then restore
+        let state = stabilizer.snapshot();
+        stabilizer.set_disturbance(1.0, 2.0, 3.0);
+        for _ in 0..5 {
+            let _ = stabilizer.control(
+                (-20.0, 20.0, 0.0),
+                (30.0, -30.0, 90.0),
+                (5.0, 5.0, 5.0),
+                dt,
+                low_throttle,
+            );
+        }
+        stabilizer.restore(&state);
+        assert_eq!(
+            state,
+            stabilizer.snapshot(),
+            "Restored state should match the snapshot."
+        );
+
+        // Continue along the unbranched trajectory
+        for step in 10..20 {
+            let (imu_attitude, gyro_rate) = inputs(step);
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let expected_output =
+                unbranched_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert_eq!(
+                expected_output, output,
+                "Output should match the unbranched controller."
+            );
+        }
+    }
+
+    /// Test that a snapshot taken between integral updates restores the divider
+    /// of every loop, so the restored trajectory matches the unbranched one.
+    #[test]
+    fn test_stabilizer_angle3_snapshot_restore_integral_divisor() {
+        let mut config = default_config();
+        config.0.integral_divisor = 2;
+        config.1.integral_divisor = 3;
+        config.2.integral_divisor = 2;
+        let mut stabilizer = stabilizer_with(config);
+        let mut unbranched_stabilizer = stabilizer_with(config);
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 1.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01;
+
+        // Snapshot partway into the period of every loop
+        let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+        let _ = unbranched_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+        let state = stabilizer.snapshot();
+        for _ in 0..2 {
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+        }
+        stabilizer.restore(&state);
+
+        for _ in 0..6 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+            let expected_output =
+                unbranched_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+            assert_eq!(
+                expected_output, output,
+                "Output should match the unbranched controller."
+            );
+        }
+    }
+
+    /// Test that the fixed-rate step matches the variable-rate step with the same
+    /// constant `dt`, with the rate limits and back-calculation active.
+    #[test]
+    fn test_stabilizer_angle3_fixed_rate() {
+        let (mut angle_config, rate_config, mut accel_config, angle_blending, rate_blending) =
+            default_config();
+        angle_config.max_set_point_rate_roll = Some(100.0);
+        angle_config.max_set_point_rate_yaw = Some(50.0);
+        accel_config.max_output_rate_pitch = Some(2.0);
+        accel_config.kt_roll = 5.0;
+        accel_config.kt_yaw = 2.0;
+        accel_config.output_min = Some(-0.05);
+        accel_config.output_max = Some(0.05);
+        let config = (
+            angle_config,
+            rate_config,
+            accel_config,
+            angle_blending,
+            rate_blending,
+        );
+        let mut stabilizer = stabilizer_with(config);
+        let mut reference_stabilizer = stabilizer_with(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (30.0, -30.0, 30.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        stabilizer.set_fixed_rate(dt).unwrap();
+
+        for step in 0..50 {
+            let low_throttle = step == 20;
+            let output = stabilizer
+                .control_fixed_rate(set_point, imu_attitude, gyro_rate, low_throttle)
+                .unwrap();
+            let expected =
+                reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert_eq!(expected, output, "Outputs should match at step {}.", step);
+        }
+        assert_eq!(reference_stabilizer.snapshot(), stabilizer.snapshot());
+    }
+}
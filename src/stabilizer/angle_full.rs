@@ -5,8 +5,14 @@
 //! This is an angle-based PID flight stabilization controller.
 //! Yaw also relies on angle-based stabilization.
 
+use super::state::PidState;
 use crate::pid::{compute_angle, AngleControlData};
-use crate::{FlightStabilizer, FlightStabilizerConfig, Number};
+use crate::pid::{compute_terms, NotchFilter, PidTerms};
+use crate::{
+    bumpless_integral, freeze_axes, integral_axes, select_axes, track_integrals, FixedRate,
+    FlightStabilizer, FlightStabilizerConfig, IntegralDivider, Number, SaturationState,
+    StabilizerError, StabilizerState, Telemetry, TimingStats,
+};
 use piddiy::PidController;
 
 /// Struct representing the Angle PID Flight Stabilization Controller.
@@ -14,8 +20,22 @@ pub struct AngleFullStabilizer<T: Number> {
     roll_pid: PidController<T, AngleControlData<T>>,
     pitch_pid: PidController<T, AngleControlData<T>>,
     yaw_pid: PidController<T, AngleControlData<T>>,
-    i_limit: T,
-    scale: T,
+    config: FlightStabilizerConfig<T>,
+    set_point: (T, T, T),
+    stored_set_point: (T, T, T),
+    terms: [PidTerms<T>; 3],
+    telemetry: [Telemetry<T>; 3],
+    saturation: [SaturationState; 3],
+    prev_output: (T, T, T),
+    quantization_error: (T, T, T),
+    timing: TimingStats<T>,
+    integral_divider: IntegralDivider<T>,
+    notch: [NotchFilter<T>; 3],
+    enabled_axes: [bool; 3],
+    frozen_integrals: [bool; 3],
+    disturbance: (T, T, T),
+    filtered_attitude: Option<(T, T, T)>,
+    fixed_rate: Option<FixedRate<T>>,
 }
 
 impl<T: Number> Default for AngleFullStabilizer<T> {
@@ -55,8 +75,22 @@ impl<T: Number> AngleFullStabilizer<T> {
             roll_pid,
             pitch_pid,
             yaw_pid,
-            i_limit: config.i_limit,
-            scale: config.scale,
+            config,
+            set_point: config.set_points(),
+            stored_set_point: config.set_points(),
+            terms: [PidTerms::new(); 3],
+            telemetry: [Telemetry::new(); 3],
+            saturation: [SaturationState::None; 3],
+            prev_output: (T::zero(), T::zero(), T::zero()),
+            quantization_error: (T::zero(), T::zero(), T::zero()),
+            timing: TimingStats::new(),
+            integral_divider: IntegralDivider::new(),
+            notch: [NotchFilter::default(); 3],
+            enabled_axes: [true; 3],
+            frozen_integrals: [false; 3],
+            disturbance: (T::zero(), T::zero(), T::zero()),
+            filtered_attitude: None,
+            fixed_rate: None,
         }
     }
 
@@ -64,56 +98,557 @@ impl<T: Number> AngleFullStabilizer<T> {
     pub fn new() -> Self {
         Self::with_config(FlightStabilizerConfig::new())
     }
-}
 
-impl<T: Number> FlightStabilizer<T> for AngleFullStabilizer<T> {
-    fn control(
+    /// Returns the configuration.
+    pub fn config(&self) -> &FlightStabilizerConfig<T> {
+        &self.config
+    }
+
+    /// Returns the gain-multiplied PID terms of the most recent control step
+    /// for roll, pitch, and yaw. The terms are unscaled, so their sum times
+    /// the axis scale is the output before clamping.
+    pub fn last_terms(&self) -> [PidTerms<T>; 3] {
+        self.terms
+    }
+
+    /// Returns the raw output of the most recent control step for roll, pitch, and yaw,
+    /// the sum of the `last_terms` before the axis scale is applied. The raw output times
+    /// the scale is the output before clamping, rate limiting, and the deadband.
+    pub fn last_raw_output(&self) -> (T, T, T) {
+        let [roll, pitch, yaw] = self.terms;
+        (roll.sum(), pitch.sum(), yaw.sum())
+    }
+
+    /// Returns a copy of the internal state, like the integrals, previous errors
+    /// and filter histories, to `restore` later. The configuration and gains are not included.
+    pub fn snapshot(&self) -> StabilizerState<T> {
+        let mut state = StabilizerState::new();
+        state.pids[0] = [
+            PidState::from_pid(&self.roll_pid),
+            PidState::from_pid(&self.pitch_pid),
+            PidState::from_pid(&self.yaw_pid),
+        ];
+        state.set_point = self.set_point;
+        state.stored_set_point = self.stored_set_point;
+        state.terms = self.terms;
+        state.saturation = self.saturation;
+        state.prev_output = self.prev_output;
+        state.quantization_error = self.quantization_error;
+        state.notch = self.notch;
+        state.disturbance = self.disturbance;
+        state.filtered_attitude = self.filtered_attitude;
+        state.integral_dividers[0] = self.integral_divider;
+        state
+    }
+
+    /// Restores the internal state from a `snapshot` of this controller.
+    pub fn restore(&mut self, state: &StabilizerState<T>) {
+        state.pids[0][0].restore(&mut self.roll_pid);
+        state.pids[0][1].restore(&mut self.pitch_pid);
+        state.pids[0][2].restore(&mut self.yaw_pid);
+        self.set_point = state.set_point;
+        self.stored_set_point = state.stored_set_point;
+        self.terms = state.terms;
+        self.saturation = state.saturation;
+        self.prev_output = state.prev_output;
+        self.quantization_error = state.quantization_error;
+        self.notch = state.notch;
+        self.disturbance = state.disturbance;
+        self.filtered_attitude = state.filtered_attitude;
+        self.integral_divider = state.integral_dividers[0];
+    }
+
+    /// Returns the configuration with the gains currently applied by the PID controllers.
+    /// Output scales are not folded in; the gains in actuator units are the reported
+    /// gains times the configured `scales()`.
+    pub fn effective_gains(&self) -> FlightStabilizerConfig<T> {
+        let mut config = self.config;
+        config.kp_roll = self.roll_pid.kp;
+        config.ki_roll = self.roll_pid.ki;
+        config.kd_roll = self.roll_pid.kd;
+        config.kp_pitch = self.pitch_pid.kp;
+        config.ki_pitch = self.pitch_pid.ki;
+        config.kd_pitch = self.pitch_pid.kd;
+        config.kp_yaw = self.yaw_pid.kp;
+        config.ki_yaw = self.yaw_pid.ki;
+        config.kd_yaw = self.yaw_pid.kd;
+        config
+    }
+
+    /// Replaces the PID gains with the gains from the provided configuration without
+    /// a bump in the integral terms, for live tuning. Each stored integral is rescaled
+    /// so `ki * integral` is unchanged, then clamped to the integral limits for the
+    /// new gains. An axis with a new `ki` of zero keeps its integral.
+    /// Other settings and the remaining internal state are left untouched.
+    pub fn update_gains(&mut self, config: &FlightStabilizerConfig<T>) {
+        let integrals = (
+            bumpless_integral(self.roll_pid.integral, self.roll_pid.ki, config.ki_roll),
+            bumpless_integral(self.pitch_pid.integral, self.pitch_pid.ki, config.ki_pitch),
+            bumpless_integral(self.yaw_pid.integral, self.yaw_pid.ki, config.ki_yaw),
+        );
+        self.roll_pid
+            .kp(config.kp_roll)
+            .ki(config.ki_roll)
+            .kd(config.kd_roll);
+        self.pitch_pid
+            .kp(config.kp_pitch)
+            .ki(config.ki_pitch)
+            .kd(config.kd_pitch);
+        self.yaw_pid
+            .kp(config.kp_yaw)
+            .ki(config.ki_yaw)
+            .kd(config.kd_yaw);
+        let (roll, pitch, yaw) = integrals;
+        self.set_integral(roll, pitch, yaw);
+    }
+
+    /// Replaces the configuration with a reloaded one, like a tuning stored in flash,
+    /// applying only what changed. Gains are applied like `update_gains`, so only an
+    /// axis with a changed `ki` rescales its integral, and the integrals are clamped
+    /// to the new limits. An axis with a changed initial setpoint stores the new
+    /// setpoint. Other settings, like limits and filters, apply from the next step.
+    /// The remaining internal state is left untouched.
+    pub fn apply_config(&mut self, config: &FlightStabilizerConfig<T>) {
+        let (roll, pitch, yaw) = self.config.set_points();
+        let unchanged = [
+            roll == config.set_point_roll,
+            pitch == config.set_point_pitch,
+            yaw == config.set_point_yaw,
+        ];
+        self.config = *config;
+        if let Some(fixed_rate) = self.fixed_rate {
+            self.fixed_rate = Some(config.fixed_rate(fixed_rate.dt));
+        }
+        self.update_gains(config);
+        let (roll, pitch, yaw) = self.stored_set_point;
+        let (set_point_roll, set_point_pitch, set_point_yaw) = config.set_points();
+        let [roll, pitch, yaw] = select_axes(
+            unchanged,
+            [roll, pitch, yaw],
+            [set_point_roll, set_point_pitch, set_point_yaw],
+        );
+        self.stored_set_point = (roll, pitch, yaw);
+    }
+
+    /// Sets a constant loop period for `control_fixed_rate`, precomputing the
+    /// coefficients that depend on it. The coefficients are refreshed by `apply_config`.
+    /// Returns an error, leaving the loop period unchanged, if `dt` is not positive
+    /// and finite.
+    pub fn set_fixed_rate(&mut self, dt: T) -> Result<(), StabilizerError> {
+        if !dt.is_finite() {
+            return Err(StabilizerError::NotFinite("dt"));
+        }
+        if dt <= T::zero() {
+            return Err(StabilizerError::NonPositiveDt);
+        }
+        self.fixed_rate = Some(self.config.fixed_rate(dt));
+        Ok(())
+    }
+
+    /// Returns the precomputed coefficients of the loop period set by `set_fixed_rate`,
+    /// or `None` if no loop period is set.
+    pub fn fixed_rate(&self) -> Option<&FixedRate<T>> {
+        self.fixed_rate.as_ref()
+    }
+
+    /// Computes the control outputs like `control`, with the loop period and the
+    /// coefficients precomputed by `set_fixed_rate` instead of a `dt` argument.
+    /// The outputs match `control` with the same constant `dt`.
+    /// Returns `StabilizerError::NoLoopPeriod` if no loop period is set.
+    pub fn control_fixed_rate(
         &mut self,
         set_point: (T, T, T),
         imu_attitude: (T, T, T),
         gyro_rate: (T, T, T),
-        dt: T,
         low_throttle: bool,
+    ) -> Result<(T, T, T), StabilizerError> {
+        let fixed_rate = self.fixed_rate.ok_or(StabilizerError::NoLoopPeriod)?;
+        Ok(self.control_with(
+            set_point,
+            imu_attitude,
+            gyro_rate,
+            fixed_rate,
+            low_throttle,
+            T::one(),
+        ))
+    }
+
+    /// Computes the control outputs for `control` and `control_throttle`.
+    fn control_with(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        fixed_rate: FixedRate<T>,
+        low_throttle: bool,
+        throttle: T,
     ) -> (T, T, T) {
+        let dt = fixed_rate.dt;
+        self.timing.record(dt);
+
+        // Reset the integrals like a low throttle while idling
+        let low_throttle = low_throttle || self.config.is_idle(throttle);
+
+        // Hold the integrals of disabled and frozen axes
+        let held_integrals = [
+            self.roll_pid.integral,
+            self.pitch_pid.integral,
+            self.yaw_pid.integral,
+        ];
+
+        // Smooth the attitude measurements, restarting the filter at low throttle
+        let previous_attitude = if low_throttle {
+            None
+        } else {
+            self.filtered_attitude
+        };
+        let imu_attitude = self.config.filter_attitude(previous_attitude, imu_attitude);
+        self.filtered_attitude = Some(imu_attitude);
+
+        // Map the measurements and setpoints onto the reversed and coupled axes
+        let imu_attitude = self.config.transform_axes(imu_attitude);
+        let gyro_rate = self.config.transform_axes(gyro_rate);
+
+        // Update the integral every `integral_divisor` ticks over the summed time steps
+        let integral_divider = self.integral_divider.step(self.config.integral_divisor, dt);
+        let max_integral_rate = self.config.max_integral_rate * integral_divider;
+
+        // Scale integral accumulation by the throttle fraction, stopping it on frozen axes
+        let integral_scale = self.config.integral_throttle_scale(throttle) * integral_divider;
+        let integral_scales = freeze_axes(self.frozen_integrals, integral_scale);
+        let integral_leaks = freeze_axes(self.frozen_integrals, self.config.integral_leak);
+
+        // Keep the previous setpoints for the derivative setpoint weight
+        let (prev_set_point_roll, prev_set_point_pitch, prev_set_point_yaw) =
+            self.config.transform_axes(self.set_point);
+
+        // Limit the rate of change of the setpoints
+        self.set_point = fixed_rate.slew_set_points(self.set_point, set_point);
+
         // Set the setpoints for roll, pitch, and yaw
-        let (set_point_roll, set_point_pitch, set_point_yaw) = set_point;
+        let (set_point_roll, set_point_pitch, set_point_yaw) =
+            self.config.transform_axes(self.set_point);
         self.roll_pid.set_point(set_point_roll);
         self.pitch_pid.set_point(set_point_pitch);
         self.yaw_pid.set_point(set_point_yaw);
 
         // Prepare control data for roll and pitch
+        let ki = (self.roll_pid.ki, self.pitch_pid.ki, self.yaw_pid.ki);
+        let (i_limit_roll, i_limit_pitch, i_limit_yaw) = self.config.integral_limits(ki);
         let (imu_roll, imu_pitch, imu_yaw) = imu_attitude;
+        let (raw_roll, raw_pitch, raw_yaw) = gyro_rate;
+        // Remove motor vibration from the gyro rates feeding the derivative
+        let gyro_rate = self.config.notch_rates(&mut self.notch, gyro_rate, dt);
         let (gyro_roll, gyro_pitch, gyro_yaw) = gyro_rate;
         let roll_data = AngleControlData {
             measurement: imu_roll,
-            rate: gyro_roll,
+            rate: Some(gyro_roll),
+            derivative_source: self.config.derivative_source,
             dt,
-            integral_limit: self.i_limit,
+            measurement_updated: true,
+            integral_limit: i_limit_roll,
             reset_integral: low_throttle,
+            integral_scale: integral_scales[0],
+            max_integral_rate,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: self.config.derivative_alpha_roll,
+            d_term_limit: self.config.d_term_limit_roll,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: integral_leaks[0],
+            integration_method: self.config.integration_method,
+            form: self.config.pid_form,
+            b_weight: self.config.b_weight,
+            c_weight: self.config.c_weight,
+            prev_set_point: prev_set_point_roll,
         };
         let pitch_data = AngleControlData {
             measurement: imu_pitch,
-            rate: gyro_pitch,
+            rate: Some(gyro_pitch),
+            derivative_source: self.config.derivative_source,
             dt,
-            integral_limit: self.i_limit,
+            measurement_updated: true,
+            integral_limit: i_limit_pitch,
             reset_integral: low_throttle,
+            integral_scale: integral_scales[1],
+            max_integral_rate,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: self.config.derivative_alpha_pitch,
+            d_term_limit: self.config.d_term_limit_pitch,
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: integral_leaks[1],
+            integration_method: self.config.integration_method,
+            form: self.config.pid_form,
+            b_weight: self.config.b_weight,
+            c_weight: self.config.c_weight,
+            prev_set_point: prev_set_point_pitch,
         };
 
         // Prepare control data for yaw
         let yaw_data = AngleControlData {
             measurement: imu_yaw,
-            rate: gyro_yaw,
+            rate: Some(gyro_yaw),
+            derivative_source: self.config.derivative_source,
             dt,
-            integral_limit: self.i_limit,
+            measurement_updated: true,
+            integral_limit: i_limit_yaw,
             reset_integral: low_throttle,
+            integral_scale: integral_scales[2],
+            max_integral_rate,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: self.config.derivative_alpha_yaw,
+            d_term_limit: self.config.d_term_limit_yaw,
+            saturation: SaturationState::None,
+            wrap: self.config.yaw_wrap_range(),
+            integral_leak: integral_leaks[2],
+            integration_method: self.config.integration_method,
+            form: self.config.pid_form,
+            b_weight: self.config.b_weight,
+            c_weight: self.config.c_weight,
+            prev_set_point: prev_set_point_yaw,
         };
 
+        // Feed-forward the setpoints and measured disturbance, bypassing the PID
+        let (disturbance_roll, disturbance_pitch, disturbance_yaw) =
+            self.config.transform_axes(self.disturbance);
+        let roll_ff =
+            self.config.kff_roll * set_point_roll + self.config.kdist_roll * disturbance_roll;
+        let pitch_ff =
+            self.config.kff_pitch * set_point_pitch + self.config.kdist_pitch * disturbance_pitch;
+        let yaw_ff = self.config.kff_yaw * set_point_yaw + self.config.kdist_yaw * disturbance_yaw;
+
         // Compute outputs for roll, pitch, and yaw
-        let roll_output = self.scale * self.roll_pid.compute(roll_data);
-        let pitch_output = self.scale * self.pitch_pid.compute(pitch_data);
-        let yaw_output = self.scale * self.yaw_pid.compute(yaw_data);
+        let (scale_roll, scale_pitch, scale_yaw) = self.config.scales();
+        let roll_terms = compute_terms(
+            &mut self.roll_pid,
+            compute_angle,
+            roll_data,
+            self.config.p_term_limit_roll,
+        )
+        .with_feed_forward(roll_ff);
+        let pitch_terms = compute_terms(
+            &mut self.pitch_pid,
+            compute_angle,
+            pitch_data,
+            self.config.p_term_limit_pitch,
+        )
+        .with_feed_forward(pitch_ff);
+        let yaw_terms = compute_terms(
+            &mut self.yaw_pid,
+            compute_angle,
+            yaw_data,
+            self.config.p_term_limit_yaw,
+        )
+        .with_feed_forward(yaw_ff);
+        let roll_output = scale_roll * roll_terms.sum();
+        let pitch_output = scale_pitch * pitch_terms.sum();
+        let yaw_output = scale_yaw * yaw_terms.sum();
+
+        // Record the PID terms for telemetry
+        self.terms = [roll_terms, pitch_terms, yaw_terms];
+
+        // Clamp outputs to the actuator range
+        let roll_clamped = self.config.clamp_output(roll_output);
+        let pitch_clamped = self.config.clamp_output(pitch_output);
+        let yaw_clamped = self.config.clamp_output(yaw_output);
+        self.saturation = [
+            self.config.saturation(roll_output),
+            self.config.saturation(pitch_output),
+            self.config.saturation(yaw_output),
+        ];
+
+        // Back-calculation anti-windup feeds the saturation back into the integral
+        let tracking = fixed_rate.back_calculation(
+            (roll_clamped, pitch_clamped, yaw_clamped),
+            (roll_output, pitch_output, yaw_output),
+        );
+        [
+            self.roll_pid.integral,
+            self.pitch_pid.integral,
+            self.yaw_pid.integral,
+        ] = track_integrals(
+            [
+                self.roll_pid.integral,
+                self.pitch_pid.integral,
+                self.yaw_pid.integral,
+            ],
+            tracking,
+            (i_limit_roll, i_limit_pitch, i_limit_yaw),
+            low_throttle,
+        );
+
+        // Limit the rate of change of the outputs
+        self.prev_output =
+            fixed_rate.slew_outputs(self.prev_output, (roll_clamped, pitch_clamped, yaw_clamped));
+
+        // Disabled axes hold their integrals and output zero, and frozen axes
+        // hold their integrals unless reset at low throttle
+        let enabled = self.enabled_axes;
+        let integrals = integral_axes(enabled, self.frozen_integrals, low_throttle);
+        [
+            self.roll_pid.integral,
+            self.pitch_pid.integral,
+            self.yaw_pid.integral,
+        ] = select_axes(
+            integrals,
+            [
+                self.roll_pid.integral,
+                self.pitch_pid.integral,
+                self.yaw_pid.integral,
+            ],
+            held_integrals,
+        );
+        self.terms = select_axes(enabled, self.terms, [PidTerms::new(); 3]);
+        self.saturation = select_axes(enabled, self.saturation, [SaturationState::None; 3]);
+        let (roll, pitch, yaw) = self.prev_output;
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+        self.prev_output = (roll, pitch, yaw);
+        let (roll, pitch, yaw) = self.quantization_error;
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+
+        // Round the outputs to the actuator grid, carrying the rounding error forward
+        let (quantized, quantization_error) = self
+            .config
+            .quantize_outputs(self.prev_output, (roll, pitch, yaw));
+        self.quantization_error = quantization_error;
+        let (roll_clamped, pitch_clamped, yaw_clamped) = quantized;
+
+        // Suppress jitter near zero after the integral has seen the clamped output
+        let output = (
+            self.config.apply_deadband(roll_clamped),
+            self.config.apply_deadband(pitch_clamped),
+            self.config.apply_deadband(yaw_clamped),
+        );
+
+        // Hold the idle output below the idle throttle, handing off to the PID above it
+        let (roll, pitch, yaw) = self.config.idle_outputs(throttle, output);
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+        let output = (roll, pitch, yaw);
+
+        // Record the output loop for telemetry
+        let [roll_terms, pitch_terms, yaw_terms] = self.terms;
+        self.telemetry = [
+            Telemetry::from_pid(&self.roll_pid, roll_terms, raw_roll, gyro_roll, output.0),
+            Telemetry::from_pid(
+                &self.pitch_pid,
+                pitch_terms,
+                raw_pitch,
+                gyro_pitch,
+                output.1,
+            ),
+            Telemetry::from_pid(&self.yaw_pid, yaw_terms, raw_yaw, gyro_yaw, output.2),
+        ];
+        output
+    }
+}
+
+impl<T: Number> FlightStabilizer<T> for AngleFullStabilizer<T> {
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        let fixed_rate = self.config.fixed_rate(dt);
+        self.control_with(
+            set_point,
+            imu_attitude,
+            gyro_rate,
+            fixed_rate,
+            low_throttle,
+            T::one(),
+        )
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        let fixed_rate = self.config.fixed_rate(dt);
+        self.control_with(
+            set_point,
+            imu_attitude,
+            gyro_rate,
+            fixed_rate,
+            false,
+            throttle,
+        )
+    }
+
+    fn reset(&mut self) {
+        self.roll_pid.reset();
+        self.pitch_pid.reset();
+        self.yaw_pid.reset();
+        self.set_point = self.config.set_points();
+        self.stored_set_point = self.config.set_points();
+        self.terms = [PidTerms::new(); 3];
+        self.telemetry = [Telemetry::new(); 3];
+        self.saturation = [SaturationState::None; 3];
+        self.prev_output = (T::zero(), T::zero(), T::zero());
+        self.quantization_error = (T::zero(), T::zero(), T::zero());
+        self.timing = TimingStats::new();
+        self.integral_divider = IntegralDivider::new();
+        self.notch = [NotchFilter::default(); 3];
+        self.disturbance = (T::zero(), T::zero(), T::zero());
+        self.filtered_attitude = None;
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stored_set_point = (roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.disturbance = (roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stored_set_point
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.saturation
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        let ki = (self.roll_pid.ki, self.pitch_pid.ki, self.yaw_pid.ki);
+        let (i_limit_roll, i_limit_pitch, i_limit_yaw) = self.config.integral_limits(ki);
+        self.roll_pid.integral = roll.clamp(-i_limit_roll, i_limit_roll);
+        self.pitch_pid.integral = pitch.clamp(-i_limit_pitch, i_limit_pitch);
+        self.yaw_pid.integral = yaw.clamp(-i_limit_yaw, i_limit_yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.enabled_axes = [roll, pitch, yaw];
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.enabled_axes
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.frozen_integrals = [roll, pitch, yaw];
+    }
 
-        (roll_output, pitch_output, yaw_output)
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.frozen_integrals
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        self.telemetry
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.timing
     }
 }
 
@@ -121,6 +656,7 @@ impl<T: Number> FlightStabilizer<T> for AngleFullStabilizer<T> {
 mod tests {
     use super::*;
     use crate::test_utils::*;
+    use crate::AngleUnit;
 
     /// Default test configuration.
     fn default_config() -> FlightStabilizerConfig<f32> {
@@ -283,4 +819,194 @@ mod tests {
             "PID outputs should match specific values."
         );
     }
+
+    /// Test that reset returns the controller to the state of a fresh controller.
+    #[test]
+    fn test_stabilizer_angle_full_reset() {
+        let config = default_config();
+        let mut stabilizer = AngleFullStabilizer::with_config(config);
+        let mut fresh_stabilizer = AngleFullStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -10.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 1.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Build up internal state
+        for _ in 0..10 {
+            let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
+        stabilizer.reset();
+
+        // Perform the control computation
+        let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output =
+            fresh_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        assert_eq!(
+            expected_output, output,
+            "Output should match a fresh controller."
+        );
+    }
+
+    /// Test that the yaw error wraps at the ±180 degree boundary while roll and pitch do not.
+    #[test]
+    fn test_stabilizer_angle_full_yaw_wrap() {
+        let config = default_config();
+        let mut stabilizer = AngleFullStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (179.0, -179.0, 179.0); // desired roll, pitch, yaw
+        let imu_attitude = (-179.0, 179.0, -179.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Perform the control computation
+        let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+
+        assert!(
+            value_close(358.0, stabilizer.roll_pid.error),
+            "Roll error should not wrap."
+        );
+        assert!(
+            value_close(-358.0, stabilizer.pitch_pid.error),
+            "Pitch error should not wrap."
+        );
+        assert!(
+            value_close(-2.0, stabilizer.yaw_pid.error),
+            "Yaw error should wrap to -2."
+        );
+    }
+
+    /// Test that the errors computed from the configuration match the errors of
+    /// `control`, across the yaw wrap boundary and with reversed and coupled axes.
+    #[test]
+    fn test_stabilizer_angle_full_compute_errors() {
+        let mut radians_config = default_config();
+        radians_config.angle_unit = AngleUnit::Radians;
+        let mut mapped_config = default_config();
+        mapped_config.reverse_pitch = true;
+        mapped_config.coupling[0][1] = 0.5;
+        let cases = [
+            (
+                default_config(),
+                (179.0, -179.0, 179.0),
+                (-179.0, 179.0, -179.0),
+            ),
+            (default_config(), (10.0, 0.0, -170.0), (5.0, 5.0, 170.0)),
+            (radians_config, (3.0, -3.0, 3.0), (-3.0, 3.0, -3.0)),
+            (mapped_config, (10.0, -20.0, 90.0), (0.0, 5.0, -100.0)),
+        ];
+
+        for (config, set_point, imu_attitude) in cases {
+            let mut stabilizer = AngleFullStabilizer::with_config(config);
+            let errors = config.compute_errors(set_point, imu_attitude);
+
+            let _ = stabilizer.control(set_point, imu_attitude, (0.0, 0.0, 0.0), 0.01, false);
+            let pid_errors = (
+                stabilizer.roll_pid.error,
+                stabilizer.pitch_pid.error,
+                stabilizer.yaw_pid.error,
+            );
+            assert_eq!(pid_errors, errors, "Errors should match control.");
+        }
+    }
+
+    /// Test that the yaw error wraps at the ±π boundary with radians.
+    #[test]
+    fn test_stabilizer_angle_full_yaw_wrap_radians() {
+        let mut config = default_config();
+        config.angle_unit = AngleUnit::Radians;
+        let mut stabilizer = AngleFullStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (3.0, -3.0, 3.0); // desired roll, pitch, yaw
+        let imu_attitude = (-3.0, 3.0, -3.0); // current roll, pitch, yaw
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Perform the control computation
+        let _ = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+
+        assert!(
+            value_close(6.0, stabilizer.roll_pid.error),
+            "Roll error should not wrap."
+        );
+        assert!(
+            value_close(6.0 - 2.0 * core::f32::consts::PI, stabilizer.yaw_pid.error),
+            "Yaw error should wrap to 6 - 2π."
+        );
+    }
+
+    /// Test that 6 radians of yaw error does not wrap with degrees.
+    #[test]
+    fn test_stabilizer_angle_full_yaw_wrap_degrees_boundary() {
+        let mut stabilizer = AngleFullStabilizer::with_config(default_config());
+        let _ = stabilizer.control(
+            (0.0, 0.0, 3.0),
+            (0.0, 0.0, -3.0),
+            (0.0, 0.0, 0.0),
+            0.01,
+            false,
+        );
+        assert!(
+            value_close(6.0, stabilizer.yaw_pid.error),
+            "Yaw error should not wrap below 180."
+        );
+    }
+
+    /// Test that a huge finite yaw sample wraps in constant time instead of hanging.
+    #[test]
+    fn test_stabilizer_angle_full_yaw_wrap_huge() {
+        let mut stabilizer = AngleFullStabilizer::with_config(default_config());
+        let output = stabilizer.control(
+            (0.0, 0.0, 0.0),
+            (0.0, 0.0, 3.0e10),
+            (0.0, 0.0, 0.0),
+            0.01,
+            false,
+        );
+        assert!(output.2.is_finite(), "Yaw output should be finite.");
+        assert!(
+            stabilizer.yaw_pid.error.abs() <= 180.0,
+            "Yaw error should wrap into range."
+        );
+    }
+
+    /// Test that the fixed-rate step matches the variable-rate step with the same
+    /// constant `dt`, with the rate limits and back-calculation active.
+    #[test]
+    fn test_stabilizer_angle_full_fixed_rate() {
+        let mut config = default_config();
+        config.max_set_point_rate_roll = Some(100.0);
+        config.max_set_point_rate_yaw = Some(50.0);
+        config.max_output_rate_pitch = Some(2.0);
+        config.kt_roll = 5.0;
+        config.kt_yaw = 2.0;
+        config.output_min = Some(-0.05);
+        config.output_max = Some(0.05);
+        let mut stabilizer = AngleFullStabilizer::with_config(config);
+        let mut reference_stabilizer = AngleFullStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (30.0, -30.0, 30.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        stabilizer.set_fixed_rate(dt).unwrap();
+
+        for step in 0..50 {
+            let low_throttle = step == 20;
+            let output = stabilizer
+                .control_fixed_rate(set_point, imu_attitude, gyro_rate, low_throttle)
+                .unwrap();
+            let expected =
+                reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert_eq!(expected, output, "Outputs should match at step {}.", step);
+        }
+        assert_eq!(reference_stabilizer.snapshot(), stabilizer.snapshot());
+    }
 }
@@ -0,0 +1,303 @@
+// src/stabilizer/arming.rs
+
+//! # Arming PID Flight Stabilization Controller
+//!
+//! This is a wrapper that manages the arming state of any flight stabilizer.
+//! Resetting the integrators, ramping the gains after arming, and holding the
+//! outputs at zero on the ground are otherwise handled by hand in the flight loop.
+//! The wrapper composes the `RampedStabilizer` gain ramp with the `reset` of the
+//! wrapped stabilizer, so arming and disarming are single calls.
+
+use crate::{FlightStabilizer, Number, RampedStabilizer, SaturationState, Telemetry, TimingStats};
+
+/// Struct representing the Arming PID Flight Stabilization Controller.
+/// The controller starts disarmed.
+///
+/// While disarmed, the control steps return zero outputs without running the
+/// wrapped stabilizer, so its integrators and filters stay at rest.
+/// Arming resets the wrapped stabilizer and restarts the gain ramp, and disarming
+/// resets it again, zeroing the integrators. Once ramped it is transparent.
+pub struct ArmingStabilizer<T: Number, S: FlightStabilizer<T>> {
+    stabilizer: RampedStabilizer<T, S>,
+    armed: bool,
+}
+
+impl<T: Number, S: FlightStabilizer<T>> ArmingStabilizer<T, S> {
+    /// Creates a new disarmed controller that ramps the gains of `stabilizer`
+    /// from zero to the configured values over `ramp_duration` after arming.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{
+    ///     AngleStabilizer, ArmingStabilizer, FlightStabilizer, FlightStabilizerConfig,
+    /// };
+    ///
+    /// let config = FlightStabilizerConfig::<f32>::new();
+    /// let mut stabilizer = ArmingStabilizer::new(AngleStabilizer::with_config(config), 2.0);
+    /// let zero = (0.0, 0.0, 0.0);
+    ///
+    /// // The outputs are held at zero until armed.
+    /// let output = stabilizer.control(zero, (1.0, 0.0, 0.0), zero, 0.01, false);
+    /// assert_eq!(zero, output);
+    ///
+    /// // The gains reach the configured values two seconds after arming.
+    /// stabilizer.arm();
+    /// let output = stabilizer.control(zero, (1.0, 0.0, 0.0), zero, 0.01, false);
+    /// assert!(output.0 < 0.0);
+    /// ```
+    pub fn new(stabilizer: S, ramp_duration: T) -> Self {
+        Self::with_initial_fraction(stabilizer, ramp_duration, T::zero())
+    }
+
+    /// Creates a new disarmed controller that ramps the gains of `stabilizer` from
+    /// `initial_fraction` of the configured values over `ramp_duration` after arming.
+    /// The initial fraction is clamped to the range zero to one.
+    pub fn with_initial_fraction(stabilizer: S, ramp_duration: T, initial_fraction: T) -> Self {
+        ArmingStabilizer {
+            stabilizer: RampedStabilizer::with_initial_fraction(
+                stabilizer,
+                ramp_duration,
+                initial_fraction,
+            ),
+            armed: false,
+        }
+    }
+
+    /// Returns a reference to the wrapped stabilizer.
+    pub fn inner(&self) -> &S {
+        self.stabilizer.inner()
+    }
+
+    /// Returns a mutable reference to the wrapped stabilizer.
+    pub fn inner_mut(&mut self) -> &mut S {
+        self.stabilizer.inner_mut()
+    }
+
+    /// Arms the controller, resetting the wrapped stabilizer and restarting the gain ramp.
+    /// Arming an armed controller has no effect.
+    pub fn arm(&mut self) {
+        if !self.armed {
+            self.stabilizer.reset();
+            self.armed = true;
+        }
+    }
+
+    /// Disarms the controller, resetting the wrapped stabilizer so the integrators
+    /// are zeroed, and holding the outputs at zero until the next `arm`.
+    pub fn disarm(&mut self) {
+        self.stabilizer.reset();
+        self.armed = false;
+    }
+
+    /// Returns `true` if the controller is armed.
+    pub fn is_armed(&self) -> bool {
+        self.armed
+    }
+
+    /// Returns the current fraction of the configured gains, zero while disarmed,
+    /// and from the initial fraction at arm to one once the ramp is complete.
+    pub fn gain_fraction(&self) -> T {
+        if self.armed {
+            self.stabilizer.gain_fraction()
+        } else {
+            T::zero()
+        }
+    }
+}
+
+impl<T: Number, S: FlightStabilizer<T>> FlightStabilizer<T> for ArmingStabilizer<T, S> {
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        if !self.armed {
+            return (T::zero(), T::zero(), T::zero());
+        }
+        self.stabilizer
+            .control(set_point, imu_attitude, gyro_rate, dt, low_throttle)
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        if !self.armed {
+            return (T::zero(), T::zero(), T::zero());
+        }
+        self.stabilizer
+            .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle)
+    }
+
+    fn reset(&mut self) {
+        self.stabilizer.reset();
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_setpoints(roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_disturbance(roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stabilizer.setpoints()
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.stabilizer.saturation()
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_integral(roll, pitch, yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.enable_axes(roll, pitch, yaw);
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.stabilizer.enabled_axes()
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.freeze_integral(roll, pitch, yaw);
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.stabilizer.frozen_integrals()
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        if !self.armed {
+            return [Telemetry::new(); 3];
+        }
+        self.stabilizer.last_telemetry()
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.stabilizer.timing_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{AngleStabilizer, FlightStabilizerConfig};
+
+    /// Default test configuration.
+    fn default_config() -> FlightStabilizerConfig<f32> {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+
+        // Set the PID gains for roll, pitch, and yaw.
+        config.kp_roll = 0.2;
+        config.ki_roll = 0.3;
+        config.kd_roll = -0.05;
+
+        config.kp_pitch = config.kp_roll;
+        config.ki_pitch = config.ki_roll;
+        config.kd_pitch = config.kd_roll;
+
+        config.kp_yaw = 0.3;
+        config.ki_yaw = 0.05;
+        config.kd_yaw = 0.00015;
+
+        // Set the upper limit for the integral term to prevent windup.
+        config.i_limit = 25.0;
+
+        // Set the scale to adjust the PID outputs to the actuator range.
+        config.scale = 0.01;
+
+        config
+    }
+
+    // Simulated sensor inputs and desired setpoints
+    const SET_POINT: (f32, f32, f32) = (10.0, 0.0, 10.0); // desired roll, pitch, yaw
+    const IMU_ATTITUDE: (f32, f32, f32) = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+    const GYRO_RATE: (f32, f32, f32) = (1.0, -1.0, -1.0); // current roll, pitch, yaw rates
+    const DT: f32 = 0.25; // time step
+
+    /// Compare the armed output against a bare stabilizer started at arm for several steps.
+    fn assert_armed(
+        stabilizer: &mut ArmingStabilizer<f32, AngleStabilizer<f32>>,
+        expected_fractions: &[f32],
+    ) {
+        let mut bare_stabilizer = AngleStabilizer::with_config(default_config());
+        for fraction in expected_fractions {
+            let output = stabilizer.control(SET_POINT, IMU_ATTITUDE, GYRO_RATE, DT, false);
+            let (roll, pitch, yaw) =
+                bare_stabilizer.control(SET_POINT, IMU_ATTITUDE, GYRO_RATE, DT, false);
+            tuple_close((fraction * roll, fraction * pitch, fraction * yaw), output);
+        }
+    }
+
+    /// Test that a disarmed controller holds zero outputs and leaves the stabilizer at rest.
+    #[test]
+    fn test_stabilizer_arming_disarmed() {
+        let inner = AngleStabilizer::with_config(default_config());
+        let mut stabilizer = ArmingStabilizer::new(inner, 1.0);
+        assert!(!stabilizer.is_armed());
+        assert_eq!(0.0, stabilizer.gain_fraction());
+        for _ in 0..4 {
+            let output = stabilizer.control(SET_POINT, IMU_ATTITUDE, GYRO_RATE, DT, false);
+            assert_eq!((0.0, 0.0, 0.0), output);
+            let output = stabilizer.control_throttle(SET_POINT, IMU_ATTITUDE, GYRO_RATE, DT, 1.0);
+            assert_eq!((0.0, 0.0, 0.0), output);
+        }
+        assert_eq!(
+            AngleStabilizer::with_config(default_config()).snapshot(),
+            stabilizer.inner().snapshot()
+        );
+    }
+
+    /// Test that arming ramps the gains and is transparent once ramped.
+    #[test]
+    fn test_stabilizer_arming_ramp() {
+        let inner = AngleStabilizer::with_config(default_config());
+        let mut stabilizer = ArmingStabilizer::new(inner, 1.0);
+        stabilizer.arm();
+        assert!(stabilizer.is_armed());
+        assert_eq!(0.0, stabilizer.gain_fraction());
+        assert_armed(&mut stabilizer, &[0.25, 0.5, 0.75, 1.0, 1.0, 1.0]);
+        assert_eq!(1.0, stabilizer.gain_fraction());
+
+        // Arming again does not restart the ramp
+        stabilizer.arm();
+        assert_eq!(1.0, stabilizer.gain_fraction());
+    }
+
+    /// Test that disarming zeroes the integrators and the next arm starts afresh.
+    #[test]
+    fn test_stabilizer_arming_disarm() {
+        let inner = AngleStabilizer::with_config(default_config());
+        let mut stabilizer = ArmingStabilizer::with_initial_fraction(inner, 1.0, 0.5);
+        stabilizer.arm();
+        assert_armed(&mut stabilizer, &[0.625, 0.75, 0.875, 1.0, 1.0]);
+        assert!(value_not_close(
+            0.0,
+            stabilizer.inner().last_terms()[0].integral
+        ));
+
+        stabilizer.disarm();
+        assert!(!stabilizer.is_armed());
+        for terms in stabilizer.inner().last_terms() {
+            assert_eq!(0.0, terms.integral);
+        }
+        assert_eq!([Telemetry::new(); 3], stabilizer.last_telemetry());
+        let output = stabilizer.control(SET_POINT, IMU_ATTITUDE, GYRO_RATE, DT, false);
+        assert_eq!((0.0, 0.0, 0.0), output);
+
+        // The integrators restart from zero, matching a fresh stabilizer
+        stabilizer.arm();
+        assert_armed(&mut stabilizer, &[0.625, 0.75, 0.875, 1.0]);
+    }
+}
@@ -0,0 +1,251 @@
+// src/stabilizer/autotune.rs
+
+//! # Relay Feedback Autotune
+//!
+//! This module tunes the gains of one axis from a relay (bang-bang) experiment.
+//! A relay drives the axis with a square wave of fixed amplitude, switching on the
+//! sign of the angle error, so the vehicle settles into a limit cycle at its ultimate
+//! frequency. The amplitude and period of the attitude oscillation give the ultimate
+//! gain and period, and the Ziegler-Nichols rules turn them into PID gains.
+//!
+//! The experiment is run with `relay_output` in place of the stabilizer output, logging
+//! each step as a `ReplaySample`. The log is then analyzed offline with `autotune_axis`,
+//! so the same routine works on a log from the vehicle or from a simulation.
+
+use crate::{FlightStabilizerConfig, Number, ReplaySample};
+
+/// A single (roll, pitch, yaw) axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Axis {
+    /// The roll axis.
+    Roll,
+    /// The pitch axis.
+    Pitch,
+    /// The yaw axis.
+    Yaw,
+}
+
+impl Axis {
+    /// Returns the value of the axis from a (roll, pitch, yaw) tuple.
+    pub fn select<T>(self, values: (T, T, T)) -> T {
+        match self {
+            Axis::Roll => values.0,
+            Axis::Pitch => values.1,
+            Axis::Yaw => values.2,
+        }
+    }
+}
+
+/// Returns the relay output of a relay feedback experiment, `amplitude` on the axis
+/// while its attitude is below the setpoint, and `-amplitude` otherwise.
+/// The other axes are zero. Apply it in place of the stabilizer output, which is
+/// already scaled, so the amplitude is in the units of the actuator command.
+pub fn relay_output<T: Number>(
+    axis: Axis,
+    amplitude: T,
+    set_point: (T, T, T),
+    imu_attitude: (T, T, T),
+) -> (T, T, T) {
+    let output = if axis.select(imu_attitude) < axis.select(set_point) {
+        amplitude
+    } else {
+        -amplitude
+    };
+    match axis {
+        Axis::Roll => (output, T::zero(), T::zero()),
+        Axis::Pitch => (T::zero(), output, T::zero()),
+        Axis::Yaw => (T::zero(), T::zero(), output),
+    }
+}
+
+/// Computes Ziegler-Nichols gains for one axis from the logged attitude response
+/// of a relay feedback experiment driven by `relay_output` with the same amplitude.
+///
+/// The first upward crossing of the setpoint is skipped as the transient, and the
+/// oscillation after it must hold at least two full periods. Over those periods the
+/// mean period is the ultimate period, `pu`, and half the peak-to-peak attitude is
+/// the amplitude, `a`, so the ultimate gain is `ku = 4 * amplitude / (π * a)`.
+/// The classic Ziegler-Nichols rules give `kp = 0.6 * ku`, `ki = 1.2 * ku / pu`, and
+/// a derivative gain of `0.075 * ku * pu`, negated for the derivative on the measured
+/// rate of the angle loops. These gains are aggressive, typically overshooting a step
+/// by more than half, so they are a starting point for tuning by hand.
+///
+/// The gains are set on the axis of a default configuration, with the default scale of
+/// one. Yaw gains are for a yaw angle loop, like that of `AngleFullStabilizer`.
+/// Returns `None` if the log does not hold two full periods after the transient.
+///
+/// Example Usage
+/// ```
+/// use free_flight_stabilization::{autotune_axis, Axis, FlightStabilizerConfig};
+///
+/// // A logged roll oscillation with a period of 0.4 seconds and an amplitude of one
+/// let zero = (0.0, 0.0, 0.0);
+/// let mut samples = [(zero, zero, zero, 0.01, false); 200];
+/// for (tick, sample) in samples.iter_mut().enumerate() {
+///     let phase = (tick % 40) as f32 / 40.0;
+///     let roll = if phase < 0.5 { 4.0 * phase - 1.0 } else { 3.0 - 4.0 * phase };
+///     sample.1 = (roll, 0.0, 0.0);
+/// }
+///
+/// let config = autotune_axis(Axis::Roll, 0.5, &samples).unwrap();
+/// assert!(config.kp_roll > 0.0 && config.kd_roll < 0.0);
+/// assert_eq!(FlightStabilizerConfig::<f32>::new().kp_pitch, config.kp_pitch);
+/// ```
+pub fn autotune_axis<T: Number>(
+    axis: Axis,
+    amplitude: T,
+    samples: &[ReplaySample<T>],
+) -> Option<FlightStabilizerConfig<T>> {
+    let mut time = T::zero();
+    let mut crossings: usize = 0;
+    let (mut start, mut end) = (T::zero(), T::zero());
+    let (mut low, mut high) = (T::zero(), T::zero());
+    let (mut min_error, mut max_error) = (T::zero(), T::zero());
+    let mut prev_error = None;
+    for &(set_point, imu_attitude, _, dt, _) in samples {
+        time += dt;
+        let error = axis.select(imu_attitude) - axis.select(set_point);
+        if 2 <= crossings {
+            // Track the extremes of the oscillation after the transient
+            if error < low {
+                low = error;
+            }
+            if high < error {
+                high = error;
+            }
+        }
+        match prev_error {
+            Some(prev_error) if prev_error < T::zero() && T::zero() <= error => {
+                // Interpolate the time of the upward crossing within the step
+                let crossing = time - dt * error / (error - prev_error);
+                crossings += 1;
+                if crossings == 2 {
+                    start = crossing;
+                    (low, high) = (error, error);
+                } else if 2 < crossings {
+                    end = crossing;
+                    (min_error, max_error) = (low, high);
+                }
+            }
+            _ => {}
+        }
+        prev_error = Some(error);
+    }
+
+    // At least two full periods after the transient
+    let periods = crossings.checked_sub(2).filter(|&periods| 2 <= periods)?;
+    let two = T::one() + T::one();
+    let ultimate_period = (end - start) / T::from_usize(periods)?;
+    let oscillation_amplitude = (max_error - min_error) / two;
+    if amplitude <= T::zero() || ultimate_period <= T::zero() || oscillation_amplitude <= T::zero()
+    {
+        return None;
+    }
+
+    // The describing function of the relay gives the ultimate gain
+    let pi = T::from_f64(core::f64::consts::PI)?;
+    let ultimate_gain = two * two * amplitude / (pi * oscillation_amplitude);
+    let kp = T::from_f64(0.6)? * ultimate_gain;
+    let ki = T::from_f64(1.2)? * ultimate_gain / ultimate_period;
+    let kd = -(T::from_f64(0.075)? * ultimate_gain * ultimate_period);
+    let mut config = FlightStabilizerConfig::new();
+    match axis {
+        Axis::Roll => (config.kp_roll, config.ki_roll, config.kd_roll) = (kp, ki, kd),
+        Axis::Pitch => (config.kp_pitch, config.ki_pitch, config.kd_pitch) = (kp, ki, kd),
+        Axis::Yaw => (config.kp_yaw, config.ki_yaw, config.kd_yaw) = (kp, ki, kd),
+    }
+    Some(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{AngleStabilizer, FlightStabilizer};
+
+    /// A logged roll triangle wave with a period of 0.4 seconds and an amplitude of one.
+    fn triangle_samples<const N: usize>() -> [ReplaySample<f32>; N] {
+        let zero = (0.0, 0.0, 0.0);
+        let mut samples = [(zero, zero, zero, 0.01, false); N];
+        for (tick, sample) in samples.iter_mut().enumerate() {
+            let phase = (tick % 40) as f32 / 40.0;
+            let roll = if phase < 0.5 {
+                4.0 * phase - 1.0
+            } else {
+                3.0 - 4.0 * phase
+            };
+            sample.0 = (5.0, 0.0, 0.0);
+            sample.1 = (roll + 5.0, 0.0, 0.0);
+        }
+        samples
+    }
+
+    /// Test the ultimate gain and period, and the gains, of a known oscillation.
+    #[test]
+    fn test_autotune_axis_known_oscillation() {
+        // ku = 4 * 0.5 / π and pu = 0.4
+        let ku = 2.0 / core::f32::consts::PI;
+        let config = autotune_axis(Axis::Roll, 0.5, &triangle_samples::<200>()).unwrap();
+        assert!(value_close(0.6 * ku, config.kp_roll));
+        assert!(value_close(1.2 * ku / 0.4, config.ki_roll));
+        assert!(value_close(-0.075 * ku * 0.4, config.kd_roll));
+        assert_eq!(
+            FlightStabilizerConfig::<f32>::new().kp_pitch,
+            config.kp_pitch
+        );
+
+        // The same log on another axis has no oscillation
+        assert_eq!(
+            None,
+            autotune_axis(Axis::Pitch, 0.5, &triangle_samples::<200>())
+        );
+        // Two periods are needed after the transient
+        assert!(autotune_axis(Axis::Roll, 0.5, &triangle_samples::<100>()).is_none());
+        assert!(autotune_axis(Axis::Roll, 0.5, &triangle_samples::<131>()).is_some());
+        assert_eq!(
+            None,
+            autotune_axis(Axis::Roll, 0.0, &triangle_samples::<200>())
+        );
+    }
+
+    /// Test that relay feedback against the simulated plant produces gains that
+    /// stabilize it.
+    #[test]
+    fn test_autotune_axis_plant() {
+        let (gain, time_constant, dt, amplitude) = (1000.0, 0.05, 0.001, 0.01);
+        let set_point = (0.0, 0.0, 0.0);
+
+        // Run the relay experiment on pitch, logging each step
+        let mut plant = Plant::new(gain, time_constant);
+        let zero = (0.0, 0.0, 0.0);
+        let mut samples = [(zero, zero, zero, dt, false); 2000];
+        for sample in samples.iter_mut() {
+            *sample = (set_point, plant.attitude, plant.rate, dt, false);
+            plant.step(
+                relay_output(Axis::Pitch, amplitude, set_point, plant.attitude),
+                dt,
+            );
+        }
+        assert_eq!((0.0, 0.0), (plant.attitude.0, plant.attitude.2));
+        let config = autotune_axis(Axis::Pitch, amplitude, &samples).unwrap();
+        assert!(0.0 < config.kp_pitch && 0.0 < config.ki_pitch && config.kd_pitch < 0.0);
+
+        // Step pitch with the tuned gains
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let mut plant = Plant::new(gain, time_constant);
+        let set_point = (0.0, 10.0, 0.0);
+        let mut response = StepResponse::new(0.0, set_point.1, 0.1);
+        for _ in 0..2000 {
+            let output = stabilizer.control(set_point, plant.attitude, plant.rate, dt, false);
+            plant.step((0.0, output.1, 0.0), dt);
+            response.record(plant.attitude.1);
+        }
+        assert!(
+            response.settling_tick.is_some_and(|tick| tick < 1000),
+            "{:?}",
+            response
+        );
+        assert!(plant.rate.1.abs() < 0.05);
+    }
+}
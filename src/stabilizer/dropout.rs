@@ -0,0 +1,386 @@
+// src/stabilizer/dropout.rs
+
+//! # Dropout PID Flight Stabilization Controller
+//!
+//! This is a wrapper that keeps the outputs of any flight stabilizer sane while
+//! the attitude measurement is invalid, like an IMU that stops responding or
+//! fails its self-check. Feeding stale or garbage measurements to the PID loops
+//! winds up the integrators and can slam the actuators, so during a dropout the
+//! wrapped stabilizer is not run and the outputs follow a configured behavior.
+
+use crate::{FlightStabilizer, Number, SaturationState, Telemetry, TimingStats};
+
+/// The outputs of a `DropoutStabilizer` while the measurement is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DropoutBehavior<T> {
+    /// Hold the last output computed from a valid measurement.
+    #[default]
+    Hold,
+    /// Ramp linearly from the last valid output to zero over `duration`.
+    /// A non-positive duration drops the outputs to zero immediately.
+    Ramp {
+        /// The time from the start of the dropout to neutral outputs.
+        duration: T,
+    },
+    /// Jump to a fixed (roll, pitch, yaw) failsafe output.
+    Failsafe {
+        /// The output held for the rest of the dropout.
+        output: (T, T, T),
+    },
+}
+
+/// Struct representing the Dropout PID Flight Stabilization Controller.
+///
+/// A control step with a valid measurement runs the wrapped stabilizer and records
+/// its output. A control step with an invalid measurement returns the output of the
+/// configured `DropoutBehavior` without running the wrapped stabilizer, so its
+/// integrators, filters, and telemetry are frozen at the last valid step.
+/// The next valid measurement ends the dropout and control resumes from there.
+pub struct DropoutStabilizer<T: Number, S: FlightStabilizer<T>> {
+    stabilizer: S,
+    behavior: DropoutBehavior<T>,
+    last_output: (T, T, T),
+    dropout_elapsed: Option<T>,
+}
+
+impl<T: Number, S: FlightStabilizer<T>> DropoutStabilizer<T, S> {
+    /// Creates a new controller that applies `behavior` to the outputs of
+    /// `stabilizer` while the measurement is invalid.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{
+    ///     AngleStabilizer, DropoutBehavior, DropoutStabilizer, FlightStabilizerConfig,
+    /// };
+    ///
+    /// let config = FlightStabilizerConfig::<f32>::new();
+    /// let behavior = DropoutBehavior::Ramp { duration: 0.5 };
+    /// let mut stabilizer = DropoutStabilizer::new(AngleStabilizer::with_config(config), behavior);
+    /// let zero = (0.0, 0.0, 0.0);
+    ///
+    /// let output = stabilizer.control_measured(zero, (1.0, 0.0, 0.0), zero, 0.25, false, true);
+    /// assert_eq!(-1.0, output.0);
+    ///
+    /// // The IMU drops out, and the outputs ramp to zero over half a second.
+    /// let output = stabilizer.control_measured(zero, zero, zero, 0.25, false, false);
+    /// assert_eq!(-0.5, output.0);
+    /// let output = stabilizer.control_measured(zero, zero, zero, 0.25, false, false);
+    /// assert_eq!(0.0, output.0);
+    /// ```
+    pub fn new(stabilizer: S, behavior: DropoutBehavior<T>) -> Self {
+        DropoutStabilizer {
+            stabilizer,
+            behavior,
+            last_output: (T::zero(), T::zero(), T::zero()),
+            dropout_elapsed: None,
+        }
+    }
+
+    /// Returns a reference to the wrapped stabilizer.
+    pub fn inner(&self) -> &S {
+        &self.stabilizer
+    }
+
+    /// Returns a mutable reference to the wrapped stabilizer.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.stabilizer
+    }
+
+    /// Returns the dropout behavior.
+    pub fn behavior(&self) -> DropoutBehavior<T> {
+        self.behavior
+    }
+
+    /// Sets the dropout behavior, taking effect on the next control step.
+    pub fn set_behavior(&mut self, behavior: DropoutBehavior<T>) {
+        self.behavior = behavior;
+    }
+
+    /// Returns `true` if the most recent control step had an invalid measurement.
+    pub fn is_dropout(&self) -> bool {
+        self.dropout_elapsed.is_some()
+    }
+
+    /// Returns the time since the start of the current dropout, or `None` if the
+    /// measurement is valid.
+    pub fn dropout_elapsed(&self) -> Option<T> {
+        self.dropout_elapsed
+    }
+
+    /// Returns the most recent output computed from a valid measurement.
+    pub fn last_output(&self) -> (T, T, T) {
+        self.last_output
+    }
+
+    /// Computes the control output like `control`, running the wrapped stabilizer
+    /// if `measurement_valid` is set and applying the dropout behavior otherwise.
+    pub fn control_measured(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+        measurement_valid: bool,
+    ) -> (T, T, T) {
+        if !measurement_valid {
+            return self.dropout(dt);
+        }
+        self.dropout_elapsed = None;
+        self.last_output =
+            self.stabilizer
+                .control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        self.last_output
+    }
+
+    /// Computes the control output like `control_throttle`, running the wrapped
+    /// stabilizer if `measurement_valid` is set and applying the dropout behavior otherwise.
+    pub fn control_throttle_measured(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+        measurement_valid: bool,
+    ) -> (T, T, T) {
+        if !measurement_valid {
+            return self.dropout(dt);
+        }
+        self.dropout_elapsed = None;
+        self.last_output =
+            self.stabilizer
+                .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle);
+        self.last_output
+    }
+
+    /// Advances the dropout, ignoring non-positive time steps, and returns the
+    /// output of the dropout behavior.
+    fn dropout(&mut self, dt: T) -> (T, T, T) {
+        let mut elapsed = self.dropout_elapsed.unwrap_or_else(T::zero);
+        if T::zero() < dt {
+            elapsed += dt;
+        }
+        self.dropout_elapsed = Some(elapsed);
+        match self.behavior {
+            DropoutBehavior::Hold => self.last_output,
+            DropoutBehavior::Ramp { duration } => {
+                if duration <= T::zero() || duration <= elapsed {
+                    return (T::zero(), T::zero(), T::zero());
+                }
+                let fraction = T::one() - elapsed / duration;
+                let (roll, pitch, yaw) = self.last_output;
+                (fraction * roll, fraction * pitch, fraction * yaw)
+            }
+            DropoutBehavior::Failsafe { output } => output,
+        }
+    }
+}
+
+impl<T: Number, S: FlightStabilizer<T>> FlightStabilizer<T> for DropoutStabilizer<T, S> {
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        self.control_measured(set_point, imu_attitude, gyro_rate, dt, low_throttle, true)
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        self.control_throttle_measured(set_point, imu_attitude, gyro_rate, dt, throttle, true)
+    }
+
+    fn reset(&mut self) {
+        self.stabilizer.reset();
+        self.last_output = (T::zero(), T::zero(), T::zero());
+        self.dropout_elapsed = None;
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_setpoints(roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_disturbance(roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stabilizer.setpoints()
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.stabilizer.saturation()
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_integral(roll, pitch, yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.enable_axes(roll, pitch, yaw);
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.stabilizer.enabled_axes()
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.freeze_integral(roll, pitch, yaw);
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.stabilizer.frozen_integrals()
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        self.stabilizer.last_telemetry()
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.stabilizer.timing_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{AngleStabilizer, FlightStabilizerConfig};
+
+    /// Default test configuration.
+    fn default_config() -> FlightStabilizerConfig<f32> {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+
+        // Set the PID gains for roll, pitch, and yaw.
+        config.kp_roll = 0.2;
+        config.ki_roll = 0.3;
+        config.kd_roll = -0.05;
+
+        config.kp_pitch = config.kp_roll;
+        config.ki_pitch = config.ki_roll;
+        config.kd_pitch = config.kd_roll;
+
+        config.kp_yaw = 0.3;
+        config.ki_yaw = 0.05;
+        config.kd_yaw = 0.00015;
+
+        // Set the upper limit for the integral term to prevent windup.
+        config.i_limit = 25.0;
+
+        // Set the scale to adjust the PID outputs to the actuator range.
+        config.scale = 0.01;
+
+        config
+    }
+
+    // Simulated sensor inputs and desired setpoints
+    const SET_POINT: (f32, f32, f32) = (10.0, 0.0, 10.0); // desired roll, pitch, yaw
+    const IMU_ATTITUDE: (f32, f32, f32) = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+    const GYRO_RATE: (f32, f32, f32) = (1.0, -1.0, -1.0); // current roll, pitch, yaw rates
+    const DT: f32 = 0.25; // time step
+
+    /// Runs valid steps, then checks that the dropout freezes the wrapped stabilizer
+    /// and returns the dropout output of each step.
+    fn run_dropout(
+        stabilizer: &mut DropoutStabilizer<f32, AngleStabilizer<f32>>,
+        steps: usize,
+    ) -> [(f32, f32, f32); 4] {
+        let mut output = (0.0, 0.0, 0.0);
+        for _ in 0..4 {
+            output =
+                stabilizer.control_measured(SET_POINT, IMU_ATTITUDE, GYRO_RATE, DT, false, true);
+        }
+        assert!(!stabilizer.is_dropout());
+        assert_eq!(output, stabilizer.last_output());
+
+        // The measurements are garbage while invalid
+        let snapshot = stabilizer.inner().snapshot();
+        let garbage = (1000.0, -1000.0, 1000.0);
+        let mut outputs = [(0.0, 0.0, 0.0); 4];
+        for dropout_output in outputs.iter_mut().take(steps) {
+            *dropout_output =
+                stabilizer.control_measured(SET_POINT, garbage, garbage, DT, false, false);
+        }
+        assert!(stabilizer.is_dropout());
+        assert_eq!(snapshot, stabilizer.inner().snapshot());
+        outputs
+    }
+
+    /// Test that the hold behavior keeps the last valid output with the integrators frozen.
+    #[test]
+    fn test_stabilizer_dropout_hold() {
+        let inner = AngleStabilizer::with_config(default_config());
+        let mut stabilizer = DropoutStabilizer::new(inner, DropoutBehavior::Hold);
+        let integral = stabilizer.inner().last_terms()[0].integral;
+        let outputs = run_dropout(&mut stabilizer, 4);
+        let output = stabilizer.last_output();
+        assert!(value_not_close(
+            integral,
+            stabilizer.inner().last_terms()[0].integral
+        ));
+        assert_eq!([output; 4], outputs);
+        assert_eq!(Some(4.0 * DT), stabilizer.dropout_elapsed());
+
+        // A valid measurement resumes control from the frozen state
+        let mut bare_stabilizer = AngleStabilizer::with_config(default_config());
+        let mut expected = (0.0, 0.0, 0.0);
+        for _ in 0..5 {
+            expected = bare_stabilizer.control(SET_POINT, IMU_ATTITUDE, GYRO_RATE, DT, false);
+        }
+        let resumed =
+            stabilizer.control_measured(SET_POINT, IMU_ATTITUDE, GYRO_RATE, DT, false, true);
+        assert!(!stabilizer.is_dropout());
+        tuple_close(expected, resumed);
+    }
+
+    /// Test that the ramp behavior decays the last valid output to zero over the duration.
+    #[test]
+    fn test_stabilizer_dropout_ramp() {
+        let inner = AngleStabilizer::with_config(default_config());
+        let behavior = DropoutBehavior::Ramp { duration: 0.75 };
+        let mut stabilizer = DropoutStabilizer::new(inner, behavior);
+        let outputs = run_dropout(&mut stabilizer, 4);
+        let output = stabilizer.last_output();
+        let (roll, pitch, yaw) = output;
+        for (fraction, dropout_output) in [2.0 / 3.0, 1.0 / 3.0, 0.0, 0.0].iter().zip(outputs) {
+            tuple_close(
+                (fraction * roll, fraction * pitch, fraction * yaw),
+                dropout_output,
+            );
+        }
+
+        // A new dropout ramps from the new last valid output
+        stabilizer.set_behavior(DropoutBehavior::Ramp { duration: 0.0 });
+        let outputs = run_dropout(&mut stabilizer, 1);
+        assert_eq!((0.0, 0.0, 0.0), outputs[0]);
+    }
+
+    /// Test that the failsafe behavior jumps to the configured output.
+    #[test]
+    fn test_stabilizer_dropout_failsafe() {
+        let inner = AngleStabilizer::with_config(default_config());
+        let failsafe = (0.0, 0.1, -0.1);
+        let behavior = DropoutBehavior::Failsafe { output: failsafe };
+        let mut stabilizer = DropoutStabilizer::new(inner, behavior);
+        let outputs = run_dropout(&mut stabilizer, 4);
+        let output = stabilizer.last_output();
+        assert!(value_not_close(failsafe.1, output.1));
+        assert_eq!([failsafe; 4], outputs);
+
+        // Reset ends the dropout and clears the last valid output
+        stabilizer.reset();
+        assert!(!stabilizer.is_dropout());
+        assert_eq!((0.0, 0.0, 0.0), stabilizer.last_output());
+    }
+}
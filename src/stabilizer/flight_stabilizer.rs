@@ -4,11 +4,21 @@
 //! It includes configuration structures and a trait defining the stabilization
 //! functionality.
 
+use crate::pid::NotchFilter;
+use crate::{Telemetry, TimingStats};
+use num_traits::{Bounded, FromPrimitive};
 use piddiy::Number as PiddiyNumber;
 
 /// Custom trait to encapsulate base number requirements.
-pub trait Number: PiddiyNumber {
+///
+/// The controllers only rely on arithmetic and comparison operations,
+/// so floating point and fixed-point types are both supported.
+/// Default methods must not rely on float-only operations, like `NaN` or infinity.
+/// Constants, like wrap boundaries, are converted with `FromPrimitive`, and the
+/// saturating operations clamp to the extremes given by `Bounded`.
+pub trait Number: PiddiyNumber + FromPrimitive + Bounded {
     /// Clamps generic PartialOrd values within a given range.
+    /// Fixed-point safe, as it only compares values.
     fn clamp(self, min: Self, max: Self) -> Self {
         if self < min {
             min
@@ -18,12 +28,335 @@ pub trait Number: PiddiyNumber {
             self
         }
     }
+
+    /// Wraps an angle into `[-half_range, half_range]`.
+    /// A half range of 180 wraps degrees, and a half range of π wraps radians.
+    /// The angle is returned unchanged if the half range is not positive
+    /// or the angle is not finite, as an infinite angle would never wrap.
+    /// Fixed-point safe, as it only takes the remainder, adds, subtracts and compares
+    /// values. It takes constant time, however large the angle.
+    fn wrap(self, half_range: Self) -> Self {
+        if half_range <= Self::zero() || !self.is_finite() {
+            return self;
+        }
+        let full_range = half_range + half_range;
+        // The remainder is within one full range of zero, with the sign of the angle.
+        let result = self % full_range;
+        if half_range < result {
+            result - full_range
+        } else if result < -half_range {
+            result + full_range
+        } else {
+            result
+        }
+    }
+
+    /// Rounds to the nearest multiple of `step`, with halves rounded away from zero.
+    /// The value is returned unchanged if the step is not positive or the value is not
+    /// finite. Fixed-point safe, as it only takes the remainder, adds, and compares
+    /// values, saturating at the extremes of the type.
+    fn quantize(self, step: Self) -> Self {
+        if step <= Self::zero() || !self.is_finite() {
+            return self;
+        }
+        let remainder = self % step;
+        let truncated = self - remainder;
+        let twice_remainder = remainder.saturating_add(remainder);
+        if step <= twice_remainder {
+            truncated.saturating_add(step)
+        } else if twice_remainder <= -step {
+            truncated.saturating_add(-step)
+        } else {
+            truncated
+        }
+    }
+
+    /// Returns `true` if the value is neither `NaN` nor infinite.
+    /// Fixed-point safe, as non-finite values are the only values that
+    /// do not subtract from themselves to zero.
+    #[allow(clippy::eq_op)]
+    fn is_finite(self) -> bool {
+        self - self == Self::zero()
+    }
+
+    /// Returns `true` if the type absorbs a unit step at its maximum, as floating point
+    /// types do, which overflow to infinity instead of wrapping. Fixed-point safe, as it
+    /// only subtracts from the maximum. The result is constant for a type, so the check
+    /// folds away after inlining.
+    fn absorbs_overflow() -> bool {
+        Self::max_value() - Self::one() == Self::max_value()
+    }
+
+    /// Adds two values, clamping to the extremes of the type instead of wrapping.
+    /// Floating point types use plain addition, overflowing to infinity as usual.
+    /// Fixed-point safe, as the headroom is checked before adding.
+    fn saturating_add(self, rhs: Self) -> Self {
+        if Self::absorbs_overflow() {
+            self + rhs
+        } else if Self::zero() < rhs && Self::max_value() - rhs < self {
+            Self::max_value()
+        } else if rhs < Self::zero() && self < Self::min_value() - rhs {
+            Self::min_value()
+        } else {
+            self + rhs
+        }
+    }
+
+    /// Multiplies two values, clamping to the extremes of the type instead of wrapping.
+    /// Floating point types use plain multiplication, overflowing to infinity as usual.
+    /// Fixed-point safe, as a product is only checked against the maximum, by division,
+    /// when both factors are outside `[-1, 1]`, the only case where it can grow.
+    fn saturating_mul(self, rhs: Self) -> Self {
+        let one = Self::one();
+        let within_one = |value: Self| -one <= value && value <= one;
+        if Self::absorbs_overflow() || within_one(self) || within_one(rhs) {
+            return self * rhs;
+        }
+        let extreme = if (self < Self::zero()) == (rhs < Self::zero()) {
+            Self::max_value()
+        } else {
+            Self::min_value()
+        };
+        // The minimum has no positive counterpart, and overflows with any factor beyond one.
+        if self == Self::min_value() || rhs == Self::min_value() {
+            return extreme;
+        }
+        if Self::max_value() / rhs.abs() < self.abs() {
+            extreme
+        } else {
+            self * rhs
+        }
+    }
+
+    /// Returns the value if it is finite, otherwise `fallback`.
+    /// A non-finite `fallback` is replaced with zero.
+    fn finite_or(self, fallback: Self) -> Self {
+        if self.is_finite() {
+            self
+        } else if fallback.is_finite() {
+            fallback
+        } else {
+            Self::zero()
+        }
+    }
+}
+
+impl<T: PiddiyNumber + FromPrimitive + Bounded> Number for T {}
+
+/// Angle unit of the setpoints, measurements, and gains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AngleUnit {
+    /// Angles are in degrees, wrapping at ±180.
+    #[default]
+    Degrees,
+    /// Angles are in radians, wrapping at ±π.
+    Radians,
+}
+
+impl AngleUnit {
+    /// Returns half of a full turn, 180 for degrees or π for radians.
+    /// Returns `None` if the value is not representable by the number type.
+    pub fn half_turn<T: Number>(self) -> Option<T> {
+        match self {
+            AngleUnit::Degrees => T::from_u8(180),
+            AngleUnit::Radians => T::from_f64(core::f64::consts::PI),
+        }
+    }
+}
+
+/// Numerical integration method of the integral term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntegrationMethod {
+    /// Forward Euler integration, `integral += error * dt`.
+    #[default]
+    Rectangular,
+    /// Trapezoidal integration of the previous and current errors,
+    /// `integral += (error + prev_error) / 2 * dt`. More accurate for slow loops,
+    /// as the error is assumed to change linearly between samples.
+    Trapezoidal,
+}
+
+/// Structure of the PID, deciding which terms act on the setpoint.
+/// With error `e = set_point - measurement` and the measured rate `ṁ`:
+///
+/// - `Standard`: `kp * e + ki * ∫e + kd * ṁ`
+/// - `TwoDegreesOfFreedom`: `kp * (b * set_point - measurement) + ki * ∫e + kd * (ṁ - c * ṡet_point)`
+/// - `IPd`: `kp * (-measurement) + ki * ∫e + kd * ṁ`
+///
+/// The derivative follows the sign of the measured rate, as with `AngleControlData`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PidForm {
+    /// Proportional on the error and derivative on the measurement, ignoring the
+    /// setpoint weights.
+    Standard,
+    /// Proportional and derivative setpoint weights of `b_weight` and `c_weight`.
+    /// Equal to `Standard` for the default weights of one and zero.
+    #[default]
+    TwoDegreesOfFreedom,
+    /// Integral only on the error, with the proportional and derivative terms acting
+    /// on the measurement alone, so a setpoint step causes no proportional or derivative
+    /// kick and the integral carries the response. Ignores the setpoint weights and
+    /// derivative on error.
+    IPd,
+}
+
+/// Source of the derivative of a loop that differentiates the measurement.
+/// The measurement derivative is the change in the measurement over `dt`, which
+/// cross-checks the supplied rate when the gyro and attitude estimate disagree.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DerivativeSource<T> {
+    /// The supplied rate, typically from a gyro.
+    #[default]
+    Rate,
+    /// The change in the measurement over `dt`, ignoring the supplied rate.
+    Measurement,
+    /// Complementary blend of the two, `alpha * rate + (1 - alpha) * measurement derivative`.
+    /// An `alpha` of one matches `Rate`, and zero matches `Measurement`.
+    Blend(T),
+}
+
+/// Saturation state of a clamped output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SaturationState {
+    /// The output is within range.
+    #[default]
+    None,
+    /// The output is clamped to the upper bound.
+    Upper,
+    /// The output is clamped to the lower bound.
+    Lower,
+}
+
+/// Error describing the first invalid field found by `FlightStabilizerConfig::validate()`
+/// or `CascadeBlendingConfigBuilder::build()`, or the first invalid argument of
+/// `FlightStabilizerConfig::from_bandwidth()`, or the blending configuration
+/// rejected by `Angle2Stabilizer::try_with_config()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// The field is `NaN` or infinite.
+    NotFinite(&'static str),
+    /// The integral limit field is negative.
+    NegativeIntegralLimit(&'static str),
+    /// The integral limit field is zero while the matching integral gain is not,
+    /// so the integral term can never contribute.
+    ZeroIntegralLimit(&'static str),
+    /// The argument is zero, negative, or not finite.
+    NotPositive(&'static str),
+    /// The blending weight is negative or greater than one.
+    WeightOutOfRange(&'static str),
+    /// The blending configuration has a different number of `beta` weights
+    /// than the stabilizer blends.
+    BlendingLengthMismatch {
+        /// The number of weights the stabilizer blends.
+        expected: usize,
+        /// The number of weights in the blending configuration.
+        actual: usize,
+    },
+}
+
+impl core::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConfigError::NotFinite(field) => write!(f, "`{}` is not finite", field),
+            ConfigError::NegativeIntegralLimit(field) => write!(f, "`{}` is negative", field),
+            ConfigError::ZeroIntegralLimit(field) => {
+                write!(f, "`{}` is zero with a nonzero integral gain", field)
+            }
+            ConfigError::NotPositive(field) => write!(f, "`{}` is not positive", field),
+            ConfigError::WeightOutOfRange(field) => {
+                write!(f, "`{}` is outside the range zero to one", field)
+            }
+            ConfigError::BlendingLengthMismatch { expected, actual } => write!(
+                f,
+                "`beta` has {} weights, but {} are blended",
+                actual, expected
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConfigError {}
+
+/// Error describing the first invalid input found by `FlightStabilizer::try_control()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StabilizerError {
+    /// The input has a `NaN` or infinite axis.
+    NotFinite(&'static str),
+    /// The time delta is zero or negative.
+    NonPositiveDt,
+    /// A fixed-rate step was requested before a loop period was set.
+    NoLoopPeriod,
+}
+
+impl core::fmt::Display for StabilizerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            StabilizerError::NotFinite(input) => write!(f, "`{}` is not finite", input),
+            StabilizerError::NonPositiveDt => write!(f, "`dt` is not positive"),
+            StabilizerError::NoLoopPeriod => write!(f, "no loop period is set"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StabilizerError {}
+
+/// First-order model of the rate response of an axis to the scaled control output,
+/// `rate / output = gain / (time_constant * s + 1)`, with the angle integrating the rate.
+/// The rate settles at `gain * output` with the time constant in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlantModel<T> {
+    /// Steady state rate per unit of scaled output.
+    pub gain: T,
+    /// Time constant of the rate response, in seconds.
+    pub time_constant: T,
 }
 
-impl<T: PiddiyNumber> Number for T {}
+impl<T: Number> PlantModel<T> {
+    /// Advances the (angle, rate) of an axis by `dt` seconds with the scaled output
+    /// applied, using a backward Euler rate step, stable for any `dt`, and a trapezoidal
+    /// angle step. A non-positive time step leaves the state unchanged.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::PlantModel;
+    ///
+    /// let plant = PlantModel { gain: 100.0, time_constant: 0.25 };
+    ///
+    /// // The rate covers a fifth of the way to 100.0 in the first 0.0625 seconds
+    /// let (angle, rate) = plant.step((0.0, 0.0), 1.0, 0.0625);
+    /// assert_eq!(20.0, rate);
+    /// assert_eq!(0.625, angle);
+    /// ```
+    pub fn step(&self, state: (T, T), output: T, dt: T) -> (T, T) {
+        if dt <= T::zero() {
+            return state;
+        }
+        let (angle, rate) = state;
+        let alpha = dt / (self.time_constant + dt);
+        let next_rate = rate + alpha * (self.gain * output - rate);
+        let two = T::one() + T::one();
+        (angle + (rate + next_rate) / two * dt, next_rate)
+    }
+}
 
 /// Configuration for PID gains and other settings.
-#[derive(Clone, Copy)]
+///
+/// Derivative gains follow the native convention of the compute functions.
+/// Loops that differentiate the measurement, like the angle loops, take the
+/// derivative with the sign of the measured rate, so damping needs a negative `kd`.
+/// Loops that differentiate the error, like the rate loops, damp with a positive `kd`.
+/// Use `normalize_gains` to convert gains from firmware where a positive `kd` always damps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FlightStabilizerConfig<T: Number> {
     /// Proportional gain for roll control.
     pub kp_roll: T,
@@ -43,16 +376,254 @@ pub struct FlightStabilizerConfig<T: Number> {
     pub ki_yaw: T,
     /// Derivative gain for yaw control.
     pub kd_yaw: T,
+    /// Feed-forward gain for roll control, applied to the setpoint.
+    /// Cascade stabilizers apply it to the inner rate loop setpoint.
+    pub kff_roll: T,
+    /// Feed-forward gain for pitch control, applied to the setpoint.
+    /// Cascade stabilizers apply it to the inner rate loop setpoint.
+    pub kff_pitch: T,
+    /// Feed-forward gain for yaw control, applied to the setpoint.
+    /// Cascade stabilizers apply it to the inner rate loop setpoint.
+    pub kff_yaw: T,
+    /// Disturbance feed-forward gain for roll control, applied to the measured
+    /// roll disturbance. Zero disables disturbance feed-forward.
+    /// Cascade stabilizers apply it to the output loop.
+    pub kdist_roll: T,
+    /// Disturbance feed-forward gain for pitch control, applied to the measured
+    /// pitch disturbance. Zero disables disturbance feed-forward.
+    /// Cascade stabilizers apply it to the output loop.
+    pub kdist_pitch: T,
+    /// Disturbance feed-forward gain for yaw control, applied to the measured
+    /// yaw disturbance. Zero disables disturbance feed-forward.
+    /// Cascade stabilizers apply it to the output loop.
+    pub kdist_yaw: T,
+    /// Back-calculation anti-windup tracking gain for roll control.
+    pub kt_roll: T,
+    /// Back-calculation anti-windup tracking gain for pitch control.
+    pub kt_pitch: T,
+    /// Back-calculation anti-windup tracking gain for yaw control.
+    pub kt_yaw: T,
     /// Initial setpoint for roll angle.
     pub set_point_roll: T,
     /// Initial setpoint for pitch angle.
     pub set_point_pitch: T,
     /// Initial setpoint for roll angle.
     pub set_point_yaw: T,
+    /// Optional maximum rate of change for the roll setpoint, in units per second.
+    /// The setpoint is not rate limited if `None`.
+    pub max_set_point_rate_roll: Option<T>,
+    /// Optional maximum rate of change for the pitch setpoint, in units per second.
+    /// The setpoint is not rate limited if `None`.
+    pub max_set_point_rate_pitch: Option<T>,
+    /// Optional maximum rate of change for the yaw setpoint, in units per second.
+    /// The setpoint is not rate limited if `None`.
+    pub max_set_point_rate_yaw: Option<T>,
+    /// Smoothing coefficient of the exponential moving average applied to the roll
+    /// attitude measurement, from zero to one. Each tick the filtered attitude moves
+    /// this fraction of the way toward the new measurement.
+    /// A value of one disables the filter. The filter restarts from the raw measurement
+    /// on `reset` and at low throttle. Rate and quaternion stabilizers do not apply it.
+    pub attitude_alpha_roll: T,
+    /// Smoothing coefficient for the pitch attitude measurement. See `attitude_alpha_roll`.
+    pub attitude_alpha_pitch: T,
+    /// Smoothing coefficient for the yaw attitude measurement. See `attitude_alpha_roll`.
+    /// The yaw filter takes the short way around the `yaw_wrap` boundary.
+    pub attitude_alpha_yaw: T,
     /// Upper limit for integral term to prevent integral windup.
+    /// Applies to every axis without a per-axis override.
+    /// Limits the raw accumulated integral, `∫error·dt`, unless `clamp_integral_term` is set.
     pub i_limit: T,
+    /// Optional roll override for the integral limit. Defaults to `i_limit` if `None`.
+    pub i_limit_roll: Option<T>,
+    /// Optional pitch override for the integral limit. Defaults to `i_limit` if `None`.
+    pub i_limit_pitch: Option<T>,
+    /// Optional yaw override for the integral limit. Defaults to `i_limit` if `None`.
+    pub i_limit_yaw: Option<T>,
+    /// Flag to express the integral limits in output units, limiting the integral
+    /// term `ki · ∫error·dt` instead of the raw accumulated integral.
+    /// The limit is in PID output units, before `scale` is applied.
+    /// Axes with a zero integral gain fall back to limiting the raw integral.
+    pub clamp_integral_term: bool,
+    /// Optional integral limit as a fraction of the output range, `output_max - output_min`,
+    /// like `0.4` for an integral term that never contributes more than 40% of the range.
+    /// The limit is converted to the raw accumulated integral of each axis by dividing by
+    /// the magnitude of its `ki` times its output scale, overriding `i_limit`, the
+    /// per-axis overrides, and `clamp_integral_term`. The raw limits still apply while
+    /// either output bound is `None`, and on axes with a zero integral gain or scale.
+    pub i_limit_fraction: Option<T>,
+    /// Leak coefficient that decays the stored integral toward zero, in units per second.
+    /// Each tick the integral is multiplied by `1 - integral_leak * dt` before the error
+    /// is accumulated, so a small persistent bias cannot pin it at the limit indefinitely.
+    /// A value of zero disables the leak. Only stabilizers built on `AngleControlData`
+    /// apply it.
+    pub integral_leak: T,
+    /// Maximum rate of change of the raw accumulated integral, in units per second,
+    /// so each tick adds at most `max_integral_rate * dt` to the integral. This smooths
+    /// the integral after a reset, unlike `i_limit`, which bounds its magnitude, and
+    /// `integral_leak`, which decays it. A value of zero or infinity disables the limit.
+    pub max_integral_rate: T,
+    /// Number of ticks between integral updates. The integral, which changes slowly,
+    /// skips its accumulation between updates and then accumulates the error over the
+    /// summed time steps, while the proportional and derivative terms run every tick.
+    /// `integral_leak` still decays the integral every tick. Ticks with a non-positive
+    /// time step are not counted. Zero and one update every tick.
+    pub integral_divisor: u16,
+    /// Numerical integration method of the integral term. Defaults to rectangular.
+    /// Only stabilizers built on `AngleControlData` apply it.
+    pub integration_method: IntegrationMethod,
+    /// Smoothing coefficient of the first-order low-pass filter on the roll derivative.
+    /// The filtered derivative is `alpha * derivative + (1 - alpha) * prev_derivative`,
+    /// so smaller values filter more, and the filter state is kept per axis.
+    /// Each loop filters with its own configuration, except the outer angle loops of
+    /// cascade stabilizers, which do not filter. A value of one disables the filter.
+    pub derivative_alpha_roll: T,
+    /// Smoothing coefficient of the pitch derivative. See `derivative_alpha_roll`.
+    pub derivative_alpha_pitch: T,
+    /// Smoothing coefficient of the yaw derivative. See `derivative_alpha_roll`.
+    pub derivative_alpha_yaw: T,
+    /// Maximum magnitude of the roll derivative term, `kd_roll * derivative`, after
+    /// filtering and before the terms are summed, so a sudden rate spike cannot slam
+    /// the actuator. The proportional and integral terms are not limited, unlike with
+    /// `output_min` and `output_max`. A value of zero or infinity disables the limit.
+    pub d_term_limit_roll: T,
+    /// Maximum magnitude of the pitch derivative term. See `d_term_limit_roll`.
+    pub d_term_limit_pitch: T,
+    /// Maximum magnitude of the yaw derivative term. See `d_term_limit_roll`.
+    pub d_term_limit_yaw: T,
+    /// Maximum magnitude of the roll proportional term, `kp_roll * error`, so a large
+    /// error cannot command more than a fixed share of the output. The limit applies
+    /// before the terms are summed, and `output_min` and `output_max` still clamp the
+    /// scaled total afterwards. The stored error is not clipped, so the integral and
+    /// derivative are unaffected. A value of zero or infinity disables the limit.
+    pub p_term_limit_roll: T,
+    /// Maximum magnitude of the pitch proportional term. See `p_term_limit_roll`.
+    pub p_term_limit_pitch: T,
+    /// Maximum magnitude of the yaw proportional term. See `p_term_limit_roll`.
+    pub p_term_limit_yaw: T,
+    /// Center frequency, in hertz, of a biquad notch filter applied to the gyro rates
+    /// that feed the derivative, to remove a dominant motor and propeller vibration
+    /// without the phase lag of a strong low-pass filter. The sample rate is derived
+    /// from `dt`, so the center must be below the Nyquist frequency, `0.5 / dt`.
+    /// Rate loops also use the gyro rate as their measurement, so the filtered rate
+    /// feeds all three terms there. Cascade stabilizers filter the shared gyro rates
+    /// with the notch of the rate loop configuration. A value of zero disables the filter.
+    pub notch_center_hz: T,
+    /// Optional roll override for the notch center frequency. Defaults to
+    /// `notch_center_hz` if `None`, and `Some(0.0)` leaves roll unfiltered.
+    pub notch_center_hz_roll: Option<T>,
+    /// Optional pitch override for the notch center frequency. See `notch_center_hz_roll`.
+    pub notch_center_hz_pitch: Option<T>,
+    /// Optional yaw override for the notch center frequency. See `notch_center_hz_roll`.
+    pub notch_center_hz_yaw: Option<T>,
+    /// Quality factor of the notch filter, the center frequency over the bandwidth.
+    /// Higher values make a narrower notch. Defaults to one.
+    pub notch_q: T,
+    /// Throttle fraction below which `control_throttle` scales integral accumulation
+    /// by `throttle / integral_throttle_threshold`, so the integrator fades out toward
+    /// zero throttle instead of resetting at a hard arming threshold.
+    /// A value of zero disables the scaling.
+    pub integral_throttle_threshold: T,
+    /// Throttle fraction below which `control_throttle` outputs `idle_output` on every
+    /// enabled axis instead of the PID output, and resets the integrals like a low throttle.
+    /// This keeps the motors spinning at a safe minimum before the loop takes over.
+    /// A value of zero disables idle gating.
+    pub idle_throttle: T,
+    /// Output of every enabled axis while the throttle is below `idle_throttle`.
+    /// Defaults to zero.
+    pub idle_output: T,
+    /// Throttle width above `idle_throttle` over which the output hands off
+    /// linearly from `idle_output` to the PID output. A value of zero switches
+    /// at the threshold.
+    pub idle_handoff: T,
+    /// Setpoint weight of the proportional term, so P acts on
+    /// `b_weight * set_point - measurement`. Values below one reduce the
+    /// proportional kick on setpoint steps. Defaults to one, standard PID.
+    /// Only stabilizers built on `AngleControlData` apply it, with the two degrees
+    /// of freedom `pid_form`.
+    pub b_weight: T,
+    /// Setpoint weight of the derivative term, so D acts on
+    /// `measurement - c_weight * set_point`. Defaults to zero, derivative on
+    /// measurement. Only stabilizers built on `AngleControlData` apply it, with the
+    /// two degrees of freedom `pid_form`.
+    pub c_weight: T,
+    /// Structure of the PID, deciding which terms act on the setpoint. Defaults to
+    /// two degrees of freedom with `b_weight` and `c_weight`. Only stabilizers built on
+    /// `AngleControlData` apply it.
+    pub pid_form: PidForm,
+    /// Source of the derivative, the supplied gyro rate, the change in the attitude
+    /// measurement, or a blend of both. Defaults to the gyro rate.
+    /// Only the angle loops of `AngleStabilizer` and `AngleFullStabilizer` apply it.
+    pub derivative_source: DerivativeSource<T>,
     /// Scale factor applied to PID output to match actuator range.
+    /// Applies to every axis without a per-axis override.
     pub scale: T,
+    /// Optional roll override for the output scale. Defaults to `scale` if `None`.
+    pub scale_roll: Option<T>,
+    /// Optional pitch override for the output scale. Defaults to `scale` if `None`.
+    pub scale_pitch: Option<T>,
+    /// Optional yaw override for the output scale. Defaults to `scale` if `None`.
+    pub scale_yaw: Option<T>,
+    /// Angle unit of the setpoints, measurements, and gains.
+    /// Only consulted where the unit matters, like the yaw wrap boundary.
+    pub angle_unit: AngleUnit,
+    /// Flag to wrap the yaw angle error at half a turn of `angle_unit`,
+    /// ±180 for degrees or ±π for radians, so the controller takes the
+    /// short way around the boundary. Only stabilizers that control the
+    /// yaw angle apply it.
+    pub yaw_wrap: bool,
+    /// Flag for a roll axis with a reversed sensor or actuator. The setpoint, attitude,
+    /// gyro rate, and disturbance of the axis are negated before the PID, which negates
+    /// the error, every term, and the output, while the gains stay positive and the
+    /// integral limits and anti-windup keep working as for a normal axis.
+    /// The output bounds still apply to the negated output.
+    /// Cascade stabilizers apply the flags of the angle configuration to every stage.
+    pub reverse_roll: bool,
+    /// Flag for a reversed pitch axis. See `reverse_roll`.
+    pub reverse_pitch: bool,
+    /// Flag for a reversed yaw axis. See `reverse_roll`.
+    pub reverse_yaw: bool,
+    /// Coupling gain matrix applied to the (roll, pitch, yaw) error vector before the
+    /// per-axis PIDs, after the reverse flags. Row `i` holds the gains of the error
+    /// of each axis into the PID of axis `i`, so `coupling[1][0]` lets a roll error
+    /// add a pitch correction on an airframe with coupled roll and pitch.
+    /// The identity matrix leaves the axes independent.
+    ///
+    /// The matrix maps the setpoints, attitude, gyro rates, and disturbance alike,
+    /// so the error, derivative, and feed-forward of each PID all see the coupled axes.
+    /// Cascade stabilizers apply the matrix of the angle configuration to every stage.
+    /// The yaw error is wrapped after coupling, so keep the yaw row and column at
+    /// identity while yaw wrapping is enabled. The yaw loop of single-loop angle
+    /// stabilizers controls a rate, so coupling yaw with roll or pitch mixes units.
+    pub coupling: [[T; 3]; 3],
+    /// Optional lower bound for the scaled output. Output is not bounded below if `None`.
+    pub output_min: Option<T>,
+    /// Optional upper bound for the scaled output. Output is not bounded above if `None`.
+    pub output_max: Option<T>,
+    /// Deadband for the scaled and clamped output. Outputs with a magnitude below
+    /// the deadband are forced to zero to suppress motor jitter near zero.
+    /// The integral is unaffected. A value of zero disables the deadband.
+    pub output_deadband: T,
+    /// Step size of the output grid, like one PWM count in output units. Each output
+    /// is rounded to the nearest multiple of the step after rate limiting and before
+    /// the deadband, so the outputs land on counts the actuator can produce.
+    /// The integral and the output rate limit see the unrounded output, and rounding
+    /// can pass an output bound that is off the grid by up to half a step.
+    /// A value of zero disables the quantization.
+    pub output_quantum: T,
+    /// Flag to carry the rounding error of each output into the next step, so the
+    /// outputs dither between neighboring grid points and average to the unrounded
+    /// output instead of drifting by up to half a step. The error starts from zero
+    /// after `reset`, and disabled axes discard it.
+    pub quantization_feedback: bool,
+    /// Optional maximum rate of change for the roll output, in output units per second.
+    /// The change from the previous output is limited to `max_output_rate_roll * dt`,
+    /// after clamping and before the deadband, starting from zero after `reset`.
+    /// The output is not rate limited if `None` or infinite.
+    pub max_output_rate_roll: Option<T>,
+    /// Optional maximum rate of change for the pitch output. See `max_output_rate_roll`.
+    pub max_output_rate_pitch: Option<T>,
+    /// Optional maximum rate of change for the yaw output. See `max_output_rate_roll`.
+    pub max_output_rate_yaw: Option<T>,
 }
 
 impl<T: Number> Default for FlightStabilizerConfig<T> {
@@ -64,6 +635,9 @@ impl<T: Number> Default for FlightStabilizerConfig<T> {
 impl<T: Number> FlightStabilizerConfig<T> {
     /// Creates a new configuration with default values for all parameters.
     /// Default values of zero or one are used.
+    /// Feed-forward gains of zero disable feed-forward.
+    /// Tracking gains of zero disable back-calculation anti-windup.
+    /// Angles are in degrees and the yaw angle error wraps at ±180 degrees.
     /// These should be replaced meaningful values that are tuned for the hardware.
     ///
     /// Example Usage
@@ -94,9 +668,19 @@ impl<T: Number> FlightStabilizerConfig<T> {
     /// // Set the upper limit for the integral term to prevent windup.
     /// config.i_limit = 25.0;
     ///
+    /// // Optionally override the integral limit for a single axis.
+    /// config.i_limit_yaw = Some(10.0);
+    ///
     /// // Set the scale to adjust the PID outputs to the actuator range.
     /// config.scale = 0.01;
     ///
+    /// // Optionally override the scale for a single axis.
+    /// config.scale_yaw = Some(0.02);
+    ///
+    /// // Optionally bound the scaled outputs to the actuator range.
+    /// config.output_min = Some(-1.0);
+    /// config.output_max = Some(1.0);
+    ///
     /// // The configuration is ready to use.
     /// use free_flight_stabilization::AngleStabilizer;
     ///
@@ -113,80 +697,3288 @@ impl<T: Number> FlightStabilizerConfig<T> {
             kp_yaw: T::one(),
             ki_yaw: T::zero(),
             kd_yaw: T::zero(),
+            kff_roll: T::zero(),
+            kff_pitch: T::zero(),
+            kff_yaw: T::zero(),
+            kdist_roll: T::zero(),
+            kdist_pitch: T::zero(),
+            kdist_yaw: T::zero(),
+            kt_roll: T::zero(),
+            kt_pitch: T::zero(),
+            kt_yaw: T::zero(),
             set_point_roll: T::zero(),
             set_point_pitch: T::zero(),
             set_point_yaw: T::zero(),
+            max_set_point_rate_roll: None,
+            max_set_point_rate_pitch: None,
+            max_set_point_rate_yaw: None,
+            attitude_alpha_roll: T::one(),
+            attitude_alpha_pitch: T::one(),
+            attitude_alpha_yaw: T::one(),
             i_limit: T::one(),
+            i_limit_roll: None,
+            i_limit_pitch: None,
+            i_limit_yaw: None,
+            clamp_integral_term: false,
+            i_limit_fraction: None,
+            integral_leak: T::zero(),
+            max_integral_rate: T::zero(),
+            integral_divisor: 1,
+            integration_method: IntegrationMethod::Rectangular,
+            derivative_alpha_roll: T::one(),
+            derivative_alpha_pitch: T::one(),
+            derivative_alpha_yaw: T::one(),
+            d_term_limit_roll: T::zero(),
+            d_term_limit_pitch: T::zero(),
+            d_term_limit_yaw: T::zero(),
+            p_term_limit_roll: T::zero(),
+            p_term_limit_pitch: T::zero(),
+            p_term_limit_yaw: T::zero(),
+            notch_center_hz: T::zero(),
+            notch_center_hz_roll: None,
+            notch_center_hz_pitch: None,
+            notch_center_hz_yaw: None,
+            notch_q: T::one(),
+            integral_throttle_threshold: T::zero(),
+            idle_throttle: T::zero(),
+            idle_output: T::zero(),
+            idle_handoff: T::zero(),
+            b_weight: T::one(),
+            c_weight: T::zero(),
+            pid_form: PidForm::TwoDegreesOfFreedom,
+            derivative_source: DerivativeSource::Rate,
             scale: T::one(),
+            scale_roll: None,
+            scale_pitch: None,
+            scale_yaw: None,
+            angle_unit: AngleUnit::Degrees,
+            yaw_wrap: true,
+            reverse_roll: false,
+            reverse_pitch: false,
+            reverse_yaw: false,
+            coupling: [
+                [T::one(), T::zero(), T::zero()],
+                [T::zero(), T::one(), T::zero()],
+                [T::zero(), T::zero(), T::one()],
+            ],
+            output_min: None,
+            output_max: None,
+            output_deadband: T::zero(),
+            output_quantum: T::zero(),
+            quantization_feedback: false,
+            max_output_rate_roll: None,
+            max_output_rate_pitch: None,
+            max_output_rate_yaw: None,
         }
     }
-}
 
-/// Configuration for PID cascade blending.
-#[derive(Clone, Copy)]
-pub struct CascadeBlendingConfig<T: Number, const N: usize> {
-    /// Blending weight.
-    pub beta: [T; N],
-    /// Pre-blend gain.
-    pub k: T,
-    /// Pre-blend scaling limit.
-    pub limit: T,
-}
+    /// Creates a configuration with gains placing the closed-loop poles of every axis
+    /// at the natural frequency `wn`, in radians per second, and the damping ratio `zeta`
+    /// for the plant model, with the default scale of one.
+    ///
+    /// Roll and pitch are angle loops with PD gains, the derivative acting on the measured
+    /// rate. Against the angle plant `gain / (s * (time_constant * s + 1))`, the closed loop
+    /// is the standard second-order system `wn² / (s² + 2 * zeta * wn * s + wn²)`, so
+    /// the integral gains are zero. Yaw is a rate loop with PI gains, like the yaw loop of
+    /// `AngleStabilizer`, with the same poles. The proportional gain also adds a zero to
+    /// the yaw response, so it overshoots more than the damping ratio suggests.
+    ///
+    /// A derivative gain is positive, and drives the loop, if the plant is already more
+    /// damped than requested, `2 * zeta * wn * time_constant < 1`.
+    /// The other fields keep their defaults, so raise `i_limit` if it clamps the yaw
+    /// integral needed to hold a rate, `rate / (wn² * time_constant)`.
+    /// Returns `ConfigError::NotPositive` naming the first argument that is not a positive
+    /// finite value.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{FlightStabilizerConfig, PlantModel};
+    ///
+    /// let plant = PlantModel { gain: 1000.0, time_constant: 0.05 };
+    /// let config = FlightStabilizerConfig::<f32>::from_bandwidth(20.0, 0.7, plant).unwrap();
+    /// assert!((0.02 - config.kp_roll).abs() < 1e-6);
+    /// assert!((-0.0004 - config.kd_roll).abs() < 1e-6);
+    /// ```
+    pub fn from_bandwidth(wn: T, zeta: T, plant: PlantModel<T>) -> Result<Self, ConfigError> {
+        let arguments = [
+            ("wn", wn),
+            ("zeta", zeta),
+            ("gain", plant.gain),
+            ("time_constant", plant.time_constant),
+        ];
+        for (argument, value) in arguments {
+            if !value.is_finite() || value <= T::zero() {
+                return Err(ConfigError::NotPositive(argument));
+            }
+        }
 
-impl<T: Number, const N: usize> Default for CascadeBlendingConfig<T, N> {
-    fn default() -> Self {
-        Self::new()
+        // Match the characteristic polynomial time_constant * s² + (1 + gain * kd) * s + gain * kp
+        let two = T::one() + T::one();
+        let tau_over_gain = plant.time_constant / plant.gain;
+        let proportional = wn * wn * tau_over_gain;
+        let damping = (two * zeta * wn * plant.time_constant - T::one()) / plant.gain;
+
+        let mut config = Self::new();
+        config.kp_roll = proportional;
+        config.ki_roll = T::zero();
+        config.kd_roll = -damping;
+        config.kp_pitch = proportional;
+        config.ki_pitch = T::zero();
+        config.kd_pitch = -damping;
+        config.kp_yaw = damping;
+        config.ki_yaw = proportional;
+        config.kd_yaw = T::zero();
+        Ok(config)
     }
-}
 
-impl<T: Number, const N: usize> CascadeBlendingConfig<T, N> {
-    /// Creates a new configuration with default values for all blending parameters.
-    /// Default values of one are used.
-    /// These should be replaced meaningful values that are tuned for the hardware.
+    /// Linearly interpolates between two configurations, like for gain scheduling or
+    /// crossfading to a new tuning. Every gain, setpoint, limit, scale, filter, and
+    /// coupling field is `a * (1 - t) + b * t`, with `t` clamped to the range zero to one,
+    /// so `t` of zero returns `a` and one returns `b`.
+    ///
+    /// Optional fields are interpolated when set in both configurations. Optional fields
+    /// set in only one, and the flags and modes, are taken from `a` while `t` is below
+    /// one half, and from `b` after. A field that is disabled at zero, like
+    /// `notch_center_hz`, is enabled as soon as `t` moves away from the disabled end.
     ///
     /// Example Usage
     /// ```
-    /// use free_flight_stabilization::CascadeBlendingConfig;
-    ///
-    /// let mut blending_config = CascadeBlendingConfig::<f32, 2>::new();
-    /// blending_config.k = 30.0;
-    /// blending_config.beta = [0.9; 2];
-    /// blending_config.limit = 240.0;
+    /// use free_flight_stabilization::FlightStabilizerConfig;
     ///
-    /// // The configuration is ready to use.
-    /// use free_flight_stabilization::{Angle2Stabilizer, FlightStabilizerConfig};
-    /// let angle_config = FlightStabilizerConfig::<f32>::new();
-    /// let rate_config = FlightStabilizerConfig::<f32>::new();
+    /// let mut low_config = FlightStabilizerConfig::<f32>::new();
+    /// low_config.kp_roll = 0.1;
+    /// let mut high_config = low_config;
+    /// high_config.kp_roll = 0.3;
     ///
-    /// let flight_stabilizer = Angle2Stabilizer::with_config(angle_config, rate_config, blending_config);
+    /// let config = FlightStabilizerConfig::lerp(&low_config, &high_config, 0.5);
+    /// assert!((0.2 - config.kp_roll).abs() < 1e-6);
     /// ```
-    pub fn new() -> Self {
+    pub fn lerp(a: &Self, b: &Self, t: T) -> Self {
+        let t = t.clamp(T::zero(), T::one());
+        let half = T::one() / (T::one() + T::one());
+        let nearer = if t < half { a } else { b };
+        let blend = |a: T, b: T| a * (T::one() - t) + b * t;
+        let blend_option = |a: Option<T>, b: Option<T>, nearer: Option<T>| match (a, b) {
+            (Some(a), Some(b)) => Some(blend(a, b)),
+            _ => nearer,
+        };
+        let mut coupling = a.coupling;
+        for (row, (a_row, b_row)) in coupling.iter_mut().zip(a.coupling.iter().zip(b.coupling)) {
+            for (gain, (a_gain, b_gain)) in row.iter_mut().zip(a_row.iter().zip(b_row)) {
+                *gain = blend(*a_gain, b_gain);
+            }
+        }
         Self {
-            beta: [T::one(); N],
-            k: T::one(),
-            limit: T::one(),
+            kp_roll: blend(a.kp_roll, b.kp_roll),
+            ki_roll: blend(a.ki_roll, b.ki_roll),
+            kd_roll: blend(a.kd_roll, b.kd_roll),
+            kp_pitch: blend(a.kp_pitch, b.kp_pitch),
+            ki_pitch: blend(a.ki_pitch, b.ki_pitch),
+            kd_pitch: blend(a.kd_pitch, b.kd_pitch),
+            kp_yaw: blend(a.kp_yaw, b.kp_yaw),
+            ki_yaw: blend(a.ki_yaw, b.ki_yaw),
+            kd_yaw: blend(a.kd_yaw, b.kd_yaw),
+            kff_roll: blend(a.kff_roll, b.kff_roll),
+            kff_pitch: blend(a.kff_pitch, b.kff_pitch),
+            kff_yaw: blend(a.kff_yaw, b.kff_yaw),
+            kdist_roll: blend(a.kdist_roll, b.kdist_roll),
+            kdist_pitch: blend(a.kdist_pitch, b.kdist_pitch),
+            kdist_yaw: blend(a.kdist_yaw, b.kdist_yaw),
+            kt_roll: blend(a.kt_roll, b.kt_roll),
+            kt_pitch: blend(a.kt_pitch, b.kt_pitch),
+            kt_yaw: blend(a.kt_yaw, b.kt_yaw),
+            set_point_roll: blend(a.set_point_roll, b.set_point_roll),
+            set_point_pitch: blend(a.set_point_pitch, b.set_point_pitch),
+            set_point_yaw: blend(a.set_point_yaw, b.set_point_yaw),
+            max_set_point_rate_roll: blend_option(
+                a.max_set_point_rate_roll,
+                b.max_set_point_rate_roll,
+                nearer.max_set_point_rate_roll,
+            ),
+            max_set_point_rate_pitch: blend_option(
+                a.max_set_point_rate_pitch,
+                b.max_set_point_rate_pitch,
+                nearer.max_set_point_rate_pitch,
+            ),
+            max_set_point_rate_yaw: blend_option(
+                a.max_set_point_rate_yaw,
+                b.max_set_point_rate_yaw,
+                nearer.max_set_point_rate_yaw,
+            ),
+            attitude_alpha_roll: blend(a.attitude_alpha_roll, b.attitude_alpha_roll),
+            attitude_alpha_pitch: blend(a.attitude_alpha_pitch, b.attitude_alpha_pitch),
+            attitude_alpha_yaw: blend(a.attitude_alpha_yaw, b.attitude_alpha_yaw),
+            i_limit: blend(a.i_limit, b.i_limit),
+            i_limit_roll: blend_option(a.i_limit_roll, b.i_limit_roll, nearer.i_limit_roll),
+            i_limit_pitch: blend_option(a.i_limit_pitch, b.i_limit_pitch, nearer.i_limit_pitch),
+            i_limit_yaw: blend_option(a.i_limit_yaw, b.i_limit_yaw, nearer.i_limit_yaw),
+            clamp_integral_term: nearer.clamp_integral_term,
+            i_limit_fraction: blend_option(
+                a.i_limit_fraction,
+                b.i_limit_fraction,
+                nearer.i_limit_fraction,
+            ),
+            integral_leak: blend(a.integral_leak, b.integral_leak),
+            max_integral_rate: blend(a.max_integral_rate, b.max_integral_rate),
+            integral_divisor: nearer.integral_divisor,
+            integration_method: nearer.integration_method,
+            derivative_alpha_roll: blend(a.derivative_alpha_roll, b.derivative_alpha_roll),
+            derivative_alpha_pitch: blend(a.derivative_alpha_pitch, b.derivative_alpha_pitch),
+            derivative_alpha_yaw: blend(a.derivative_alpha_yaw, b.derivative_alpha_yaw),
+            d_term_limit_roll: blend(a.d_term_limit_roll, b.d_term_limit_roll),
+            d_term_limit_pitch: blend(a.d_term_limit_pitch, b.d_term_limit_pitch),
+            d_term_limit_yaw: blend(a.d_term_limit_yaw, b.d_term_limit_yaw),
+            p_term_limit_roll: blend(a.p_term_limit_roll, b.p_term_limit_roll),
+            p_term_limit_pitch: blend(a.p_term_limit_pitch, b.p_term_limit_pitch),
+            p_term_limit_yaw: blend(a.p_term_limit_yaw, b.p_term_limit_yaw),
+            notch_center_hz: blend(a.notch_center_hz, b.notch_center_hz),
+            notch_center_hz_roll: blend_option(
+                a.notch_center_hz_roll,
+                b.notch_center_hz_roll,
+                nearer.notch_center_hz_roll,
+            ),
+            notch_center_hz_pitch: blend_option(
+                a.notch_center_hz_pitch,
+                b.notch_center_hz_pitch,
+                nearer.notch_center_hz_pitch,
+            ),
+            notch_center_hz_yaw: blend_option(
+                a.notch_center_hz_yaw,
+                b.notch_center_hz_yaw,
+                nearer.notch_center_hz_yaw,
+            ),
+            notch_q: blend(a.notch_q, b.notch_q),
+            integral_throttle_threshold: blend(
+                a.integral_throttle_threshold,
+                b.integral_throttle_threshold,
+            ),
+            idle_throttle: blend(a.idle_throttle, b.idle_throttle),
+            idle_output: blend(a.idle_output, b.idle_output),
+            idle_handoff: blend(a.idle_handoff, b.idle_handoff),
+            b_weight: blend(a.b_weight, b.b_weight),
+            c_weight: blend(a.c_weight, b.c_weight),
+            pid_form: nearer.pid_form,
+            derivative_source: nearer.derivative_source,
+            scale: blend(a.scale, b.scale),
+            scale_roll: blend_option(a.scale_roll, b.scale_roll, nearer.scale_roll),
+            scale_pitch: blend_option(a.scale_pitch, b.scale_pitch, nearer.scale_pitch),
+            scale_yaw: blend_option(a.scale_yaw, b.scale_yaw, nearer.scale_yaw),
+            angle_unit: nearer.angle_unit,
+            yaw_wrap: nearer.yaw_wrap,
+            reverse_roll: nearer.reverse_roll,
+            reverse_pitch: nearer.reverse_pitch,
+            reverse_yaw: nearer.reverse_yaw,
+            coupling,
+            output_min: blend_option(a.output_min, b.output_min, nearer.output_min),
+            output_max: blend_option(a.output_max, b.output_max, nearer.output_max),
+            output_deadband: blend(a.output_deadband, b.output_deadband),
+            output_quantum: blend(a.output_quantum, b.output_quantum),
+            quantization_feedback: nearer.quantization_feedback,
+            max_output_rate_roll: blend_option(
+                a.max_output_rate_roll,
+                b.max_output_rate_roll,
+                nearer.max_output_rate_roll,
+            ),
+            max_output_rate_pitch: blend_option(
+                a.max_output_rate_pitch,
+                b.max_output_rate_pitch,
+                nearer.max_output_rate_pitch,
+            ),
+            max_output_rate_yaw: blend_option(
+                a.max_output_rate_yaw,
+                b.max_output_rate_yaw,
+                nearer.max_output_rate_yaw,
+            ),
         }
     }
-}
 
-/// A trait for PID-based flight stabilizers that handle roll, pitch,
-/// and yaw control based on attitude and gyro data and dt..
-pub trait FlightStabilizer<T: Number> {
-    /// Takes desired setpoints, current IMU attitude, and gyro rates, then computes the control outputs.
+    /// Converts every number of the configuration to another `Number` type with `f`,
+    /// leaving the flags and enums unchanged. This moves a configuration tuned in one
+    /// precision, like `f64` on a host, to the type of the flight controller.
     ///
-    /// - `set_point`: A tuple of (roll, pitch, yaw) desired setpoints.
-    /// - `imu_attitude`: A tuple of (roll, pitch, yaw) current IMU measurements.
-    /// - `gyro_rate`: A tuple of (roll rate, pitch rate, yaw rate) from the gyroscope.
-    /// - `dt`: Time delta since the last update.
-    /// - `low_throttle`: Flag indicating if the throttle is low. Used for anti-integral windup.
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::FlightStabilizerConfig;
     ///
-    /// Returns a tuple of (roll control, pitch control, yaw control) outputs scaled for actuation.
-    fn control(
-        &mut self,
-        set_point: (T, T, T),
-        imu_attitude: (T, T, T),
-        gyro_rate: (T, T, T),
+    /// let mut tuned = FlightStabilizerConfig::<f64>::new();
+    /// tuned.kp_roll = 0.2;
+    /// tuned.i_limit_yaw = Some(10.0);
+    ///
+    /// let config: FlightStabilizerConfig<f32> = tuned.map(|value| value as f32);
+    /// assert_eq!(0.2, config.kp_roll);
+    /// assert_eq!(Some(10.0), config.i_limit_yaw);
+    /// ```
+    pub fn map<U: Number>(&self, mut f: impl FnMut(T) -> U) -> FlightStabilizerConfig<U> {
+        match self.try_map(|value| Ok::<U, core::convert::Infallible>(f(value))) {
+            Ok(config) => config,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Converts every number of the configuration to another `Number` type with a
+    /// fallible `f`, like `map`, returning the first error. The closure decides what
+    /// is an error, like a value out of range of a fixed-point type or a conversion
+    /// that loses precision.
+    ///
+    /// Example Usage
+    /// ```
+    /// use fixed::types::I16F16;
+    /// use free_flight_stabilization::FlightStabilizerConfig;
+    ///
+    /// let mut tuned = FlightStabilizerConfig::<f64>::new();
+    /// tuned.kp_roll = 0.5;
+    /// let config = tuned.try_map(|value| I16F16::checked_from_num(value).ok_or(value));
+    /// assert_eq!(Ok(I16F16::from_num(0.5)), config.map(|config| config.kp_roll));
+    ///
+    /// // The integral limit does not fit in sixteen integer bits.
+    /// tuned.i_limit = 1.0e6;
+    /// let config = tuned.try_map(|value| I16F16::checked_from_num(value).ok_or(value));
+    /// assert_eq!(Err(1.0e6), config);
+    /// ```
+    pub fn try_map<U: Number, E>(
+        &self,
+        mut f: impl FnMut(T) -> Result<U, E>,
+    ) -> Result<FlightStabilizerConfig<U>, E> {
+        let mut coupling = [[U::zero(); 3]; 3];
+        for (row, source_row) in coupling.iter_mut().zip(self.coupling) {
+            for (gain, source_gain) in row.iter_mut().zip(source_row) {
+                *gain = f(source_gain)?;
+            }
+        }
+        Ok(FlightStabilizerConfig {
+            kp_roll: f(self.kp_roll)?,
+            ki_roll: f(self.ki_roll)?,
+            kd_roll: f(self.kd_roll)?,
+            kp_pitch: f(self.kp_pitch)?,
+            ki_pitch: f(self.ki_pitch)?,
+            kd_pitch: f(self.kd_pitch)?,
+            kp_yaw: f(self.kp_yaw)?,
+            ki_yaw: f(self.ki_yaw)?,
+            kd_yaw: f(self.kd_yaw)?,
+            kff_roll: f(self.kff_roll)?,
+            kff_pitch: f(self.kff_pitch)?,
+            kff_yaw: f(self.kff_yaw)?,
+            kdist_roll: f(self.kdist_roll)?,
+            kdist_pitch: f(self.kdist_pitch)?,
+            kdist_yaw: f(self.kdist_yaw)?,
+            kt_roll: f(self.kt_roll)?,
+            kt_pitch: f(self.kt_pitch)?,
+            kt_yaw: f(self.kt_yaw)?,
+            set_point_roll: f(self.set_point_roll)?,
+            set_point_pitch: f(self.set_point_pitch)?,
+            set_point_yaw: f(self.set_point_yaw)?,
+            max_set_point_rate_roll: self.max_set_point_rate_roll.map(&mut f).transpose()?,
+            max_set_point_rate_pitch: self.max_set_point_rate_pitch.map(&mut f).transpose()?,
+            max_set_point_rate_yaw: self.max_set_point_rate_yaw.map(&mut f).transpose()?,
+            attitude_alpha_roll: f(self.attitude_alpha_roll)?,
+            attitude_alpha_pitch: f(self.attitude_alpha_pitch)?,
+            attitude_alpha_yaw: f(self.attitude_alpha_yaw)?,
+            i_limit: f(self.i_limit)?,
+            i_limit_roll: self.i_limit_roll.map(&mut f).transpose()?,
+            i_limit_pitch: self.i_limit_pitch.map(&mut f).transpose()?,
+            i_limit_yaw: self.i_limit_yaw.map(&mut f).transpose()?,
+            clamp_integral_term: self.clamp_integral_term,
+            i_limit_fraction: self.i_limit_fraction.map(&mut f).transpose()?,
+            integral_leak: f(self.integral_leak)?,
+            max_integral_rate: f(self.max_integral_rate)?,
+            integral_divisor: self.integral_divisor,
+            integration_method: self.integration_method,
+            derivative_alpha_roll: f(self.derivative_alpha_roll)?,
+            derivative_alpha_pitch: f(self.derivative_alpha_pitch)?,
+            derivative_alpha_yaw: f(self.derivative_alpha_yaw)?,
+            d_term_limit_roll: f(self.d_term_limit_roll)?,
+            d_term_limit_pitch: f(self.d_term_limit_pitch)?,
+            d_term_limit_yaw: f(self.d_term_limit_yaw)?,
+            p_term_limit_roll: f(self.p_term_limit_roll)?,
+            p_term_limit_pitch: f(self.p_term_limit_pitch)?,
+            p_term_limit_yaw: f(self.p_term_limit_yaw)?,
+            notch_center_hz: f(self.notch_center_hz)?,
+            notch_center_hz_roll: self.notch_center_hz_roll.map(&mut f).transpose()?,
+            notch_center_hz_pitch: self.notch_center_hz_pitch.map(&mut f).transpose()?,
+            notch_center_hz_yaw: self.notch_center_hz_yaw.map(&mut f).transpose()?,
+            notch_q: f(self.notch_q)?,
+            integral_throttle_threshold: f(self.integral_throttle_threshold)?,
+            idle_throttle: f(self.idle_throttle)?,
+            idle_output: f(self.idle_output)?,
+            idle_handoff: f(self.idle_handoff)?,
+            b_weight: f(self.b_weight)?,
+            c_weight: f(self.c_weight)?,
+            pid_form: self.pid_form,
+            derivative_source: match self.derivative_source {
+                DerivativeSource::Rate => DerivativeSource::Rate,
+                DerivativeSource::Measurement => DerivativeSource::Measurement,
+                DerivativeSource::Blend(alpha) => DerivativeSource::Blend(f(alpha)?),
+            },
+            scale: f(self.scale)?,
+            scale_roll: self.scale_roll.map(&mut f).transpose()?,
+            scale_pitch: self.scale_pitch.map(&mut f).transpose()?,
+            scale_yaw: self.scale_yaw.map(&mut f).transpose()?,
+            angle_unit: self.angle_unit,
+            yaw_wrap: self.yaw_wrap,
+            reverse_roll: self.reverse_roll,
+            reverse_pitch: self.reverse_pitch,
+            reverse_yaw: self.reverse_yaw,
+            coupling,
+            output_min: self.output_min.map(&mut f).transpose()?,
+            output_max: self.output_max.map(&mut f).transpose()?,
+            output_deadband: f(self.output_deadband)?,
+            output_quantum: f(self.output_quantum)?,
+            quantization_feedback: self.quantization_feedback,
+            max_output_rate_roll: self.max_output_rate_roll.map(&mut f).transpose()?,
+            max_output_rate_pitch: self.max_output_rate_pitch.map(&mut f).transpose()?,
+            max_output_rate_yaw: self.max_output_rate_yaw.map(&mut f).transpose()?,
+        })
+    }
+
+    /// Converts derivative gains from the firmware convention, where a positive `kd` damps
+    /// every loop, to the native convention by negating `kd` on the measurement-derivative axes.
+    /// `measurement_axes` flags the (roll, pitch, yaw) axes whose loop differentiates the
+    /// measurement for the stabilizer that will use the configuration:
+    ///
+    /// - `AngleStabilizer`, `ScheduledStabilizer`: `(true, true, false)`
+    /// - `AngleFullStabilizer`, `QuatStabilizer`: `(true, true, true)`
+    /// - `RateStabilizer`: `(false, false, false)`
+    /// - Cascade angle configurations: `(true, true, true)`
+    /// - Cascade rate and acceleration configurations: `(false, false, false)`
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::FlightStabilizerConfig;
+    ///
+    /// let mut config = FlightStabilizerConfig::<f32>::new();
+    /// config.kd_roll = 0.05; // firmware convention
+    /// config.kd_yaw = 0.00015;
+    ///
+    /// let config = config.normalize_gains((true, true, false));
+    /// assert_eq!(-0.05, config.kd_roll);
+    /// assert_eq!(0.00015, config.kd_yaw);
+    /// ```
+    pub fn normalize_gains(mut self, measurement_axes: (bool, bool, bool)) -> Self {
+        let (roll, pitch, yaw) = measurement_axes;
+        if roll {
+            self.kd_roll = -self.kd_roll;
+        }
+        if pitch {
+            self.kd_pitch = -self.kd_pitch;
+        }
+        if yaw {
+            self.kd_yaw = -self.kd_yaw;
+        }
+        self
+    }
+
+    /// Returns the (roll, pitch, yaw) integral limits.
+    /// Axes without an override use the shared `i_limit`.
+    pub fn i_limits(&self) -> (T, T, T) {
+        (
+            self.i_limit_roll.unwrap_or(self.i_limit),
+            self.i_limit_pitch.unwrap_or(self.i_limit),
+            self.i_limit_yaw.unwrap_or(self.i_limit),
+        )
+    }
+
+    /// Returns the (roll, pitch, yaw) output scales.
+    /// Axes without an override use the shared `scale`.
+    pub fn scales(&self) -> (T, T, T) {
+        (
+            self.scale_roll.unwrap_or(self.scale),
+            self.scale_pitch.unwrap_or(self.scale),
+            self.scale_yaw.unwrap_or(self.scale),
+        )
+    }
+
+    /// Returns the half range for wrapping the yaw angle error, or `None`
+    /// if `yaw_wrap` is not set.
+    pub fn yaw_wrap_range(&self) -> Option<T> {
+        if self.yaw_wrap {
+            self.angle_unit.half_turn()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the fraction of the error accumulated into the integral for a throttle
+    /// fraction in `[0, 1]`. Rises linearly from zero at zero throttle to one at
+    /// `integral_throttle_threshold`, and is always one if the threshold is not positive.
+    pub fn integral_throttle_scale(&self, throttle: T) -> T {
+        if self.integral_throttle_threshold <= T::zero() {
+            return T::one();
+        }
+        (throttle / self.integral_throttle_threshold).clamp(T::zero(), T::one())
+    }
+
+    /// Returns true if a throttle fraction is below `idle_throttle`, so the
+    /// stabilizer holds `idle_output` and resets its integrals.
+    pub fn is_idle(&self, throttle: T) -> bool {
+        throttle < self.idle_throttle
+    }
+
+    /// Hands the (roll, pitch, yaw) outputs off from `idle_output` to the PID outputs
+    /// for a throttle fraction. Below `idle_throttle` every output is `idle_output`, and
+    /// across the `idle_handoff` width above it the outputs move linearly to the PID outputs.
+    /// The outputs are returned unchanged if idle gating is disabled.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::FlightStabilizerConfig;
+    ///
+    /// let mut config = FlightStabilizerConfig::<f32>::new();
+    /// config.idle_throttle = 0.125;
+    /// config.idle_output = 0.25;
+    /// config.idle_handoff = 0.25;
+    ///
+    /// let output = (1.0, -1.0, 0.0);
+    /// assert_eq!((0.25, 0.25, 0.25), config.idle_outputs(0.0, output));
+    /// assert_eq!((0.625, -0.375, 0.125), config.idle_outputs(0.25, output));
+    /// assert_eq!(output, config.idle_outputs(0.5, output));
+    /// ```
+    pub fn idle_outputs(&self, throttle: T, output: (T, T, T)) -> (T, T, T) {
+        if self.idle_throttle <= T::zero() {
+            return output;
+        }
+        let fraction = if self.is_idle(throttle) {
+            T::zero()
+        } else if self.idle_handoff <= T::zero() {
+            T::one()
+        } else {
+            ((throttle - self.idle_throttle) / self.idle_handoff).clamp(T::zero(), T::one())
+        };
+        if fraction == T::one() {
+            return output;
+        }
+        let idle = self.idle_output;
+        (
+            idle + fraction * (output.0 - idle),
+            idle + fraction * (output.1 - idle),
+            idle + fraction * (output.2 - idle),
+        )
+    }
+
+    /// Returns the (roll, pitch, yaw) limits on the raw accumulated integral
+    /// for the given (roll, pitch, yaw) integral gains.
+    /// If `i_limit_fraction` and both output bounds are set, the fraction of the output
+    /// range is divided by the magnitude of each gain times its output scale.
+    /// Otherwise, if `clamp_integral_term` is set, the output unit integral limits are
+    /// divided by the magnitude of the gains. Otherwise the gains are ignored.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::FlightStabilizerConfig;
+    ///
+    /// let mut config = FlightStabilizerConfig::<f32>::new();
+    /// config.scale = 0.5;
+    /// config.output_min = Some(-1.0);
+    /// config.output_max = Some(1.0);
+    /// config.i_limit_fraction = Some(0.4);
+    ///
+    /// // The integral term, 0.5 * 0.2 * 8.0, is at most 0.4 * 2.0 of the output range
+    /// assert_eq!((8.0, 8.0, 8.0), config.integral_limits((0.2, -0.2, 0.2)));
+    /// ```
+    pub fn integral_limits(&self, ki: (T, T, T)) -> (T, T, T) {
+        let range_limit = self.integral_range_limit();
+        let limit = |i_limit: T, ki: T, scale: T| match range_limit {
+            Some(range_limit) if ki * scale != T::zero() => range_limit / (ki * scale).abs(),
+            _ if self.clamp_integral_term && ki != T::zero() => i_limit / ki.abs(),
+            _ => i_limit,
+        };
+        let (i_limit_roll, i_limit_pitch, i_limit_yaw) = self.i_limits();
+        let (scale_roll, scale_pitch, scale_yaw) = self.scales();
+        (
+            limit(i_limit_roll, ki.0, scale_roll),
+            limit(i_limit_pitch, ki.1, scale_pitch),
+            limit(i_limit_yaw, ki.2, scale_yaw),
+        )
+    }
+
+    /// Returns the limit of the scaled integral term set by `i_limit_fraction`,
+    /// or `None` if it or either output bound is not set.
+    fn integral_range_limit(&self) -> Option<T> {
+        match (self.i_limit_fraction, self.output_min, self.output_max) {
+            (Some(fraction), Some(min), Some(max)) => Some(fraction * (max - min)),
+            _ => None,
+        }
+    }
+
+    /// Returns the initial (roll, pitch, yaw) setpoints.
+    pub fn set_points(&self) -> (T, T, T) {
+        (
+            self.set_point_roll,
+            self.set_point_pitch,
+            self.set_point_yaw,
+        )
+    }
+
+    /// Negates the (roll, pitch, yaw) values of the axes flagged by `reverse_roll`,
+    /// `reverse_pitch`, and `reverse_yaw`.
+    pub fn reverse_axes(&self, value: (T, T, T)) -> (T, T, T) {
+        let reverse = |value: T, reverse: bool| if reverse { -value } else { value };
+        (
+            reverse(value.0, self.reverse_roll),
+            reverse(value.1, self.reverse_pitch),
+            reverse(value.2, self.reverse_yaw),
+        )
+    }
+
+    /// Multiplies the (roll, pitch, yaw) values by the `coupling` matrix.
+    /// Zero gains are skipped, so a non-finite value only reaches the axes it is
+    /// coupled into, and the identity matrix returns the values unchanged.
+    pub fn couple_axes(&self, value: (T, T, T)) -> (T, T, T) {
+        let values = [value.0, value.1, value.2];
+        let couple = |gains: [T; 3]| {
+            gains
+                .into_iter()
+                .zip(values)
+                .filter(|(gain, _)| *gain != T::zero())
+                .fold(T::zero(), |sum, (gain, value)| sum + gain * value)
+        };
+        (
+            couple(self.coupling[0]),
+            couple(self.coupling[1]),
+            couple(self.coupling[2]),
+        )
+    }
+
+    /// Maps the (roll, pitch, yaw) values onto the axes of the per-axis PIDs,
+    /// negating the reversed axes and then applying the coupling matrix.
+    pub fn transform_axes(&self, value: (T, T, T)) -> (T, T, T) {
+        self.couple_axes(self.reverse_axes(value))
+    }
+
+    /// Returns the (roll, pitch, yaw) angle errors, `set_point - imu_attitude`, with the
+    /// conventions of the angle loops in `control`. Both are mapped onto the reversed and
+    /// coupled axes, and the yaw error is wrapped at `yaw_wrap_range`, so the errors match
+    /// the proportional errors of `AngleFullStabilizer` with the default `b_weight`.
+    /// No state is read or advanced, so setpoint slewing and attitude smoothing are not
+    /// applied. Stabilizers with a yaw rate loop, like `AngleStabilizer`, compare the yaw
+    /// setpoint with the gyro rate instead of the yaw angle.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::FlightStabilizerConfig;
+    ///
+    /// let config = FlightStabilizerConfig::<f32>::new();
+    /// let errors = config.compute_errors((10.0, 0.0, 170.0), (5.0, 2.0, -170.0));
+    /// assert_eq!((5.0, -2.0, -20.0), errors);
+    /// ```
+    pub fn compute_errors(&self, set_point: (T, T, T), imu_attitude: (T, T, T)) -> (T, T, T) {
+        let (set_point_roll, set_point_pitch, set_point_yaw) = self.transform_axes(set_point);
+        let (imu_roll, imu_pitch, imu_yaw) = self.transform_axes(imu_attitude);
+        let yaw_error = set_point_yaw - imu_yaw;
+        (
+            set_point_roll - imu_roll,
+            set_point_pitch - imu_pitch,
+            match self.yaw_wrap_range() {
+                Some(half_range) => yaw_error.wrap(half_range),
+                None => yaw_error,
+            },
+        )
+    }
+
+    /// Moves the previous setpoints toward the commanded setpoints,
+    /// limited by the maximum setpoint rates over the time step `dt`.
+    pub fn slew_set_points(&self, previous: (T, T, T), commanded: (T, T, T), dt: T) -> (T, T, T) {
+        self.fixed_rate(dt).slew_set_points(previous, commanded)
+    }
+
+    /// Moves the previous outputs toward the clamped outputs,
+    /// limited by the maximum output rates over the time step `dt`.
+    pub fn slew_outputs(&self, previous: (T, T, T), output: (T, T, T), dt: T) -> (T, T, T) {
+        self.fixed_rate(dt).slew_outputs(previous, output)
+    }
+
+    /// Precomputes the coefficients of a control loop with the constant period `dt`,
+    /// for stabilizers to reuse every tick instead of recomputing them.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::FlightStabilizerConfig;
+    ///
+    /// let mut config = FlightStabilizerConfig::<f32>::new();
+    /// config.max_output_rate_roll = Some(2.0);
+    /// config.kt_roll = 4.0;
+    ///
+    /// let fixed_rate = config.fixed_rate(0.25);
+    /// assert_eq!(Some(0.5), fixed_rate.max_output_steps.0);
+    /// assert_eq!(1.0, fixed_rate.tracking_steps.0);
+    /// assert_eq!((0.5, 0.0, 0.0), fixed_rate.slew_outputs((0.0, 0.0, 0.0), (1.0, 0.0, 0.0)));
+    /// ```
+    pub fn fixed_rate(&self, dt: T) -> FixedRate<T> {
+        let step = |max_rate: Option<T>| max_rate.map(|max_rate| max_rate * dt);
+        FixedRate {
+            dt,
+            max_set_point_steps: (
+                step(self.max_set_point_rate_roll),
+                step(self.max_set_point_rate_pitch),
+                step(self.max_set_point_rate_yaw),
+            ),
+            max_output_steps: (
+                step(self.max_output_rate_roll),
+                step(self.max_output_rate_pitch),
+                step(self.max_output_rate_yaw),
+            ),
+            tracking_steps: (self.kt_roll * dt, self.kt_pitch * dt, self.kt_yaw * dt),
+        }
+    }
+
+    /// Returns the (roll, pitch, yaw) notch center frequencies.
+    /// Axes without an override use the shared `notch_center_hz`.
+    pub fn notch_centers(&self) -> (T, T, T) {
+        (
+            self.notch_center_hz_roll.unwrap_or(self.notch_center_hz),
+            self.notch_center_hz_pitch.unwrap_or(self.notch_center_hz),
+            self.notch_center_hz_yaw.unwrap_or(self.notch_center_hz),
+        )
+    }
+
+    /// Filters the gyro rates through the notch filters with the center frequencies of
+    /// `notch_centers` and `notch_q`, and returns the filtered rates. The filters pass
+    /// the rates of axes with a disabled notch through unchanged.
+    pub fn notch_rates(
+        &self,
+        notches: &mut [NotchFilter<T>; 3],
+        rate: (T, T, T),
         dt: T,
-        low_throttle: bool,
-    ) -> (T, T, T);
+    ) -> (T, T, T) {
+        let (center_roll, center_pitch, center_yaw) = self.notch_centers();
+        for (notch, center_hz) in notches
+            .iter_mut()
+            .zip([center_roll, center_pitch, center_yaw])
+        {
+            notch.center_hz = center_hz;
+            notch.q = self.notch_q;
+        }
+        (
+            notches[0].apply(rate.0, dt),
+            notches[1].apply(rate.1, dt),
+            notches[2].apply(rate.2, dt),
+        )
+    }
+
+    /// Moves the previous filtered attitude toward the measured attitude by the
+    /// attitude smoothing coefficients, and returns the new filtered attitude.
+    /// The filter starts from the measurement if there is no previous filtered
+    /// attitude, or an axis of it is not finite.
+    pub fn filter_attitude(&self, previous: Option<(T, T, T)>, measured: (T, T, T)) -> (T, T, T) {
+        fn ema<T: Number>(previous: Option<T>, measured: T, alpha: T, wrap: Option<T>) -> T {
+            match previous {
+                Some(previous) if alpha != T::one() && previous.is_finite() => match wrap {
+                    Some(range) => {
+                        (previous + alpha * (measured - previous).wrap(range)).wrap(range)
+                    }
+                    None => previous + alpha * (measured - previous),
+                },
+                _ => measured,
+            }
+        }
+        (
+            ema(
+                previous.map(|previous| previous.0),
+                measured.0,
+                self.attitude_alpha_roll,
+                None,
+            ),
+            ema(
+                previous.map(|previous| previous.1),
+                measured.1,
+                self.attitude_alpha_pitch,
+                None,
+            ),
+            ema(
+                previous.map(|previous| previous.2),
+                measured.2,
+                self.attitude_alpha_yaw,
+                self.yaw_wrap_range(),
+            ),
+        )
+    }
+
+    /// Clamps a scaled output to the configured output bounds.
+    /// Missing bounds do not limit the output.
+    ///
+    /// A non-finite output saturates instead of reaching the actuators.
+    /// Positive infinity goes to `output_max`, negative infinity goes to
+    /// `output_min`, and NaN goes to zero. A missing bound also falls back to zero.
+    /// Fixed-point types are always finite, so wrapped overflow is not detected.
+    pub fn clamp_output(&self, output: T) -> T {
+        if !output.is_finite() {
+            return if T::zero() < output {
+                self.output_max.unwrap_or(T::zero())
+            } else if output < T::zero() {
+                self.output_min.unwrap_or(T::zero())
+            } else {
+                T::zero()
+            };
+        }
+        match (self.output_min, self.output_max) {
+            (Some(min), Some(max)) => output.clamp(min, max),
+            (Some(min), None) if output < min => min,
+            (None, Some(max)) if max < output => max,
+            _ => output,
+        }
+    }
+
+    /// Returns the saturation state of a scaled output against the output bounds.
+    /// An output equal to a bound is within range. A NaN output is within range,
+    /// as `clamp_output` sends it to zero rather than to either bound.
+    pub fn saturation(&self, output: T) -> SaturationState {
+        match (self.output_min, self.output_max) {
+            (_, Some(max)) if max < output => SaturationState::Upper,
+            (Some(min), _) if output < min => SaturationState::Lower,
+            _ => SaturationState::None,
+        }
+    }
+
+    /// Returns the back-calculation anti-windup correction for the integral,
+    /// `(clamped - output) * kt * dt`.
+    /// The correction is zero for a non-positive `dt` or a non-finite output,
+    /// so a saturated overflow never poisons the integral.
+    pub fn back_calculation(&self, clamped: T, output: T, kt: T, dt: T) -> T {
+        back_calculation(clamped, output, kt * dt, dt)
+    }
+
+    /// Rounds the (roll, pitch, yaw) outputs to the nearest multiples of `output_quantum`,
+    /// returning the rounded outputs and the rounding errors to pass in on the next step.
+    /// With `quantization_feedback`, the previous errors are added to the outputs before
+    /// rounding. Otherwise the previous errors are ignored and the errors are zero.
+    /// The outputs pass through unchanged while the quantization is disabled.
+    pub fn quantize_outputs(&self, output: (T, T, T), error: (T, T, T)) -> ((T, T, T), (T, T, T)) {
+        let quantize = |output: T, error: T| {
+            if self.output_quantum <= T::zero() {
+                return (output, T::zero());
+            }
+            if !self.quantization_feedback {
+                return (output.quantize(self.output_quantum), T::zero());
+            }
+            let output = output + error;
+            let quantized = output.quantize(self.output_quantum);
+            // A non-finite output must not poison the following steps
+            (quantized, (output - quantized).finite_or(T::zero()))
+        };
+        let (roll, roll_error) = quantize(output.0, error.0);
+        let (pitch, pitch_error) = quantize(output.1, error.1);
+        let (yaw, yaw_error) = quantize(output.2, error.2);
+        ((roll, pitch, yaw), (roll_error, pitch_error, yaw_error))
+    }
+
+    /// Forces an output with a magnitude below `output_deadband` to zero.
+    pub fn apply_deadband(&self, output: T) -> T {
+        if output.abs() < self.output_deadband {
+            T::zero()
+        } else {
+            output
+        }
+    }
+
+    /// Checks the configuration for values that would silently produce bad flight behavior.
+    /// Returns an error naming the first offending field if any value is `NaN` or infinite,
+    /// or if an integral limit is negative, or zero while its axis has a nonzero integral gain.
+    /// Validation is optional; stabilizers accept any configuration.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        // Positive infinity disables the maximum integral and output rates
+        // and the derivative term limits, so every positive value is valid
+        let positive = |value: T| {
+            if T::zero() < value {
+                T::zero()
+            } else {
+                value
+            }
+        };
+        let max_output_rate = |rate: Option<T>| match rate {
+            Some(rate) if T::zero() < rate => None,
+            rate => rate,
+        };
+        let values = [
+            ("kp_roll", Some(self.kp_roll)),
+            ("ki_roll", Some(self.ki_roll)),
+            ("kd_roll", Some(self.kd_roll)),
+            ("kp_pitch", Some(self.kp_pitch)),
+            ("ki_pitch", Some(self.ki_pitch)),
+            ("kd_pitch", Some(self.kd_pitch)),
+            ("kp_yaw", Some(self.kp_yaw)),
+            ("ki_yaw", Some(self.ki_yaw)),
+            ("kd_yaw", Some(self.kd_yaw)),
+            ("kff_roll", Some(self.kff_roll)),
+            ("kff_pitch", Some(self.kff_pitch)),
+            ("kff_yaw", Some(self.kff_yaw)),
+            ("kdist_roll", Some(self.kdist_roll)),
+            ("kdist_pitch", Some(self.kdist_pitch)),
+            ("kdist_yaw", Some(self.kdist_yaw)),
+            ("kt_roll", Some(self.kt_roll)),
+            ("kt_pitch", Some(self.kt_pitch)),
+            ("kt_yaw", Some(self.kt_yaw)),
+            ("set_point_roll", Some(self.set_point_roll)),
+            ("set_point_pitch", Some(self.set_point_pitch)),
+            ("set_point_yaw", Some(self.set_point_yaw)),
+            ("max_set_point_rate_roll", self.max_set_point_rate_roll),
+            ("max_set_point_rate_pitch", self.max_set_point_rate_pitch),
+            ("max_set_point_rate_yaw", self.max_set_point_rate_yaw),
+            ("attitude_alpha_roll", Some(self.attitude_alpha_roll)),
+            ("attitude_alpha_pitch", Some(self.attitude_alpha_pitch)),
+            ("attitude_alpha_yaw", Some(self.attitude_alpha_yaw)),
+            ("i_limit", Some(self.i_limit)),
+            ("i_limit_roll", self.i_limit_roll),
+            ("i_limit_pitch", self.i_limit_pitch),
+            ("i_limit_yaw", self.i_limit_yaw),
+            ("i_limit_fraction", self.i_limit_fraction),
+            ("integral_leak", Some(self.integral_leak)),
+            ("max_integral_rate", Some(positive(self.max_integral_rate))),
+            ("derivative_alpha_roll", Some(self.derivative_alpha_roll)),
+            ("derivative_alpha_pitch", Some(self.derivative_alpha_pitch)),
+            ("derivative_alpha_yaw", Some(self.derivative_alpha_yaw)),
+            ("d_term_limit_roll", Some(positive(self.d_term_limit_roll))),
+            (
+                "d_term_limit_pitch",
+                Some(positive(self.d_term_limit_pitch)),
+            ),
+            ("d_term_limit_yaw", Some(positive(self.d_term_limit_yaw))),
+            ("p_term_limit_roll", Some(positive(self.p_term_limit_roll))),
+            (
+                "p_term_limit_pitch",
+                Some(positive(self.p_term_limit_pitch)),
+            ),
+            ("p_term_limit_yaw", Some(positive(self.p_term_limit_yaw))),
+            ("notch_center_hz", Some(self.notch_center_hz)),
+            ("notch_center_hz_roll", self.notch_center_hz_roll),
+            ("notch_center_hz_pitch", self.notch_center_hz_pitch),
+            ("notch_center_hz_yaw", self.notch_center_hz_yaw),
+            ("notch_q", Some(self.notch_q)),
+            (
+                "integral_throttle_threshold",
+                Some(self.integral_throttle_threshold),
+            ),
+            ("idle_throttle", Some(self.idle_throttle)),
+            ("idle_output", Some(self.idle_output)),
+            ("idle_handoff", Some(self.idle_handoff)),
+            ("b_weight", Some(self.b_weight)),
+            ("c_weight", Some(self.c_weight)),
+            (
+                "derivative_source",
+                match self.derivative_source {
+                    DerivativeSource::Blend(alpha) => Some(alpha),
+                    _ => None,
+                },
+            ),
+            ("scale", Some(self.scale)),
+            ("scale_roll", self.scale_roll),
+            ("scale_pitch", self.scale_pitch),
+            ("scale_yaw", self.scale_yaw),
+            ("output_min", self.output_min),
+            ("output_max", self.output_max),
+            ("output_deadband", Some(self.output_deadband)),
+            ("output_quantum", Some(self.output_quantum)),
+            (
+                "max_output_rate_roll",
+                max_output_rate(self.max_output_rate_roll),
+            ),
+            (
+                "max_output_rate_pitch",
+                max_output_rate(self.max_output_rate_pitch),
+            ),
+            (
+                "max_output_rate_yaw",
+                max_output_rate(self.max_output_rate_yaw),
+            ),
+        ];
+        for (field, value) in values {
+            match value {
+                Some(value) if !value.is_finite() => return Err(ConfigError::NotFinite(field)),
+                _ => {}
+            }
+        }
+
+        let i_limits = [
+            ("i_limit", Some(self.i_limit)),
+            ("i_limit_roll", self.i_limit_roll),
+            ("i_limit_pitch", self.i_limit_pitch),
+            ("i_limit_yaw", self.i_limit_yaw),
+            ("i_limit_fraction", self.i_limit_fraction),
+        ];
+        for (field, i_limit) in i_limits {
+            match i_limit {
+                Some(i_limit) if i_limit < T::zero() => {
+                    return Err(ConfigError::NegativeIntegralLimit(field))
+                }
+                _ => {}
+            }
+        }
+
+        let ki = (self.ki_roll, self.ki_pitch, self.ki_yaw);
+        let (i_limit_roll, i_limit_pitch, i_limit_yaw) = self.integral_limits(ki);
+        let (scale_roll, scale_pitch, scale_yaw) = self.scales();
+        // Name the field that set the limit of each axis
+        let field = |i_limit: Option<T>, axis_field, ki: T, scale: T| {
+            if self.integral_range_limit().is_some() && ki * scale != T::zero() {
+                "i_limit_fraction"
+            } else {
+                i_limit.map_or("i_limit", |_| axis_field)
+            }
+        };
+        let axes = [
+            (
+                field(self.i_limit_roll, "i_limit_roll", ki.0, scale_roll),
+                i_limit_roll,
+                ki.0,
+            ),
+            (
+                field(self.i_limit_pitch, "i_limit_pitch", ki.1, scale_pitch),
+                i_limit_pitch,
+                ki.1,
+            ),
+            (
+                field(self.i_limit_yaw, "i_limit_yaw", ki.2, scale_yaw),
+                i_limit_yaw,
+                ki.2,
+            ),
+        ];
+        for (field, i_limit, ki) in axes {
+            if i_limit == T::zero() && ki != T::zero() {
+                return Err(ConfigError::ZeroIntegralLimit(field));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Coefficients of a fixed-rate control loop, precomputed from a configuration and
+/// a constant loop period by `FlightStabilizerConfig::fixed_rate`.
+///
+/// The products of the loop period with the setpoint and output rate limits and with
+/// the back-calculation tracking gains are computed once instead of every tick.
+/// The integral stores the raw accumulated error, `∫error·dt`, and applies `ki`
+/// afterwards, so there is no `ki * dt` product to cache. The attitude and derivative
+/// smoothing coefficients are already per tick, and the notch filters cache their
+/// coefficients while `dt` is unchanged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedRate<T> {
+    /// The constant loop period.
+    pub dt: T,
+    /// The (roll, pitch, yaw) largest setpoint changes per tick, `max_set_point_rate_* * dt`.
+    pub max_set_point_steps: (Option<T>, Option<T>, Option<T>),
+    /// The (roll, pitch, yaw) largest output changes per tick, `max_output_rate_* * dt`.
+    pub max_output_steps: (Option<T>, Option<T>, Option<T>),
+    /// The (roll, pitch, yaw) back-calculation tracking gains per tick, `kt_* * dt`.
+    pub tracking_steps: (T, T, T),
+}
+
+impl<T: Number> FixedRate<T> {
+    /// Moves the previous setpoints toward the commanded setpoints, limited by the
+    /// largest setpoint changes per tick. See `FlightStabilizerConfig::slew_set_points`.
+    pub fn slew_set_points(&self, previous: (T, T, T), commanded: (T, T, T)) -> (T, T, T) {
+        let (roll, pitch, yaw) = self.max_set_point_steps;
+        (
+            slew(previous.0, commanded.0, roll, self.dt),
+            slew(previous.1, commanded.1, pitch, self.dt),
+            slew(previous.2, commanded.2, yaw, self.dt),
+        )
+    }
+
+    /// Moves the previous outputs toward the clamped outputs, limited by the
+    /// largest output changes per tick. See `FlightStabilizerConfig::slew_outputs`.
+    pub fn slew_outputs(&self, previous: (T, T, T), output: (T, T, T)) -> (T, T, T) {
+        let (roll, pitch, yaw) = self.max_output_steps;
+        (
+            slew(previous.0, output.0, roll, self.dt),
+            slew(previous.1, output.1, pitch, self.dt),
+            slew(previous.2, output.2, yaw, self.dt),
+        )
+    }
+
+    /// Returns the (roll, pitch, yaw) back-calculation anti-windup corrections for the
+    /// integrals. See `FlightStabilizerConfig::back_calculation`.
+    pub fn back_calculation(&self, clamped: (T, T, T), output: (T, T, T)) -> (T, T, T) {
+        let (roll, pitch, yaw) = self.tracking_steps;
+        (
+            back_calculation(clamped.0, output.0, roll, self.dt),
+            back_calculation(clamped.1, output.1, pitch, self.dt),
+            back_calculation(clamped.2, output.2, yaw, self.dt),
+        )
+    }
+}
+
+/// Returns the back-calculation correction `(clamped - output) * tracking_step`,
+/// or zero for a non-positive `dt` or a non-finite output.
+fn back_calculation<T: Number>(clamped: T, output: T, tracking_step: T, dt: T) -> T {
+    if dt <= T::zero() || !output.is_finite() {
+        return T::zero();
+    }
+    (clamped - output) * tracking_step
+}
+
+/// Moves a previous value toward a commanded value, limited by the largest change
+/// over the time step `dt`. The value is not rate limited if the step is `None`.
+fn slew<T: Number>(previous: T, commanded: T, max_step: Option<T>, dt: T) -> T {
+    match max_step {
+        Some(max_step) if T::zero() < dt => {
+            // Reach the commanded value exactly when the step is within the limit.
+            let step = commanded - previous;
+            if max_step < step {
+                previous + max_step
+            } else if step < -max_step {
+                previous - max_step
+            } else {
+                commanded
+            }
+        }
+        // Hold the previous value rather than slewing backwards in time.
+        Some(_) => previous,
+        None => commanded,
+    }
+}
+
+/// Fluent builder for `FlightStabilizerConfig`.
+/// Groups that are not set keep the defaults from `FlightStabilizerConfig::new()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlightStabilizerConfigBuilder<T: Number> {
+    config: FlightStabilizerConfig<T>,
+}
+
+impl<T: Number> Default for FlightStabilizerConfigBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Number> FlightStabilizerConfigBuilder<T> {
+    /// Creates a new builder starting from the default configuration.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{AngleStabilizer, FlightStabilizerConfigBuilder};
+    ///
+    /// let config = FlightStabilizerConfigBuilder::<f32>::new()
+    ///     .roll_gains(0.2, 0.3, -0.05)
+    ///     .pitch_gains(0.2, 0.3, -0.05)
+    ///     .yaw_gains(0.3, 0.05, 0.00015)
+    ///     .setpoints(0.0, 0.0, 0.0)
+    ///     .i_limit(25.0)
+    ///     .scale(0.01)
+    ///     .build();
+    ///
+    /// let flight_stabilizer = AngleStabilizer::with_config(config);
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            config: FlightStabilizerConfig::new(),
+        }
+    }
+
+    /// Sets the proportional, integral, and derivative gains for roll control.
+    pub fn roll_gains(mut self, kp: T, ki: T, kd: T) -> Self {
+        self.config.kp_roll = kp;
+        self.config.ki_roll = ki;
+        self.config.kd_roll = kd;
+        self
+    }
+
+    /// Sets the proportional, integral, and derivative gains for pitch control.
+    pub fn pitch_gains(mut self, kp: T, ki: T, kd: T) -> Self {
+        self.config.kp_pitch = kp;
+        self.config.ki_pitch = ki;
+        self.config.kd_pitch = kd;
+        self
+    }
+
+    /// Sets the proportional, integral, and derivative gains for yaw control.
+    pub fn yaw_gains(mut self, kp: T, ki: T, kd: T) -> Self {
+        self.config.kp_yaw = kp;
+        self.config.ki_yaw = ki;
+        self.config.kd_yaw = kd;
+        self
+    }
+
+    /// Sets the initial setpoints for roll, pitch, and yaw.
+    pub fn setpoints(mut self, roll: T, pitch: T, yaw: T) -> Self {
+        self.config.set_point_roll = roll;
+        self.config.set_point_pitch = pitch;
+        self.config.set_point_yaw = yaw;
+        self
+    }
+
+    /// Sets the shared upper limit for the integral term.
+    pub fn i_limit(mut self, i_limit: T) -> Self {
+        self.config.i_limit = i_limit;
+        self
+    }
+
+    /// Sets the scale factor applied to the PID outputs.
+    pub fn scale(mut self, scale: T) -> Self {
+        self.config.scale = scale;
+        self
+    }
+
+    /// Returns the finished configuration.
+    pub fn build(self) -> FlightStabilizerConfig<T> {
+        self.config
+    }
+}
+
+impl<T: Number> FlightStabilizerConfig<T> {
+    /// Creates a builder starting from the default configuration.
+    pub fn builder() -> FlightStabilizerConfigBuilder<T> {
+        FlightStabilizerConfigBuilder::new()
+    }
+}
+
+/// Configuration for PID cascade blending.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CascadeBlendingConfig<T: Number, const N: usize> {
+    /// Blending weight.
+    #[cfg_attr(feature = "serde", serde(with = "serde_array"))]
+    pub beta: [T; N],
+    /// Pre-blend gain.
+    pub k: T,
+    /// Pre-blend scaling limit.
+    pub limit: T,
+    /// Shifts each `beta` toward the unsaturated stage when one stage saturates.
+    /// Defaults to `false`. See `blend_cascade_with_saturation`.
+    pub saturation_aware: bool,
+}
+
+impl<T: Number, const N: usize> Default for CascadeBlendingConfig<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Number, const N: usize> CascadeBlendingConfig<T, N> {
+    /// Creates a new configuration with default values for all blending parameters.
+    /// Default values of one are used.
+    /// These should be replaced meaningful values that are tuned for the hardware.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::CascadeBlendingConfig;
+    ///
+    /// let mut blending_config = CascadeBlendingConfig::<f32, 2>::new();
+    /// blending_config.k = 30.0;
+    /// blending_config.beta = [0.9; 2];
+    /// blending_config.limit = 240.0;
+    ///
+    /// // The configuration is ready to use.
+    /// use free_flight_stabilization::{Angle2Stabilizer, FlightStabilizerConfig};
+    /// let angle_config = FlightStabilizerConfig::<f32>::new();
+    /// let rate_config = FlightStabilizerConfig::<f32>::new();
+    ///
+    /// let flight_stabilizer = Angle2Stabilizer::with_config(angle_config, rate_config, blending_config);
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            beta: [T::one(); N],
+            k: T::one(),
+            limit: T::one(),
+            saturation_aware: false,
+        }
+    }
+}
+
+/// Fluent builder for `CascadeBlendingConfig` that validates the blending weights.
+/// Values that are not set keep the defaults from `CascadeBlendingConfig::new()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CascadeBlendingConfigBuilder<T: Number, const N: usize> {
+    config: CascadeBlendingConfig<T, N>,
+    normalize: bool,
+}
+
+impl<T: Number, const N: usize> Default for CascadeBlendingConfigBuilder<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Number, const N: usize> CascadeBlendingConfigBuilder<T, N> {
+    /// Creates a new builder starting from the default configuration.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{CascadeBlendingConfigBuilder, ConfigError};
+    ///
+    /// let blending_config = CascadeBlendingConfigBuilder::<f32, 2>::new()
+    ///     .beta([3.0, 1.0])
+    ///     .normalize(true)
+    ///     .k(30.0)
+    ///     .limit(240.0)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!([0.75, 0.25], blending_config.beta);
+    ///
+    /// // Without normalization the weights must be from zero to one.
+    /// let result = CascadeBlendingConfigBuilder::<f32, 2>::new()
+    ///     .beta([3.0, 1.0])
+    ///     .build();
+    /// assert_eq!(Err(ConfigError::WeightOutOfRange("beta")), result);
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            config: CascadeBlendingConfig::new(),
+            normalize: false,
+        }
+    }
+
+    /// Sets the blending weight of each axis.
+    pub fn beta(mut self, beta: [T; N]) -> Self {
+        self.config.beta = beta;
+        self
+    }
+
+    /// Sets whether the blending weights are divided by their sum so they sum to one.
+    pub fn normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    /// Sets the pre-blend gain.
+    pub fn k(mut self, k: T) -> Self {
+        self.config.k = k;
+        self
+    }
+
+    /// Sets the pre-blend scaling limit.
+    pub fn limit(mut self, limit: T) -> Self {
+        self.config.limit = limit;
+        self
+    }
+
+    /// Sets whether each `beta` shifts toward the unsaturated stage.
+    pub fn saturation_aware(mut self, saturation_aware: bool) -> Self {
+        self.config.saturation_aware = saturation_aware;
+        self
+    }
+
+    /// Returns the finished configuration, or the first invalid field.
+    /// Every value must be finite and every weight must be non-negative.
+    /// Normalized weights must have a positive sum, and weights that are not
+    /// normalized must be at most one. Valid weights are returned unchanged
+    /// unless normalized.
+    pub fn build(self) -> Result<CascadeBlendingConfig<T, N>, ConfigError> {
+        let mut config = self.config;
+        for (field, value) in [("k", config.k), ("limit", config.limit)] {
+            if !value.is_finite() {
+                return Err(ConfigError::NotFinite(field));
+            }
+        }
+        let mut sum = T::zero();
+        for beta in config.beta {
+            if !beta.is_finite() {
+                return Err(ConfigError::NotFinite("beta"));
+            }
+            if beta < T::zero() || (!self.normalize && T::one() < beta) {
+                return Err(ConfigError::WeightOutOfRange("beta"));
+            }
+            sum += beta;
+        }
+        if self.normalize {
+            if !sum.is_finite() || sum <= T::zero() {
+                return Err(ConfigError::NotPositive("beta"));
+            }
+            for beta in config.beta.iter_mut() {
+                *beta /= sum;
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl<T: Number, const N: usize> CascadeBlendingConfig<T, N> {
+    /// Creates a builder starting from the default configuration.
+    pub fn builder() -> CascadeBlendingConfigBuilder<T, N> {
+        CascadeBlendingConfigBuilder::new()
+    }
+}
+
+/// Blends the outputs of an outer cascade stage into the setpoints of the inner stage.
+/// Each of the `N` axes is multiplied by `k`, clamped to `±limit`, and then
+/// low pass filtered against the previous inner setpoint with its own `beta`:
+/// `beta * clamp(k * output) + (1 - beta) * previous`.
+///
+/// Example Usage
+/// ```
+/// use free_flight_stabilization::{blend_cascade, CascadeBlendingConfig};
+///
+/// let mut blending_config = CascadeBlendingConfig::<f32, 2>::new();
+/// blending_config.k = 30.0;
+/// blending_config.beta = [0.5, 1.0];
+/// blending_config.limit = 240.0;
+///
+/// let set_points = blend_cascade([1.0, 10.0], [10.0, 0.0], &blending_config);
+/// assert_eq!([20.0, 240.0], set_points);
+/// ```
+pub fn blend_cascade<T: Number, const N: usize>(
+    outputs: [T; N],
+    prev_set_points: [T; N],
+    config: &CascadeBlendingConfig<T, N>,
+) -> [T; N] {
+    blend_cascade_with_saturation(outputs, prev_set_points, [false; N], config)
+}
+
+/// Blends like `blend_cascade`, taking a per-axis flag for whether the inner stage
+/// saturated on the previous step.
+/// With `saturation_aware` enabled, `beta` moves halfway toward the unsaturated stage.
+/// If the outer stage is clipped by `limit`, `beta` is halved to lean on the previous
+/// inner setpoint. If the inner stage is saturated, `beta` moves halfway to one to
+/// lean on the fresh outer command. When both or neither saturate, `beta` is used as is.
+/// With `saturation_aware` disabled, the flags are ignored.
+///
+/// Example Usage
+/// ```
+/// use free_flight_stabilization::{blend_cascade_with_saturation, CascadeBlendingConfig};
+///
+/// let mut blending_config = CascadeBlendingConfig::<f32, 2>::new();
+/// blending_config.k = 30.0;
+/// blending_config.beta = [0.5, 0.5];
+/// blending_config.limit = 240.0;
+/// blending_config.saturation_aware = true;
+///
+/// // The inner roll stage is saturated, so roll leans on the new command.
+/// // The outer pitch stage is clipped, so pitch leans on the previous setpoint.
+/// let set_points =
+///     blend_cascade_with_saturation([1.0, 10.0], [10.0, 0.0], [true, false], &blending_config);
+/// assert_eq!([25.0, 60.0], set_points);
+/// ```
+pub fn blend_cascade_with_saturation<T: Number, const N: usize>(
+    outputs: [T; N],
+    prev_set_points: [T; N],
+    inner_saturated: [bool; N],
+    config: &CascadeBlendingConfig<T, N>,
+) -> [T; N] {
+    let two = T::one() + T::one();
+    let mut result = outputs;
+    for (axis, value) in result.iter_mut().enumerate() {
+        let scaled = *value * config.k;
+        let limited = scaled.clamp(-config.limit, config.limit);
+        let outer_saturated = limited != scaled;
+        let beta = match (
+            config.saturation_aware,
+            outer_saturated,
+            inner_saturated[axis],
+        ) {
+            (true, true, false) => config.beta[axis] / two,
+            (true, false, true) => (config.beta[axis] + T::one()) / two,
+            _ => config.beta[axis],
+        };
+        *value = beta * limited + (T::one() - beta) * prev_set_points[axis];
+    }
+    result
+}
+
+/// Desired (roll, pitch, yaw) setpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetPoint<T> {
+    /// Roll setpoint.
+    pub roll: T,
+    /// Pitch setpoint.
+    pub pitch: T,
+    /// Yaw setpoint.
+    pub yaw: T,
+}
+
+impl<T> From<(T, T, T)> for SetPoint<T> {
+    fn from((roll, pitch, yaw): (T, T, T)) -> Self {
+        Self { roll, pitch, yaw }
+    }
+}
+
+impl<T> From<SetPoint<T>> for (T, T, T) {
+    fn from(value: SetPoint<T>) -> Self {
+        (value.roll, value.pitch, value.yaw)
+    }
+}
+
+/// Measured (roll, pitch, yaw) attitude, typically from the IMU.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Attitude<T> {
+    /// Roll angle.
+    pub roll: T,
+    /// Pitch angle.
+    pub pitch: T,
+    /// Yaw angle.
+    pub yaw: T,
+}
+
+impl<T> From<(T, T, T)> for Attitude<T> {
+    fn from((roll, pitch, yaw): (T, T, T)) -> Self {
+        Self { roll, pitch, yaw }
+    }
+}
+
+impl<T> From<Attitude<T>> for (T, T, T) {
+    fn from(value: Attitude<T>) -> Self {
+        (value.roll, value.pitch, value.yaw)
+    }
+}
+
+/// Measured (roll, pitch, yaw) rates, typically from the gyroscope.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GyroRate<T> {
+    /// Roll rate.
+    pub roll: T,
+    /// Pitch rate.
+    pub pitch: T,
+    /// Yaw rate.
+    pub yaw: T,
+}
+
+impl<T> From<(T, T, T)> for GyroRate<T> {
+    fn from((roll, pitch, yaw): (T, T, T)) -> Self {
+        Self { roll, pitch, yaw }
+    }
+}
+
+impl<T> From<GyroRate<T>> for (T, T, T) {
+    fn from(value: GyroRate<T>) -> Self {
+        (value.roll, value.pitch, value.yaw)
+    }
+}
+
+/// Converts a time delta in integer microseconds to seconds.
+/// A delta that cannot be represented by the number type, like more than
+/// 127 seconds for `I8F24`, converts to zero so the tick is skipped.
+///
+/// Example Usage
+/// ```
+/// use free_flight_stabilization::dt_from_us;
+///
+/// assert_eq!(0.01, dt_from_us::<f32>(10_000));
+/// ```
+pub fn dt_from_us<T: Number>(dt_us: u32) -> T {
+    T::from_f64(f64::from(dt_us) / 1_000_000.0).unwrap_or(T::zero())
+}
+
+/// Clamps each axis of a (roll, pitch, yaw) tuple to `[min, max]` with `Number::clamp`,
+/// and returns the clamped tuple with the saturation state of each axis.
+/// A value equal to a bound is within range.
+///
+/// Example Usage
+/// ```
+/// use free_flight_stabilization::{clamp3, SaturationState};
+///
+/// let (output, saturation) = clamp3((1.5, 0.5, -2.0), -1.0, 1.0);
+/// assert_eq!((1.0, 0.5, -1.0), output);
+/// assert_eq!(
+///     [SaturationState::Upper, SaturationState::None, SaturationState::Lower],
+///     saturation
+/// );
+/// ```
+pub fn clamp3<T: Number>(value: (T, T, T), min: T, max: T) -> ((T, T, T), [SaturationState; 3]) {
+    let saturation = |value: T| {
+        if max < value {
+            SaturationState::Upper
+        } else if value < min {
+            SaturationState::Lower
+        } else {
+            SaturationState::None
+        }
+    };
+    let (roll, pitch, yaw) = value;
+    (
+        (
+            roll.clamp(min, max),
+            pitch.clamp(min, max),
+            yaw.clamp(min, max),
+        ),
+        [saturation(roll), saturation(pitch), saturation(yaw)],
+    )
+}
+
+/// Returns the integral that keeps the integral term `ki * integral` unchanged
+/// when the integral gain changes from `ki` to `new_ki`, for bumpless gain transfer.
+/// The integral is kept if the gain is unchanged, or if the new gain is zero,
+/// as no integral can match the old term.
+pub(crate) fn bumpless_integral<T: Number>(integral: T, ki: T, new_ki: T) -> T {
+    if new_ki == ki || new_ki == T::zero() {
+        integral
+    } else {
+        (ki * integral / new_ki).finite_or(integral)
+    }
+}
+
+/// Adds the back-calculation corrections to the (roll, pitch, yaw) integrals,
+/// clamped to the integral limits like the integral accumulated by the loop.
+/// Nothing is added at low throttle, so an integral that was reset stays zero.
+pub(crate) fn track_integrals<T: Number>(
+    integrals: [T; 3],
+    tracking: (T, T, T),
+    integral_limits: (T, T, T),
+    low_throttle: bool,
+) -> [T; 3] {
+    if low_throttle {
+        return integrals;
+    }
+    let track = |integral: T, tracking: T, limit: T| (integral + tracking).clamp(-limit, limit);
+    [
+        track(integrals[0], tracking.0, integral_limits.0),
+        track(integrals[1], tracking.1, integral_limits.1),
+        track(integrals[2], tracking.2, integral_limits.2),
+    ]
+}
+
+/// Returns the value of each enabled (roll, pitch, yaw) axis, and the fallback
+/// of each disabled axis.
+pub(crate) fn select_axes<U: Copy>(enabled: [bool; 3], values: [U; 3], fallback: [U; 3]) -> [U; 3] {
+    let mut result = values;
+    for axis in 0..3 {
+        if !enabled[axis] {
+            result[axis] = fallback[axis];
+        }
+    }
+    result
+}
+
+/// Returns the value for each (roll, pitch, yaw) axis, and zero for each frozen axis,
+/// like the integral scale and leak of a frozen integrator.
+pub(crate) fn freeze_axes<T: Number>(frozen: [bool; 3], value: T) -> [T; 3] {
+    select_axes(frozen, [T::zero(); 3], [value; 3])
+}
+
+/// Counts the ticks and sums the time steps between the integral updates
+/// of one loop for `integral_divisor`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct IntegralDivider<T> {
+    ticks: u16,
+    dt: T,
+}
+
+impl<T: Number> IntegralDivider<T> {
+    /// Creates a divider that updates the integral on the next tick.
+    pub(crate) fn new() -> Self {
+        Self {
+            ticks: 0,
+            dt: T::zero(),
+        }
+    }
+
+    /// Counts a tick and returns the factor on the integral scale and maximum integral
+    /// rate of the loop. The factor is zero between updates, and on every `divisor`-th
+    /// tick it is the summed time steps over `dt`, so the integral accumulates
+    /// `error * summed dt`. A non-positive `dt` is not counted and returns zero.
+    pub(crate) fn step(&mut self, divisor: u16, dt: T) -> T {
+        if divisor <= 1 {
+            return T::one();
+        }
+        if dt <= T::zero() {
+            return T::zero();
+        }
+        self.ticks += 1;
+        self.dt += dt;
+        if self.ticks < divisor {
+            return T::zero();
+        }
+        let factor = (self.dt / dt).finite_or(T::zero());
+        *self = Self::new();
+        factor
+    }
+}
+
+/// Returns the (roll, pitch, yaw) axes whose integrals update this step, the enabled
+/// axes that are not frozen, or that are reset at low throttle.
+pub(crate) fn integral_axes(enabled: [bool; 3], frozen: [bool; 3], reset: bool) -> [bool; 3] {
+    [0, 1, 2].map(|axis| enabled[axis] && (reset || !frozen[axis]))
+}
+
+/// A trait for PID-based flight stabilizers that handle roll, pitch,
+/// and yaw control based on attitude and gyro data and dt..
+pub trait FlightStabilizer<T: Number> {
+    /// Takes desired setpoints, current IMU attitude, and gyro rates, then computes the control outputs.
+    ///
+    /// - `set_point`: A tuple of (roll, pitch, yaw) desired setpoints.
+    /// - `imu_attitude`: A tuple of (roll, pitch, yaw) current IMU measurements.
+    /// - `gyro_rate`: A tuple of (roll rate, pitch rate, yaw rate) from the gyroscope.
+    /// - `dt`: Time delta since the last update, in seconds. The integral accumulates
+    ///   `error * dt` and the derivative divides by `dt`, so passing microseconds would
+    ///   scale the integral by a million; use `control_us` instead. A non-positive value
+    ///   skips integral accumulation, derivative updates and setpoint slewing for the tick.
+    /// - `low_throttle`: Flag indicating if the throttle is low. Used for anti-integral windup.
+    ///
+    /// Returns a tuple of (roll control, pitch control, yaw control) outputs scaled for actuation.
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T);
+
+    /// Returns the controller to its initial state, as when it was created.
+    /// Clears the integral terms, derivative filter state, previous error history,
+    /// and any other history, like rate limited setpoints and outputs, and filtered attitude.
+    /// The configuration is kept.
+    /// Use this when arming or switching flight modes.
+    /// Stored setpoints return to the initial setpoints of the configuration.
+    fn reset(&mut self);
+
+    /// Stores (roll, pitch, yaw) setpoints for use by `control_stored`.
+    /// Use this for flight modes that hold a setpoint across many ticks.
+    /// The stored setpoints start at the initial setpoints of the configuration
+    /// and are left untouched by `control`.
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T);
+
+    /// Returns the stored (roll, pitch, yaw) setpoints.
+    fn setpoints(&self) -> (T, T, T);
+
+    /// Returns the saturation state of the (roll, pitch, yaw) outputs of the most recent
+    /// control step, before the deadband. An axis is saturated when its scaled output
+    /// was beyond `output_min` or `output_max` and was clamped.
+    /// Every axis is `SaturationState::None` before the first step and after `reset`.
+    /// The default reports every axis as `SaturationState::None`.
+    fn saturation(&self) -> [SaturationState; 3] {
+        [SaturationState::None; 3]
+    }
+
+    /// Stores a measured (roll, pitch, yaw) disturbance, like lateral acceleration,
+    /// for disturbance feed-forward. Each output gains `kdist` times its disturbance
+    /// before scaling, bypassing the PID like setpoint feed-forward.
+    /// The disturbance is held until replaced and is cleared by `reset`.
+    /// The default ignores the disturbance, for stabilizers without feed-forward.
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        let _ = (roll, pitch, yaw);
+    }
+
+    /// Seeds the (roll, pitch, yaw) integrals of the output loop, so a mode transition
+    /// can hand off accumulated trim instead of starting the integrators at zero.
+    /// Cascade stabilizers seed the innermost loop. The values are raw accumulated
+    /// integrals, `∫error·dt`, clamped to the integral limits, so the immediate
+    /// integral term contribution is `ki` times the clamped value.
+    /// The default ignores the integrals, for stabilizers without an integral term.
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        let _ = (roll, pitch, yaw);
+    }
+
+    /// Enables or disables the (roll, pitch, yaw) axes, like for a tailsitter or a
+    /// single-axis test rig. A disabled axis outputs zero and holds the integrals of
+    /// every cascade stage, while enabled axes are controlled normally. Its telemetry
+    /// terms are zero and it is never saturated. Every axis starts enabled,
+    /// and the flags are kept by `reset`.
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool);
+
+    /// Returns the (roll, pitch, yaw) flags set by `enable_axes`.
+    fn enabled_axes(&self) -> [bool; 3];
+
+    /// Freezes or thaws the integrals of the (roll, pitch, yaw) axes. A frozen axis
+    /// stops accumulating the integrals of every cascade stage without zeroing them,
+    /// so its integral term holds, and accumulation resumes where it left off once
+    /// thawed. Unlike a disabled axis, a frozen axis is still controlled. The leak and
+    /// back-calculation are held too, but a low throttle reset still zeroes the integrals.
+    /// Every axis starts thawed, and the flags are kept by `reset`.
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool);
+
+    /// Returns the (roll, pitch, yaw) flags set by `freeze_integral`.
+    fn frozen_integrals(&self) -> [bool; 3];
+
+    /// Returns the (roll, pitch, yaw) telemetry of the output loop for the most recent
+    /// control step, with the error, terms, gyro rates before and after the notch filter,
+    /// derivative, and final output of each axis. Every record is zero before the first
+    /// step and after `reset`. See `TelemetryStabilizer` to receive it every tick.
+    /// The default returns zero records, for stabilizers that do not record telemetry.
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        [Telemetry::new(); 3]
+    }
+
+    /// Returns statistics of the time steps received since creation or `reset`,
+    /// with the minimum, maximum, mean, and jitter, the mean change between
+    /// successive time steps. An irregular flight loop integrates and differentiates
+    /// unevenly, so these help to diagnose a misbehaving integral. Every control
+    /// method records its time step, including `control_fixed_rate`.
+    /// The default returns empty statistics, for stabilizers that do not record them.
+    fn timing_stats(&self) -> TimingStats<T> {
+        TimingStats::new()
+    }
+
+    /// Computes the control outputs like `control`, taking a throttle fraction in `[0, 1]`
+    /// instead of the `low_throttle` flag. The integral is never reset; instead its
+    /// accumulation is scaled by `integral_throttle_scale(throttle)` of each loop's
+    /// configuration, so it fades out smoothly below `integral_throttle_threshold`.
+    /// With the default threshold of zero the integral accumulates at any throttle.
+    /// Below `idle_throttle` of the output loop's configuration, the integrals are reset
+    /// and the outputs are held at `idle_output`, handing off to the PID as in `idle_outputs`.
+    /// The default calls `control`, with a low throttle for a throttle of zero or below.
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        let low_throttle = throttle <= T::zero();
+        self.control(set_point, imu_attitude, gyro_rate, dt, low_throttle)
+    }
+
+    /// Computes the control outputs like `control`, along with the saturation state
+    /// of each axis, so the caller can react to clamped outputs.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{
+    ///     AngleStabilizer, FlightStabilizer, FlightStabilizerConfig, SaturationState,
+    /// };
+    ///
+    /// let mut config = FlightStabilizerConfig::<f32>::new();
+    /// config.output_max = Some(1.0);
+    /// let mut stabilizer = AngleStabilizer::with_config(config);
+    ///
+    /// let zero = (0.0, 0.0, 0.0);
+    /// let (output, status) = stabilizer.control_with_status((5.0, 0.0, 0.0), zero, zero, 0.01, false);
+    /// assert_eq!(1.0, output.0);
+    /// assert_eq!(SaturationState::Upper, status[0]);
+    /// ```
+    fn control_with_status(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> ((T, T, T), [SaturationState; 3]) {
+        let output = self.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        (output, self.saturation())
+    }
+
+    /// Computes the control outputs like `control`, after checking the inputs.
+    /// Returns an error naming the first input with a `NaN` or infinite axis,
+    /// or if `dt` is not positive. The controller is not touched on error,
+    /// so a bad tick is skipped instead of corrupting the integrators.
+    /// Fixed-point inputs are always finite, so only `dt` can fail for them.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{AngleStabilizer, FlightStabilizer, StabilizerError};
+    ///
+    /// let mut stabilizer = AngleStabilizer::<f32>::new();
+    /// let zero = (0.0, 0.0, 0.0);
+    /// let result = stabilizer.try_control(zero, (f32::NAN, 0.0, 0.0), zero, 0.01, false);
+    /// assert_eq!(Err(StabilizerError::NotFinite("imu_attitude")), result);
+    /// ```
+    fn try_control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> Result<(T, T, T), StabilizerError> {
+        let inputs = [
+            ("set_point", set_point),
+            ("imu_attitude", imu_attitude),
+            ("gyro_rate", gyro_rate),
+        ];
+        for (input, (roll, pitch, yaw)) in inputs {
+            if !(roll.is_finite() && pitch.is_finite() && yaw.is_finite()) {
+                return Err(StabilizerError::NotFinite(input));
+            }
+        }
+        if !dt.is_finite() {
+            return Err(StabilizerError::NotFinite("dt"));
+        }
+        if dt <= T::zero() {
+            return Err(StabilizerError::NonPositiveDt);
+        }
+        Ok(self.control(set_point, imu_attitude, gyro_rate, dt, low_throttle))
+    }
+
+    /// Computes the control outputs like `control`, using the stored setpoints.
+    fn control_stored(
+        &mut self,
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        let set_point = self.setpoints();
+        self.control(set_point, imu_attitude, gyro_rate, dt, low_throttle)
+    }
+
+    /// Computes the control outputs like `control`, taking typed inputs
+    /// so the attitude and rate arguments cannot be swapped.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{
+    ///     AngleStabilizer, Attitude, FlightStabilizer, GyroRate, SetPoint,
+    /// };
+    ///
+    /// let mut stabilizer = AngleStabilizer::<f32>::new();
+    /// let set_point = SetPoint { roll: 10.0, pitch: 0.0, yaw: 0.0 };
+    /// let imu_attitude = Attitude { roll: 5.0, pitch: 0.0, yaw: 0.0 };
+    /// let gyro_rate = GyroRate { roll: 1.0, pitch: 0.0, yaw: 0.0 };
+    /// let output = stabilizer.control_typed(set_point, imu_attitude, gyro_rate, 0.01, false);
+    /// ```
+    fn control_typed(
+        &mut self,
+        set_point: SetPoint<T>,
+        imu_attitude: Attitude<T>,
+        gyro_rate: GyroRate<T>,
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        self.control(
+            set_point.into(),
+            imu_attitude.into(),
+            gyro_rate.into(),
+            dt,
+            low_throttle,
+        )
+    }
+
+    /// Computes the control outputs like `control`, taking the time delta as an
+    /// integer number of microseconds, as measured by most flight loops.
+    /// The time delta is converted to seconds with `dt_from_us`.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{AngleStabilizer, FlightStabilizer};
+    ///
+    /// let mut stabilizer = AngleStabilizer::<f32>::new();
+    /// let dt_us = 10_000; // 100 Hz loop
+    /// let output = stabilizer.control_us((0.0, 0.0, 0.0), (1.0, 0.0, 0.0), (0.0, 0.0, 0.0), dt_us, false);
+    /// ```
+    fn control_us(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt_us: u32,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        let dt = dt_from_us(dt_us);
+        self.control(set_point, imu_attitude, gyro_rate, dt, low_throttle)
+    }
+}
+
+/// Serde support for const generic arrays without allocation.
+/// Serde only implements array support for fixed lengths up to 32.
+#[cfg(feature = "serde")]
+pub(crate) mod serde_array {
+    use super::Number;
+    use core::fmt;
+    use core::marker::PhantomData;
+    use serde::de::{Deserialize, Deserializer, Error, SeqAccess, Visitor};
+    use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+    pub fn serialize<S, T, const N: usize>(array: &[T; N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        let mut tuple = serializer.serialize_tuple(N)?;
+        for value in array {
+            tuple.serialize_element(value)?;
+        }
+        tuple.end()
+    }
+
+    struct ArrayVisitor<T, const N: usize>(PhantomData<T>);
+
+    impl<'de, T, const N: usize> Visitor<'de> for ArrayVisitor<T, N>
+    where
+        T: Number + Deserialize<'de>,
+    {
+        type Value = [T; N];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "an array of length {}", N)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut array = [T::zero(); N];
+            for (i, value) in array.iter_mut().enumerate() {
+                *value = seq
+                    .next_element()?
+                    .ok_or_else(|| Error::invalid_length(i, &self))?;
+            }
+            if seq.next_element::<T>()?.is_some() {
+                return Err(Error::invalid_length(N + 1, &self));
+            }
+            Ok(array)
+        }
+    }
+
+    pub fn deserialize<'de, D, T, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Number + Deserialize<'de>,
+    {
+        deserializer.deserialize_tuple(N, ArrayVisitor::<T, N>(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    /// Test that a tuned configuration passes validation.
+    #[test]
+    fn test_flight_stabilizer_config_validate() {
+        let config = FlightStabilizerConfig::<f32>::builder()
+            .roll_gains(0.2, 0.3, -0.05)
+            .pitch_gains(0.2, 0.3, -0.05)
+            .yaw_gains(0.3, 0.05, 0.00015)
+            .i_limit(25.0)
+            .scale(0.01)
+            .build();
+        assert_eq!(Ok(()), config.validate());
+        assert_eq!(Ok(()), FlightStabilizerConfig::<f32>::new().validate());
+    }
+
+    /// Test that `NaN` and infinite values are rejected with the offending field.
+    #[test]
+    fn test_flight_stabilizer_config_validate_not_finite() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.set_point_pitch = f32::NAN;
+        assert_eq!(
+            Err(ConfigError::NotFinite("set_point_pitch")),
+            config.validate()
+        );
+
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.scale = f32::INFINITY;
+        assert_eq!(Err(ConfigError::NotFinite("scale")), config.validate());
+
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.output_min = Some(f32::NEG_INFINITY);
+        assert_eq!(Err(ConfigError::NotFinite("output_min")), config.validate());
+
+        // Positive infinity disables the maximum integral rate
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.max_integral_rate = f32::INFINITY;
+        assert_eq!(Ok(()), config.validate());
+        config.max_integral_rate = f32::NAN;
+        assert_eq!(
+            Err(ConfigError::NotFinite("max_integral_rate")),
+            config.validate()
+        );
+
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.derivative_source = DerivativeSource::Blend(f32::NAN);
+        assert_eq!(
+            Err(ConfigError::NotFinite("derivative_source")),
+            config.validate()
+        );
+    }
+
+    /// Test that negative integral limits are rejected with the offending field.
+    #[test]
+    fn test_flight_stabilizer_config_validate_negative_i_limit() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.i_limit = -1.0;
+        assert_eq!(
+            Err(ConfigError::NegativeIntegralLimit("i_limit")),
+            config.validate()
+        );
+
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.i_limit_yaw = Some(-1.0);
+        assert_eq!(
+            Err(ConfigError::NegativeIntegralLimit("i_limit_yaw")),
+            config.validate()
+        );
+    }
+
+    /// Test that a zero integral limit with a nonzero integral gain is rejected.
+    #[test]
+    fn test_flight_stabilizer_config_validate_zero_i_limit() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.i_limit = 0.0;
+        assert_eq!(Ok(()), config.validate());
+
+        config.ki_pitch = 0.3;
+        assert_eq!(
+            Err(ConfigError::ZeroIntegralLimit("i_limit")),
+            config.validate()
+        );
+
+        config.i_limit_pitch = Some(0.0);
+        assert_eq!(
+            Err(ConfigError::ZeroIntegralLimit("i_limit_pitch")),
+            config.validate()
+        );
+    }
+
+    /// Test that per-axis scales override the shared scale.
+    #[test]
+    fn test_flight_stabilizer_config_scales() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.scale = 0.01;
+        assert_eq!((0.01, 0.01, 0.01), config.scales());
+
+        config.scale_yaw = Some(0.02);
+        assert_eq!((0.01, 0.01, 0.02), config.scales());
+    }
+
+    /// Run the typed and tuple control paths side by side and compare them.
+    fn assert_typed_matches<S: FlightStabilizer<f32>>(mut typed: S, mut tuple: S) {
+        let set_point = (10.0, -10.0, 5.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 1.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        for _ in 0..5 {
+            let expected_output = tuple.control(set_point, imu_attitude, gyro_rate, dt, false);
+            let output = typed.control_typed(
+                set_point.into(),
+                imu_attitude.into(),
+                gyro_rate.into(),
+                dt,
+                false,
+            );
+            assert_eq!(expected_output, output, "Typed output should match.");
+        }
+    }
+
+    /// Test that the typed and tuple control paths produce identical output.
+    #[test]
+    fn test_flight_stabilizer_control_typed() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.ki_roll = 0.3;
+        config.kd_roll = -0.05;
+        config.kd_yaw = 0.00015;
+        config.i_limit = 25.0;
+        config.scale = 0.01;
+        let blending_config = CascadeBlendingConfig::<f32, 3>::new();
+
+        assert_typed_matches(
+            crate::AngleStabilizer::with_config(config),
+            crate::AngleStabilizer::with_config(config),
+        );
+        assert_typed_matches(
+            crate::RateStabilizer::with_config(config),
+            crate::RateStabilizer::with_config(config),
+        );
+        assert_typed_matches(
+            crate::Angle2FullStabilizer::with_config(config, config, blending_config),
+            crate::Angle2FullStabilizer::with_config(config, config, blending_config),
+        );
+    }
+
+    /// Test that microsecond time deltas convert to seconds.
+    #[test]
+    fn test_dt_from_us() {
+        use fixed::types::{I16F16, I8F24};
+
+        assert_eq!(0.01, dt_from_us::<f32>(10_000));
+        assert_eq!(0.0025, dt_from_us::<f64>(2_500));
+        assert_eq!(1.0, dt_from_us::<f32>(1_000_000));
+        assert_eq!(0.0, dt_from_us::<f32>(0));
+        assert!(number_close(0.01, dt_from_us::<I16F16>(10_000)));
+        assert_eq!(I8F24::ZERO, dt_from_us::<I8F24>(200_000_000));
+    }
+
+    /// Test that control_us behaves identically to control with the time delta in seconds.
+    #[test]
+    fn test_flight_stabilizer_control_us() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.ki_roll = 0.3;
+        config.kd_roll = -0.05;
+        config.ki_yaw = 0.05;
+        config.i_limit = 25.0;
+        let mut stabilizer = crate::AngleStabilizer::with_config(config);
+        let mut seconds_stabilizer = crate::AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, 0.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+
+        for _ in 0..10 {
+            let output = stabilizer.control_us(set_point, imu_attitude, gyro_rate, 10_000, false);
+            let expected_output =
+                seconds_stabilizer.control(set_point, imu_attitude, gyro_rate, 0.01, false);
+            assert_eq!(expected_output, output, "Outputs should be identical.");
+        }
+    }
+
+    /// Test that try_control rejects bad inputs without touching the controller.
+    #[test]
+    fn test_flight_stabilizer_try_control() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.ki_roll = 0.3;
+        config.kd_roll = -0.05;
+        config.ki_yaw = 0.05;
+        config.i_limit = 25.0;
+        let mut stabilizer = crate::AngleStabilizer::with_config(config);
+        let mut reference_stabilizer = crate::AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, 0.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+
+        for _ in 0..5 {
+            assert_eq!(
+                Err(StabilizerError::NotFinite("imu_attitude")),
+                stabilizer.try_control(set_point, (f32::NAN, 5.0, 0.0), gyro_rate, dt, false)
+            );
+            assert_eq!(
+                Err(StabilizerError::NotFinite("gyro_rate")),
+                stabilizer.try_control(
+                    set_point,
+                    imu_attitude,
+                    (1.0, -1.0, f32::INFINITY),
+                    dt,
+                    false
+                )
+            );
+            assert_eq!(
+                Err(StabilizerError::NotFinite("set_point")),
+                stabilizer.try_control(
+                    (f32::NEG_INFINITY, 0.0, 0.0),
+                    imu_attitude,
+                    gyro_rate,
+                    dt,
+                    false
+                )
+            );
+            assert_eq!(
+                Err(StabilizerError::NotFinite("dt")),
+                stabilizer.try_control(set_point, imu_attitude, gyro_rate, f32::NAN, false)
+            );
+            assert_eq!(
+                Err(StabilizerError::NonPositiveDt),
+                stabilizer.try_control(set_point, imu_attitude, gyro_rate, -dt, false)
+            );
+            assert_eq!(
+                Err(StabilizerError::NonPositiveDt),
+                stabilizer.try_control(set_point, imu_attitude, gyro_rate, 0.0, false)
+            );
+
+            // Rejected ticks leave the integrators as if they never happened
+            let output = stabilizer.try_control(set_point, imu_attitude, gyro_rate, dt, false);
+            let expected_output =
+                reference_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+            assert_eq!(Ok(expected_output), output, "Outputs should be identical.");
+            assert_eq!(
+                reference_stabilizer.last_terms(),
+                stabilizer.last_terms(),
+                "Integral terms should be identical."
+            );
+        }
+    }
+
+    /// Test that typed inputs convert to and from (roll, pitch, yaw) tuples.
+    #[test]
+    fn test_flight_stabilizer_typed_inputs() {
+        let attitude = Attitude::from((1.0, 2.0, 3.0));
+        assert_eq!(
+            Attitude {
+                roll: 1.0,
+                pitch: 2.0,
+                yaw: 3.0
+            },
+            attitude
+        );
+        assert_eq!((1.0, 2.0, 3.0), attitude.into());
+        let gyro_rate: GyroRate<f32> = (4.0, 5.0, 6.0).into();
+        assert_eq!(5.0, gyro_rate.pitch);
+        let set_point: SetPoint<f32> = Default::default();
+        assert_eq!((0.0, 0.0, 0.0), set_point.into());
+    }
+
+    /// Test that the output deadband snaps small outputs to zero.
+    #[test]
+    fn test_flight_stabilizer_config_apply_deadband() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        assert_eq!(0.001, config.apply_deadband(0.001));
+
+        config.output_deadband = 0.01;
+        assert_eq!(0.0, config.apply_deadband(0.005));
+        assert_eq!(0.0, config.apply_deadband(-0.005));
+        assert_eq!(0.01, config.apply_deadband(0.01));
+        assert_eq!(-0.5, config.apply_deadband(-0.5));
+    }
+
+    /// Test that firmware convention gains normalize to identical output.
+    #[test]
+    fn test_flight_stabilizer_config_normalize_gains() {
+        // Native convention gains
+        let mut native_config = FlightStabilizerConfig::<f32>::new();
+        native_config.kd_roll = -0.05;
+        native_config.kd_pitch = -0.05;
+        native_config.kd_yaw = 0.00015;
+        native_config.scale = 0.01;
+
+        // Firmware convention gains, where every positive kd damps
+        let mut firmware_config = native_config;
+        firmware_config.kd_roll = 0.05;
+        firmware_config.kd_pitch = 0.05;
+
+        let set_point = (10.0, -10.0, 5.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 1.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+
+        let normalized_config = firmware_config.normalize_gains((true, true, false));
+        assert_eq!(native_config, normalized_config);
+        let mut native = crate::AngleStabilizer::with_config(native_config);
+        let mut normalized = crate::AngleStabilizer::with_config(normalized_config);
+        for _ in 0..5 {
+            assert_eq!(
+                native.control(set_point, imu_attitude, gyro_rate, dt, false),
+                normalized.control(set_point, imu_attitude, gyro_rate, dt, false),
+                "Normalized output should match the native convention."
+            );
+        }
+
+        // Every axis of the full angle stabilizer differentiates the measurement
+        native_config.kd_yaw = -0.00015;
+        let normalized_config = firmware_config.normalize_gains((true, true, true));
+        assert_eq!(native_config, normalized_config);
+        let mut native = crate::AngleFullStabilizer::with_config(native_config);
+        let mut normalized = crate::AngleFullStabilizer::with_config(normalized_config);
+        for _ in 0..5 {
+            assert_eq!(
+                native.control(set_point, imu_attitude, gyro_rate, dt, false),
+                normalized.control(set_point, imu_attitude, gyro_rate, dt, false),
+                "Normalized output should match the native convention."
+            );
+        }
+    }
+
+    /// Test that the yaw wrap range follows the angle unit.
+    #[test]
+    fn test_flight_stabilizer_config_yaw_wrap_range() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        assert_eq!(AngleUnit::Degrees, config.angle_unit);
+        assert_eq!(Some(180.0), config.yaw_wrap_range());
+
+        config.angle_unit = AngleUnit::Radians;
+        assert_eq!(Some(core::f32::consts::PI), config.yaw_wrap_range());
+
+        config.yaw_wrap = false;
+        assert_eq!(None, config.yaw_wrap_range());
+    }
+
+    /// Test that the errors map the axes and wrap only the yaw error.
+    #[test]
+    fn test_flight_stabilizer_config_compute_errors() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        let set_point = (179.0, -179.0, 179.0);
+        let imu_attitude = (-179.0, 179.0, -179.0);
+        assert_eq!(
+            (358.0, -358.0, -2.0),
+            config.compute_errors(set_point, imu_attitude)
+        );
+
+        config.yaw_wrap = false;
+        assert_eq!(
+            (358.0, -358.0, 358.0),
+            config.compute_errors(set_point, imu_attitude)
+        );
+
+        // Reversed axes negate the error before the wrap
+        config.yaw_wrap = true;
+        config.reverse_roll = true;
+        config.reverse_yaw = true;
+        assert_eq!(
+            (-358.0, -358.0, 2.0),
+            config.compute_errors(set_point, imu_attitude)
+        );
+    }
+
+    /// Test that the output quantization rounds each axis and carries the error
+    /// only with feedback.
+    #[test]
+    fn test_flight_stabilizer_config_quantize_outputs() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        let output = (0.3, -0.3, 0.05);
+        let error = (0.125, 0.0, -0.125);
+        assert_eq!(
+            (output, (0.0, 0.0, 0.0)),
+            config.quantize_outputs(output, error)
+        );
+
+        config.output_quantum = 0.25;
+        let (quantized, error) = config.quantize_outputs(output, error);
+        assert_eq!((0.25, -0.25, 0.0), quantized);
+        assert_eq!((0.0, 0.0, 0.0), error);
+
+        config.quantization_feedback = true;
+        let (quantized, error) = config.quantize_outputs(output, (0.125, 0.0, -0.125));
+        assert_eq!((0.5, -0.25, 0.0), quantized);
+        assert!(vector_close((-0.075, -0.05, -0.075), error));
+
+        // A non-finite output does not carry an error
+        let (_, error) = config.quantize_outputs((f32::NAN, 0.0, 0.0), (0.0, 0.0, 0.0));
+        assert_eq!(0.0, error.0);
+    }
+
+    /// Test that integral limits are only divided by the gains in output unit mode.
+    #[test]
+    fn test_flight_stabilizer_config_integral_limits() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.i_limit = 10.0;
+        config.i_limit_yaw = Some(4.0);
+        let ki = (0.5, -2.0, 0.0);
+        assert_eq!((10.0, 10.0, 4.0), config.integral_limits(ki));
+
+        config.clamp_integral_term = true;
+        assert_eq!((20.0, 5.0, 4.0), config.integral_limits(ki));
+    }
+
+    /// Test that a fraction of the output range gives the raw integral limit
+    /// at which the scaled integral term reaches that fraction of the range.
+    #[test]
+    fn test_flight_stabilizer_config_integral_limit_fraction() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.i_limit = 10.0;
+        config.i_limit_yaw = Some(4.0);
+        config.scale = 0.01;
+        config.scale_pitch = Some(0.02);
+        config.i_limit_fraction = Some(0.4);
+        let ki = (0.5, -2.0, 0.0);
+
+        // Without both output bounds the raw limits apply
+        config.output_max = Some(1.0);
+        assert_eq!((10.0, 10.0, 4.0), config.integral_limits(ki));
+
+        // 40% of a range of 2.0 is 0.8, over |ki * scale| of 0.005 and 0.04.
+        // Yaw has no integral gain and keeps its raw limit.
+        config.output_min = Some(-1.0);
+        let (roll, pitch, yaw) = config.integral_limits(ki);
+        assert!(value_close(160.0, roll));
+        assert!(value_close(20.0, pitch));
+        assert_eq!(4.0, yaw);
+        assert!(value_close(0.8, 0.5 * 0.01 * roll));
+        assert!(value_close(0.8, (-2.0 * 0.02 * pitch).abs()));
+
+        // The fraction overrides the output unit limits too
+        config.clamp_integral_term = true;
+        assert_eq!((roll, pitch, yaw), config.integral_limits(ki));
+
+        // An asymmetric range, like a throttle-style output from 0 to 1000
+        config.output_min = Some(0.0);
+        config.output_max = Some(1000.0);
+        let (roll, _, _) = config.integral_limits(ki);
+        assert!(value_close(80000.0, roll));
+
+        // Validation flags a negative or zero fraction
+        config.ki_roll = 0.5;
+        config.i_limit_fraction = Some(-0.4);
+        assert_eq!(
+            Err(ConfigError::NegativeIntegralLimit("i_limit_fraction")),
+            config.validate()
+        );
+        config.i_limit_fraction = Some(0.0);
+        assert_eq!(
+            Err(ConfigError::ZeroIntegralLimit("i_limit_fraction")),
+            config.validate()
+        );
+        config.i_limit = 0.0;
+        config.i_limit_fraction = Some(0.4);
+        assert_eq!(Ok(()), config.validate());
+    }
+
+    /// Test that the coupling matrix mixes the axes and keeps non-finite values to their axes.
+    #[test]
+    fn test_config_couple_axes() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        assert_eq!((1.0, -2.0, 3.0), config.couple_axes((1.0, -2.0, 3.0)));
+        let (roll, pitch, yaw) = config.couple_axes((f32::NAN, -2.0, 3.0));
+        assert!(roll.is_nan());
+        assert_eq!((-2.0, 3.0), (pitch, yaw));
+
+        config.coupling[0][1] = 0.5;
+        config.coupling[1][0] = -0.25;
+        config.reverse_pitch = true;
+        assert_eq!((2.0, 1.75, 3.0), config.transform_axes((1.0, -2.0, 3.0)));
+    }
+
+    /// Test that the bandwidth constructor rejects arguments that are not positive.
+    #[test]
+    fn test_config_from_bandwidth_arguments() {
+        let plant = PlantModel {
+            gain: 1000.0,
+            time_constant: 0.05,
+        };
+        let from_bandwidth = FlightStabilizerConfig::<f32>::from_bandwidth;
+        assert!(from_bandwidth(20.0, 0.7, plant).is_ok());
+        assert_eq!(
+            Err(ConfigError::NotPositive("wn")),
+            from_bandwidth(0.0, 0.7, plant)
+        );
+        assert_eq!(
+            Err(ConfigError::NotPositive("zeta")),
+            from_bandwidth(20.0, f32::NAN, plant)
+        );
+        let plant = PlantModel {
+            gain: -1.0,
+            ..plant
+        };
+        assert_eq!(
+            Err(ConfigError::NotPositive("gain")),
+            from_bandwidth(20.0, 0.7, plant)
+        );
+        let plant = PlantModel {
+            gain: 1000.0,
+            time_constant: 0.0,
+        };
+        assert_eq!(
+            Err(ConfigError::NotPositive("time_constant")),
+            from_bandwidth(20.0, 0.7, plant)
+        );
+    }
+
+    /// Test that each axis clamps independently high and low.
+    #[test]
+    fn test_clamp3() {
+        use SaturationState::{Lower, None, Upper};
+        let cases = [
+            ((2.0, 0.0, 0.0), (1.0, 0.0, 0.0), [Upper, None, None]),
+            ((-2.0, 0.0, 0.0), (-1.0, 0.0, 0.0), [Lower, None, None]),
+            ((0.0, 2.0, 0.0), (0.0, 1.0, 0.0), [None, Upper, None]),
+            ((0.0, -2.0, 0.0), (0.0, -1.0, 0.0), [None, Lower, None]),
+            ((0.0, 0.0, 2.0), (0.0, 0.0, 1.0), [None, None, Upper]),
+            ((0.0, 0.0, -2.0), (0.0, 0.0, -1.0), [None, None, Lower]),
+            ((1.0, -1.0, 0.5), (1.0, -1.0, 0.5), [None, None, None]),
+        ];
+        for (value, expected, saturation) in cases {
+            assert_eq!((expected, saturation), clamp3(value, -1.0, 1.0));
+        }
+    }
+
+    /// Test that angles wrap into the half range.
+    #[test]
+    fn test_number_wrap() {
+        assert_eq!(-2.0, 358.0_f32.wrap(180.0));
+        assert_eq!(2.0, (-358.0_f32).wrap(180.0));
+        assert_eq!(180.0, 180.0_f32.wrap(180.0));
+        assert_eq!(-180.0, (-180.0_f32).wrap(180.0));
+        assert_eq!(10.0, 730.0_f32.wrap(180.0));
+        assert_eq!(358.0, 358.0_f32.wrap(0.0));
+        assert_eq!(f32::INFINITY, f32::INFINITY.wrap(180.0));
+        assert!(f32::NAN.wrap(180.0).is_nan());
+
+        // A huge finite angle wraps in constant time
+        let wrapped = 3.0e10_f32.wrap(180.0);
+        assert!((-180.0..=180.0).contains(&wrapped));
+        assert!((-180.0..=180.0).contains(&f32::MAX.wrap(180.0)));
+        assert!((-180.0..=180.0).contains(&(-f32::MAX).wrap(180.0)));
+        let wrapped = 3.0e10_f64.wrap(core::f64::consts::PI);
+        assert!(wrapped.abs() <= core::f64::consts::PI);
+        let wrapped =
+            fixed::types::I16F16::from_num(32000).wrap(fixed::types::I16F16::from_num(180));
+        assert_eq!(fixed::types::I16F16::from_num(-40), wrapped);
+    }
+
+    /// Test that non-finite values are replaced with a finite fallback.
+    #[test]
+    fn test_number_finite_or() {
+        assert_eq!(1.0, 1.0_f32.finite_or(2.0));
+        assert_eq!(2.0, f32::INFINITY.finite_or(2.0));
+        assert_eq!(2.0, f32::NAN.finite_or(2.0));
+        assert_eq!(0.0, f32::NAN.finite_or(f32::NEG_INFINITY));
+    }
+
+    /// Test rounding to a grid, with halves away from zero, for floating and fixed point.
+    #[test]
+    fn test_number_quantize() {
+        use fixed::types::I16F16;
+
+        assert_eq!(1.25, 1.3_f32.quantize(0.25));
+        assert_eq!(1.5, 1.4_f32.quantize(0.25));
+        assert_eq!(1.5, 1.375_f32.quantize(0.25));
+        assert_eq!(-1.5, (-1.375_f32).quantize(0.25));
+        assert_eq!(-1.25, (-1.3_f32).quantize(0.25));
+        assert_eq!(0.0, 0.1_f32.quantize(0.25));
+        assert_eq!(1.3, 1.3_f32.quantize(0.0));
+        assert!(f32::NAN.quantize(0.25).is_nan());
+
+        let step = I16F16::from_num(0.25);
+        assert_eq!(
+            I16F16::from_num(1.5),
+            I16F16::from_num(1.375).quantize(step)
+        );
+        assert_eq!(
+            I16F16::from_num(-1.25),
+            I16F16::from_num(-1.3).quantize(step)
+        );
+        assert_eq!(I16F16::MAX, I16F16::MAX.quantize(I16F16::from_num(100)));
+        assert_eq!(I16F16::MIN, I16F16::MIN.quantize(I16F16::from_num(100)));
+    }
+
+    /// Test that fixed-point products and sums saturate at the extremes instead of wrapping.
+    /// The inherent methods of the fixed-point type are bypassed to call the trait methods.
+    #[test]
+    fn test_number_saturating_fixed() {
+        use fixed::types::I16F16;
+        let n = <I16F16 as TestNumber>::from_f32;
+        let (max, min) = (I16F16::MAX, I16F16::MIN);
+        assert!(!<I16F16 as Number>::absorbs_overflow());
+        assert_eq!(n(6.0), Number::saturating_mul(n(2.0), n(3.0)));
+        assert_eq!(n(-0.25), Number::saturating_mul(n(0.5), n(-0.5)));
+        assert_eq!(max, Number::saturating_mul(n(200.0), n(200.0)));
+        assert_eq!(max, Number::saturating_mul(n(-200.0), n(-200.0)));
+        assert_eq!(min, Number::saturating_mul(n(200.0), n(-200.0)));
+        assert_eq!(min, Number::saturating_mul(max, n(-2.0)));
+        assert_eq!(max, Number::saturating_mul(min, n(-2.0)));
+        assert_eq!(min, Number::saturating_mul(min, n(1.0)));
+        assert_eq!(n(-16384.0), Number::saturating_mul(min, n(0.5)));
+        assert_eq!(n(32000.0), Number::saturating_mul(n(16000.0), n(2.0)));
+
+        assert_eq!(n(3.5), Number::saturating_add(n(1.5), n(2.0)));
+        assert_eq!(max, Number::saturating_add(n(30000.0), n(30000.0)));
+        assert_eq!(min, Number::saturating_add(n(-30000.0), n(-30000.0)));
+        assert_eq!(max, Number::saturating_add(max, n(1.0)));
+        assert_eq!(-I16F16::DELTA, Number::saturating_add(max, min));
+        assert_eq!(n(-32767.0), Number::saturating_add(min, n(1.0)));
+    }
+
+    /// Test that floating point products and sums are plain operations.
+    #[test]
+    fn test_number_saturating_float() {
+        assert!(<f32 as Number>::absorbs_overflow());
+        assert!(<f64 as Number>::absorbs_overflow());
+        assert_eq!(6.0, Number::saturating_mul(2.0_f32, 3.0));
+        assert_eq!(f32::INFINITY, Number::saturating_mul(f32::MAX, 2.0));
+        assert_eq!(
+            f32::NEG_INFINITY,
+            Number::saturating_add(f32::MIN, f32::MIN)
+        );
+        assert!(Number::saturating_mul(f32::NAN, 2.0).is_nan());
+        assert!(Number::saturating_add(f32::INFINITY, f32::NEG_INFINITY).is_nan());
+    }
+
+    /// Test that the integral throttle scale rises linearly up to the threshold.
+    #[test]
+    fn test_flight_stabilizer_config_integral_throttle_scale() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        assert_eq!(1.0, config.integral_throttle_scale(0.0));
+
+        config.integral_throttle_threshold = 0.25;
+        assert_eq!(0.0, config.integral_throttle_scale(0.0));
+        assert_eq!(0.0, config.integral_throttle_scale(-0.1));
+        assert_eq!(0.5, config.integral_throttle_scale(0.125));
+        assert_eq!(1.0, config.integral_throttle_scale(0.25));
+        assert_eq!(1.0, config.integral_throttle_scale(1.0));
+    }
+
+    /// Test that the idle gate holds the idle output and hands off to the PID output.
+    #[test]
+    fn test_flight_stabilizer_config_idle_outputs() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        let output = (1.0, -1.0, 0.5);
+        assert!(!config.is_idle(0.0));
+        assert_eq!(output, config.idle_outputs(0.0, output));
+
+        // Without a handoff the output switches at the threshold
+        config.idle_throttle = 0.1;
+        config.idle_output = 0.2;
+        assert!(config.is_idle(0.05));
+        assert!(!config.is_idle(0.1));
+        assert_eq!((0.2, 0.2, 0.2), config.idle_outputs(0.05, output));
+        assert_eq!(output, config.idle_outputs(0.1, output));
+
+        // With a handoff the output moves linearly to the PID output
+        config.idle_handoff = 0.2;
+        assert_eq!((0.2, 0.2, 0.2), config.idle_outputs(0.1, output));
+        assert!(vector_close(
+            (0.6, -0.4, 0.35),
+            config.idle_outputs(0.2, output)
+        ));
+        assert_eq!(output, config.idle_outputs(0.3, output));
+        assert_eq!(output, config.idle_outputs(1.0, output));
+    }
+
+    /// Test that the integral divider sums the time steps between updates.
+    #[test]
+    fn test_integral_divider() {
+        let mut divider = IntegralDivider::<f32>::new();
+        assert_eq!(1.0, divider.step(0, 0.01));
+        assert_eq!(1.0, divider.step(1, 0.01));
+
+        // Every third tick the factor is the summed time steps over the time step
+        assert_eq!(0.0, divider.step(3, 0.01));
+        assert_eq!(0.0, divider.step(3, 0.02));
+        // A non-positive time step is not counted
+        assert_eq!(0.0, divider.step(3, 0.0));
+        assert_eq!(0.0, divider.step(3, -0.01));
+        assert!(value_close(4.0, divider.step(3, 0.01)));
+        assert_eq!(0.0, divider.step(3, 0.01));
+    }
+
+    /// Test the attitude filter, including the yaw wrap boundary and restarts.
+    #[test]
+    fn test_flight_stabilizer_config_filter_attitude() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        let measured = (10.0, -10.0, 170.0);
+        assert_eq!(
+            measured,
+            config.filter_attitude(Some((0.0, 0.0, 0.0)), measured)
+        );
+
+        config.attitude_alpha_roll = 0.5;
+        config.attitude_alpha_pitch = 0.25;
+        config.attitude_alpha_yaw = 0.5;
+        assert_eq!(measured, config.filter_attitude(None, measured));
+        assert_eq!(
+            (5.0, -2.5, 85.0),
+            config.filter_attitude(Some((0.0, 0.0, 0.0)), measured)
+        );
+
+        // Yaw takes the short way around the boundary
+        assert_eq!(
+            (10.0, -10.0, -175.0),
+            config.filter_attitude(Some((10.0, -10.0, 160.0)), (10.0, -10.0, -150.0))
+        );
+
+        // A non-finite axis restarts from the measurement
+        assert_eq!(
+            (10.0, -5.0, 170.0),
+            config.filter_attitude(Some((f32::NAN, 0.0, f32::INFINITY)), (10.0, -20.0, 170.0))
+        );
+    }
+
+    /// Test that output slewing limits each axis and reaches the output exactly within the limit.
+    #[test]
+    fn test_flight_stabilizer_config_slew_outputs() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        let previous = (0.1, 0.1, 0.1);
+        let output = (0.3, -0.3, 0.3);
+        assert_eq!(output, config.slew_outputs(previous, output, 0.01));
+
+        config.max_output_rate_roll = Some(10.0);
+        config.max_output_rate_pitch = Some(10.0);
+        config.max_output_rate_yaw = Some(f32::INFINITY);
+        let slewed = config.slew_outputs(previous, output, 0.01);
+        assert!(vector_close((0.2, 0.0, 0.3), slewed));
+        assert_eq!(
+            output.2, slewed.2,
+            "Infinity should reach the output exactly."
+        );
+        assert_eq!(output, config.slew_outputs(previous, output, 1.0));
+        assert_eq!(previous, config.slew_outputs(previous, output, 0.0));
+        assert_eq!(Ok(()), config.validate());
+    }
+
+    /// Test that non-finite outputs saturate to the configured bounds.
+    #[test]
+    fn test_flight_stabilizer_config_clamp_output_non_finite() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        assert_eq!(0.0, config.clamp_output(f32::INFINITY));
+        assert_eq!(0.0, config.clamp_output(f32::NAN));
+
+        config.output_min = Some(-0.5);
+        config.output_max = Some(1.0);
+        assert_eq!(1.0, config.clamp_output(f32::INFINITY));
+        assert_eq!(-0.5, config.clamp_output(f32::NEG_INFINITY));
+        assert_eq!(0.0, config.clamp_output(f32::NAN));
+    }
+
+    /// Test that the saturation state matches the output bounds.
+    #[test]
+    fn test_flight_stabilizer_config_saturation() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        assert_eq!(SaturationState::None, config.saturation(1000.0));
+
+        config.output_min = Some(-0.5);
+        config.output_max = Some(1.0);
+        assert_eq!(SaturationState::Upper, config.saturation(1.5));
+        assert_eq!(SaturationState::Upper, config.saturation(f32::INFINITY));
+        assert_eq!(SaturationState::Lower, config.saturation(-0.75));
+        assert_eq!(SaturationState::None, config.saturation(1.0));
+        assert_eq!(SaturationState::None, config.saturation(-0.5));
+        assert_eq!(SaturationState::None, config.saturation(f32::NAN));
+    }
+
+    /// Test that back-calculation ignores non-finite outputs and non-positive time steps.
+    #[test]
+    fn test_flight_stabilizer_config_back_calculation() {
+        let config = FlightStabilizerConfig::<f32>::new();
+        assert_eq!(-0.5, config.back_calculation(1.0, 2.0, 5.0, 0.1));
+        assert_eq!(0.0, config.back_calculation(1.0, 2.0, 5.0, 0.0));
+        assert_eq!(0.0, config.back_calculation(1.0, f32::INFINITY, 5.0, 0.1));
+        assert_eq!(0.0, config.back_calculation(0.0, f32::NAN, 5.0, 0.1));
+    }
+
+    /// Test that a builder-produced configuration equals a manually populated one.
+    #[test]
+    fn test_flight_stabilizer_config_builder() {
+        let mut expected = FlightStabilizerConfig::<f32>::new();
+        expected.kp_roll = 0.2;
+        expected.ki_roll = 0.3;
+        expected.kd_roll = -0.05;
+        expected.kp_pitch = 0.25;
+        expected.ki_pitch = 0.35;
+        expected.kd_pitch = -0.04;
+        expected.kp_yaw = 0.3;
+        expected.ki_yaw = 0.05;
+        expected.kd_yaw = 0.00015;
+        expected.set_point_roll = 1.0;
+        expected.set_point_pitch = -1.0;
+        expected.set_point_yaw = 90.0;
+        expected.i_limit = 25.0;
+        expected.scale = 0.01;
+
+        let config = FlightStabilizerConfig::builder()
+            .roll_gains(0.2, 0.3, -0.05)
+            .pitch_gains(0.25, 0.35, -0.04)
+            .yaw_gains(0.3, 0.05, 0.00015)
+            .setpoints(1.0, -1.0, 90.0)
+            .i_limit(25.0)
+            .scale(0.01)
+            .build();
+
+        assert_eq!(expected, config, "Configurations should match.");
+    }
+
+    /// Test that unset builder groups keep the default values.
+    #[test]
+    fn test_flight_stabilizer_config_builder_defaults() {
+        let default_config = FlightStabilizerConfigBuilder::<f32>::new().build();
+        assert_eq!(FlightStabilizerConfig::new(), default_config);
+
+        let mut expected = FlightStabilizerConfig::<f32>::new();
+        expected.kp_yaw = 0.3;
+        expected.ki_yaw = 0.05;
+        expected.kd_yaw = 0.00015;
+        let config = FlightStabilizerConfig::builder()
+            .yaw_gains(0.3, 0.05, 0.00015)
+            .build();
+        assert_eq!(expected, config, "Only yaw gains should change.");
+    }
+
+    /// Test interpolation between two configurations field by field.
+    #[test]
+    fn test_flight_stabilizer_config_lerp() {
+        let mut a = FlightStabilizerConfig::<f32>::new();
+        a.kp_roll = 0.2;
+        a.kd_yaw = 0.0001;
+        a.set_point_pitch = -10.0;
+        a.i_limit = 20.0;
+        a.i_limit_roll = Some(10.0);
+        a.scale = 0.01;
+        a.output_min = Some(-1.0);
+        a.coupling[0][1] = 0.1;
+        let mut b = a;
+        b.kp_roll = 0.4;
+        b.kd_yaw = 0.0003;
+        b.set_point_pitch = 10.0;
+        b.i_limit = 30.0;
+        b.i_limit_roll = Some(20.0);
+        b.i_limit_yaw = Some(5.0);
+        b.scale = 0.02;
+        b.output_min = None;
+        b.coupling[0][1] = 0.3;
+        b.reverse_yaw = true;
+
+        assert_eq!(a, FlightStabilizerConfig::lerp(&a, &b, 0.0));
+        assert_eq!(b, FlightStabilizerConfig::lerp(&a, &b, 1.0));
+        assert_eq!(a, FlightStabilizerConfig::lerp(&a, &b, -1.0));
+        assert_eq!(b, FlightStabilizerConfig::lerp(&a, &b, 2.0));
+
+        let config = FlightStabilizerConfig::lerp(&a, &b, 0.5);
+        assert!(value_close(0.3, config.kp_roll));
+        assert!(value_close(0.0002, config.kd_yaw));
+        assert!(value_close(0.0, config.set_point_pitch));
+        assert!(value_close(25.0, config.i_limit));
+        assert!(value_close(15.0, config.i_limit_roll.unwrap()));
+        assert!(value_close(0.015, config.scale));
+        assert!(value_close(0.2, config.coupling[0][1]));
+        assert_eq!(1.0, config.coupling[1][1]);
+
+        // Fields set in only one configuration, and flags, switch at one half
+        assert_eq!(Some(5.0), config.i_limit_yaw);
+        assert_eq!(None, config.output_min);
+        assert!(config.reverse_yaw);
+        let config = FlightStabilizerConfig::lerp(&a, &b, 0.25);
+        assert_eq!(None, config.i_limit_yaw);
+        assert_eq!(Some(-1.0), config.output_min);
+        assert!(!config.reverse_yaw);
+    }
+
+    /// Test that fixed-point configurations with integer values interpolate exactly.
+    #[test]
+    fn test_flight_stabilizer_config_lerp_fixed() {
+        use fixed::types::I16F16;
+
+        let mut a = FlightStabilizerConfig::<I16F16>::new();
+        a.kp_roll = I16F16::from_num(2);
+        a.set_point_yaw = I16F16::from_num(-90);
+        a.i_limit = I16F16::from_num(25);
+        let mut b = a;
+        b.kp_roll = I16F16::from_num(6);
+        b.set_point_yaw = I16F16::from_num(90);
+        b.i_limit = I16F16::from_num(35);
+
+        let half = I16F16::from_num(0.5);
+        let quarter = I16F16::from_num(0.25);
+        assert_eq!(a, FlightStabilizerConfig::lerp(&a, &b, I16F16::ZERO));
+        assert_eq!(b, FlightStabilizerConfig::lerp(&a, &b, I16F16::ONE));
+        let config = FlightStabilizerConfig::lerp(&a, &b, half);
+        assert_eq!(I16F16::from_num(4), config.kp_roll);
+        assert_eq!(I16F16::ZERO, config.set_point_yaw);
+        assert_eq!(I16F16::from_num(30), config.i_limit);
+        let config = FlightStabilizerConfig::lerp(&a, &b, quarter);
+        assert_eq!(I16F16::from_num(3), config.kp_roll);
+        assert_eq!(I16F16::from_num(-45), config.set_point_yaw);
+        assert_eq!(I16F16::from_num(27.5), config.i_limit);
+    }
+
+    /// Test that a fully populated `f64` configuration converts to `f32` and back
+    /// with every field within `f32` precision.
+    #[test]
+    fn test_flight_stabilizer_config_map() {
+        let mut config = FlightStabilizerConfig::<f64>::new();
+        config.i_limit_roll = Some(0.0);
+        config.i_limit_pitch = Some(0.0);
+        config.i_limit_yaw = Some(0.0);
+        config.i_limit_fraction = Some(0.0);
+        config.notch_center_hz_roll = Some(0.0);
+        config.notch_center_hz_pitch = Some(0.0);
+        config.notch_center_hz_yaw = Some(0.0);
+        config.max_set_point_rate_roll = Some(0.0);
+        config.max_set_point_rate_pitch = Some(0.0);
+        config.max_set_point_rate_yaw = Some(0.0);
+        config.scale_roll = Some(0.0);
+        config.scale_pitch = Some(0.0);
+        config.scale_yaw = Some(0.0);
+        config.output_min = Some(0.0);
+        config.output_max = Some(0.0);
+        config.max_output_rate_roll = Some(0.0);
+        config.max_output_rate_pitch = Some(0.0);
+        config.max_output_rate_yaw = Some(0.0);
+        config.angle_unit = AngleUnit::Radians;
+        config.reverse_pitch = true;
+        config.clamp_integral_term = true;
+        config.derivative_source = DerivativeSource::Blend(0.0);
+
+        // Give every number a distinct value that f32 cannot represent exactly
+        let mut count = 0;
+        let config = config.map(|_| {
+            count += 1;
+            (count as f64 + 1.0 / 3.0) * if count % 2 == 0 { 1.0e-3 } else { -1.0e2 }
+        });
+        let mut values = [0.0; 128];
+        let mut fields = 0;
+        config.map(|value| {
+            values[fields] = value;
+            fields += 1;
+            value
+        });
+        assert_eq!(count, fields);
+
+        let converted: FlightStabilizerConfig<f32> = config.map(|value| value as f32);
+        assert_eq!(AngleUnit::Radians, converted.angle_unit);
+        assert!(converted.reverse_pitch && !converted.reverse_roll);
+        assert!(converted.clamp_integral_term);
+        match (config.derivative_source, converted.derivative_source) {
+            (DerivativeSource::Blend(alpha), DerivativeSource::Blend(converted)) => {
+                assert_eq!(alpha as f32, converted)
+            }
+            sources => panic!("derivative source should be kept: {:?}", sources),
+        }
+        assert_eq!(config.kp_roll as f32, converted.kp_roll);
+        assert_eq!(config.coupling[2][1] as f32, converted.coupling[2][1]);
+        assert_eq!(
+            config.max_output_rate_yaw.map(|value| value as f32),
+            converted.max_output_rate_yaw
+        );
+
+        let round_trip: FlightStabilizerConfig<f64> = converted.map(f64::from);
+        assert_ne!(config, round_trip);
+        let mut field = 0;
+        round_trip.map(|value| {
+            let expected = values[field];
+            assert!(
+                (expected - value).abs() <= expected.abs() * f32::EPSILON as f64,
+                "field {}: {} != {}",
+                field,
+                expected,
+                value
+            );
+            field += 1;
+            value
+        });
+        assert_eq!(fields, field);
+        assert_eq!(
+            round_trip,
+            round_trip.map(|value| value as f32).map(f64::from)
+        );
+    }
+
+    /// Test that a fallible conversion to fixed point flags the first value out of range.
+    #[test]
+    fn test_flight_stabilizer_config_try_map_fixed() {
+        use fixed::types::I16F16;
+
+        let checked = |value: f64| I16F16::checked_from_num(value).ok_or(value);
+        let mut config = FlightStabilizerConfig::<f64>::new();
+        config.kp_roll = 0.25;
+        config.i_limit = 25.0;
+        config.output_max = Some(1.0);
+        let converted = config.try_map(checked).unwrap();
+        assert_eq!(I16F16::from_num(0.25), converted.kp_roll);
+        assert_eq!(I16F16::from_num(25), converted.i_limit);
+        assert_eq!(Some(I16F16::ONE), converted.output_max);
+        assert_eq!(config, converted.map(|value| value.to_num::<f64>()));
+
+        config.output_max = Some(1.0e6);
+        config.output_min = Some(-1.0e7);
+        assert_eq!(Err(-1.0e7), config.try_map(checked));
+    }
+
+    /// Test that a fully populated configuration survives a JSON round trip.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_flight_stabilizer_config_serde_round_trip() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.kp_roll = 0.2;
+        config.ki_roll = 0.3;
+        config.kd_roll = -0.05;
+        config.kp_pitch = 0.25;
+        config.ki_pitch = 0.35;
+        config.kd_pitch = -0.04;
+        config.kp_yaw = 0.3;
+        config.ki_yaw = 0.05;
+        config.kd_yaw = 0.00015;
+        config.kt_roll = 1.0;
+        config.kt_pitch = 2.0;
+        config.kt_yaw = 3.0;
+        config.set_point_roll = 1.0;
+        config.set_point_pitch = -1.0;
+        config.set_point_yaw = 90.0;
+        config.i_limit = 25.0;
+        config.i_limit_pitch = Some(10.0);
+        config.scale = 0.01;
+        config.output_min = Some(-1.0);
+        config.output_max = Some(1.0);
+
+        let json = serde_json::to_string(&config).unwrap();
+        let round_trip: FlightStabilizerConfig<f32> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(config, round_trip, "Configuration should round trip.");
+    }
+
+    /// Test the blend of a two axis cascade against hand computed values.
+    #[test]
+    fn test_blend_cascade_two_axes() {
+        let mut config = CascadeBlendingConfig::<f32, 2>::new();
+        config.beta = [0.9, 0.5];
+        config.k = 30.0;
+        config.limit = 240.0;
+
+        // roll:  0.9 * (30 * 1.0) + 0.1 * 50 = 32
+        // pitch: 0.5 * (30 * -0.5) + 0.5 * -5 = -10
+        let result = blend_cascade([1.0, -0.5], [50.0, -5.0], &config);
+        assert!(value_close(32.0, result[0]));
+        assert!(value_close(-10.0, result[1]));
+    }
+
+    /// Test the blend of a three axis cascade against hand computed values.
+    #[test]
+    fn test_blend_cascade_three_axes() {
+        let mut config = CascadeBlendingConfig::<f32, 3>::new();
+        config.beta = [1.0, 0.0, 0.25];
+        config.k = 2.0;
+        config.limit = 10.0;
+
+        // roll:  1 * (2 * 3) + 0 * 100 = 6
+        // pitch: 0 * (2 * 3) + 1 * 7 = 7
+        // yaw:   0.25 * clamp(2 * -20) + 0.75 * 4 = 0.25 * -10 + 3 = 0.5
+        let result = blend_cascade([3.0, 3.0, -20.0], [100.0, 7.0, 4.0], &config);
+        assert!(vector_close(
+            (6.0, 7.0, 0.5),
+            (result[0], result[1], result[2])
+        ));
+    }
+
+    /// Test that saturation-aware blending shifts beta toward the unsaturated stage.
+    #[test]
+    fn test_blend_cascade_with_saturation() {
+        let mut config = CascadeBlendingConfig::<f32, 3>::new();
+        config.beta = [0.5; 3];
+        config.k = 2.0;
+        config.limit = 10.0;
+        config.saturation_aware = true;
+
+        // roll:  inner saturated, beta 0.75: 0.75 * (2 * 3) + 0.25 * 2 = 5
+        // pitch: outer clipped, beta 0.25: 0.25 * 10 + 0.75 * 2 = 4
+        // yaw:   both saturated, beta 0.5: 0.5 * -10 + 0.5 * 2 = -4
+        let result = blend_cascade_with_saturation(
+            [3.0, 20.0, -20.0],
+            [2.0; 3],
+            [true, false, true],
+            &config,
+        );
+        assert!(vector_close(
+            (5.0, 4.0, -4.0),
+            (result[0], result[1], result[2])
+        ));
+
+        // Without saturation awareness the flags are ignored.
+        config.saturation_aware = false;
+        let result = blend_cascade_with_saturation(
+            [3.0, 20.0, -20.0],
+            [2.0; 3],
+            [true, false, true],
+            &config,
+        );
+        let expected = blend_cascade([3.0, 20.0, -20.0], [2.0; 3], &config);
+        assert_eq!(expected, result);
+    }
+
+    /// Test that normalized blending weights sum to one.
+    #[test]
+    fn test_cascade_blending_config_builder_normalize() {
+        let config = CascadeBlendingConfig::<f32, 3>::builder()
+            .beta([2.0, 1.0, 1.0])
+            .normalize(true)
+            .k(30.0)
+            .limit(240.0)
+            .saturation_aware(true)
+            .build()
+            .unwrap();
+        assert_eq!([0.5, 0.25, 0.25], config.beta);
+        assert_eq!(30.0, config.k);
+        assert_eq!(240.0, config.limit);
+        assert!(config.saturation_aware);
+
+        // A set of zero weights cannot be normalized
+        let result = CascadeBlendingConfig::<f32, 2>::builder()
+            .beta([0.0; 2])
+            .normalize(true)
+            .build();
+        assert_eq!(Err(ConfigError::NotPositive("beta")), result);
+    }
+
+    /// Test that negative, out of range, and non-finite values are rejected.
+    #[test]
+    fn test_cascade_blending_config_builder_rejects() {
+        for normalize in [false, true] {
+            let result = CascadeBlendingConfig::<f32, 2>::builder()
+                .beta([0.5, -0.5])
+                .normalize(normalize)
+                .build();
+            assert_eq!(Err(ConfigError::WeightOutOfRange("beta")), result);
+            let result = CascadeBlendingConfig::<f32, 2>::builder()
+                .beta([f32::NAN, 0.5])
+                .normalize(normalize)
+                .build();
+            assert_eq!(Err(ConfigError::NotFinite("beta")), result);
+        }
+        let result = CascadeBlendingConfig::<f32, 2>::builder()
+            .beta([0.5, 1.5])
+            .build();
+        assert_eq!(Err(ConfigError::WeightOutOfRange("beta")), result);
+        let result = CascadeBlendingConfig::<f32, 2>::builder()
+            .k(f32::INFINITY)
+            .build();
+        assert_eq!(Err(ConfigError::NotFinite("k")), result);
+        let result = CascadeBlendingConfig::<f32, 2>::builder()
+            .limit(f32::NAN)
+            .build();
+        assert_eq!(Err(ConfigError::NotFinite("limit")), result);
+    }
+
+    /// Test that a valid set of weights passes through the builder unchanged.
+    #[test]
+    fn test_cascade_blending_config_builder_valid() {
+        let mut expected = CascadeBlendingConfig::<f32, 3>::new();
+        expected.beta = [0.9, 0.0, 1.0];
+        expected.k = 30.0;
+        expected.limit = 240.0;
+        let config = CascadeBlendingConfigBuilder::new()
+            .beta([0.9, 0.0, 1.0])
+            .k(30.0)
+            .limit(240.0)
+            .build();
+        assert_eq!(Ok(expected), config);
+        assert_eq!(
+            Ok(CascadeBlendingConfig::new()),
+            CascadeBlendingConfigBuilder::<f32, 2>::new().build()
+        );
+
+        // Weights that already sum to one are unchanged by normalization
+        let config = CascadeBlendingConfigBuilder::<f32, 2>::new()
+            .beta([0.75, 0.25])
+            .normalize(true)
+            .build()
+            .unwrap();
+        assert_eq!([0.75, 0.25], config.beta);
+    }
+
+    /// Test that a cascade blending configuration survives a JSON round trip.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_cascade_blending_config_serde_round_trip() {
+        let mut config = CascadeBlendingConfig::<f32, 3>::new();
+        config.beta = [0.9, 0.8, 0.7];
+        config.k = 30.0;
+        config.limit = 240.0;
+
+        let json = serde_json::to_string(&config).unwrap();
+        let round_trip: CascadeBlendingConfig<f32, 3> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(config, round_trip, "Configuration should round trip.");
+    }
+
+    /// Test that a blending weight array of the wrong length is rejected.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_cascade_blending_config_serde_wrong_length() {
+        let json = r#"{"beta":[0.9,0.8,0.7],"k":30.0,"limit":240.0}"#;
+        let result: Result<CascadeBlendingConfig<f32, 2>, _> = serde_json::from_str(json);
+        assert!(result.is_err(), "Wrong length should be rejected.");
+    }
+
+    /// Test that a stabilizer implementing only the required methods gets the
+    /// documented defaults for the optional ones.
+    #[test]
+    fn test_flight_stabilizer_default_methods() {
+        struct Proportional {
+            low_throttle: bool,
+        }
+
+        impl FlightStabilizer<f32> for Proportional {
+            fn control(
+                &mut self,
+                set_point: (f32, f32, f32),
+                imu_attitude: (f32, f32, f32),
+                _gyro_rate: (f32, f32, f32),
+                _dt: f32,
+                low_throttle: bool,
+            ) -> (f32, f32, f32) {
+                self.low_throttle = low_throttle;
+                (
+                    set_point.0 - imu_attitude.0,
+                    set_point.1 - imu_attitude.1,
+                    set_point.2 - imu_attitude.2,
+                )
+            }
+            fn reset(&mut self) {}
+            fn set_setpoints(&mut self, _roll: f32, _pitch: f32, _yaw: f32) {}
+            fn setpoints(&self) -> (f32, f32, f32) {
+                (0.0, 0.0, 0.0)
+            }
+            fn enable_axes(&mut self, _roll: bool, _pitch: bool, _yaw: bool) {}
+            fn enabled_axes(&self) -> [bool; 3] {
+                [true; 3]
+            }
+            fn freeze_integral(&mut self, _roll: bool, _pitch: bool, _yaw: bool) {}
+            fn frozen_integrals(&self) -> [bool; 3] {
+                [false; 3]
+            }
+        }
+
+        let mut stabilizer = Proportional {
+            low_throttle: false,
+        };
+        let zero = (0.0, 0.0, 0.0);
+        stabilizer.set_disturbance(1.0, 2.0, 3.0);
+        stabilizer.set_integral(1.0, 2.0, 3.0);
+        assert_eq!([SaturationState::None; 3], stabilizer.saturation());
+        assert_eq!([Telemetry::new(); 3], stabilizer.last_telemetry());
+        assert_eq!(TimingStats::new(), stabilizer.timing_stats());
+
+        // The throttle passes through as a low throttle at zero
+        let output = stabilizer.control_throttle((1.0, 2.0, 3.0), zero, zero, 0.01, 0.5);
+        assert_eq!((1.0, 2.0, 3.0), output);
+        assert!(!stabilizer.low_throttle);
+        let _ = stabilizer.control_throttle((1.0, 2.0, 3.0), zero, zero, 0.01, 0.0);
+        assert!(stabilizer.low_throttle);
+    }
 }
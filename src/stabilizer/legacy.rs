@@ -0,0 +1,159 @@
+// src/stabilizer/legacy.rs
+
+//! # Legacy Gains Module
+//!
+//! This module converts PID gains from Betaflight-style firmware, which stores them
+//! as small integers in firmware-specific units, into a `FlightStabilizerConfig`.
+//! The integers are multiplied by the scaling factors the firmware applies before
+//! its PID loop, so an existing tune can be ported without retuning from scratch.
+
+use crate::{FlightStabilizerConfig, Number};
+
+/// Betaflight-style (P, I, D) gains for roll, pitch, and yaw, as shown in the
+/// firmware configurator and CLI, like `p_roll = 45`.
+///
+/// Converting into a `FlightStabilizerConfig` multiplies each gain by the Betaflight
+/// scaling factors, `P_SCALE`, `I_SCALE`, and `D_SCALE`, and sets `scale` to
+/// `OUTPUT_SCALE`, so an output of one is the full mixer range.
+/// The gains are in degrees per second, the default `angle_unit`.
+///
+/// Betaflight takes the derivative of the negated gyro rate, so a positive D damps.
+/// The rate loops here differentiate the error, which matches for a steady setpoint,
+/// so the converted `kd` stays positive. Use `normalize_gains` before passing the
+/// gains to a loop that differentiates the measurement, like an angle loop.
+/// The feed-forward gain is not converted, because Betaflight feeds forward the
+/// setpoint derivative rather than the setpoint. Other settings keep their defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LegacyGains {
+    /// Roll gains as `[P, I, D]`.
+    pub roll: [u8; 3],
+    /// Pitch gains as `[P, I, D]`.
+    pub pitch: [u8; 3],
+    /// Yaw gains as `[P, I, D]`.
+    pub yaw: [u8; 3],
+}
+
+impl LegacyGains {
+    /// Betaflight `PTERM_SCALE`, converting P to a proportional gain.
+    pub const P_SCALE: f64 = 0.032029;
+    /// Betaflight `ITERM_SCALE`, converting I to an integral gain per second.
+    pub const I_SCALE: f64 = 0.244381;
+    /// Betaflight `DTERM_SCALE`, converting D to a derivative gain in seconds.
+    pub const D_SCALE: f64 = 0.000529;
+    /// Reciprocal of Betaflight `PID_MIXER_SCALING`, converting the PID sum to
+    /// the mixer range.
+    pub const OUTPUT_SCALE: f64 = 0.001;
+
+    /// Creates gains from the (roll, pitch, yaw) `[P, I, D]` arrays.
+    pub fn new(roll: [u8; 3], pitch: [u8; 3], yaw: [u8; 3]) -> Self {
+        Self { roll, pitch, yaw }
+    }
+}
+
+impl From<[[u8; 3]; 3]> for LegacyGains {
+    fn from([roll, pitch, yaw]: [[u8; 3]; 3]) -> Self {
+        Self { roll, pitch, yaw }
+    }
+}
+
+impl<T: Number> From<LegacyGains> for FlightStabilizerConfig<T> {
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{FlightStabilizerConfig, LegacyGains};
+    ///
+    /// let gains = LegacyGains::new([45, 80, 40], [47, 84, 46], [45, 80, 0]);
+    /// let config = FlightStabilizerConfig::<f64>::from(gains);
+    /// assert_eq!(45.0 * LegacyGains::P_SCALE, config.kp_roll);
+    /// assert_eq!(84.0 * LegacyGains::I_SCALE, config.ki_pitch);
+    /// assert_eq!(0.0, config.kd_yaw);
+    /// assert_eq!(LegacyGains::OUTPUT_SCALE, config.scale);
+    /// ```
+    fn from(gains: LegacyGains) -> Self {
+        // A factor the number type cannot represent falls back to zero
+        let scaled =
+            |gain: u8, scale: f64| T::from_f64(f64::from(gain) * scale).unwrap_or(T::zero());
+        let mut config = FlightStabilizerConfig::new();
+        let [p, i, d] = gains.roll;
+        config.kp_roll = scaled(p, LegacyGains::P_SCALE);
+        config.ki_roll = scaled(i, LegacyGains::I_SCALE);
+        config.kd_roll = scaled(d, LegacyGains::D_SCALE);
+        let [p, i, d] = gains.pitch;
+        config.kp_pitch = scaled(p, LegacyGains::P_SCALE);
+        config.ki_pitch = scaled(i, LegacyGains::I_SCALE);
+        config.kd_pitch = scaled(d, LegacyGains::D_SCALE);
+        let [p, i, d] = gains.yaw;
+        config.kp_yaw = scaled(p, LegacyGains::P_SCALE);
+        config.ki_yaw = scaled(i, LegacyGains::I_SCALE);
+        config.kd_yaw = scaled(d, LegacyGains::D_SCALE);
+        config.scale = scaled(1, LegacyGains::OUTPUT_SCALE);
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{FlightStabilizer, RateStabilizer};
+
+    /// Test the conversion of the Betaflight 4.4 default gains.
+    #[test]
+    fn test_legacy_gains_betaflight_defaults() {
+        let gains = LegacyGains::from([[45, 80, 40], [47, 84, 46], [45, 80, 0]]);
+        let config = FlightStabilizerConfig::<f32>::from(gains);
+        assert!(value_close(1.441305, config.kp_roll));
+        assert!(value_close(19.55048, config.ki_roll));
+        assert!(value_close(0.02116, config.kd_roll));
+        assert!(value_close(1.505363, config.kp_pitch));
+        assert!(value_close(20.528004, config.ki_pitch));
+        assert!(value_close(0.024334, config.kd_pitch));
+        assert!(value_close(1.441305, config.kp_yaw));
+        assert!(value_close(19.55048, config.ki_yaw));
+        assert_eq!(0.0, config.kd_yaw);
+        assert!(value_close(0.001, config.scale));
+
+        // The derivative damps with a positive gain in the rate loop,
+        // and is negated for a loop that differentiates the measurement
+        assert!(0.0 < config.kd_roll);
+        let angle_config = config.normalize_gains((true, true, false));
+        assert_eq!(-config.kd_roll, angle_config.kd_roll);
+        assert_eq!(config.kd_yaw, angle_config.kd_yaw);
+    }
+
+    /// Test that a converted proportional gain matches the Betaflight P term,
+    /// `PTERM_SCALE * P * error`, over the mixer scaling.
+    #[test]
+    fn test_legacy_gains_rate_output() {
+        let gains = LegacyGains::new([50, 0, 0], [0; 3], [0; 3]);
+        let mut stabilizer = RateStabilizer::with_config(gains.into());
+        let output = stabilizer.control(
+            (100.0, 0.0, 0.0),
+            (0.0, 0.0, 0.0),
+            (0.0, 0.0, 0.0),
+            0.01,
+            false,
+        );
+        let expected = LegacyGains::P_SCALE * 50.0 * 100.0 * LegacyGains::OUTPUT_SCALE;
+        assert!(vector_close((expected as f32, 0.0, 0.0), output));
+    }
+
+    /// Test the conversion into a fixed-point configuration.
+    #[test]
+    fn test_legacy_gains_fixed() {
+        use fixed::types::I16F16;
+
+        let gains = LegacyGains::new([45, 80, 40], [45, 80, 40], [45, 80, 0]);
+        let config = FlightStabilizerConfig::<I16F16>::from(gains);
+        assert_eq!(
+            I16F16::from_num(45.0 * LegacyGains::P_SCALE),
+            config.kp_roll
+        );
+        assert_eq!(
+            I16F16::from_num(40.0 * LegacyGains::D_SCALE),
+            config.kd_roll
+        );
+        assert_eq!(I16F16::from_num(LegacyGains::OUTPUT_SCALE), config.scale);
+    }
+}
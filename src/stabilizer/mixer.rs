@@ -0,0 +1,348 @@
+// src/stabilizer/mixer.rs
+
+//! # Mixed PID Flight Stabilization Controller
+//!
+//! This module provides an output mixing stage and a wrapper that applies it to
+//! any flight stabilizer. A stabilizer outputs (roll, pitch, yaw) commands, but a
+//! quadcopter, hexacopter or fixed-wing airframe needs them mixed onto its motor
+//! and servo channels. The mixer multiplies the commands by an `M` × 3 matrix to
+//! produce one command per actuator channel.
+
+use core::marker::PhantomData;
+
+use crate::{FlightStabilizer, Number, SaturationState, Telemetry, TimingStats};
+
+/// Mixing stage from (roll, pitch, yaw) commands to `M` actuator channels.
+///
+/// Each channel is the dot product of its matrix row with the (roll, pitch, yaw)
+/// command, clamped to its own optional bounds. Throttle is not part of the mix,
+/// so a multirotor adds it to every motor channel after mixing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mixer<T: Number, const M: usize> {
+    /// Mix matrix with one (roll, pitch, yaw) row per actuator channel.
+    pub matrix: [[T; 3]; M],
+    /// Optional lower bound for each channel. A channel is not bounded below if `None`.
+    pub output_min: [Option<T>; M],
+    /// Optional upper bound for each channel. A channel is not bounded above if `None`.
+    pub output_max: [Option<T>; M],
+}
+
+impl<T: Number, const M: usize> Mixer<T, M> {
+    /// Creates a new mixer with the provided mix matrix and unbounded channels.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::Mixer;
+    ///
+    /// // Quad-X with a positive roll raising the left motors, a positive pitch
+    /// // raising the front motors, and a positive yaw raising the clockwise motors.
+    /// let mut mixer = Mixer::<f32, 4>::new([
+    ///     [1.0, 1.0, -1.0],   // front left
+    ///     [-1.0, 1.0, 1.0],   // front right
+    ///     [-1.0, -1.0, -1.0], // rear right
+    ///     [1.0, -1.0, 1.0],   // rear left
+    /// ]);
+    /// mixer.output_min = [Some(-0.5); 4];
+    /// mixer.output_max = [Some(0.5); 4];
+    ///
+    /// assert_eq!([0.2, -0.2, -0.2, 0.2], mixer.mix((0.2, 0.0, 0.0)));
+    /// assert_eq!([0.5, -0.5, -0.5, 0.5], mixer.mix((0.7, 0.0, 0.0)));
+    /// ```
+    pub fn new(matrix: [[T; 3]; M]) -> Self {
+        Self {
+            matrix,
+            output_min: [None; M],
+            output_max: [None; M],
+        }
+    }
+
+    /// Mixes a (roll, pitch, yaw) command onto the actuator channels,
+    /// clamping each channel to its bounds.
+    pub fn mix(&self, output: (T, T, T)) -> [T; M] {
+        let (roll, pitch, yaw) = output;
+        let mut result = [T::zero(); M];
+        for (channel, value) in result.iter_mut().enumerate() {
+            let [k_roll, k_pitch, k_yaw] = self.matrix[channel];
+            *value = self.clamp_channel(channel, k_roll * roll + k_pitch * pitch + k_yaw * yaw);
+        }
+        result
+    }
+
+    /// Clamps a mixed value to the bounds of a channel.
+    /// Missing bounds do not limit the value.
+    ///
+    /// A non-finite value saturates instead of reaching the actuators, like
+    /// `FlightStabilizerConfig::clamp_output`. Positive infinity goes to the upper
+    /// bound, negative infinity goes to the lower bound, and NaN goes to zero.
+    /// A missing bound also falls back to zero.
+    pub fn clamp_channel(&self, channel: usize, value: T) -> T {
+        let (min, max) = (self.output_min[channel], self.output_max[channel]);
+        if !value.is_finite() {
+            return if T::zero() < value {
+                max.unwrap_or(T::zero())
+            } else if value < T::zero() {
+                min.unwrap_or(T::zero())
+            } else {
+                T::zero()
+            };
+        }
+        match (min, max) {
+            (Some(min), Some(max)) => value.clamp(min, max),
+            (Some(min), None) if value < min => min,
+            (None, Some(max)) if max < value => max,
+            _ => value,
+        }
+    }
+}
+
+/// Struct representing the Mixed PID Flight Stabilization Controller.
+/// `control_mixed` runs the wrapped stabilizer and mixes its output onto `M`
+/// actuator channels with a `Mixer`.
+///
+/// The trait methods pass through to the wrapped stabilizer unmixed, so the
+/// wrapper can stand in wherever a `FlightStabilizer` is expected.
+pub struct MixedStabilizer<T: Number, S: FlightStabilizer<T>, const M: usize> {
+    stabilizer: S,
+    mixer: Mixer<T, M>,
+    _number: PhantomData<T>,
+}
+
+impl<T: Number, S: FlightStabilizer<T>, const M: usize> MixedStabilizer<T, S, M> {
+    /// Creates a new controller that mixes the output of `stabilizer` with `mixer`.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{
+    ///     AngleStabilizer, FlightStabilizerConfig, MixedStabilizer, Mixer,
+    /// };
+    ///
+    /// let mixer = Mixer::<f32, 4>::new([
+    ///     [1.0, 1.0, -1.0],   // front left
+    ///     [-1.0, 1.0, 1.0],   // front right
+    ///     [-1.0, -1.0, -1.0], // rear right
+    ///     [1.0, -1.0, 1.0],   // rear left
+    /// ]);
+    /// let config = FlightStabilizerConfig::<f32>::new();
+    /// let mut stabilizer = MixedStabilizer::new(AngleStabilizer::with_config(config), mixer);
+    ///
+    /// // One command per motor instead of (roll, pitch, yaw).
+    /// let zero = (0.0, 0.0, 0.0);
+    /// let motors = stabilizer.control_mixed((1.0, 0.0, 0.0), zero, zero, 0.01, false);
+    /// assert_eq!([1.0, -1.0, -1.0, 1.0], motors);
+    /// ```
+    pub fn new(stabilizer: S, mixer: Mixer<T, M>) -> Self {
+        MixedStabilizer {
+            stabilizer,
+            mixer,
+            _number: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the wrapped stabilizer.
+    pub fn inner(&self) -> &S {
+        &self.stabilizer
+    }
+
+    /// Returns a mutable reference to the wrapped stabilizer.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.stabilizer
+    }
+
+    /// Returns a reference to the mixer.
+    pub fn mixer(&self) -> &Mixer<T, M> {
+        &self.mixer
+    }
+
+    /// Returns a mutable reference to the mixer.
+    pub fn mixer_mut(&mut self) -> &mut Mixer<T, M> {
+        &mut self.mixer
+    }
+
+    /// Computes the outputs like `control` and mixes them onto the actuator channels.
+    pub fn control_mixed(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> [T; M] {
+        let output = self
+            .stabilizer
+            .control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        self.mixer.mix(output)
+    }
+
+    /// Computes the outputs like `control_throttle` and mixes them onto the actuator channels.
+    pub fn control_throttle_mixed(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> [T; M] {
+        let output =
+            self.stabilizer
+                .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle);
+        self.mixer.mix(output)
+    }
+}
+
+impl<T: Number, S: FlightStabilizer<T>, const M: usize> FlightStabilizer<T>
+    for MixedStabilizer<T, S, M>
+{
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        self.stabilizer
+            .control(set_point, imu_attitude, gyro_rate, dt, low_throttle)
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        self.stabilizer
+            .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle)
+    }
+
+    fn reset(&mut self) {
+        self.stabilizer.reset();
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_setpoints(roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_disturbance(roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stabilizer.setpoints()
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.stabilizer.saturation()
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_integral(roll, pitch, yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.enable_axes(roll, pitch, yaw);
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.stabilizer.enabled_axes()
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.freeze_integral(roll, pitch, yaw);
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.stabilizer.frozen_integrals()
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        self.stabilizer.last_telemetry()
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.stabilizer.timing_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{AngleStabilizer, FlightStabilizerConfig};
+
+    /// Quad-X mix matrix with a positive roll raising the left motors,
+    /// a positive pitch raising the front motors, and a positive yaw
+    /// raising the clockwise motors.
+    fn quad_x() -> Mixer<f32, 4> {
+        Mixer::new([
+            [1.0, 1.0, -1.0],   // front left
+            [-1.0, 1.0, 1.0],   // front right
+            [-1.0, -1.0, -1.0], // rear right
+            [1.0, -1.0, 1.0],   // rear left
+        ])
+    }
+
+    /// Test that a pure roll command produces a left/right motor differential.
+    #[test]
+    fn test_mixer_quad_x_roll() {
+        let mixer = quad_x();
+        let [front_left, front_right, rear_right, rear_left] = mixer.mix((0.2, 0.0, 0.0));
+        assert!(value_close(0.2, front_left));
+        assert!(value_close(0.2, rear_left));
+        assert!(value_close(-0.2, front_right));
+        assert!(value_close(-0.2, rear_right));
+    }
+
+    /// Test that combined commands add up on each channel.
+    #[test]
+    fn test_mixer_quad_x_combined() {
+        let mixer = quad_x();
+        let motors = mixer.mix((0.1, 0.2, 0.05));
+        let expected = [0.25, 0.15, -0.35, -0.05];
+        for (target, value) in expected.iter().zip(motors) {
+            assert!(value_close(*target, value), "Channels should add the axes.");
+        }
+    }
+
+    /// Test that each channel is clamped to its own bounds.
+    #[test]
+    fn test_mixer_channel_bounds() {
+        let mut mixer = quad_x();
+        mixer.output_min = [Some(-0.1), None, Some(-0.5), None];
+        mixer.output_max = [Some(0.1), Some(0.3), None, None];
+        assert_eq!([0.1, -0.4, -0.4, 0.4], mixer.mix((0.4, 0.0, 0.0)));
+        assert_eq!([-0.1, 0.3, 0.4, -0.4], mixer.mix((-0.4, 0.0, 0.0)));
+
+        // Non-finite values saturate to the bounds
+        assert_eq!(0.1, mixer.clamp_channel(0, f32::INFINITY));
+        assert_eq!(-0.5, mixer.clamp_channel(2, f32::NEG_INFINITY));
+        assert_eq!(0.0, mixer.clamp_channel(3, f32::INFINITY));
+        assert_eq!(0.0, mixer.clamp_channel(0, f32::NAN));
+    }
+
+    /// Test that the mixed stabilizer mixes the output of the wrapped stabilizer.
+    #[test]
+    fn test_stabilizer_mixed_outputs() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.ki_roll = 0.3;
+        config.kd_roll = -0.05;
+        config.i_limit = 25.0;
+        config.scale = 0.01;
+        let mixer = quad_x();
+        let mut stabilizer = MixedStabilizer::new(AngleStabilizer::with_config(config), mixer);
+        let mut plain_stabilizer = AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -5.0, 0.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+
+        for _ in 0..5 {
+            let motors = stabilizer.control_mixed(set_point, imu_attitude, gyro_rate, dt, false);
+            let output = plain_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, false);
+            assert_eq!(mixer.mix(output), motors);
+        }
+    }
+}
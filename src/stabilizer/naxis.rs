@@ -0,0 +1,295 @@
+// src/stabilizer/naxis.rs
+
+//! # N-Axis Angle-Based PID Stabilization Controller
+//!
+//! This is an angle-based PID stabilization controller for any number of axes,
+//! like a two-axis gimbal or a vehicle with a separate thrust-vector axis.
+//! Each axis runs the same PID math as the angle loops of `AngleFullStabilizer`,
+//! with per-axis gains, setpoints, and limits chosen by a `[T; N]` array.
+//!
+//! The three-axis stabilizers keep their (roll, pitch, yaw) interface and the
+//! `FlightStabilizer` trait, with filters, feed-forward, and axis mapping that this
+//! controller leaves out, so they are not aliases of it.
+
+#[cfg(feature = "serde")]
+use super::flight_stabilizer::serde_array;
+use crate::pid::{compute_angle, compute_terms, AngleControlData, PidTerms};
+use crate::Number;
+use piddiy::PidController;
+
+/// Configuration for the N-axis stabilization controller, with an entry per axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NaxisConfig<T: Number, const N: usize> {
+    /// Proportional gain of each axis.
+    #[cfg_attr(feature = "serde", serde(with = "serde_array"))]
+    pub kp: [T; N],
+    /// Integral gain of each axis.
+    #[cfg_attr(feature = "serde", serde(with = "serde_array"))]
+    pub ki: [T; N],
+    /// Derivative gain of each axis, acting on the measured rate,
+    /// so a negative value damps the axis.
+    #[cfg_attr(feature = "serde", serde(with = "serde_array"))]
+    pub kd: [T; N],
+    /// Initial setpoint of each axis.
+    #[cfg_attr(feature = "serde", serde(with = "serde_array"))]
+    pub set_point: [T; N],
+    /// Upper limit of the raw accumulated integral of each axis, before `ki` is applied.
+    #[cfg_attr(feature = "serde", serde(with = "serde_array"))]
+    pub i_limit: [T; N],
+    /// Scale factor applied to the PID output of each axis.
+    #[cfg_attr(feature = "serde", serde(with = "serde_array"))]
+    pub scale: [T; N],
+    /// Optional lower bound of every scaled output. Unbounded if `None`.
+    pub output_min: Option<T>,
+    /// Optional upper bound of every scaled output. Unbounded if `None`.
+    pub output_max: Option<T>,
+}
+
+impl<T: Number, const N: usize> Default for NaxisConfig<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Number, const N: usize> NaxisConfig<T, N> {
+    /// Creates a new configuration with zero gains, setpoints, and integral limits,
+    /// a scale of one, and unbounded outputs.
+    /// These should be replaced meaningful values that are tuned for the hardware.
+    pub fn new() -> Self {
+        Self {
+            kp: [T::zero(); N],
+            ki: [T::zero(); N],
+            kd: [T::zero(); N],
+            set_point: [T::zero(); N],
+            i_limit: [T::zero(); N],
+            scale: [T::one(); N],
+            output_min: None,
+            output_max: None,
+        }
+    }
+
+    /// Clamps a scaled output to the configured bounds.
+    fn clamp_output(&self, output: T) -> T {
+        let output = match self.output_max {
+            Some(max) if max < output => max,
+            _ => output,
+        };
+        match self.output_min {
+            Some(min) if output < min => min,
+            _ => output,
+        }
+    }
+}
+
+/// Struct representing the N-Axis Angle PID Stabilization Controller.
+pub struct NaxisStabilizer<T: Number, const N: usize> {
+    pids: [PidController<T, AngleControlData<T>>; N],
+    config: NaxisConfig<T, N>,
+    terms: [PidTerms<T>; N],
+}
+
+impl<T: Number, const N: usize> Default for NaxisStabilizer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Number, const N: usize> NaxisStabilizer<T, N> {
+    /// Creates a new controller using the provided configuration.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{NaxisConfig, NaxisStabilizer};
+    ///
+    /// // A two-axis gimbal, tilt and pan.
+    /// let mut config = NaxisConfig::<f32, 2>::new();
+    /// config.kp = [0.2, 0.3];
+    /// config.kd = [-0.05, -0.02];
+    /// config.scale = [0.01; 2];
+    ///
+    /// let mut stabilizer = NaxisStabilizer::with_config(config);
+    /// let output = stabilizer.control([10.0, 0.0], [0.0, 0.0], [0.0, 0.0], 0.01, false);
+    /// assert!((0.02 - output[0]).abs() < 1e-6);
+    /// assert_eq!(0.0, output[1]);
+    /// ```
+    pub fn with_config(config: NaxisConfig<T, N>) -> Self {
+        let pids = core::array::from_fn(|axis| {
+            let mut pid = PidController::new();
+            pid.compute_fn(compute_angle)
+                .set_point(config.set_point[axis])
+                .kp(config.kp[axis])
+                .ki(config.ki[axis])
+                .kd(config.kd[axis]);
+            pid
+        });
+        NaxisStabilizer {
+            pids,
+            config,
+            terms: [PidTerms::new(); N],
+        }
+    }
+
+    /// Creates a new controller with default settings
+    pub fn new() -> Self {
+        Self::with_config(NaxisConfig::new())
+    }
+
+    /// Returns the configuration.
+    pub fn config(&self) -> &NaxisConfig<T, N> {
+        &self.config
+    }
+
+    /// Returns the gain-multiplied PID terms of the most recent control step for each
+    /// axis. The terms are unscaled, so their sum times the axis scale is the output
+    /// before clamping.
+    pub fn last_terms(&self) -> [PidTerms<T>; N] {
+        self.terms
+    }
+
+    /// Computes the control output of each axis from its setpoint, measured angle,
+    /// and measured rate. Each axis is independent, and the integrals are reset
+    /// while `low_throttle` is set.
+    pub fn control(
+        &mut self,
+        set_point: [T; N],
+        attitude: [T; N],
+        rate: [T; N],
+        dt: T,
+        low_throttle: bool,
+    ) -> [T; N] {
+        let mut output = [T::zero(); N];
+        for (axis, pid) in self.pids.iter_mut().enumerate() {
+            let data = AngleControlData {
+                measurement: attitude[axis],
+                rate: Some(rate[axis]),
+                dt,
+                integral_limit: self.config.i_limit[axis],
+                reset_integral: low_throttle,
+                ..AngleControlData::default()
+            };
+            pid.set_point(set_point[axis]);
+            self.terms[axis] = compute_terms(pid, compute_angle, data, T::zero());
+            output[axis] = self
+                .config
+                .clamp_output(self.config.scale[axis] * self.terms[axis].sum());
+        }
+        output
+    }
+
+    /// Resets the integrals, errors, and derivatives of every axis, and the setpoints
+    /// to the configured values.
+    pub fn reset(&mut self) {
+        for (axis, pid) in self.pids.iter_mut().enumerate() {
+            pid.reset();
+            pid.set_point(self.config.set_point[axis]);
+        }
+        self.terms = [PidTerms::new(); N];
+    }
+
+    /// Seeds the integral of each axis, clamped to the integral limits.
+    pub fn set_integral(&mut self, integral: [T; N]) {
+        for (axis, pid) in self.pids.iter_mut().enumerate() {
+            let limit = self.config.i_limit[axis];
+            pid.integral = integral[axis].clamp(-limit, limit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{AngleFullStabilizer, FlightStabilizer, FlightStabilizerConfig};
+
+    /// Test that each axis of a two-axis stabilizer is controlled independently.
+    #[test]
+    fn test_stabilizer_naxis_two_axes() {
+        let mut config = NaxisConfig::<f32, 2>::new();
+        config.kp = [0.2, 0.4];
+        config.ki = [0.3, 0.0];
+        config.kd = [-0.05, -0.1];
+        config.i_limit = [25.0; 2];
+        config.scale = [0.01, 0.02];
+        let mut stabilizer = NaxisStabilizer::with_config(config);
+
+        // roll: 0.01 * (0.2 * 10 + 0.3 * 10 * 0.5 + -0.05 * 1) = 0.0345
+        // pan:  0.02 * (0.4 * -5 + 0 + -0.1 * -2) = -0.036
+        let output = stabilizer.control([10.0, -5.0], [0.0, 0.0], [1.0, -2.0], 0.5, false);
+        assert!(value_close(0.0345, output[0]));
+        assert!(value_close(-0.036, output[1]));
+        assert!(value_close(1.5, stabilizer.last_terms()[0].integral));
+        assert_eq!(0.0, stabilizer.last_terms()[1].integral);
+
+        // Moving one axis leaves the other unchanged.
+        // roll: 0.01 * (0.2 * 10 + 0.3 * 10 * 1.0 + -0.05 * 1) = 0.0495
+        // pan:  0.02 * (0.4 * 5 + 0 + -0.1 * -2) = 0.044
+        let moved = stabilizer.control([10.0, 5.0], [0.0, 0.0], [1.0, -2.0], 0.5, false);
+        assert!(value_close(0.0495, moved[0]));
+        assert!(value_close(0.044, moved[1]));
+
+        // A low throttle step resets the integrals.
+        stabilizer.control([0.0; 2], [0.0; 2], [0.0; 2], 0.5, true);
+        assert_eq!([PidTerms::new(); 2], stabilizer.last_terms());
+    }
+
+    /// Test that four axes match the per-axis math of the three-axis angle stabilizer.
+    #[test]
+    fn test_stabilizer_naxis_four_axes() {
+        let mut three_axis_config = FlightStabilizerConfig::<f32>::new();
+        three_axis_config.kp_roll = 0.2;
+        three_axis_config.ki_roll = 0.3;
+        three_axis_config.kd_roll = -0.05;
+        three_axis_config.kp_pitch = 0.25;
+        three_axis_config.ki_pitch = 0.1;
+        three_axis_config.kd_pitch = -0.04;
+        three_axis_config.kp_yaw = 0.3;
+        three_axis_config.ki_yaw = 0.05;
+        three_axis_config.kd_yaw = -0.02;
+        three_axis_config.i_limit = 25.0;
+        three_axis_config.scale = 0.01;
+        three_axis_config.yaw_wrap = false;
+        let mut three_axis = AngleFullStabilizer::with_config(three_axis_config);
+
+        let mut config = NaxisConfig::<f32, 4>::new();
+        config.kp = [0.2, 0.25, 0.3, 1.0];
+        config.ki = [0.3, 0.1, 0.05, 0.0];
+        config.kd = [-0.05, -0.04, -0.02, 0.0];
+        config.i_limit = [25.0; 4];
+        config.scale = [0.01, 0.01, 0.01, 0.5];
+        config.output_min = Some(-1.0);
+        config.output_max = Some(1.0);
+        let mut stabilizer = NaxisStabilizer::with_config(config);
+
+        for step in 0..10 {
+            let thrust_vector = step as f32;
+            let output = stabilizer.control(
+                [10.0, -10.0, 20.0, thrust_vector],
+                [5.0, 5.0, 0.0, 0.0],
+                [1.0, -1.0, -1.0, 0.0],
+                0.01,
+                false,
+            );
+            let (roll, pitch, yaw) = three_axis.control(
+                (10.0, -10.0, 20.0),
+                (5.0, 5.0, 0.0),
+                (1.0, -1.0, -1.0),
+                0.01,
+                false,
+            );
+            tuple_close((roll, pitch, yaw), (output[0], output[1], output[2]));
+
+            // The fourth axis is a proportional loop clamped to the output bounds.
+            let expected = (0.5 * thrust_vector).clamp(-1.0, 1.0);
+            assert!(value_close(expected, output[3]));
+        }
+
+        // Seeded integrals are clamped, and a reset clears them.
+        stabilizer.set_integral([50.0, -50.0, 1.0, 0.0]);
+        assert_eq!(25.0, stabilizer.pids[0].integral);
+        assert_eq!(-25.0, stabilizer.pids[1].integral);
+        stabilizer.reset();
+        assert_eq!(0.0, stabilizer.pids[2].integral);
+        assert_eq!(0.0, stabilizer.pids[3].set_point);
+    }
+}
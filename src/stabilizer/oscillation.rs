@@ -0,0 +1,360 @@
+// src/stabilizer/oscillation.rs
+
+//! # Oscillation Detecting PID Flight Stabilization Controller
+//!
+//! This module provides a detector that counts the zero crossings of the
+//! (roll, pitch, yaw) outputs over a window of recent control steps, and a wrapper
+//! that feeds it the outputs of any flight stabilizer. An axis whose output keeps
+//! changing sign is oscillating, which usually means its gains are too high.
+//! Autotune and safety logic can poll the detector instead of keeping their own
+//! output history in the flight loop.
+
+use core::marker::PhantomData;
+
+use crate::{FlightStabilizer, Number, SaturationState, Telemetry, TimingStats};
+
+/// Zero crossing counter over the last `N` (roll, pitch, yaw) outputs.
+///
+/// The window is a fixed ring buffer of output signs, so it needs no allocation.
+/// A crossing is a change of sign between consecutive nonzero outputs in the window,
+/// so an output resting at zero, like inside a deadband, neither adds nor breaks one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OscillationDetector<const N: usize> {
+    signs: [[i8; N]; 3],
+    head: usize,
+    len: usize,
+    threshold: usize,
+}
+
+impl<const N: usize> OscillationDetector<N> {
+    /// Creates a new detector that reports an axis as oscillating when its output
+    /// crosses zero more than `threshold` times within the last `N` outputs.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::OscillationDetector;
+    ///
+    /// let mut detector = OscillationDetector::<8>::new(4);
+    /// for step in 0..8 {
+    ///     let roll = if step % 2 == 0 { 1.0 } else { -1.0 };
+    ///     detector.update((roll, 1.0, 0.0));
+    /// }
+    /// assert_eq!([7, 0, 0], detector.zero_crossings());
+    /// assert_eq!([true, false, false], detector.is_oscillating());
+    /// ```
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            signs: [[0; N]; 3],
+            head: 0,
+            len: 0,
+            threshold,
+        }
+    }
+
+    /// Returns the number of zero crossings above which an axis is oscillating.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Sets the number of zero crossings above which an axis is oscillating.
+    pub fn set_threshold(&mut self, threshold: usize) {
+        self.threshold = threshold;
+    }
+
+    /// Records the (roll, pitch, yaw) output of a control step, dropping the oldest
+    /// output once the window is full. A zero-length window records nothing.
+    pub fn update<T: Number>(&mut self, output: (T, T, T)) {
+        if N == 0 {
+            return;
+        }
+        let sign = |value: T| {
+            if T::zero() < value {
+                1
+            } else if value < T::zero() {
+                -1
+            } else {
+                0
+            }
+        };
+        let (roll, pitch, yaw) = output;
+        self.signs[0][self.head] = sign(roll);
+        self.signs[1][self.head] = sign(pitch);
+        self.signs[2][self.head] = sign(yaw);
+        self.head = (self.head + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    /// Returns the number of zero crossings of each axis within the window.
+    pub fn zero_crossings(&self) -> [usize; 3] {
+        let oldest = (self.head + N - self.len) % N.max(1);
+        let mut crossings = [0; 3];
+        for (axis, signs) in self.signs.iter().enumerate() {
+            let mut prev_sign = 0;
+            for step in 0..self.len {
+                let sign = signs[(oldest + step) % N];
+                if sign != 0 {
+                    if sign == -prev_sign {
+                        crossings[axis] += 1;
+                    }
+                    prev_sign = sign;
+                }
+            }
+        }
+        crossings
+    }
+
+    /// Returns `true` for each axis whose output crossed zero more than the
+    /// threshold number of times within the window.
+    pub fn is_oscillating(&self) -> [bool; 3] {
+        self.zero_crossings()
+            .map(|crossings| self.threshold < crossings)
+    }
+
+    /// Clears the recorded outputs, keeping the threshold.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.threshold);
+    }
+}
+
+/// Struct representing the Oscillation Detecting PID Flight Stabilization Controller.
+/// After every `control` and `control_throttle` step, the outputs of the wrapped
+/// stabilizer are recorded in an `OscillationDetector` with a window of `N` steps.
+///
+/// The outputs of the wrapped stabilizer are not affected. Resetting the controller
+/// also clears the recorded outputs.
+pub struct OscillationStabilizer<T: Number, S: FlightStabilizer<T>, const N: usize> {
+    stabilizer: S,
+    detector: OscillationDetector<N>,
+    _number: PhantomData<T>,
+}
+
+impl<T: Number, S: FlightStabilizer<T>, const N: usize> OscillationStabilizer<T, S, N> {
+    /// Creates a new controller that reports an axis of `stabilizer` as oscillating
+    /// when its output crosses zero more than `threshold` times within `N` steps.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{
+    ///     AngleStabilizer, FlightStabilizer, FlightStabilizerConfig, OscillationStabilizer,
+    /// };
+    ///
+    /// let config = FlightStabilizerConfig::<f32>::new();
+    /// let mut stabilizer =
+    ///     OscillationStabilizer::<_, _, 16>::new(AngleStabilizer::with_config(config), 6);
+    ///
+    /// let zero = (0.0, 0.0, 0.0);
+    /// for step in 0..16 {
+    ///     let roll = if step % 2 == 0 { 1.0 } else { -1.0 };
+    ///     stabilizer.control(zero, (roll, 0.0, 0.0), zero, 0.01, false);
+    /// }
+    /// assert_eq!([true, false, false], stabilizer.is_oscillating());
+    /// ```
+    pub fn new(stabilizer: S, threshold: usize) -> Self {
+        OscillationStabilizer {
+            stabilizer,
+            detector: OscillationDetector::new(threshold),
+            _number: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the wrapped stabilizer.
+    pub fn inner(&self) -> &S {
+        &self.stabilizer
+    }
+
+    /// Returns a mutable reference to the wrapped stabilizer.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.stabilizer
+    }
+
+    /// Returns a reference to the detector.
+    pub fn detector(&self) -> &OscillationDetector<N> {
+        &self.detector
+    }
+
+    /// Returns a mutable reference to the detector, to change the threshold.
+    pub fn detector_mut(&mut self) -> &mut OscillationDetector<N> {
+        &mut self.detector
+    }
+
+    /// Returns the number of zero crossings of each output within the window.
+    pub fn zero_crossings(&self) -> [usize; 3] {
+        self.detector.zero_crossings()
+    }
+
+    /// Returns `true` for each axis whose output crossed zero more than the
+    /// threshold number of times within the window.
+    pub fn is_oscillating(&self) -> [bool; 3] {
+        self.detector.is_oscillating()
+    }
+}
+
+impl<T: Number, S: FlightStabilizer<T>, const N: usize> FlightStabilizer<T>
+    for OscillationStabilizer<T, S, N>
+{
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        let output = self
+            .stabilizer
+            .control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        self.detector.update(output);
+        output
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        let output =
+            self.stabilizer
+                .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle);
+        self.detector.update(output);
+        output
+    }
+
+    fn reset(&mut self) {
+        self.stabilizer.reset();
+        self.detector.reset();
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_setpoints(roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_disturbance(roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stabilizer.setpoints()
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.stabilizer.saturation()
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_integral(roll, pitch, yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.enable_axes(roll, pitch, yaw);
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.stabilizer.enabled_axes()
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.freeze_integral(roll, pitch, yaw);
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.stabilizer.frozen_integrals()
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        self.stabilizer.last_telemetry()
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.stabilizer.timing_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AngleStabilizer, FlightStabilizerConfig};
+    use core::f32::consts::PI;
+
+    /// Test that a sinusoidal output trips the detector once enough crossings fill the window.
+    #[test]
+    fn test_stabilizer_oscillation_sinusoid() {
+        let mut detector = OscillationDetector::<32>::new(4);
+        assert_eq!([false; 3], detector.is_oscillating());
+
+        // A roll sinusoid with a period of 8 steps, and a slow pitch sinusoid
+        // with a period of 64 steps, which crosses zero at most once in the window.
+        for step in 0..64 {
+            let time = step as f32 + 0.5;
+            let roll = (2.0 * PI * time / 8.0).sin();
+            let pitch = (2.0 * PI * time / 64.0).sin();
+            detector.update((roll, pitch, 0.0));
+            if step == 15 {
+                // Three crossings in the first 16 steps.
+                assert_eq!([3, 0, 0], detector.zero_crossings());
+                assert_eq!([false; 3], detector.is_oscillating());
+            }
+        }
+        // Only the last 32 outputs count, so the roll crossings stay bounded.
+        assert_eq!([7, 0, 0], detector.zero_crossings());
+        assert_eq!([true, false, false], detector.is_oscillating());
+
+        detector.reset();
+        assert_eq!([0; 3], detector.zero_crossings());
+        assert_eq!(4, detector.threshold());
+    }
+
+    /// Test that a monotonic output, and zeros between outputs of one sign, never trip it.
+    #[test]
+    fn test_stabilizer_oscillation_monotonic() {
+        let mut detector = OscillationDetector::<16>::new(2);
+        for step in 0..40 {
+            let ramp = step as f32 - 20.0;
+            let resting = if step % 2 == 0 { 0.0 } else { 1.0 };
+            detector.update((ramp, -ramp, resting));
+        }
+        // The ramps crossed zero once, before the window.
+        assert_eq!([0, 0, 0], detector.zero_crossings());
+        assert_eq!([false; 3], detector.is_oscillating());
+
+        // A zero-length window records nothing.
+        let mut empty = OscillationDetector::<0>::new(0);
+        empty.update((1.0, -1.0, 1.0));
+        assert_eq!([0; 3], empty.zero_crossings());
+    }
+
+    /// Test that the wrapper records the outputs of the stabilizer, and a reset clears them.
+    #[test]
+    fn test_stabilizer_oscillation_wrapper() {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.kp_roll = 0.2;
+        config.kp_pitch = 0.2;
+        config.scale = 0.01;
+        let inner = AngleStabilizer::with_config(config);
+        let mut stabilizer = OscillationStabilizer::<_, _, 10>::new(inner, 3);
+        let mut bare_stabilizer = AngleStabilizer::with_config(config);
+
+        let zero = (0.0, 0.0, 0.0);
+        for step in 0..10 {
+            // Roll overshoots back and forth, while pitch settles from one side.
+            let roll = if step % 2 == 0 { 5.0 } else { -5.0 };
+            let pitch = 10.0 - step as f32;
+            let attitude = (roll, pitch, 0.0);
+            let output = stabilizer.control(zero, attitude, zero, 0.01, false);
+            assert_eq!(
+                bare_stabilizer.control(zero, attitude, zero, 0.01, false),
+                output
+            );
+        }
+        assert_eq!([9, 0, 0], stabilizer.zero_crossings());
+        assert_eq!([true, false, false], stabilizer.is_oscillating());
+
+        stabilizer.detector_mut().set_threshold(9);
+        assert_eq!([false; 3], stabilizer.is_oscillating());
+
+        stabilizer.reset();
+        assert_eq!([0; 3], stabilizer.zero_crossings());
+    }
+}
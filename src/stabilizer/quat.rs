@@ -0,0 +1,794 @@
+// src/stabilizer/quat.rs
+
+//! # Quaternion-Based PID Flight Stabilization Controller
+//!
+//! This is an angle-based PID flight stabilization controller that takes the
+//! attitude and setpoint as unit quaternions instead of Euler angles.
+//! The shortest-arc error rotation drives all three axes, which avoids
+//! yaw wraparound and the pitch singularity of Euler angles.
+
+use super::state::PidState;
+use crate::pid::{compute_angle, AngleControlData};
+use crate::pid::{compute_terms, NotchFilter, PidTerms};
+use crate::{
+    bumpless_integral, freeze_axes, integral_axes, select_axes, track_integrals, DerivativeSource,
+    FixedRate, FlightStabilizerConfig, IntegralDivider, Number, PidForm, SaturationState,
+    StabilizerError, StabilizerState, Telemetry, TimingStats,
+};
+use piddiy::PidController;
+
+/// Computes the (roll, pitch, yaw) error between two unit quaternions.
+///
+/// Quaternions are given as `(w, x, y, z)`. The error rotation is
+/// `conj(attitude) * set_point`, expressed in the body frame, and is flipped
+/// onto the same hemisphere as the identity so it takes the shortest arc.
+/// The vector part is doubled, so each component approximates the axis error
+/// in radians for small rotations.
+pub fn quat_error<T: Number>(set_point: (T, T, T, T), attitude: (T, T, T, T)) -> (T, T, T) {
+    let (sw, sx, sy, sz) = set_point;
+    let (aw, ax, ay, az) = attitude;
+
+    // Hamilton product of the conjugated attitude and the setpoint
+    let w = aw * sw + ax * sx + ay * sy + az * sz;
+    let x = aw * sx - ax * sw - ay * sz + az * sy;
+    let y = aw * sy + ax * sz - ay * sw - az * sx;
+    let z = aw * sz - ax * sy + ay * sx - az * sw;
+
+    // q and -q are the same rotation, so pick the shorter one
+    let two = T::one() + T::one();
+    let sign = if w < T::zero() { -two } else { two };
+    (sign * x, sign * y, sign * z)
+}
+
+/// Struct representing the Quaternion PID Flight Stabilization Controller.
+///
+/// The gains, integral limits, scale, output bounds and tracking gains come from
+/// the configuration. Euler specific settings, like the setpoints, setpoint rate
+/// limits, feed-forward gains and yaw wrap, are not used.
+pub struct QuatStabilizer<T: Number> {
+    roll_pid: PidController<T, AngleControlData<T>>,
+    pitch_pid: PidController<T, AngleControlData<T>>,
+    yaw_pid: PidController<T, AngleControlData<T>>,
+    config: FlightStabilizerConfig<T>,
+    terms: [PidTerms<T>; 3],
+    telemetry: [Telemetry<T>; 3],
+    saturation: [SaturationState; 3],
+    prev_output: (T, T, T),
+    quantization_error: (T, T, T),
+    timing: TimingStats<T>,
+    fixed_rate: Option<FixedRate<T>>,
+    integral_divider: IntegralDivider<T>,
+    notch: [NotchFilter<T>; 3],
+    enabled_axes: [bool; 3],
+    frozen_integrals: [bool; 3],
+}
+
+impl<T: Number> Default for QuatStabilizer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Number> QuatStabilizer<T> {
+    /// Creates a new controller using the provided configuration
+    pub fn with_config(config: FlightStabilizerConfig<T>) -> Self {
+        let mut roll_pid = PidController::new();
+        roll_pid
+            .compute_fn(compute_angle)
+            .kp(config.kp_roll)
+            .ki(config.ki_roll)
+            .kd(config.kd_roll);
+
+        let mut pitch_pid = PidController::new();
+        pitch_pid
+            .compute_fn(compute_angle)
+            .kp(config.kp_pitch)
+            .ki(config.ki_pitch)
+            .kd(config.kd_pitch);
+
+        let mut yaw_pid = PidController::new();
+        yaw_pid
+            .compute_fn(compute_angle)
+            .kp(config.kp_yaw)
+            .ki(config.ki_yaw)
+            .kd(config.kd_yaw);
+
+        QuatStabilizer {
+            roll_pid,
+            pitch_pid,
+            yaw_pid,
+            config,
+            terms: [PidTerms::new(); 3],
+            telemetry: [Telemetry::new(); 3],
+            saturation: [SaturationState::None; 3],
+            prev_output: (T::zero(), T::zero(), T::zero()),
+            quantization_error: (T::zero(), T::zero(), T::zero()),
+            timing: TimingStats::new(),
+            fixed_rate: None,
+            integral_divider: IntegralDivider::new(),
+            notch: [NotchFilter::default(); 3],
+            enabled_axes: [true; 3],
+            frozen_integrals: [false; 3],
+        }
+    }
+
+    /// Creates a new controller with default settings
+    pub fn new() -> Self {
+        Self::with_config(FlightStabilizerConfig::new())
+    }
+
+    /// Returns the configuration.
+    pub fn config(&self) -> &FlightStabilizerConfig<T> {
+        &self.config
+    }
+
+    /// Returns the gain-multiplied PID terms of the most recent control step
+    /// for roll, pitch, and yaw. The terms are unscaled, so their sum times
+    /// the axis scale is the output before clamping.
+    pub fn last_terms(&self) -> [PidTerms<T>; 3] {
+        self.terms
+    }
+
+    /// Returns the raw output of the most recent control step for roll, pitch, and yaw,
+    /// the sum of the `last_terms` before the axis scale is applied. The raw output times
+    /// the scale is the output before clamping, rate limiting, and the deadband.
+    pub fn last_raw_output(&self) -> (T, T, T) {
+        let [roll, pitch, yaw] = self.terms;
+        (roll.sum(), pitch.sum(), yaw.sum())
+    }
+
+    /// Returns a copy of the internal state, like the integrals, previous errors
+    /// and filter histories, to `restore` later. The configuration and gains are not included.
+    pub fn snapshot(&self) -> StabilizerState<T> {
+        let mut state = StabilizerState::new();
+        state.pids[0] = [
+            PidState::from_pid(&self.roll_pid),
+            PidState::from_pid(&self.pitch_pid),
+            PidState::from_pid(&self.yaw_pid),
+        ];
+        state.terms = self.terms;
+        state.saturation = self.saturation;
+        state.prev_output = self.prev_output;
+        state.quantization_error = self.quantization_error;
+        state.notch = self.notch;
+        state.integral_dividers[0] = self.integral_divider;
+        state
+    }
+
+    /// Restores the internal state from a `snapshot` of this controller.
+    pub fn restore(&mut self, state: &StabilizerState<T>) {
+        state.pids[0][0].restore(&mut self.roll_pid);
+        state.pids[0][1].restore(&mut self.pitch_pid);
+        state.pids[0][2].restore(&mut self.yaw_pid);
+        self.terms = state.terms;
+        self.saturation = state.saturation;
+        self.prev_output = state.prev_output;
+        self.quantization_error = state.quantization_error;
+        self.notch = state.notch;
+        self.integral_divider = state.integral_dividers[0];
+    }
+
+    /// Returns the configuration with the gains currently applied by the PID controllers.
+    /// Output scales are not folded in; the gains in actuator units are the reported
+    /// gains times the configured `scales()`.
+    pub fn effective_gains(&self) -> FlightStabilizerConfig<T> {
+        let mut config = self.config;
+        config.kp_roll = self.roll_pid.kp;
+        config.ki_roll = self.roll_pid.ki;
+        config.kd_roll = self.roll_pid.kd;
+        config.kp_pitch = self.pitch_pid.kp;
+        config.ki_pitch = self.pitch_pid.ki;
+        config.kd_pitch = self.pitch_pid.kd;
+        config.kp_yaw = self.yaw_pid.kp;
+        config.ki_yaw = self.yaw_pid.ki;
+        config.kd_yaw = self.yaw_pid.kd;
+        config
+    }
+
+    /// Replaces the PID gains with the gains from the provided configuration without
+    /// a bump in the integral terms, for live tuning. Each stored integral is rescaled
+    /// so `ki * integral` is unchanged, then clamped to the integral limits for the
+    /// new gains. An axis with a new `ki` of zero keeps its integral.
+    /// Other settings and the remaining internal state are left untouched.
+    pub fn update_gains(&mut self, config: &FlightStabilizerConfig<T>) {
+        let integrals = (
+            bumpless_integral(self.roll_pid.integral, self.roll_pid.ki, config.ki_roll),
+            bumpless_integral(self.pitch_pid.integral, self.pitch_pid.ki, config.ki_pitch),
+            bumpless_integral(self.yaw_pid.integral, self.yaw_pid.ki, config.ki_yaw),
+        );
+        self.roll_pid
+            .kp(config.kp_roll)
+            .ki(config.ki_roll)
+            .kd(config.kd_roll);
+        self.pitch_pid
+            .kp(config.kp_pitch)
+            .ki(config.ki_pitch)
+            .kd(config.kd_pitch);
+        self.yaw_pid
+            .kp(config.kp_yaw)
+            .ki(config.ki_yaw)
+            .kd(config.kd_yaw);
+        let (roll, pitch, yaw) = integrals;
+        self.set_integral(roll, pitch, yaw);
+    }
+
+    /// Replaces the configuration with a reloaded one, like a tuning stored in flash,
+    /// applying only what changed. Gains are applied like `update_gains`, so only an
+    /// axis with a changed `ki` rescales its integral, and the integrals are clamped
+    /// to the new limits. Other settings, like limits and filters, apply from the next step.
+    /// The remaining internal state is left untouched.
+    pub fn apply_config(&mut self, config: &FlightStabilizerConfig<T>) {
+        self.config = *config;
+        if let Some(fixed_rate) = self.fixed_rate {
+            self.fixed_rate = Some(config.fixed_rate(fixed_rate.dt));
+        }
+        self.update_gains(config);
+    }
+
+    /// Sets a constant loop period for `control_quat_fixed_rate`, precomputing the
+    /// coefficients that depend on it. The coefficients are refreshed by `apply_config`.
+    /// Returns an error, leaving the loop period unchanged, if `dt` is not positive
+    /// and finite.
+    pub fn set_fixed_rate(&mut self, dt: T) -> Result<(), StabilizerError> {
+        if !dt.is_finite() {
+            return Err(StabilizerError::NotFinite("dt"));
+        }
+        if dt <= T::zero() {
+            return Err(StabilizerError::NonPositiveDt);
+        }
+        self.fixed_rate = Some(self.config.fixed_rate(dt));
+        Ok(())
+    }
+
+    /// Returns the precomputed coefficients of the loop period set by `set_fixed_rate`,
+    /// or `None` if no loop period is set.
+    pub fn fixed_rate(&self) -> Option<&FixedRate<T>> {
+        self.fixed_rate.as_ref()
+    }
+
+    /// Takes the desired attitude, current IMU attitude, and gyro rates, then computes the control outputs.
+    ///
+    /// - `set_point`: The desired attitude as a unit quaternion `(w, x, y, z)`.
+    /// - `imu_attitude`: The current attitude as a unit quaternion `(w, x, y, z)`.
+    /// - `gyro_rate`: A tuple of (roll rate, pitch rate, yaw rate) from the gyroscope, in radians per second.
+    /// - `dt`: Time delta since the last update.
+    /// - `low_throttle`: Flag indicating if the throttle is low. Used for anti-integral windup.
+    ///
+    /// Returns a tuple of (roll control, pitch control, yaw control) outputs scaled for actuation.
+    pub fn control_quat(
+        &mut self,
+        set_point: (T, T, T, T),
+        imu_attitude: (T, T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        let fixed_rate = self.config.fixed_rate(dt);
+        self.control_quat_with(set_point, imu_attitude, gyro_rate, fixed_rate, low_throttle)
+    }
+
+    /// Computes the control outputs like `control_quat`, with the loop period and the
+    /// coefficients precomputed by `set_fixed_rate` instead of a `dt` argument.
+    /// The outputs match `control_quat` with the same constant `dt`.
+    /// Returns `StabilizerError::NoLoopPeriod` if no loop period is set.
+    pub fn control_quat_fixed_rate(
+        &mut self,
+        set_point: (T, T, T, T),
+        imu_attitude: (T, T, T, T),
+        gyro_rate: (T, T, T),
+        low_throttle: bool,
+    ) -> Result<(T, T, T), StabilizerError> {
+        let fixed_rate = self.fixed_rate.ok_or(StabilizerError::NoLoopPeriod)?;
+        Ok(self.control_quat_with(set_point, imu_attitude, gyro_rate, fixed_rate, low_throttle))
+    }
+
+    /// Computes the control outputs for `control_quat` and `control_quat_fixed_rate`.
+    fn control_quat_with(
+        &mut self,
+        set_point: (T, T, T, T),
+        imu_attitude: (T, T, T, T),
+        gyro_rate: (T, T, T),
+        fixed_rate: FixedRate<T>,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        let dt = fixed_rate.dt;
+        self.timing.record(dt);
+
+        // Hold the integrals of disabled and frozen axes
+        let held_integrals = [
+            self.roll_pid.integral,
+            self.pitch_pid.integral,
+            self.yaw_pid.integral,
+        ];
+
+        // The error rotation is the setpoint, measured from a zero attitude
+        let (error_roll, error_pitch, error_yaw) = quat_error(set_point, imu_attitude);
+
+        // Map the error and gyro rates onto the reversed and coupled axes
+        let (error_roll, error_pitch, error_yaw) =
+            self.config
+                .transform_axes((error_roll, error_pitch, error_yaw));
+        let gyro_rate = self.config.transform_axes(gyro_rate);
+        self.roll_pid.set_point(error_roll);
+        self.pitch_pid.set_point(error_pitch);
+        self.yaw_pid.set_point(error_yaw);
+
+        // Prepare control data for roll, pitch, and yaw
+        let ki = (self.roll_pid.ki, self.pitch_pid.ki, self.yaw_pid.ki);
+        let (i_limit_roll, i_limit_pitch, i_limit_yaw) = self.config.integral_limits(ki);
+        let (raw_roll, raw_pitch, raw_yaw) = gyro_rate;
+        // Remove motor vibration from the gyro rates feeding the derivative
+        let gyro_rate = self.config.notch_rates(&mut self.notch, gyro_rate, dt);
+        let (gyro_roll, gyro_pitch, gyro_yaw) = gyro_rate;
+        // Update the integral every `integral_divisor` ticks over the summed time steps
+        let integral_divider = self.integral_divider.step(self.config.integral_divisor, dt);
+        let max_integral_rate = self.config.max_integral_rate * integral_divider;
+        // Frozen axes stop accumulating and leaking their integrals
+        let integral_scales = freeze_axes(self.frozen_integrals, integral_divider);
+        let integral_leaks = freeze_axes(self.frozen_integrals, self.config.integral_leak);
+        let data = AngleControlData {
+            measurement: T::zero(),
+            rate: Some(T::zero()),
+            derivative_source: DerivativeSource::Rate,
+            dt,
+            measurement_updated: true,
+            integral_limit: T::zero(),
+            reset_integral: low_throttle,
+            integral_scale: T::one(),
+            max_integral_rate,
+            derivative_on_error: false,
+            suppress_derivative_on_setpoint_change: false,
+            derivative_lpf_alpha: T::one(),
+            d_term_limit: T::zero(),
+            saturation: SaturationState::None,
+            wrap: None,
+            integral_leak: self.config.integral_leak,
+            integration_method: self.config.integration_method,
+            form: PidForm::Standard,
+            b_weight: T::one(),
+            c_weight: T::zero(),
+            prev_set_point: T::zero(),
+        };
+        let roll_data = AngleControlData {
+            rate: Some(gyro_roll),
+            integral_limit: i_limit_roll,
+            derivative_lpf_alpha: self.config.derivative_alpha_roll,
+            d_term_limit: self.config.d_term_limit_roll,
+            integral_scale: integral_scales[0],
+            integral_leak: integral_leaks[0],
+            ..data
+        };
+        let pitch_data = AngleControlData {
+            rate: Some(gyro_pitch),
+            integral_limit: i_limit_pitch,
+            derivative_lpf_alpha: self.config.derivative_alpha_pitch,
+            d_term_limit: self.config.d_term_limit_pitch,
+            integral_scale: integral_scales[1],
+            integral_leak: integral_leaks[1],
+            ..data
+        };
+        let yaw_data = AngleControlData {
+            rate: Some(gyro_yaw),
+            integral_limit: i_limit_yaw,
+            derivative_lpf_alpha: self.config.derivative_alpha_yaw,
+            d_term_limit: self.config.d_term_limit_yaw,
+            integral_scale: integral_scales[2],
+            integral_leak: integral_leaks[2],
+            ..data
+        };
+
+        // Compute outputs for roll, pitch, and yaw
+        let (scale_roll, scale_pitch, scale_yaw) = self.config.scales();
+        let roll_terms = compute_terms(
+            &mut self.roll_pid,
+            compute_angle,
+            roll_data,
+            self.config.p_term_limit_roll,
+        );
+        let pitch_terms = compute_terms(
+            &mut self.pitch_pid,
+            compute_angle,
+            pitch_data,
+            self.config.p_term_limit_pitch,
+        );
+        let yaw_terms = compute_terms(
+            &mut self.yaw_pid,
+            compute_angle,
+            yaw_data,
+            self.config.p_term_limit_yaw,
+        );
+        let roll_output = scale_roll * roll_terms.sum();
+        let pitch_output = scale_pitch * pitch_terms.sum();
+        let yaw_output = scale_yaw * yaw_terms.sum();
+
+        // Record the PID terms for telemetry
+        self.terms = [roll_terms, pitch_terms, yaw_terms];
+
+        // Clamp outputs to the actuator range
+        let roll_clamped = self.config.clamp_output(roll_output);
+        let pitch_clamped = self.config.clamp_output(pitch_output);
+        let yaw_clamped = self.config.clamp_output(yaw_output);
+        self.saturation = [
+            self.config.saturation(roll_output),
+            self.config.saturation(pitch_output),
+            self.config.saturation(yaw_output),
+        ];
+
+        // Back-calculation anti-windup feeds the saturation back into the integral
+        let tracking = fixed_rate.back_calculation(
+            (roll_clamped, pitch_clamped, yaw_clamped),
+            (roll_output, pitch_output, yaw_output),
+        );
+        [
+            self.roll_pid.integral,
+            self.pitch_pid.integral,
+            self.yaw_pid.integral,
+        ] = track_integrals(
+            [
+                self.roll_pid.integral,
+                self.pitch_pid.integral,
+                self.yaw_pid.integral,
+            ],
+            tracking,
+            (i_limit_roll, i_limit_pitch, i_limit_yaw),
+            low_throttle,
+        );
+
+        // Limit the rate of change of the outputs
+        self.prev_output =
+            fixed_rate.slew_outputs(self.prev_output, (roll_clamped, pitch_clamped, yaw_clamped));
+
+        // Disabled axes hold their integrals and output zero, and frozen axes
+        // hold their integrals unless reset at low throttle
+        let enabled = self.enabled_axes;
+        let integrals = integral_axes(enabled, self.frozen_integrals, low_throttle);
+        [
+            self.roll_pid.integral,
+            self.pitch_pid.integral,
+            self.yaw_pid.integral,
+        ] = select_axes(
+            integrals,
+            [
+                self.roll_pid.integral,
+                self.pitch_pid.integral,
+                self.yaw_pid.integral,
+            ],
+            held_integrals,
+        );
+        self.terms = select_axes(enabled, self.terms, [PidTerms::new(); 3]);
+        self.saturation = select_axes(enabled, self.saturation, [SaturationState::None; 3]);
+        let (roll, pitch, yaw) = self.prev_output;
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+        self.prev_output = (roll, pitch, yaw);
+        let (roll, pitch, yaw) = self.quantization_error;
+        let [roll, pitch, yaw] = select_axes(enabled, [roll, pitch, yaw], [T::zero(); 3]);
+
+        // Round the outputs to the actuator grid, carrying the rounding error forward
+        let (quantized, quantization_error) = self
+            .config
+            .quantize_outputs(self.prev_output, (roll, pitch, yaw));
+        self.quantization_error = quantization_error;
+        let (roll_clamped, pitch_clamped, yaw_clamped) = quantized;
+
+        // Suppress jitter near zero after the integral has seen the clamped output
+        let output = (
+            self.config.apply_deadband(roll_clamped),
+            self.config.apply_deadband(pitch_clamped),
+            self.config.apply_deadband(yaw_clamped),
+        );
+
+        // Record the output loop for telemetry
+        let [roll_terms, pitch_terms, yaw_terms] = self.terms;
+        self.telemetry = [
+            Telemetry::from_pid(&self.roll_pid, roll_terms, raw_roll, gyro_roll, output.0),
+            Telemetry::from_pid(
+                &self.pitch_pid,
+                pitch_terms,
+                raw_pitch,
+                gyro_pitch,
+                output.1,
+            ),
+            Telemetry::from_pid(&self.yaw_pid, yaw_terms, raw_yaw, gyro_yaw, output.2),
+        ];
+        output
+    }
+
+    /// Returns the controller to its initial state, as when it was created.
+    /// Clears the integral terms, previous error history and telemetry.
+    /// The configuration is kept.
+    pub fn reset(&mut self) {
+        self.roll_pid.reset();
+        self.pitch_pid.reset();
+        self.yaw_pid.reset();
+        self.terms = [PidTerms::new(); 3];
+        self.telemetry = [Telemetry::new(); 3];
+        self.saturation = [SaturationState::None; 3];
+        self.prev_output = (T::zero(), T::zero(), T::zero());
+        self.quantization_error = (T::zero(), T::zero(), T::zero());
+        self.timing = TimingStats::new();
+        self.integral_divider = IntegralDivider::new();
+        self.notch = [NotchFilter::default(); 3];
+    }
+
+    /// Returns the saturation state of the (roll, pitch, yaw) outputs of the most
+    /// recent control step. See `FlightStabilizer::saturation`.
+    pub fn saturation(&self) -> [SaturationState; 3] {
+        self.saturation
+    }
+
+    /// Seeds the (roll, pitch, yaw) integrals, clamped to the integral limits.
+    /// See `FlightStabilizer::set_integral`.
+    pub fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        let ki = (self.roll_pid.ki, self.pitch_pid.ki, self.yaw_pid.ki);
+        let (i_limit_roll, i_limit_pitch, i_limit_yaw) = self.config.integral_limits(ki);
+        self.roll_pid.integral = roll.clamp(-i_limit_roll, i_limit_roll);
+        self.pitch_pid.integral = pitch.clamp(-i_limit_pitch, i_limit_pitch);
+        self.yaw_pid.integral = yaw.clamp(-i_limit_yaw, i_limit_yaw);
+    }
+
+    /// Enables or disables the (roll, pitch, yaw) axes.
+    /// See `FlightStabilizer::enable_axes`.
+    pub fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.enabled_axes = [roll, pitch, yaw];
+    }
+
+    /// Returns the (roll, pitch, yaw) flags set by `enable_axes`.
+    pub fn enabled_axes(&self) -> [bool; 3] {
+        self.enabled_axes
+    }
+
+    /// Freezes or thaws the (roll, pitch, yaw) integrals.
+    /// See `FlightStabilizer::freeze_integral`.
+    pub fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.frozen_integrals = [roll, pitch, yaw];
+    }
+
+    /// Returns the (roll, pitch, yaw) flags set by `freeze_integral`.
+    pub fn frozen_integrals(&self) -> [bool; 3] {
+        self.frozen_integrals
+    }
+
+    /// Returns the (roll, pitch, yaw) telemetry of the most recent control step.
+    /// Every record is zero before the first step and after `reset`.
+    pub fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        self.telemetry
+    }
+
+    /// Returns statistics of the time steps received since creation or `reset`.
+    pub fn timing_stats(&self) -> TimingStats<T> {
+        self.timing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use core::f32::consts::FRAC_1_SQRT_2;
+
+    /// Identity quaternion, representing a level attitude.
+    const IDENTITY: (f32, f32, f32, f32) = (1.0, 0.0, 0.0, 0.0);
+
+    /// Rotation of 0.1 radians about the roll axis.
+    const ROLL_0_1: (f32, f32, f32, f32) = (0.99875026, 0.04997917, 0.0, 0.0);
+
+    /// Rotation of 0.1 radians about the pitch axis.
+    const PITCH_0_1: (f32, f32, f32, f32) = (0.99875026, 0.0, 0.04997917, 0.0);
+
+    /// Rotation of 0.1 radians about the yaw axis.
+    const YAW_0_1: (f32, f32, f32, f32) = (0.99875026, 0.0, 0.0, 0.04997917);
+
+    /// Rotation of π/2 radians about the yaw axis.
+    const YAW_PI_2: (f32, f32, f32, f32) = (FRAC_1_SQRT_2, 0.0, 0.0, FRAC_1_SQRT_2);
+
+    /// Doubled vector part of a 0.1 radian rotation, equal to `2 * sin(0.05)`.
+    const ERROR_0_1: f32 = 0.09995834;
+
+    /// Default test configuration.
+    fn default_config() -> FlightStabilizerConfig<f32> {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+
+        // Set the PID gains for roll, pitch, and yaw.
+        config.kp_roll = 0.2;
+        config.ki_roll = 0.3;
+        config.kd_roll = -0.05;
+
+        config.kp_pitch = config.kp_roll;
+        config.ki_pitch = config.ki_roll;
+        config.kd_pitch = config.kd_roll;
+
+        config.kp_yaw = 0.3;
+        config.ki_yaw = 0.05;
+        config.kd_yaw = 0.00015;
+
+        // Set the upper limit for the integral term to prevent windup.
+        config.i_limit = 25.0;
+
+        // Set the scale to adjust the PID outputs to the actuator range.
+        config.scale = 0.01;
+
+        config
+    }
+
+    /// Test that a small rotation about each axis produces the expected per-axis error.
+    #[test]
+    fn test_quat_error_small_rotation() {
+        let error = quat_error(ROLL_0_1, IDENTITY);
+        assert!(
+            vector_close((ERROR_0_1, 0.0, 0.0), error),
+            "Roll error should match the rotation."
+        );
+
+        let error = quat_error(PITCH_0_1, IDENTITY);
+        assert!(
+            vector_close((0.0, ERROR_0_1, 0.0), error),
+            "Pitch error should match the rotation."
+        );
+
+        let error = quat_error(IDENTITY, YAW_0_1);
+        assert!(
+            vector_close((0.0, 0.0, -ERROR_0_1), error),
+            "Yaw error should oppose the measured rotation."
+        );
+    }
+
+    /// Test that the error is measured in the body frame of the current attitude.
+    #[test]
+    fn test_quat_error_body_frame() {
+        // Yawed π/2, a 0.1 radian world frame roll is a negative body frame pitch
+        let set_point = (0.7062231, 0.03534061, -0.03534061, 0.7062231);
+        let error = quat_error(set_point, YAW_PI_2);
+        assert!(
+            vector_close((0.0, -ERROR_0_1, 0.0), error),
+            "Error should be a pitch error in the body frame."
+        );
+    }
+
+    /// Test that the error takes the shortest arc for either quaternion sign.
+    #[test]
+    fn test_quat_error_shortest_arc() {
+        let negated = (-YAW_0_1.0, -YAW_0_1.1, -YAW_0_1.2, -YAW_0_1.3);
+        assert!(
+            vector_close(quat_error(YAW_0_1, IDENTITY), quat_error(negated, IDENTITY)),
+            "Negated quaternions should produce the same error."
+        );
+
+        // Crossing the π yaw boundary from -3.1 to 3.1 radians is a small error
+        let set_point = (0.02079483, 0.0, 0.0, 0.99978376);
+        let attitude = (0.02079483, 0.0, 0.0, -0.99978376);
+        let (_, _, error_yaw) = quat_error(set_point, attitude);
+        assert!(
+            value_close(-0.0831613, error_yaw),
+            "Yaw error should take the short way around."
+        );
+    }
+
+    /// Test that the stabilizer drives each axis from the error vector.
+    #[test]
+    fn test_stabilizer_quat_specific_pid_output() {
+        let config = default_config();
+        let mut stabilizer = QuatStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = ROLL_0_1; // desired attitude
+        let imu_attitude = IDENTITY; // current attitude
+        let gyro_rate = (1.0, -1.0, 1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Perform the control computation
+        let output = stabilizer.control_quat(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output = (
+            config.scale
+                * (config.kp_roll * ERROR_0_1
+                    + config.ki_roll * ERROR_0_1 * dt
+                    + config.kd_roll * gyro_rate.0),
+            config.scale * config.kd_pitch * gyro_rate.1,
+            config.scale * config.kd_yaw * gyro_rate.2,
+        );
+
+        assert!(
+            vector_close(expected_output, output),
+            "PID outputs should match specific values."
+        );
+    }
+
+    /// Test the no error contidion.
+    #[test]
+    fn test_stabilizer_quat_no_error() {
+        let config = default_config();
+        let mut stabilizer = QuatStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = YAW_PI_2; // desired attitude
+        let imu_attitude = YAW_PI_2; // current attitude
+        let gyro_rate = (0.0, 0.0, 0.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Perform the control computation
+        let output = stabilizer.control_quat(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output = (0.0, 0.0, 0.0);
+
+        assert!(
+            vector_close(expected_output, output),
+            "Outputs should be zero as there is no error."
+        );
+    }
+
+    /// Test that reset returns the controller to the state of a fresh controller.
+    #[test]
+    fn test_stabilizer_quat_reset() {
+        let config = default_config();
+        let mut stabilizer = QuatStabilizer::with_config(config);
+        let mut fresh_stabilizer = QuatStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = PITCH_0_1; // desired attitude
+        let imu_attitude = IDENTITY; // current attitude
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        // Build up internal state
+        for _ in 0..10 {
+            let _ = stabilizer.control_quat(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
+        stabilizer.reset();
+
+        // Perform the control computation
+        let output = stabilizer.control_quat(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let expected_output =
+            fresh_stabilizer.control_quat(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        assert_eq!(
+            expected_output, output,
+            "Output should match a fresh controller."
+        );
+    }
+
+    /// Test that the fixed-rate step matches the variable-rate step with the same
+    /// constant `dt`, with the rate limits and back-calculation active.
+    #[test]
+    fn test_stabilizer_quat_fixed_rate() {
+        let mut config = default_config();
+        config.max_output_rate_pitch = Some(2.0);
+        config.kt_roll = 5.0;
+        config.kt_pitch = 2.0;
+        config.output_min = Some(-0.05);
+        config.output_max = Some(0.05);
+        let mut stabilizer = QuatStabilizer::with_config(config);
+        let mut reference_stabilizer = QuatStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = PITCH_0_1; // desired attitude
+        let imu_attitude = IDENTITY; // current attitude
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        assert_eq!(
+            Err(StabilizerError::NoLoopPeriod),
+            stabilizer.control_quat_fixed_rate(set_point, imu_attitude, gyro_rate, false)
+        );
+        stabilizer.set_fixed_rate(dt).unwrap();
+
+        for step in 0..50 {
+            let low_throttle = step == 20;
+            let output = stabilizer
+                .control_quat_fixed_rate(set_point, imu_attitude, gyro_rate, low_throttle)
+                .unwrap();
+            let expected = reference_stabilizer.control_quat(
+                set_point,
+                imu_attitude,
+                gyro_rate,
+                dt,
+                low_throttle,
+            );
+            assert_eq!(expected, output, "Outputs should match at step {}.", step);
+        }
+        assert_eq!(reference_stabilizer.snapshot(), stabilizer.snapshot());
+
+        // The coefficients follow a new configuration
+        config.max_output_rate_roll = Some(1.0);
+        stabilizer.apply_config(&config);
+        assert_eq!(Some(&config.fixed_rate(dt)), stabilizer.fixed_rate());
+    }
+}
@@ -0,0 +1,292 @@
+// src/stabilizer/ramped.rs
+
+//! # Ramped PID Flight Stabilization Controller
+//!
+//! This is a wrapper that ramps the gains of any flight stabilizer after arming.
+//! Applying the full gains immediately at arm can cause a twitch, so the effective
+//! gains rise linearly from an initial fraction to the configured values over
+//! the ramp duration. Once ramped it is transparent.
+
+use crate::{FlightStabilizer, Number, SaturationState, Telemetry, TimingStats};
+
+/// Struct representing the Ramped PID Flight Stabilization Controller.
+/// The ramp is driven by the accumulated `dt` since creation or the last `reset`.
+///
+/// The output of the wrapped stabilizer is scaled by the ramp fraction, which is
+/// equivalent to scaling every gain of its final loop, feed-forward included.
+/// The internal state of the wrapped stabilizer is not affected.
+pub struct RampedStabilizer<T: Number, S: FlightStabilizer<T>> {
+    stabilizer: S,
+    ramp_duration: T,
+    initial_fraction: T,
+    elapsed: T,
+}
+
+impl<T: Number, S: FlightStabilizer<T>> RampedStabilizer<T, S> {
+    /// Creates a new controller that ramps the gains of `stabilizer` from zero
+    /// to the configured values over `ramp_duration`.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{
+    ///     AngleStabilizer, FlightStabilizer, FlightStabilizerConfig, RampedStabilizer,
+    /// };
+    ///
+    /// let config = FlightStabilizerConfig::<f32>::new();
+    /// let mut stabilizer = RampedStabilizer::new(AngleStabilizer::with_config(config), 2.0);
+    ///
+    /// // The gains reach the configured values two seconds after arming.
+    /// let output = stabilizer.control((0.0, 0.0, 0.0), (1.0, 0.0, 0.0), (0.0, 0.0, 0.0), 0.01, false);
+    /// ```
+    pub fn new(stabilizer: S, ramp_duration: T) -> Self {
+        Self::with_initial_fraction(stabilizer, ramp_duration, T::zero())
+    }
+
+    /// Creates a new controller that ramps the gains of `stabilizer` from
+    /// `initial_fraction` of the configured values over `ramp_duration`.
+    /// The initial fraction is clamped to the range zero to one.
+    pub fn with_initial_fraction(stabilizer: S, ramp_duration: T, initial_fraction: T) -> Self {
+        RampedStabilizer {
+            stabilizer,
+            ramp_duration,
+            initial_fraction: initial_fraction.clamp(T::zero(), T::one()),
+            elapsed: T::zero(),
+        }
+    }
+
+    /// Returns a reference to the wrapped stabilizer.
+    pub fn inner(&self) -> &S {
+        &self.stabilizer
+    }
+
+    /// Returns a mutable reference to the wrapped stabilizer.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.stabilizer
+    }
+
+    /// Returns the accumulated time since creation or the last `reset`,
+    /// saturating at the ramp duration.
+    pub fn elapsed(&self) -> T {
+        self.elapsed
+    }
+
+    /// Returns the current fraction of the configured gains, from the
+    /// initial fraction at arm to one once the ramp is complete.
+    /// A non-positive ramp duration disables the ramp.
+    pub fn gain_fraction(&self) -> T {
+        if self.ramp_duration <= T::zero() || self.ramp_duration <= self.elapsed {
+            return T::one();
+        }
+        let progress = self.elapsed / self.ramp_duration;
+        self.initial_fraction + (T::one() - self.initial_fraction) * progress
+    }
+
+    /// Advances the ramp, ignoring non-positive time steps and saturating at the end.
+    fn advance(&mut self, dt: T) {
+        if T::zero() < dt && self.elapsed < self.ramp_duration {
+            self.elapsed = (self.elapsed + dt).clamp(T::zero(), self.ramp_duration);
+        }
+    }
+
+    /// Scales an output of the wrapped stabilizer by the ramp fraction.
+    fn ramp(&self, output: (T, T, T)) -> (T, T, T) {
+        let fraction = self.gain_fraction();
+        if fraction == T::one() {
+            return output;
+        }
+        let (roll, pitch, yaw) = output;
+        (fraction * roll, fraction * pitch, fraction * yaw)
+    }
+}
+
+impl<T: Number, S: FlightStabilizer<T>> FlightStabilizer<T> for RampedStabilizer<T, S> {
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        self.advance(dt);
+        let output = self
+            .stabilizer
+            .control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        self.ramp(output)
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        self.advance(dt);
+        let output =
+            self.stabilizer
+                .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle);
+        self.ramp(output)
+    }
+
+    fn reset(&mut self) {
+        self.stabilizer.reset();
+        self.elapsed = T::zero();
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_setpoints(roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_disturbance(roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stabilizer.setpoints()
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.stabilizer.saturation()
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_integral(roll, pitch, yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.enable_axes(roll, pitch, yaw);
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.stabilizer.enabled_axes()
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.freeze_integral(roll, pitch, yaw);
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.stabilizer.frozen_integrals()
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        let fraction = self.gain_fraction();
+        let mut telemetry = self.stabilizer.last_telemetry();
+        for axis in telemetry.iter_mut() {
+            axis.output = fraction * axis.output;
+        }
+        telemetry
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.stabilizer.timing_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{AngleStabilizer, FlightStabilizerConfig};
+
+    /// Default test configuration.
+    fn default_config() -> FlightStabilizerConfig<f32> {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+
+        // Set the PID gains for roll, pitch, and yaw.
+        config.kp_roll = 0.2;
+        config.ki_roll = 0.3;
+        config.kd_roll = -0.05;
+
+        config.kp_pitch = config.kp_roll;
+        config.ki_pitch = config.ki_roll;
+        config.kd_pitch = config.kd_roll;
+
+        config.kp_yaw = 0.3;
+        config.ki_yaw = 0.05;
+        config.kd_yaw = 0.00015;
+
+        // Set the upper limit for the integral term to prevent windup.
+        config.i_limit = 25.0;
+
+        // Set the scale to adjust the PID outputs to the actuator range.
+        config.scale = 0.01;
+
+        config
+    }
+
+    /// Compare the ramped output against the unramped stabilizer for several steps.
+    fn assert_ramp(
+        stabilizer: &mut RampedStabilizer<f32, AngleStabilizer<f32>>,
+        expected_fractions: &[f32],
+    ) {
+        let mut unramped_stabilizer = AngleStabilizer::with_config(default_config());
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, 0.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.25; // time step
+        let low_throttle = false;
+
+        for fraction in expected_fractions {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let (roll, pitch, yaw) =
+                unramped_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let expected_output = (fraction * roll, fraction * pitch, fraction * yaw);
+            tuple_close(expected_output, output);
+        }
+    }
+
+    /// Test that the gains scale linearly during the ramp and are full afterward.
+    #[test]
+    fn test_stabilizer_ramped_linear() {
+        let inner = AngleStabilizer::with_config(default_config());
+        let mut stabilizer = RampedStabilizer::new(inner, 1.0);
+        assert_eq!(0.0, stabilizer.gain_fraction());
+        assert_ramp(&mut stabilizer, &[0.25, 0.5, 0.75, 1.0, 1.0, 1.0]);
+        assert_eq!(1.0, stabilizer.gain_fraction());
+    }
+
+    /// Test that the ramp starts from the initial fraction.
+    #[test]
+    fn test_stabilizer_ramped_initial_fraction() {
+        let inner = AngleStabilizer::with_config(default_config());
+        let mut stabilizer = RampedStabilizer::with_initial_fraction(inner, 1.0, 0.2);
+        assert_eq!(0.2, stabilizer.gain_fraction());
+        assert_ramp(&mut stabilizer, &[0.4, 0.6, 0.8, 1.0, 1.0]);
+    }
+
+    /// Test that a zero ramp duration is transparent.
+    #[test]
+    fn test_stabilizer_ramped_disabled() {
+        let inner = AngleStabilizer::with_config(default_config());
+        let mut stabilizer = RampedStabilizer::new(inner, 0.0);
+        assert_ramp(&mut stabilizer, &[1.0, 1.0, 1.0]);
+    }
+
+    /// Test that a non-positive dt does not advance the ramp.
+    #[test]
+    fn test_stabilizer_ramped_non_positive_dt() {
+        let inner = AngleStabilizer::with_config(default_config());
+        let mut stabilizer = RampedStabilizer::new(inner, 1.0);
+        let zero = (0.0, 0.0, 0.0);
+        let _ = stabilizer.control(zero, zero, zero, 0.25, false);
+        let _ = stabilizer.control(zero, zero, zero, 0.0, false);
+        let _ = stabilizer.control(zero, zero, zero, -0.25, false);
+        assert_eq!(0.25, stabilizer.elapsed());
+    }
+
+    /// Test that reset restarts the ramp along with the wrapped stabilizer.
+    #[test]
+    fn test_stabilizer_ramped_reset() {
+        let inner = AngleStabilizer::with_config(default_config());
+        let mut stabilizer = RampedStabilizer::new(inner, 1.0);
+        assert_ramp(&mut stabilizer, &[0.25, 0.5, 0.75, 1.0, 1.0]);
+        stabilizer.reset();
+        assert_eq!(0.0, stabilizer.elapsed());
+        assert_ramp(&mut stabilizer, &[0.25, 0.5, 0.75, 1.0]);
+    }
+}
@@ -0,0 +1,127 @@
+// src/stabilizer/replay.rs
+
+//! # Offline Replay
+//!
+//! This module runs logged flight data through a flight stabilizer, so gains
+//! can be evaluated offline without flying. Replaying the same log through two
+//! stabilizers with different gain sets makes it easy to diff their outputs.
+
+use crate::{FlightStabilizer, Number};
+
+/// A single logged control step, in the argument order of `FlightStabilizer::control`:
+/// (set point, IMU attitude, gyro rate, dt, low throttle).
+pub type ReplaySample<T> = ((T, T, T), (T, T, T), (T, T, T), T, bool);
+
+/// Runs each sample through the stabilizer in order, writing the control outputs
+/// to `outputs`. Stops when either slice runs out and returns the number of
+/// samples replayed. The stabilizer is not reset first.
+///
+/// Example Usage
+/// ```
+/// use free_flight_stabilization::{replay_into, AngleStabilizer, ReplaySample};
+///
+/// let mut stabilizer = AngleStabilizer::<f32>::new();
+/// let zero = (0.0, 0.0, 0.0);
+/// let samples: [ReplaySample<f32>; 2] = [
+///     (zero, (1.0, 0.0, 0.0), zero, 0.01, false),
+///     (zero, (2.0, 0.0, 0.0), zero, 0.01, false),
+/// ];
+/// let mut outputs = [zero; 2];
+///
+/// let count = replay_into(&mut stabilizer, &samples, &mut outputs);
+/// assert_eq!(2, count);
+/// ```
+pub fn replay_into<T: Number>(
+    stabilizer: &mut impl FlightStabilizer<T>,
+    samples: &[ReplaySample<T>],
+    outputs: &mut [(T, T, T)],
+) -> usize {
+    let mut count = 0;
+    for (sample, output) in samples.iter().zip(outputs.iter_mut()) {
+        let (set_point, imu_attitude, gyro_rate, dt, low_throttle) = *sample;
+        *output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        count += 1;
+    }
+    count
+}
+
+/// Runs each sample through the stabilizer in order and returns the control outputs.
+/// The stabilizer is not reset first.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn replay<T: Number>(
+    stabilizer: &mut impl FlightStabilizer<T>,
+    samples: &[ReplaySample<T>],
+) -> alloc::vec::Vec<(T, T, T)> {
+    samples
+        .iter()
+        .map(|&(set_point, imu_attitude, gyro_rate, dt, low_throttle)| {
+            stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AngleStabilizer, FlightStabilizerConfig};
+
+    const SAMPLES: usize = 50;
+
+    /// Builds a synthetic log with the roll attitude ramping away from a level setpoint.
+    fn ramp_samples() -> [ReplaySample<f32>; SAMPLES] {
+        let zero = (0.0, 0.0, 0.0);
+        let mut samples = [(zero, zero, zero, 0.01, false); SAMPLES];
+        for (i, sample) in samples.iter_mut().enumerate() {
+            sample.1 = (i as f32, 0.0, 0.0);
+        }
+        samples
+    }
+
+    /// Proportional-only stabilizer, so the output follows the attitude ramp.
+    fn stabilizer() -> AngleStabilizer<f32> {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+        config.kp_roll = 0.2;
+        config.scale = 0.01;
+        AngleStabilizer::with_config(config)
+    }
+
+    /// Test that replaying into a slice matches stepping the stabilizer by hand.
+    #[test]
+    fn test_replay_into() {
+        let samples = ramp_samples();
+        let mut outputs = [(0.0, 0.0, 0.0); SAMPLES];
+        let count = replay_into(&mut stabilizer(), &samples, &mut outputs);
+        assert_eq!(SAMPLES, count);
+
+        let mut expected_stabilizer = stabilizer();
+        for (sample, output) in samples.iter().zip(outputs.iter()) {
+            let (set_point, imu_attitude, gyro_rate, dt, low_throttle) = *sample;
+            let expected_output =
+                expected_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert_eq!(expected_output, *output, "Replay should match control.");
+        }
+    }
+
+    /// Test that replay stops at the shorter of the samples and outputs.
+    #[test]
+    fn test_replay_into_short_outputs() {
+        let samples = ramp_samples();
+        let mut outputs = [(0.0, 0.0, 0.0); 10];
+        let count = replay_into(&mut stabilizer(), &samples, &mut outputs);
+        assert_eq!(10, count);
+    }
+
+    /// Test that replay returns one monotonic output per sample of a ramp.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_replay_ramp() {
+        let samples = ramp_samples();
+        let outputs = replay(&mut stabilizer(), &samples);
+        assert_eq!(SAMPLES, outputs.len());
+        assert!(
+            outputs.windows(2).all(|pair| pair[1].0 < pair[0].0),
+            "Roll output should fall as the roll attitude ramps up."
+        );
+    }
+}
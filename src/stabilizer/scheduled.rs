@@ -0,0 +1,387 @@
+// src/stabilizer/scheduled.rs
+
+//! # Gain Scheduled Angle-Based PID Flight Stabilization Controller
+//!
+//! This is an angle-based PID flight stabilization controller with
+//! gain scheduling. The effective gains are linearly interpolated between
+//! a low-gain and a high-gain configuration based on a scheduling input,
+//! such as the throttle fraction or airspeed.
+
+use crate::pid::PidTerms;
+use crate::{
+    AngleStabilizer, FlightStabilizer, FlightStabilizerConfig, Number, SaturationState,
+    StabilizerState, Telemetry, TimingStats,
+};
+
+/// Struct representing the Gain Scheduled Angle PID Flight Stabilization Controller.
+/// Settings other than the gains are taken from the low-gain configuration.
+pub struct ScheduledStabilizer<T: Number> {
+    stabilizer: AngleStabilizer<T>,
+    low_config: FlightStabilizerConfig<T>,
+    high_config: FlightStabilizerConfig<T>,
+    schedule: T,
+}
+
+impl<T: Number> Default for ScheduledStabilizer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Number> ScheduledStabilizer<T> {
+    /// Creates a new controller using the provided low-gain and high-gain configurations.
+    /// The schedule starts at zero, using the low-gain configuration.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{
+    ///     FlightStabilizer, FlightStabilizerConfig, ScheduledStabilizer,
+    /// };
+    ///
+    /// let mut low_config = FlightStabilizerConfig::<f32>::new();
+    /// low_config.kp_roll = 0.3;
+    /// let mut high_config = low_config;
+    /// high_config.kp_roll = 0.1;
+    ///
+    /// let mut stabilizer = ScheduledStabilizer::with_config(low_config, high_config);
+    ///
+    /// // Schedule on the throttle fraction before each control step.
+    /// let throttle = 0.5;
+    /// stabilizer.set_schedule(throttle);
+    /// let output = stabilizer.control((0.0, 0.0, 0.0), (1.0, 0.0, 0.0), (0.0, 0.0, 0.0), 0.01, false);
+    /// ```
+    pub fn with_config(
+        low_config: FlightStabilizerConfig<T>,
+        high_config: FlightStabilizerConfig<T>,
+    ) -> Self {
+        ScheduledStabilizer {
+            stabilizer: AngleStabilizer::with_config(low_config),
+            low_config,
+            high_config,
+            schedule: T::zero(),
+        }
+    }
+
+    /// Creates a new controller with default settings
+    pub fn new() -> Self {
+        Self::with_config(FlightStabilizerConfig::new(), FlightStabilizerConfig::new())
+    }
+
+    /// Returns the low-gain configuration, used at a schedule point of zero.
+    pub fn low_config(&self) -> &FlightStabilizerConfig<T> {
+        &self.low_config
+    }
+
+    /// Returns the high-gain configuration, used at a schedule point of one.
+    pub fn high_config(&self) -> &FlightStabilizerConfig<T> {
+        &self.high_config
+    }
+
+    /// Returns the gain-multiplied PID terms of the most recent control step
+    /// for roll, pitch, and yaw.
+    pub fn last_terms(&self) -> [PidTerms<T>; 3] {
+        self.stabilizer.last_terms()
+    }
+
+    /// Returns the raw output of the most recent control step for roll, pitch, and yaw,
+    /// the sum of the `last_terms` before the axis scale is applied.
+    pub fn last_raw_output(&self) -> (T, T, T) {
+        self.stabilizer.last_raw_output()
+    }
+
+    /// Returns the low-gain configuration with the gains currently applied,
+    /// blended by the schedule point.
+    pub fn effective_gains(&self) -> FlightStabilizerConfig<T> {
+        self.stabilizer.effective_gains()
+    }
+
+    /// Returns a copy of the internal state of the wrapped controller, to `restore` later.
+    /// The schedule point is an input, not state, so it is not included.
+    pub fn snapshot(&self) -> StabilizerState<T> {
+        self.stabilizer.snapshot()
+    }
+
+    /// Restores the internal state from a `snapshot` of this controller.
+    pub fn restore(&mut self, state: &StabilizerState<T>) {
+        self.stabilizer.restore(state);
+    }
+
+    /// Returns the current schedule point.
+    pub fn schedule(&self) -> T {
+        self.schedule
+    }
+
+    /// Sets the schedule point used to blend the gains.
+    /// Values are clamped to the range zero (low gains) to one (high gains).
+    pub fn set_schedule(&mut self, schedule: T) {
+        self.schedule = schedule.clamp(T::zero(), T::one());
+        let gains = self.scheduled_gains();
+        self.stabilizer.set_gains(&gains);
+    }
+
+    /// Returns the low-gain configuration with the gains blended by the schedule.
+    fn scheduled_gains(&self) -> FlightStabilizerConfig<T> {
+        let blended =
+            FlightStabilizerConfig::lerp(&self.low_config, &self.high_config, self.schedule);
+        let mut config = self.low_config;
+        config.kp_roll = blended.kp_roll;
+        config.ki_roll = blended.ki_roll;
+        config.kd_roll = blended.kd_roll;
+        config.kp_pitch = blended.kp_pitch;
+        config.ki_pitch = blended.ki_pitch;
+        config.kd_pitch = blended.kd_pitch;
+        config.kp_yaw = blended.kp_yaw;
+        config.ki_yaw = blended.ki_yaw;
+        config.kd_yaw = blended.kd_yaw;
+        config
+    }
+}
+
+impl<T: Number> FlightStabilizer<T> for ScheduledStabilizer<T> {
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        self.stabilizer
+            .control(set_point, imu_attitude, gyro_rate, dt, low_throttle)
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        self.stabilizer
+            .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle)
+    }
+
+    fn reset(&mut self) {
+        self.stabilizer.reset();
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_setpoints(roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_disturbance(roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stabilizer.setpoints()
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.stabilizer.saturation()
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_integral(roll, pitch, yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.enable_axes(roll, pitch, yaw);
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.stabilizer.enabled_axes()
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.freeze_integral(roll, pitch, yaw);
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.stabilizer.frozen_integrals()
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        self.stabilizer.last_telemetry()
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.stabilizer.timing_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    /// Default test configurations.
+    fn default_config() -> (FlightStabilizerConfig<f32>, FlightStabilizerConfig<f32>) {
+        let mut low_config = FlightStabilizerConfig::<f32>::new();
+
+        // Set the low PID gains for roll, pitch, and yaw.
+        low_config.kp_roll = 0.2;
+        low_config.ki_roll = 0.3;
+        low_config.kd_roll = -0.05;
+
+        low_config.kp_pitch = low_config.kp_roll;
+        low_config.ki_pitch = low_config.ki_roll;
+        low_config.kd_pitch = low_config.kd_roll;
+
+        low_config.kp_yaw = 0.3;
+        low_config.ki_yaw = 0.05;
+        low_config.kd_yaw = 0.00015;
+
+        // Set the upper limit for the integral term to prevent windup.
+        low_config.i_limit = 25.0;
+
+        // Set the scale to adjust the PID outputs to the actuator range.
+        low_config.scale = 0.01;
+
+        // Set the high PID gains for roll, pitch, and yaw.
+        let mut high_config = low_config;
+        high_config.kp_roll = 0.4;
+        high_config.ki_roll = 0.5;
+        high_config.kd_roll = -0.15;
+
+        high_config.kp_pitch = high_config.kp_roll;
+        high_config.ki_pitch = high_config.ki_roll;
+        high_config.kd_pitch = high_config.kd_roll;
+
+        high_config.kp_yaw = 0.5;
+        high_config.ki_yaw = 0.15;
+        high_config.kd_yaw = 0.00035;
+
+        (low_config, high_config)
+    }
+
+    /// Compare the scheduled output against an angle stabilizer with the expected gains.
+    fn assert_schedule(schedule: f32, expected_config: FlightStabilizerConfig<f32>) {
+        let (low_config, high_config) = default_config();
+        let mut stabilizer = ScheduledStabilizer::with_config(low_config, high_config);
+        let mut expected_stabilizer = AngleStabilizer::with_config(expected_config);
+        stabilizer.set_schedule(schedule);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, 0.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        for _ in 0..5 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let expected_output =
+                expected_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert!(
+                vector_close(expected_output, output),
+                "Scheduled output should match the expected gains."
+            );
+        }
+    }
+
+    /// Test that a schedule of zero uses the low gains.
+    #[test]
+    fn test_stabilizer_scheduled_low() {
+        let (low_config, _) = default_config();
+        assert_schedule(0.0, low_config);
+    }
+
+    /// Test that a schedule of one half uses the midpoint gains.
+    #[test]
+    fn test_stabilizer_scheduled_midpoint() {
+        let (mut mid_config, _) = default_config();
+        mid_config.kp_roll = 0.3;
+        mid_config.ki_roll = 0.4;
+        mid_config.kd_roll = -0.1;
+
+        mid_config.kp_pitch = mid_config.kp_roll;
+        mid_config.ki_pitch = mid_config.ki_roll;
+        mid_config.kd_pitch = mid_config.kd_roll;
+
+        mid_config.kp_yaw = 0.4;
+        mid_config.ki_yaw = 0.1;
+        mid_config.kd_yaw = 0.00025;
+        assert_schedule(0.5, mid_config);
+    }
+
+    /// Test that the effective gains at a schedule of one half are the midpoint gains.
+    #[test]
+    fn test_stabilizer_scheduled_effective_gains() {
+        let (low_config, high_config) = default_config();
+        let mut stabilizer = ScheduledStabilizer::with_config(low_config, high_config);
+        assert_eq!(low_config, stabilizer.effective_gains());
+
+        stabilizer.set_schedule(0.5);
+        let gains = stabilizer.effective_gains();
+        let midpoint = |low: f32, high: f32| (low + high) / 2.0;
+        let expected = [
+            (
+                midpoint(low_config.kp_roll, high_config.kp_roll),
+                gains.kp_roll,
+            ),
+            (
+                midpoint(low_config.ki_roll, high_config.ki_roll),
+                gains.ki_roll,
+            ),
+            (
+                midpoint(low_config.kd_roll, high_config.kd_roll),
+                gains.kd_roll,
+            ),
+            (
+                midpoint(low_config.kp_pitch, high_config.kp_pitch),
+                gains.kp_pitch,
+            ),
+            (
+                midpoint(low_config.ki_pitch, high_config.ki_pitch),
+                gains.ki_pitch,
+            ),
+            (
+                midpoint(low_config.kd_pitch, high_config.kd_pitch),
+                gains.kd_pitch,
+            ),
+            (
+                midpoint(low_config.kp_yaw, high_config.kp_yaw),
+                gains.kp_yaw,
+            ),
+            (
+                midpoint(low_config.ki_yaw, high_config.ki_yaw),
+                gains.ki_yaw,
+            ),
+            (
+                midpoint(low_config.kd_yaw, high_config.kd_yaw),
+                gains.kd_yaw,
+            ),
+        ];
+        for (expected, actual) in expected {
+            assert!(
+                value_close(expected, actual),
+                "Effective gains should be the midpoint of the two configurations."
+            );
+        }
+        assert_eq!(
+            low_config.scale, gains.scale,
+            "Other settings should be low."
+        );
+    }
+
+    /// Test that a schedule of one uses the high gains.
+    #[test]
+    fn test_stabilizer_scheduled_high() {
+        let (_, high_config) = default_config();
+        assert_schedule(1.0, high_config);
+    }
+
+    /// Test that the schedule is clamped to the blend range.
+    #[test]
+    fn test_stabilizer_scheduled_clamp() {
+        let (low_config, high_config) = default_config();
+        let mut stabilizer = ScheduledStabilizer::with_config(low_config, high_config);
+        stabilizer.set_schedule(2.0);
+        assert_eq!(1.0, stabilizer.schedule());
+        stabilizer.set_schedule(-1.0);
+        assert_eq!(0.0, stabilizer.schedule());
+        assert_schedule(2.0, high_config);
+    }
+}
@@ -0,0 +1,379 @@
+// src/stabilizer/settling.rs
+
+//! # Settling Detecting PID Flight Stabilization Controller
+//!
+//! This module provides a detector that reports whether the (roll, pitch, yaw)
+//! errors have stayed within a tolerance band for a dwell time, and a wrapper that
+//! feeds it the errors of any flight stabilizer. Mode transitions and mission logic
+//! can poll it to know when the controller has converged, like waiting for the
+//! vehicle to level before takeoff, instead of keeping their own error history.
+
+use core::marker::PhantomData;
+
+use crate::{FlightStabilizer, Number, SaturationState, Telemetry, TimingStats};
+
+/// Settling detector over the last `N` (roll, pitch, yaw) errors.
+///
+/// The window is a fixed ring buffer of error magnitudes and time steps, so it needs
+/// no allocation. An axis is settled once every error recorded within the last `dwell`
+/// seconds is within the tolerance, so the window must hold at least `dwell / dt`
+/// steps for an axis to ever settle. A non-finite error is never within the tolerance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SettlingDetector<T: Number, const N: usize> {
+    errors: [[T; N]; 3],
+    dts: [T; N],
+    head: usize,
+    len: usize,
+    dwell: T,
+}
+
+impl<T: Number, const N: usize> SettlingDetector<T, N> {
+    /// Creates a new detector that reports an axis as settled once its error has
+    /// stayed within the tolerance for `dwell` seconds.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::SettlingDetector;
+    ///
+    /// let mut detector = SettlingDetector::<f32, 8>::new(1.0);
+    /// for step in 0..7 {
+    ///     let roll = 8.0 / (1 << step) as f32;
+    ///     detector.update((roll, 0.0, 5.0), 0.25);
+    /// }
+    /// // Roll has been within 1.0 for the last four steps, a full second
+    /// assert_eq!([true, true, false], detector.is_settled(1.0));
+    /// assert_eq!([false, true, false], detector.is_settled(0.5));
+    /// ```
+    pub fn new(dwell: T) -> Self {
+        Self {
+            errors: [[T::zero(); N]; 3],
+            dts: [T::zero(); N],
+            head: 0,
+            len: 0,
+            dwell,
+        }
+    }
+
+    /// Returns the time, in seconds, an error must stay within the tolerance.
+    pub fn dwell(&self) -> T {
+        self.dwell
+    }
+
+    /// Sets the time, in seconds, an error must stay within the tolerance.
+    pub fn set_dwell(&mut self, dwell: T) {
+        self.dwell = dwell;
+    }
+
+    /// Records the (roll, pitch, yaw) error of a control step that lasted `dt` seconds,
+    /// dropping the oldest error once the window is full. A zero-length window records
+    /// nothing.
+    pub fn update(&mut self, error: (T, T, T), dt: T) {
+        if N == 0 {
+            return;
+        }
+        let (roll, pitch, yaw) = error;
+        self.errors[0][self.head] = roll.abs();
+        self.errors[1][self.head] = pitch.abs();
+        self.errors[2][self.head] = yaw.abs();
+        self.dts[self.head] = dt;
+        self.head = (self.head + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    /// Returns `true` for each axis whose error magnitude has stayed within
+    /// `tolerance` for at least the dwell time. An axis is not settled before
+    /// the recorded steps cover the dwell time.
+    pub fn is_settled(&self, tolerance: T) -> [bool; 3] {
+        let mut settled = [false; 3];
+        for (axis, errors) in self.errors.iter().enumerate() {
+            let mut elapsed = T::zero();
+            for step in 1..=self.len {
+                // Walk back from the newest error
+                let index = (self.head + N - step) % N;
+                if !errors[index].is_finite() || tolerance < errors[index] {
+                    break;
+                }
+                elapsed += self.dts[index];
+                if self.dwell <= elapsed {
+                    settled[axis] = true;
+                    break;
+                }
+            }
+        }
+        settled
+    }
+
+    /// Clears the recorded errors, keeping the dwell time.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.dwell);
+    }
+}
+
+/// Struct representing the Settling Detecting PID Flight Stabilization Controller.
+/// After every `control` and `control_throttle` step, the errors of the output loop
+/// of the wrapped stabilizer, from `last_telemetry`, are recorded in a
+/// `SettlingDetector` with a window of `N` steps.
+///
+/// Cascade stabilizers report the error of their innermost loop, like the rate error,
+/// so feed a `SettlingDetector` the angle errors from `compute_errors` directly to
+/// wait on the attitude of a cascade. A disabled axis has a zero error, so it settles.
+/// The outputs of the wrapped stabilizer are not affected. Resetting the controller
+/// also clears the recorded errors.
+pub struct SettlingStabilizer<T: Number, S: FlightStabilizer<T>, const N: usize> {
+    stabilizer: S,
+    detector: SettlingDetector<T, N>,
+    _number: PhantomData<T>,
+}
+
+impl<T: Number, S: FlightStabilizer<T>, const N: usize> SettlingStabilizer<T, S, N> {
+    /// Creates a new controller that reports an axis of `stabilizer` as settled once
+    /// its error has stayed within the tolerance for `dwell` seconds.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{
+    ///     AngleStabilizer, FlightStabilizer, FlightStabilizerConfig, SettlingStabilizer,
+    /// };
+    ///
+    /// let config = FlightStabilizerConfig::<f32>::new();
+    /// let mut stabilizer =
+    ///     SettlingStabilizer::<_, _, 64>::new(AngleStabilizer::with_config(config), 0.5);
+    ///
+    /// // Level within a degree for half a second before takeoff
+    /// let zero = (0.0, 0.0, 0.0);
+    /// for _ in 0..64 {
+    ///     stabilizer.control(zero, (0.5, -0.5, 0.0), zero, 0.01, true);
+    /// }
+    /// assert_eq!([true; 3], stabilizer.is_settled(1.0));
+    /// assert_eq!([false, false, true], stabilizer.is_settled(0.1));
+    /// ```
+    pub fn new(stabilizer: S, dwell: T) -> Self {
+        SettlingStabilizer {
+            stabilizer,
+            detector: SettlingDetector::new(dwell),
+            _number: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the wrapped stabilizer.
+    pub fn inner(&self) -> &S {
+        &self.stabilizer
+    }
+
+    /// Returns a mutable reference to the wrapped stabilizer.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.stabilizer
+    }
+
+    /// Returns a reference to the detector.
+    pub fn detector(&self) -> &SettlingDetector<T, N> {
+        &self.detector
+    }
+
+    /// Returns a mutable reference to the detector, to change the dwell time.
+    pub fn detector_mut(&mut self) -> &mut SettlingDetector<T, N> {
+        &mut self.detector
+    }
+
+    /// Returns `true` for each axis whose error magnitude has stayed within
+    /// `tolerance` for at least the dwell time.
+    pub fn is_settled(&self, tolerance: T) -> [bool; 3] {
+        self.detector.is_settled(tolerance)
+    }
+
+    /// Records the errors of the most recent control step.
+    fn record(&mut self, dt: T) {
+        let [roll, pitch, yaw] = self.stabilizer.last_telemetry();
+        self.detector
+            .update((roll.error, pitch.error, yaw.error), dt);
+    }
+}
+
+impl<T: Number, S: FlightStabilizer<T>, const N: usize> FlightStabilizer<T>
+    for SettlingStabilizer<T, S, N>
+{
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        let output = self
+            .stabilizer
+            .control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        self.record(dt);
+        output
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        let output =
+            self.stabilizer
+                .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle);
+        self.record(dt);
+        output
+    }
+
+    fn reset(&mut self) {
+        self.stabilizer.reset();
+        self.detector.reset();
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_setpoints(roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_disturbance(roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stabilizer.setpoints()
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.stabilizer.saturation()
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_integral(roll, pitch, yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.enable_axes(roll, pitch, yaw);
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.stabilizer.enabled_axes()
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.freeze_integral(roll, pitch, yaw);
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.stabilizer.frozen_integrals()
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        self.stabilizer.last_telemetry()
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.stabilizer.timing_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{AngleStabilizer, FlightStabilizerConfig, PlantModel};
+
+    /// Test that a decaying error settles only after staying in the band for the dwell time.
+    #[test]
+    fn test_settling_detector_decay() {
+        let mut detector = SettlingDetector::<f32, 16>::new(1.0);
+        assert_eq!([false; 3], detector.is_settled(1.0));
+
+        // Roll halves every step, entering the band of 1.0 on the fourth step.
+        // Pitch sits in the band, except for a spike, and yaw stays outside it.
+        let mut roll = 8.0;
+        for step in 0..12 {
+            let pitch = if step == 5 { 2.0 } else { 0.5 };
+            detector.update((roll, -pitch, 3.0), 0.25);
+            roll *= -0.5;
+
+            // Four steps of 0.25 seconds make up the dwell time
+            let settled = detector.is_settled(1.0);
+            assert_eq!(6 <= step, settled[0], "step {}", step);
+            assert_eq!(
+                9 <= step || (3..5).contains(&step),
+                settled[1],
+                "step {}",
+                step
+            );
+            assert!(!settled[2]);
+        }
+
+        // A longer dwell waits for more steps, and a tighter band for smaller errors
+        detector.set_dwell(2.0);
+        assert_eq!([true, false, false], detector.is_settled(1.0));
+        assert_eq!([false; 3], detector.is_settled(0.01));
+        assert_eq!([true; 3], detector.is_settled(3.0));
+
+        // A non-finite error is never settled, and a reset forgets the history
+        detector.update((f32::NAN, 0.0, 0.0), 0.25);
+        assert!(!detector.is_settled(f32::INFINITY)[0]);
+        detector.reset();
+        assert_eq!([false; 3], detector.is_settled(3.0));
+        assert_eq!(2.0, detector.dwell());
+    }
+
+    /// Test that the window must cover the dwell time, and a zero-length window never settles.
+    #[test]
+    fn test_settling_detector_window() {
+        let mut short = SettlingDetector::<f32, 3>::new(1.0);
+        let mut empty = SettlingDetector::<f32, 0>::new(0.0);
+        for _ in 0..10 {
+            short.update((0.0, 0.0, 0.0), 0.25);
+            empty.update((0.0, 0.0, 0.0), 0.25);
+        }
+        assert_eq!([false; 3], short.is_settled(1.0));
+        short.set_dwell(0.75);
+        assert_eq!([true; 3], short.is_settled(1.0));
+        assert_eq!([false; 3], empty.is_settled(1.0));
+    }
+
+    /// Test that the wrapper settles a roll step on the simulated plant once the attitude
+    /// stays near the setpoint for the dwell time, and a reset clears it.
+    #[test]
+    fn test_stabilizer_settling_step() {
+        let model = PlantModel {
+            gain: 1000.0,
+            time_constant: 0.05,
+        };
+        let config = FlightStabilizerConfig::from_bandwidth(20.0, 0.7, model).unwrap();
+        let dwell_ticks = 100;
+        let dt = 0.001;
+        let mut stabilizer = SettlingStabilizer::<_, _, 128>::new(
+            AngleStabilizer::with_config(config),
+            dwell_ticks as f32 * dt,
+        );
+        let mut plant = Plant::new(model.gain, model.time_constant);
+        let set_point = (10.0, 0.0, 0.0);
+        let mut response = StepResponse::new(0.0, set_point.0, 0.1);
+        let mut settled_tick = None;
+        for tick in 0..1000 {
+            let output = stabilizer.control(set_point, plant.attitude, plant.rate, dt, false);
+            response.record(plant.attitude.0);
+            plant.step(output, dt);
+            if settled_tick.is_none() && stabilizer.is_settled(0.1)[0] {
+                settled_tick = Some(tick);
+            }
+        }
+
+        // The error enters the band when the attitude settles, and the detector
+        // flips a dwell time later, give or take the rounding of the time steps
+        let band_tick = response.settling_tick.unwrap();
+        let settled_tick = settled_tick.unwrap();
+        assert!(
+            (band_tick + dwell_ticks - 2..=band_tick + dwell_ticks).contains(&settled_tick),
+            "{} {}",
+            band_tick,
+            settled_tick
+        );
+        assert_eq!([true; 3], stabilizer.is_settled(0.1));
+
+        stabilizer.reset();
+        assert_eq!([false; 3], stabilizer.is_settled(0.1));
+    }
+}
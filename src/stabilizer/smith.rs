@@ -0,0 +1,392 @@
+// src/stabilizer/smith.rs
+
+//! # Smith Predictor PID Flight Stabilization Controller
+//!
+//! This is a wrapper that compensates the dead time between the actuators and the
+//! sensors of any flight stabilizer with a Smith predictor. A delayed measurement
+//! still shows the error that earlier outputs are already correcting, so a PID tuned
+//! for the plant alone over-corrects and overshoots.
+//!
+//! The predictor runs a first-order-plus-dead-time model of each axis, a `PlantModel`
+//! followed by a delay of `N` control steps. The model predicts how far the outputs
+//! still in the delay will move the vehicle, and the prediction is added to the
+//! measured attitude and gyro rates, so the wrapped stabilizer sees an estimate of the
+//! undelayed response and can keep the gains tuned for the plant without the delay.
+
+use core::marker::PhantomData;
+
+use crate::{FlightStabilizer, Number, PlantModel, SaturationState, Telemetry, TimingStats};
+
+/// Struct representing the Smith Predictor PID Flight Stabilization Controller.
+/// The dead time is `N` control steps, so the loop should run at a fixed rate.
+///
+/// The outputs of the last `N` steps are kept in a fixed ring buffer. Each step, the
+/// plant model of each axis is driven by the difference between the newest output
+/// and the output leaving the delay, which is the difference between the undelayed
+/// and delayed model responses. The model state is that difference, rather than the
+/// two responses, so the integrated angles cannot grow without bound in flight.
+/// The correction is only as good as the model; a model gain that is too high
+/// over-predicts and makes the response sluggish, and a dead time that is too long
+/// can destabilize the loop.
+///
+/// A low throttle step clears the delay and the correction, since a vehicle on the
+/// ground does not respond to the outputs. With `N` of zero the correction is always
+/// zero, and the wrapped stabilizer runs unchanged.
+pub struct SmithPredictorStabilizer<T: Number, S: FlightStabilizer<T>, const N: usize> {
+    stabilizer: S,
+    models: [PlantModel<T>; 3],
+    outputs: [(T, T, T); N],
+    head: usize,
+    correction: [(T, T); 3],
+    _number: PhantomData<T>,
+}
+
+impl<T: Number, S: FlightStabilizer<T>, const N: usize> SmithPredictorStabilizer<T, S, N> {
+    /// Creates a new controller that compensates a dead time of `N` control steps
+    /// for `stabilizer`, with the (roll, pitch, yaw) plant models.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{
+    ///     AngleStabilizer, FlightStabilizer, FlightStabilizerConfig, PlantModel,
+    ///     SmithPredictorStabilizer,
+    /// };
+    ///
+    /// let mut config = FlightStabilizerConfig::<f32>::new();
+    /// config.kp_roll = 0.2;
+    /// config.scale = 0.01;
+    ///
+    /// // A 20 ms dead time at a 1 kHz loop rate
+    /// let model = PlantModel { gain: 1000.0, time_constant: 0.05 };
+    /// let mut stabilizer = SmithPredictorStabilizer::<_, _, 20>::new(
+    ///     AngleStabilizer::with_config(config),
+    ///     [model; 3],
+    /// );
+    ///
+    /// let zero = (0.0, 0.0, 0.0);
+    /// let output = stabilizer.control((10.0, 0.0, 0.0), zero, zero, 0.001, false);
+    /// assert!(0.0 < output.0);
+    ///
+    /// // The first output is still in the delay, and the model predicts its response
+    /// let (attitude, rate) = stabilizer.correction();
+    /// assert!(0.0 < attitude.0 && 0.0 < rate.0);
+    /// ```
+    pub fn new(stabilizer: S, models: [PlantModel<T>; 3]) -> Self {
+        SmithPredictorStabilizer {
+            stabilizer,
+            models,
+            outputs: [(T::zero(), T::zero(), T::zero()); N],
+            head: 0,
+            correction: [(T::zero(), T::zero()); 3],
+            _number: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the wrapped stabilizer.
+    pub fn inner(&self) -> &S {
+        &self.stabilizer
+    }
+
+    /// Returns a mutable reference to the wrapped stabilizer.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.stabilizer
+    }
+
+    /// Returns the (roll, pitch, yaw) plant models.
+    pub fn models(&self) -> [PlantModel<T>; 3] {
+        self.models
+    }
+
+    /// Replaces the (roll, pitch, yaw) plant models. The delay and the correction are
+    /// kept, so the new models take effect gradually.
+    pub fn set_models(&mut self, models: [PlantModel<T>; 3]) {
+        self.models = models;
+    }
+
+    /// Returns the (roll, pitch, yaw) corrections added to the measured attitude and
+    /// gyro rates on the next step, as a tuple of (attitude, rate) corrections.
+    pub fn correction(&self) -> ((T, T, T), (T, T, T)) {
+        let [roll, pitch, yaw] = self.correction;
+        ((roll.0, pitch.0, yaw.0), (roll.1, pitch.1, yaw.1))
+    }
+
+    /// Clears the delayed outputs and the correction.
+    fn clear(&mut self) {
+        self.outputs = [(T::zero(), T::zero(), T::zero()); N];
+        self.head = 0;
+        self.correction = [(T::zero(), T::zero()); 3];
+    }
+
+    /// Adds the correction to the measured attitude and gyro rates.
+    fn compensate(&self, imu_attitude: (T, T, T), gyro_rate: (T, T, T)) -> ((T, T, T), (T, T, T)) {
+        let (attitude, rate) = self.correction();
+        (
+            (
+                imu_attitude.0 + attitude.0,
+                imu_attitude.1 + attitude.1,
+                imu_attitude.2 + attitude.2,
+            ),
+            (
+                gyro_rate.0 + rate.0,
+                gyro_rate.1 + rate.1,
+                gyro_rate.2 + rate.2,
+            ),
+        )
+    }
+
+    /// Pushes an output into the delay and advances the correction by `dt`.
+    fn advance(&mut self, output: (T, T, T), dt: T) {
+        let delayed = if N == 0 {
+            output
+        } else {
+            let delayed = self.outputs[self.head];
+            self.outputs[self.head] = output;
+            self.head = (self.head + 1) % N;
+            delayed
+        };
+        let inputs = [
+            output.0 - delayed.0,
+            output.1 - delayed.1,
+            output.2 - delayed.2,
+        ];
+        for ((correction, model), input) in self
+            .correction
+            .iter_mut()
+            .zip(self.models.iter())
+            .zip(inputs)
+        {
+            *correction = model.step(*correction, input, dt);
+        }
+    }
+}
+
+impl<T: Number, S: FlightStabilizer<T>, const N: usize> FlightStabilizer<T>
+    for SmithPredictorStabilizer<T, S, N>
+{
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        if low_throttle {
+            self.clear();
+        }
+        let (imu_attitude, gyro_rate) = self.compensate(imu_attitude, gyro_rate);
+        let output = self
+            .stabilizer
+            .control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        if !low_throttle {
+            self.advance(output, dt);
+        }
+        output
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        let (imu_attitude, gyro_rate) = self.compensate(imu_attitude, gyro_rate);
+        let output =
+            self.stabilizer
+                .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle);
+        self.advance(output, dt);
+        output
+    }
+
+    fn reset(&mut self) {
+        self.stabilizer.reset();
+        self.clear();
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_setpoints(roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_disturbance(roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stabilizer.setpoints()
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.stabilizer.saturation()
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_integral(roll, pitch, yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.enable_axes(roll, pitch, yaw);
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.stabilizer.enabled_axes()
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.freeze_integral(roll, pitch, yaw);
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.stabilizer.frozen_integrals()
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        self.stabilizer.last_telemetry()
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.stabilizer.timing_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{AngleStabilizer, FlightStabilizerConfig};
+
+    /// Dead time of the simulated plant, in control steps.
+    const DELAY: usize = 20;
+
+    /// The simulated plant, and gains tuned for it without the dead time.
+    fn plant_model() -> (PlantModel<f32>, FlightStabilizerConfig<f32>) {
+        let model = PlantModel {
+            gain: 1000.0,
+            time_constant: 0.05,
+        };
+        let config = FlightStabilizerConfig::from_bandwidth(20.0, 0.7, model).unwrap();
+        (model, config)
+    }
+
+    /// Steps roll on the simulated plant with the outputs delayed by `DELAY` steps,
+    /// returning the response.
+    fn delayed_step_response<S: FlightStabilizer<f32>>(stabilizer: &mut S) -> StepResponse {
+        let (model, _) = plant_model();
+        let mut plant = Plant::new(model.gain, model.time_constant);
+        let mut delay = [(0.0, 0.0, 0.0); DELAY];
+        let set_point = (10.0, 0.0, 0.0);
+        let dt = 0.001;
+        let mut response = StepResponse::new(0.0, set_point.0, 0.1);
+        for tick in 0..3000 {
+            let output = stabilizer.control(set_point, plant.attitude, plant.rate, dt, false);
+            let delayed = core::mem::replace(&mut delay[tick % DELAY], output);
+            plant.step(delayed, dt);
+            response.record(plant.attitude.0);
+        }
+        response
+    }
+
+    /// Test that the model step matches the simulated plant.
+    #[test]
+    fn test_plant_model_step() {
+        let (model, _) = plant_model();
+        let mut plant = Plant::new(model.gain, model.time_constant);
+        let mut state = (0.0, 0.0);
+        for tick in 0..100 {
+            let output = if tick < 50 { 0.01 } else { -0.02 };
+            plant.step((output, 0.0, 0.0), 0.001);
+            state = model.step(state, output, 0.001);
+            assert!(value_close(plant.attitude.0, state.0));
+            assert!(value_close(plant.rate.0, state.1));
+        }
+        assert_eq!(state, model.step(state, 1.0, 0.0));
+    }
+
+    /// Test that the predictor reduces the overshoot of a step with dead time
+    /// compared to the bare stabilizer tuned for the plant without it.
+    #[test]
+    fn test_stabilizer_smith_reduces_overshoot() {
+        let (model, config) = plant_model();
+
+        let mut bare_stabilizer = AngleStabilizer::with_config(config);
+        let bare = delayed_step_response(&mut bare_stabilizer);
+
+        let mut stabilizer = SmithPredictorStabilizer::<_, _, DELAY>::new(
+            AngleStabilizer::with_config(config),
+            [model; 3],
+        );
+        let predicted = delayed_step_response(&mut stabilizer);
+
+        // Without the dead time the tuned loop overshoots by about 5%
+        let mut undelayed_stabilizer = AngleStabilizer::with_config(config);
+        let mut plant = Plant::new(model.gain, model.time_constant);
+        let mut undelayed = StepResponse::new(0.0, 10.0, 0.1);
+        for _ in 0..3000 {
+            let output = undelayed_stabilizer.control(
+                (10.0, 0.0, 0.0),
+                plant.attitude,
+                plant.rate,
+                0.001,
+                false,
+            );
+            plant.step(output, 0.001);
+            undelayed.record(plant.attitude.0);
+        }
+
+        assert!(
+            2.0 * predicted.overshoot < bare.overshoot,
+            "{:?} {:?}",
+            predicted,
+            bare
+        );
+        assert!(
+            (predicted.overshoot - undelayed.overshoot).abs() < 0.02,
+            "{:?} {:?}",
+            predicted,
+            undelayed
+        );
+        // The compensated response is the undelayed one, shifted by the dead time
+        assert_eq!(
+            undelayed.settling_tick.map(|tick| tick + DELAY),
+            predicted.settling_tick
+        );
+    }
+
+    /// Test that a zero dead time leaves the stabilizer unchanged, and that a low
+    /// throttle step or a reset clears the correction.
+    #[test]
+    fn test_stabilizer_smith_correction() {
+        let (model, config) = plant_model();
+        let zero = (0.0, 0.0, 0.0);
+        let mut bare_stabilizer = AngleStabilizer::with_config(config);
+        let mut undelayed = SmithPredictorStabilizer::<_, _, 0>::new(
+            AngleStabilizer::with_config(config),
+            [model; 3],
+        );
+        for _ in 0..10 {
+            let output = undelayed.control((10.0, -5.0, 0.0), zero, zero, 0.001, false);
+            assert_eq!(
+                bare_stabilizer.control((10.0, -5.0, 0.0), zero, zero, 0.001, false),
+                output
+            );
+        }
+        assert_eq!((zero, zero), undelayed.correction());
+
+        let mut stabilizer = SmithPredictorStabilizer::<_, _, DELAY>::new(
+            AngleStabilizer::with_config(config),
+            [model; 3],
+        );
+        let output = stabilizer.control((10.0, -5.0, 0.0), zero, zero, 0.001, false);
+        let (attitude, rate) = stabilizer.correction();
+        assert!(value_close(model.gain * output.0 / 51.0, rate.0));
+        assert!(value_close(0.5 * rate.0 * 0.001, attitude.0));
+        assert!(rate.1 < 0.0);
+
+        stabilizer.control((10.0, -5.0, 0.0), zero, zero, 0.001, true);
+        assert_eq!((zero, zero), stabilizer.correction());
+        stabilizer.control((10.0, -5.0, 0.0), zero, zero, 0.001, false);
+        stabilizer.reset();
+        assert_eq!((zero, zero), stabilizer.correction());
+    }
+}
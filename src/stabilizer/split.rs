@@ -0,0 +1,279 @@
+// src/stabilizer/split.rs
+
+//! # Split Axis PID Flight Stabilization Controller
+//!
+//! This is a wrapper that composes two flight stabilizers, one for roll and
+//! pitch and one for yaw. A craft can hold its roll and pitch angles with an
+//! angle stabilizer while flying yaw in rate mode with a rate stabilizer.
+
+use core::marker::PhantomData;
+
+use crate::{FlightStabilizer, Number, SaturationState, Telemetry, TimingStats};
+
+/// Struct representing the Split Axis PID Flight Stabilization Controller.
+/// Roll and pitch come from the roll/pitch stabilizer and yaw comes from the yaw stabilizer.
+///
+/// Every call fans out to both wrapped stabilizers with the full inputs, and the
+/// outputs are recombined by axis. Both stabilizers run every tick, so the unused
+/// axes of each keep integrating, but their outputs are discarded. Set a zero
+/// integral gain on the unused axes to keep their state quiet.
+/// `reset`, `set_setpoints`, `set_integral` and `set_disturbance` propagate to both
+/// stabilizers, while `setpoints` and `saturation` are recombined by axis.
+pub struct SplitAxisStabilizer<T: Number, R: FlightStabilizer<T>, Y: FlightStabilizer<T>> {
+    roll_pitch: R,
+    yaw: Y,
+    _number: PhantomData<T>,
+}
+
+impl<T: Number, R: FlightStabilizer<T>, Y: FlightStabilizer<T>> SplitAxisStabilizer<T, R, Y> {
+    /// Creates a new controller that takes roll and pitch from `roll_pitch`
+    /// and yaw from `yaw`.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{
+    ///     AngleStabilizer, FlightStabilizer, FlightStabilizerConfig, RateStabilizer,
+    ///     SplitAxisStabilizer,
+    /// };
+    ///
+    /// let config = FlightStabilizerConfig::<f32>::new();
+    /// let mut stabilizer = SplitAxisStabilizer::new(
+    ///     AngleStabilizer::with_config(config),
+    ///     RateStabilizer::with_config(config),
+    /// );
+    ///
+    /// // Roll and pitch hold angles, while yaw follows a rate setpoint.
+    /// let output = stabilizer.control((0.0, 0.0, 30.0), (1.0, 0.0, 0.0), (0.0, 0.0, 0.0), 0.01, false);
+    /// ```
+    pub fn new(roll_pitch: R, yaw: Y) -> Self {
+        SplitAxisStabilizer {
+            roll_pitch,
+            yaw,
+            _number: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the roll/pitch stabilizer.
+    pub fn roll_pitch(&self) -> &R {
+        &self.roll_pitch
+    }
+
+    /// Returns a mutable reference to the roll/pitch stabilizer.
+    pub fn roll_pitch_mut(&mut self) -> &mut R {
+        &mut self.roll_pitch
+    }
+
+    /// Returns a reference to the yaw stabilizer.
+    pub fn yaw(&self) -> &Y {
+        &self.yaw
+    }
+
+    /// Returns a mutable reference to the yaw stabilizer.
+    pub fn yaw_mut(&mut self) -> &mut Y {
+        &mut self.yaw
+    }
+
+    /// Takes roll and pitch from the first output and yaw from the second.
+    fn split<U>(roll_pitch: (U, U, U), yaw: (U, U, U)) -> (U, U, U) {
+        let (roll, pitch, _) = roll_pitch;
+        let (_, _, yaw) = yaw;
+        (roll, pitch, yaw)
+    }
+}
+
+impl<T: Number, R: FlightStabilizer<T>, Y: FlightStabilizer<T>> FlightStabilizer<T>
+    for SplitAxisStabilizer<T, R, Y>
+{
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        let roll_pitch =
+            self.roll_pitch
+                .control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        let yaw = self
+            .yaw
+            .control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        Self::split(roll_pitch, yaw)
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        let roll_pitch =
+            self.roll_pitch
+                .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle);
+        let yaw = self
+            .yaw
+            .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle);
+        Self::split(roll_pitch, yaw)
+    }
+
+    fn reset(&mut self) {
+        self.roll_pitch.reset();
+        self.yaw.reset();
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.roll_pitch.set_setpoints(roll, pitch, yaw);
+        self.yaw.set_setpoints(roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        Self::split(self.roll_pitch.setpoints(), self.yaw.setpoints())
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        let [roll, pitch, _] = self.roll_pitch.saturation();
+        let [_, _, yaw] = self.yaw.saturation();
+        [roll, pitch, yaw]
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.roll_pitch.set_disturbance(roll, pitch, yaw);
+        self.yaw.set_disturbance(roll, pitch, yaw);
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        self.roll_pitch.set_integral(roll, pitch, yaw);
+        self.yaw.set_integral(roll, pitch, yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.roll_pitch.enable_axes(roll, pitch, yaw);
+        self.yaw.enable_axes(roll, pitch, yaw);
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        let [roll, pitch, _] = self.roll_pitch.enabled_axes();
+        let [_, _, yaw] = self.yaw.enabled_axes();
+        [roll, pitch, yaw]
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.roll_pitch.freeze_integral(roll, pitch, yaw);
+        self.yaw.freeze_integral(roll, pitch, yaw);
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        let [roll, pitch, _] = self.roll_pitch.frozen_integrals();
+        let [_, _, yaw] = self.yaw.frozen_integrals();
+        [roll, pitch, yaw]
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        let [roll, pitch, _] = self.roll_pitch.last_telemetry();
+        let [_, _, yaw] = self.yaw.last_telemetry();
+        [roll, pitch, yaw]
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        // Both stabilizers receive the same time steps
+        self.roll_pitch.timing_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AngleStabilizer, FlightStabilizerConfig, RateStabilizer};
+
+    /// Default test configuration.
+    fn default_config() -> FlightStabilizerConfig<f32> {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+
+        // Set the PID gains for roll, pitch, and yaw.
+        config.kp_roll = 0.2;
+        config.ki_roll = 0.3;
+        config.kd_roll = -0.05;
+
+        config.kp_pitch = config.kp_roll;
+        config.ki_pitch = config.ki_roll;
+        config.kd_pitch = config.kd_roll;
+
+        config.kp_yaw = 0.3;
+        config.ki_yaw = 0.05;
+        config.kd_yaw = 0.00015;
+
+        // Set the upper limit for the integral term to prevent windup.
+        config.i_limit = 25.0;
+
+        // Set the scale to adjust the PID outputs to the actuator range.
+        config.scale = 0.01;
+
+        config
+    }
+
+    /// Test that roll and pitch match the angle stabilizer and yaw matches the rate stabilizer.
+    #[test]
+    fn test_stabilizer_split_axis_outputs() {
+        let config = default_config();
+        let mut stabilizer = SplitAxisStabilizer::new(
+            AngleStabilizer::with_config(config),
+            RateStabilizer::with_config(config),
+        );
+        let mut angle_stabilizer = AngleStabilizer::with_config(config);
+        let mut rate_stabilizer = RateStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, -5.0, 30.0); // desired roll, pitch, yaw rate
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, 20.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        for _ in 0..10 {
+            let output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let angle_output =
+                angle_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            let rate_output =
+                rate_stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+            assert_eq!(
+                angle_output.0, output.0,
+                "Roll should match the angle output."
+            );
+            assert_eq!(
+                angle_output.1, output.1,
+                "Pitch should match the angle output."
+            );
+            assert_eq!(rate_output.2, output.2, "Yaw should match the rate output.");
+        }
+    }
+
+    /// Test that reset and stored setpoints propagate to both stabilizers.
+    #[test]
+    fn test_stabilizer_split_axis_reset_and_setpoints() {
+        let config = default_config();
+        let mut stabilizer = SplitAxisStabilizer::new(
+            AngleStabilizer::with_config(config),
+            RateStabilizer::with_config(config),
+        );
+
+        stabilizer.set_setpoints(1.0, 2.0, 3.0);
+        assert_eq!((1.0, 2.0, 3.0), stabilizer.setpoints());
+        assert_eq!((1.0, 2.0, 3.0), stabilizer.roll_pitch().setpoints());
+        assert_eq!((1.0, 2.0, 3.0), stabilizer.yaw().setpoints());
+
+        // Build up some state, then reset
+        let zero = (0.0, 0.0, 0.0);
+        let first_output = stabilizer.control_stored(zero, zero, 0.01, false);
+        for _ in 0..10 {
+            let _ = stabilizer.control_stored(zero, zero, 0.01, false);
+        }
+        stabilizer.reset();
+        assert_eq!((0.0, 0.0, 0.0), stabilizer.setpoints());
+
+        stabilizer.set_setpoints(1.0, 2.0, 3.0);
+        let output = stabilizer.control_stored(zero, zero, 0.01, false);
+        assert_eq!(first_output, output, "Reset should clear both stabilizers.");
+    }
+}
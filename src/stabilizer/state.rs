@@ -0,0 +1,122 @@
+// src/stabilizer/state.rs
+
+//! # Flight Stabilizer State Module
+//!
+//! This module provides a plain copy of the internal state of a flight
+//! stabilizer, for branching simulations. A snapshot can be taken before
+//! trying an input sequence and restored to try another from the same point,
+//! without cloning the controller or allocating.
+
+use crate::pid::{NotchFilter, PidTerms};
+use crate::{IntegralDivider, Number, SaturationState};
+use piddiy::PidController;
+
+/// Internal state of a single PID loop, excluding the gains.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PidState<T> {
+    set_point: T,
+    integral: T,
+    error: T,
+    derivative: T,
+}
+
+impl<T: Number> PidState<T> {
+    /// Creates a new loop state with every value set to zero.
+    pub(crate) fn new() -> Self {
+        Self {
+            set_point: T::zero(),
+            integral: T::zero(),
+            error: T::zero(),
+            derivative: T::zero(),
+        }
+    }
+
+    /// Reads the loop state of a PID controller.
+    pub(crate) fn from_pid<U>(pid: &PidController<T, U>) -> Self {
+        Self {
+            set_point: pid.set_point,
+            integral: pid.integral,
+            error: pid.error,
+            derivative: pid.derivative,
+        }
+    }
+
+    /// Writes the loop state back to a PID controller.
+    pub(crate) fn restore<U>(&self, pid: &mut PidController<T, U>) {
+        pid.set_point = self.set_point;
+        pid.integral = self.integral;
+        pid.error = self.error;
+        pid.derivative = self.derivative;
+    }
+}
+
+/// Copy of the internal state of a flight stabilizer, taken with `snapshot`
+/// and applied with `restore`.
+///
+/// The state holds the integrals, previous errors and derivatives of every loop,
+/// along with setpoint, filter and measurement histories, and the ticks counted
+/// toward the next integral update of each loop. The configuration and
+/// gains are not part of the state. A state should only be restored to a
+/// stabilizer of the type it was taken from.
+///
+/// Example Usage
+/// ```
+/// use free_flight_stabilization::{AngleStabilizer, FlightStabilizer, FlightStabilizerConfig};
+///
+/// let mut config = FlightStabilizerConfig::<f32>::new();
+/// config.ki_roll = 0.3;
+/// let mut stabilizer = AngleStabilizer::with_config(config);
+/// let zero = (0.0, 0.0, 0.0);
+///
+/// // Branch from the current state to compare two inputs.
+/// let state = stabilizer.snapshot();
+/// let first = stabilizer.control((10.0, 0.0, 0.0), zero, zero, 0.01, false);
+/// stabilizer.restore(&state);
+/// let second = stabilizer.control((-10.0, 0.0, 0.0), zero, zero, 0.01, false);
+/// assert_eq!(-first.0, second.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StabilizerState<T> {
+    /// Loop states by cascade stage, from the outer loop, and by (roll, pitch, yaw) axis.
+    pub(crate) pids: [[PidState<T>; 3]; 3],
+    /// Integral divider states by cascade stage, from the outer loop.
+    pub(crate) integral_dividers: [IntegralDivider<T>; 3],
+    pub(crate) set_point: (T, T, T),
+    pub(crate) stored_set_point: (T, T, T),
+    pub(crate) terms: [PidTerms<T>; 3],
+    pub(crate) saturation: [SaturationState; 3],
+    pub(crate) prev_output: (T, T, T),
+    pub(crate) quantization_error: (T, T, T),
+    pub(crate) disturbance: (T, T, T),
+    pub(crate) filtered_attitude: Option<(T, T, T)>,
+    pub(crate) notch: [NotchFilter<T>; 3],
+    /// Stabilizer specific history, like previous setpoints and measurements.
+    pub(crate) history: [(T, T, T); 5],
+    /// Integrated heading of the rate stabilizer heading hold.
+    pub(crate) heading: T,
+    /// Held heading of the rate stabilizer heading hold, if captured.
+    pub(crate) held_heading: Option<T>,
+}
+
+impl<T: Number> StabilizerState<T> {
+    /// Creates a new state with every value set to zero.
+    pub(crate) fn new() -> Self {
+        let zero = (T::zero(), T::zero(), T::zero());
+        Self {
+            pids: [[PidState::new(); 3]; 3],
+            integral_dividers: [IntegralDivider::new(); 3],
+            set_point: zero,
+            stored_set_point: zero,
+            terms: [PidTerms::new(); 3],
+            saturation: [SaturationState::None; 3],
+            prev_output: zero,
+            quantization_error: zero,
+            disturbance: zero,
+            filtered_attitude: None,
+            notch: [NotchFilter::default(); 3],
+            history: [zero; 5],
+            heading: T::zero(),
+            held_heading: None,
+        }
+    }
+}
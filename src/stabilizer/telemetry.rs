@@ -0,0 +1,420 @@
+// src/stabilizer/telemetry.rs
+
+//! # Telemetry PID Flight Stabilization Controller
+//!
+//! This module provides a per-axis record of each control step, and a wrapper that
+//! hands the record to a callback after every tick of any flight stabilizer.
+//! Logging and live tuning can then hook the controller in one place, instead of
+//! reading the terms, filters, and outputs throughout the flight loop.
+
+use core::marker::PhantomData;
+
+use crate::pid::PidTerms;
+use crate::{FlightStabilizer, Number, SaturationState, TimingStats};
+use piddiy::PidController;
+
+/// Record of the output loop of one axis for the most recent control step.
+/// Cascade stabilizers record their innermost loop, which produces the output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Telemetry<T> {
+    /// Proportional error of the loop, after the setpoint weight and yaw wrap.
+    pub error: T,
+    /// Gain-multiplied terms of the loop. Their sum times the output scale is the
+    /// output before clamping, rate limiting, and the deadband.
+    pub terms: PidTerms<T>,
+    /// Gyro rate before the notch filter.
+    pub raw_rate: T,
+    /// Gyro rate after the notch filter, which feeds the derivative.
+    pub filtered_rate: T,
+    /// Derivative of the loop after its low-pass filter and limit.
+    pub derivative: T,
+    /// Final output of the axis, as returned by the control step.
+    pub output: T,
+}
+
+impl<T: Number> Default for Telemetry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Number> Telemetry<T> {
+    /// Creates a new record with every value set to zero.
+    pub fn new() -> Self {
+        Self {
+            error: T::zero(),
+            terms: PidTerms::new(),
+            raw_rate: T::zero(),
+            filtered_rate: T::zero(),
+            derivative: T::zero(),
+            output: T::zero(),
+        }
+    }
+
+    /// Reads the error and derivative of the most recent computation from a PID controller,
+    /// along with the recorded terms, gyro rates, and final output of the axis.
+    pub fn from_pid<U>(
+        pid: &PidController<T, U>,
+        terms: PidTerms<T>,
+        raw_rate: T,
+        filtered_rate: T,
+        output: T,
+    ) -> Self {
+        Self {
+            error: pid.error,
+            terms,
+            raw_rate,
+            filtered_rate,
+            derivative: pid.derivative,
+            output,
+        }
+    }
+}
+
+/// Function pointer hook, usable without allocation.
+pub type TelemetryHook<T> = fn(&[Telemetry<T>; 3]);
+
+/// Hook that emits the (roll, pitch, yaw) telemetry as a `defmt` debug frame,
+/// so the control internals can be viewed over RTT.
+/// Pass it to `TelemetryStabilizer::new`, or use `TelemetryStabilizer::with_defmt`.
+#[cfg(feature = "defmt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "defmt")))]
+pub fn defmt_telemetry<T: defmt::Format>(telemetry: &[Telemetry<T>; 3]) {
+    let [roll, pitch, yaw] = telemetry;
+    defmt::debug!("roll: {}, pitch: {}, yaw: {}", roll, pitch, yaw);
+}
+
+/// Boxed closure hook, which can capture state like a log buffer.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub type BoxedTelemetryHook<T> = alloc::boxed::Box<dyn FnMut(&[Telemetry<T>; 3])>;
+
+/// Struct representing the Telemetry PID Flight Stabilization Controller.
+/// After every `control` and `control_throttle` step, the hook is called with the
+/// (roll, pitch, yaw) telemetry of the wrapped stabilizer.
+///
+/// The hook can be any `FnMut`, like a `TelemetryHook` function pointer in `no_std`,
+/// a closure borrowing a buffer, or a `BoxedTelemetryHook` with `alloc`.
+/// While the hook is unset, the wrapper only checks for it, and the telemetry
+/// is not copied. The outputs of the wrapped stabilizer are not affected.
+pub struct TelemetryStabilizer<T: Number, S: FlightStabilizer<T>, F: FnMut(&[Telemetry<T>; 3])> {
+    stabilizer: S,
+    hook: Option<F>,
+    _number: PhantomData<T>,
+}
+
+impl<T: Number, S: FlightStabilizer<T>, F: FnMut(&[Telemetry<T>; 3])> TelemetryStabilizer<T, S, F> {
+    /// Creates a new controller that calls `hook` after each control step of `stabilizer`.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{
+    ///     AngleStabilizer, FlightStabilizer, FlightStabilizerConfig, TelemetryStabilizer,
+    /// };
+    ///
+    /// let config = FlightStabilizerConfig::<f32>::new();
+    /// let mut roll_outputs = [0.0; 4];
+    /// let mut tick = 0;
+    /// let mut stabilizer =
+    ///     TelemetryStabilizer::new(AngleStabilizer::with_config(config), |telemetry| {
+    ///         roll_outputs[tick] = telemetry[0].output;
+    ///         tick += 1;
+    ///     });
+    ///
+    /// let zero = (0.0, 0.0, 0.0);
+    /// for _ in 0..4 {
+    ///     let output = stabilizer.control(zero, (1.0, 0.0, 0.0), zero, 0.01, false);
+    /// }
+    /// drop(stabilizer);
+    /// assert_eq!([-1.0; 4], roll_outputs);
+    /// ```
+    pub fn new(stabilizer: S, hook: F) -> Self {
+        TelemetryStabilizer {
+            stabilizer,
+            hook: Some(hook),
+            _number: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the wrapped stabilizer.
+    pub fn inner(&self) -> &S {
+        &self.stabilizer
+    }
+
+    /// Returns a mutable reference to the wrapped stabilizer.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.stabilizer
+    }
+
+    /// Replaces the hook. `None` unsets it, so control steps skip the telemetry.
+    pub fn set_hook(&mut self, hook: Option<F>) {
+        self.hook = hook;
+    }
+
+    /// Returns `true` if a hook is set.
+    pub fn has_hook(&self) -> bool {
+        self.hook.is_some()
+    }
+
+    /// Calls the hook, if set, with the telemetry of the wrapped stabilizer.
+    fn report(&mut self) {
+        if let Some(hook) = self.hook.as_mut() {
+            hook(&self.stabilizer.last_telemetry());
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "defmt")))]
+impl<T: Number + defmt::Format, S: FlightStabilizer<T>>
+    TelemetryStabilizer<T, S, TelemetryHook<T>>
+{
+    /// Creates a new controller that emits the telemetry of each control step of
+    /// `stabilizer` as a `defmt` debug frame, using `defmt_telemetry` as the hook.
+    pub fn with_defmt(stabilizer: S) -> Self {
+        Self::new(stabilizer, defmt_telemetry)
+    }
+}
+
+impl<T: Number, S: FlightStabilizer<T>, F: FnMut(&[Telemetry<T>; 3])> FlightStabilizer<T>
+    for TelemetryStabilizer<T, S, F>
+{
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        let output = self
+            .stabilizer
+            .control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        self.report();
+        output
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        let output =
+            self.stabilizer
+                .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle);
+        self.report();
+        output
+    }
+
+    fn reset(&mut self) {
+        self.stabilizer.reset();
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_setpoints(roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_disturbance(roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stabilizer.setpoints()
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.stabilizer.saturation()
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_integral(roll, pitch, yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.enable_axes(roll, pitch, yaw);
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.stabilizer.enabled_axes()
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.freeze_integral(roll, pitch, yaw);
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.stabilizer.frozen_integrals()
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        self.stabilizer.last_telemetry()
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.stabilizer.timing_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{Angle2Stabilizer, AngleStabilizer, CascadeBlendingConfig, FlightStabilizerConfig};
+
+    /// Default test configuration.
+    fn default_config() -> FlightStabilizerConfig<f32> {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+
+        // Set the PID gains for roll, pitch, and yaw.
+        config.kp_roll = 0.2;
+        config.ki_roll = 0.3;
+        config.kd_roll = -0.05;
+
+        config.kp_pitch = config.kp_roll;
+        config.ki_pitch = config.ki_roll;
+        config.kd_pitch = config.kd_roll;
+
+        config.kp_yaw = 0.3;
+        config.ki_yaw = 0.05;
+        config.kd_yaw = 0.00015;
+
+        // Set the upper limit for the integral term to prevent windup.
+        config.i_limit = 25.0;
+
+        // Set the scale to adjust the PID outputs to the actuator range.
+        config.scale = 0.01;
+
+        config
+    }
+
+    /// Run several steps and compare each reported record against the returned output.
+    fn assert_telemetry_matches<S: FlightStabilizer<f32>>(stabilizer: S) {
+        let mut records = [[Telemetry::new(); 3]; 5];
+        let mut outputs = [(0.0, 0.0, 0.0); 5];
+        let mut tick = 0;
+        let mut stabilizer = TelemetryStabilizer::new(stabilizer, |telemetry| {
+            records[tick] = *telemetry;
+            tick += 1;
+        });
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, 0.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let dt = 0.01; // time step
+        let low_throttle = false;
+
+        for output in outputs.iter_mut() {
+            *output = stabilizer.control(set_point, imu_attitude, gyro_rate, dt, low_throttle);
+        }
+        drop(stabilizer);
+
+        assert_eq!(5, tick, "The hook should run once per step.");
+        for (record, output) in records.iter().zip(outputs) {
+            let [roll, pitch, yaw] = *record;
+            assert_eq!(output, (roll.output, pitch.output, yaw.output));
+            let sums = (roll.terms.sum(), pitch.terms.sum(), yaw.terms.sum());
+            tuple_close(output, (0.01 * sums.0, 0.01 * sums.1, 0.01 * sums.2));
+            assert_eq!(
+                (1.0, -1.0, -1.0),
+                (roll.raw_rate, pitch.raw_rate, yaw.raw_rate)
+            );
+        }
+    }
+
+    /// Test that the hook receives the returned outputs and consistent terms each step.
+    #[test]
+    fn test_stabilizer_telemetry_matches_output() {
+        assert_telemetry_matches(AngleStabilizer::with_config(default_config()));
+        assert_telemetry_matches(Angle2Stabilizer::with_config(
+            default_config(),
+            default_config(),
+            CascadeBlendingConfig::new(),
+        ));
+    }
+
+    /// Test that the recorded error and derivative are those of the output loop.
+    #[test]
+    fn test_stabilizer_telemetry_error_and_derivative() {
+        let config = default_config();
+        let mut stabilizer = AngleStabilizer::with_config(config);
+        let zero = (0.0, 0.0, 0.0);
+        let output = stabilizer.control(
+            (10.0, 0.0, 0.0),
+            (4.0, 0.0, 0.0),
+            (2.0, 0.0, 0.0),
+            0.01,
+            false,
+        );
+        let [roll, _, _] = stabilizer.last_telemetry();
+        assert!(value_close(6.0, roll.error));
+        assert!(value_close(2.0, roll.derivative));
+        assert_eq!(2.0, roll.filtered_rate);
+        assert_eq!(output.0, roll.output);
+
+        stabilizer.reset();
+        assert_eq!([Telemetry::new(); 3], stabilizer.last_telemetry());
+        let _ = stabilizer.control(zero, zero, zero, 0.01, false);
+        assert_eq!(0.0, stabilizer.last_telemetry()[0].error);
+    }
+
+    /// Test that an unset hook is skipped and a function pointer hook can be set later.
+    #[test]
+    fn test_stabilizer_telemetry_set_hook() {
+        fn hook(telemetry: &[Telemetry<f32>; 3]) {
+            assert!(telemetry[0].output < 0.0);
+        }
+        let inner = AngleStabilizer::with_config(default_config());
+        let mut stabilizer = TelemetryStabilizer::new(inner, hook as TelemetryHook<f32>);
+        stabilizer.set_hook(None);
+        assert!(!stabilizer.has_hook());
+
+        let zero = (0.0, 0.0, 0.0);
+        let reference = AngleStabilizer::with_config(default_config()).control(
+            zero,
+            (1.0, 0.0, 0.0),
+            zero,
+            0.01,
+            false,
+        );
+        assert_eq!(
+            reference,
+            stabilizer.control(zero, (1.0, 0.0, 0.0), zero, 0.01, false)
+        );
+
+        stabilizer.set_hook(Some(hook));
+        assert!(stabilizer.has_hook());
+        stabilizer.reset();
+        assert_eq!(
+            reference,
+            stabilizer.control(zero, (1.0, 0.0, 0.0), zero, 0.01, false)
+        );
+    }
+
+    /// Test that a boxed closure can own its state.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_stabilizer_telemetry_boxed_hook() {
+        use alloc::rc::Rc;
+        use alloc::vec::Vec;
+        use core::cell::RefCell;
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let writer = Rc::clone(&log);
+        let hook: BoxedTelemetryHook<f32> =
+            alloc::boxed::Box::new(move |telemetry| writer.borrow_mut().push(telemetry[0]));
+        let inner = AngleStabilizer::with_config(default_config());
+        let mut stabilizer = TelemetryStabilizer::new(inner, hook);
+
+        let zero = (0.0, 0.0, 0.0);
+        let output = stabilizer.control(zero, (1.0, 0.0, 0.0), zero, 0.01, false);
+        let _ = stabilizer.control(zero, (1.0, 0.0, 0.0), zero, 0.01, false);
+        assert_eq!(2, log.borrow().len());
+        assert_eq!(output.0, log.borrow()[0].output);
+    }
+}
@@ -0,0 +1,296 @@
+// src/stabilizer/timestamped.rs
+
+//! # Timestamped PID Flight Stabilization Controller
+//!
+//! This is a wrapper that computes the time step of any flight stabilizer from
+//! absolute timestamps. Some flight loops read a monotonic microsecond counter
+//! rather than a time step, so the wrapper stores the previous timestamp and
+//! handles the first call and counter rollover in one place.
+
+use core::marker::PhantomData;
+
+use crate::{dt_from_us, FlightStabilizer, Number, SaturationState, Telemetry, TimingStats};
+
+/// Struct representing the Timestamped PID Flight Stabilization Controller.
+/// The time step of `control_at` is the difference between successive
+/// microsecond timestamps.
+///
+/// The first call after creation or `reset` has no previous timestamp, so it runs
+/// with a zero time step, as does a timestamp equal to the previous one.
+/// The difference wraps around `u32` rollover, roughly every 71 minutes, so the
+/// time step stays correct as long as calls are less than a full counter period apart.
+/// The trait methods that take a time step pass it through and keep the stored timestamp.
+pub struct TimestampedStabilizer<T: Number, S: FlightStabilizer<T>> {
+    stabilizer: S,
+    last_timestamp_us: Option<u32>,
+    _number: PhantomData<T>,
+}
+
+impl<T: Number, S: FlightStabilizer<T>> TimestampedStabilizer<T, S> {
+    /// Creates a new controller that computes the time step of `stabilizer` from timestamps.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::{
+    ///     AngleStabilizer, FlightStabilizerConfig, TimestampedStabilizer,
+    /// };
+    ///
+    /// let config = FlightStabilizerConfig::<f32>::new();
+    /// let mut stabilizer = TimestampedStabilizer::new(AngleStabilizer::with_config(config));
+    ///
+    /// // Pass the microsecond counter of the flight loop on every tick.
+    /// let zero = (0.0, 0.0, 0.0);
+    /// let output = stabilizer.control_at(zero, (1.0, 0.0, 0.0), zero, 1_000_000, false);
+    /// let output = stabilizer.control_at(zero, (1.0, 0.0, 0.0), zero, 1_010_000, false);
+    /// assert_eq!(Some(1_010_000), stabilizer.last_timestamp_us());
+    /// ```
+    pub fn new(stabilizer: S) -> Self {
+        TimestampedStabilizer {
+            stabilizer,
+            last_timestamp_us: None,
+            _number: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the wrapped stabilizer.
+    pub fn inner(&self) -> &S {
+        &self.stabilizer
+    }
+
+    /// Returns a mutable reference to the wrapped stabilizer.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.stabilizer
+    }
+
+    /// Returns the timestamp of the last `control_at` call in microseconds,
+    /// or `None` before the first call and after `reset`.
+    pub fn last_timestamp_us(&self) -> Option<u32> {
+        self.last_timestamp_us
+    }
+
+    /// Computes the outputs like `control`, with the time step taken from the
+    /// microsecond `timestamp_us` of the measurements.
+    pub fn control_at(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        timestamp_us: u32,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        let dt = self.advance(timestamp_us);
+        self.stabilizer
+            .control(set_point, imu_attitude, gyro_rate, dt, low_throttle)
+    }
+
+    /// Computes the outputs like `control_throttle`, with the time step taken from the
+    /// microsecond `timestamp_us` of the measurements.
+    pub fn control_throttle_at(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        timestamp_us: u32,
+        throttle: T,
+    ) -> (T, T, T) {
+        let dt = self.advance(timestamp_us);
+        self.stabilizer
+            .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle)
+    }
+
+    /// Stores the timestamp and returns the time step since the previous one,
+    /// zero on the first call.
+    fn advance(&mut self, timestamp_us: u32) -> T {
+        let dt_us = match self.last_timestamp_us {
+            Some(last_timestamp_us) => timestamp_us.wrapping_sub(last_timestamp_us),
+            None => 0,
+        };
+        self.last_timestamp_us = Some(timestamp_us);
+        dt_from_us(dt_us)
+    }
+}
+
+impl<T: Number, S: FlightStabilizer<T>> FlightStabilizer<T> for TimestampedStabilizer<T, S> {
+    fn control(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        low_throttle: bool,
+    ) -> (T, T, T) {
+        self.stabilizer
+            .control(set_point, imu_attitude, gyro_rate, dt, low_throttle)
+    }
+
+    fn control_throttle(
+        &mut self,
+        set_point: (T, T, T),
+        imu_attitude: (T, T, T),
+        gyro_rate: (T, T, T),
+        dt: T,
+        throttle: T,
+    ) -> (T, T, T) {
+        self.stabilizer
+            .control_throttle(set_point, imu_attitude, gyro_rate, dt, throttle)
+    }
+
+    fn reset(&mut self) {
+        self.stabilizer.reset();
+        self.last_timestamp_us = None;
+    }
+
+    fn set_setpoints(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_setpoints(roll, pitch, yaw);
+    }
+
+    fn set_disturbance(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_disturbance(roll, pitch, yaw);
+    }
+
+    fn setpoints(&self) -> (T, T, T) {
+        self.stabilizer.setpoints()
+    }
+
+    fn saturation(&self) -> [SaturationState; 3] {
+        self.stabilizer.saturation()
+    }
+
+    fn set_integral(&mut self, roll: T, pitch: T, yaw: T) {
+        self.stabilizer.set_integral(roll, pitch, yaw);
+    }
+
+    fn enable_axes(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.enable_axes(roll, pitch, yaw);
+    }
+
+    fn enabled_axes(&self) -> [bool; 3] {
+        self.stabilizer.enabled_axes()
+    }
+
+    fn freeze_integral(&mut self, roll: bool, pitch: bool, yaw: bool) {
+        self.stabilizer.freeze_integral(roll, pitch, yaw);
+    }
+
+    fn frozen_integrals(&self) -> [bool; 3] {
+        self.stabilizer.frozen_integrals()
+    }
+
+    fn last_telemetry(&self) -> [Telemetry<T>; 3] {
+        self.stabilizer.last_telemetry()
+    }
+
+    fn timing_stats(&self) -> TimingStats<T> {
+        self.stabilizer.timing_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AngleStabilizer, FlightStabilizerConfig};
+
+    /// Default test configuration.
+    fn default_config() -> FlightStabilizerConfig<f32> {
+        let mut config = FlightStabilizerConfig::<f32>::new();
+
+        // Set the PID gains for roll, pitch, and yaw.
+        config.kp_roll = 0.2;
+        config.ki_roll = 0.3;
+        config.kd_roll = -0.05;
+
+        config.kp_pitch = config.kp_roll;
+        config.ki_pitch = config.ki_roll;
+        config.kd_pitch = config.kd_roll;
+
+        config.kp_yaw = 0.3;
+        config.ki_yaw = 0.05;
+        config.kd_yaw = 0.00015;
+
+        // Set the upper limit for the integral term to prevent windup.
+        config.i_limit = 25.0;
+
+        // Set the scale to adjust the PID outputs to the actuator range.
+        config.scale = 0.01;
+
+        config
+    }
+
+    /// Run the timestamped stabilizer and a plain stabilizer side by side,
+    /// checking the timestamps against the expected time steps.
+    fn assert_steps(timestamps_us: &[u32], expected_dts: &[f32]) {
+        let config = default_config();
+        let mut stabilizer = TimestampedStabilizer::new(AngleStabilizer::with_config(config));
+        let mut plain_stabilizer = AngleStabilizer::with_config(config);
+
+        // Simulated sensor inputs and desired setpoints
+        let set_point = (10.0, 0.0, 10.0); // desired roll, pitch, yaw
+        let imu_attitude = (5.0, 5.0, 0.0); // current roll, pitch, yaw
+        let gyro_rate = (1.0, -1.0, -1.0); // current roll rate, pitch rate, yaw rate
+        let low_throttle = false;
+
+        for (timestamp_us, dt) in timestamps_us.iter().zip(expected_dts) {
+            let output = stabilizer.control_at(
+                set_point,
+                imu_attitude,
+                gyro_rate,
+                *timestamp_us,
+                low_throttle,
+            );
+            let expected_output =
+                plain_stabilizer.control(set_point, imu_attitude, gyro_rate, *dt, low_throttle);
+            assert_eq!(expected_output, output);
+            assert_eq!(Some(*timestamp_us), stabilizer.last_timestamp_us());
+        }
+    }
+
+    /// Test that the first call runs with a zero time step.
+    #[test]
+    fn test_stabilizer_timestamped_first_call() {
+        assert_steps(&[5_000_000], &[0.0]);
+    }
+
+    /// Test that successive timestamps give their difference as the time step.
+    #[test]
+    fn test_stabilizer_timestamped_normal_delta() {
+        assert_steps(&[5_000_000, 5_010_000, 5_030_000], &[0.0, 0.01, 0.02]);
+    }
+
+    /// Test that a repeated timestamp gives a zero time step.
+    #[test]
+    fn test_stabilizer_timestamped_equal_timestamp() {
+        assert_steps(&[5_000_000, 5_010_000, 5_010_000], &[0.0, 0.01, 0.0]);
+    }
+
+    /// Test that the time step wraps around counter rollover.
+    #[test]
+    fn test_stabilizer_timestamped_rollover() {
+        assert_steps(&[u32::MAX - 4_999, 5_000], &[0.0, 0.01]);
+    }
+
+    /// Test that reset forgets the previous timestamp.
+    #[test]
+    fn test_stabilizer_timestamped_reset() {
+        let config = default_config();
+        let mut stabilizer = TimestampedStabilizer::new(AngleStabilizer::with_config(config));
+        let zero = (0.0, 0.0, 0.0);
+        let _ = stabilizer.control_at(zero, zero, zero, 1_000, false);
+        stabilizer.reset();
+        assert_eq!(None, stabilizer.last_timestamp_us());
+    }
+
+    /// Test that the time steps computed from timestamps reach the timing statistics.
+    #[test]
+    fn test_stabilizer_timestamped_timing_stats() {
+        let config = default_config();
+        let mut stabilizer = TimestampedStabilizer::new(AngleStabilizer::with_config(config));
+        let zero = (0.0, 0.0, 0.0);
+        for timestamp_us in [0, 10_000, 20_000, 35_000, 40_000] {
+            stabilizer.control_at(zero, zero, zero, timestamp_us, false);
+        }
+        let stats = stabilizer.timing_stats();
+        assert_eq!(5, stats.count);
+        assert_eq!((0.0, 0.015), (stats.min, stats.max));
+        assert_eq!(stabilizer.inner().timing_stats(), stats);
+    }
+}
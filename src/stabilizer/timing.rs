@@ -0,0 +1,156 @@
+// src/stabilizer/timing.rs
+
+//! # Flight Stabilizer Timing Module
+//!
+//! This module provides running statistics of the time steps passed to a flight
+//! stabilizer. A flight loop with an irregular time step integrates and differentiates
+//! unevenly, which often shows up as a misbehaving integral, so the statistics help
+//! to diagnose scheduler problems. The accumulators are a fixed handful of values,
+//! so they are updated on every tick without allocation.
+
+use crate::Number;
+
+/// Running statistics of the time steps received by a flight stabilizer,
+/// returned by `timing_stats`.
+///
+/// Every finite time step is recorded, including zero and negative ones, which
+/// stand out in `min`. The mean and jitter are running means, so a value is
+/// weighted by one over the count. For a fixed-point type the count is clamped to
+/// the largest value of the type, after which older values keep a larger weight.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimingStats<T> {
+    /// Number of time steps recorded, saturating at `u32::MAX`.
+    pub count: u32,
+    /// Smallest time step recorded.
+    pub min: T,
+    /// Largest time step recorded.
+    pub max: T,
+    /// Mean of the time steps recorded.
+    pub mean: T,
+    /// Mean absolute change between successive time steps.
+    /// It is zero for a perfectly regular loop.
+    pub jitter: T,
+    /// Most recent time step recorded.
+    pub last: T,
+}
+
+impl<T: Number> Default for TimingStats<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Number> TimingStats<T> {
+    /// Creates new statistics with no time steps recorded and every value set to zero.
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            min: T::zero(),
+            max: T::zero(),
+            mean: T::zero(),
+            jitter: T::zero(),
+            last: T::zero(),
+        }
+    }
+
+    /// Records a time step. A non-finite time step is ignored.
+    ///
+    /// Example Usage
+    /// ```
+    /// use free_flight_stabilization::TimingStats;
+    ///
+    /// let mut stats = TimingStats::<f32>::new();
+    /// for dt in [0.25, 0.5, 0.25, 1.0] {
+    ///     stats.record(dt);
+    /// }
+    /// assert_eq!(4, stats.count);
+    /// assert_eq!((0.25, 1.0), (stats.min, stats.max));
+    /// assert_eq!(0.5, stats.mean);
+    /// // The changes are 0.25, 0.25, and 0.75
+    /// assert_eq!(0.4166667, stats.jitter);
+    /// ```
+    pub fn record(&mut self, dt: T) {
+        if !dt.is_finite() {
+            return;
+        }
+        if self.count == 0 {
+            *self = Self {
+                count: 1,
+                min: dt,
+                max: dt,
+                mean: dt,
+                jitter: T::zero(),
+                last: dt,
+            };
+            return;
+        }
+        let changes = Self::weight(self.count);
+        self.count = self.count.saturating_add(1);
+        if dt < self.min {
+            self.min = dt;
+        }
+        if self.max < dt {
+            self.max = dt;
+        }
+        self.mean += (dt - self.mean) / Self::weight(self.count);
+        self.jitter += ((dt - self.last).abs() - self.jitter) / changes;
+        self.last = dt;
+    }
+
+    /// Converts a count to `T`, clamped to the largest value of the type.
+    fn weight(count: u32) -> T {
+        T::from_u32(count).unwrap_or_else(T::max_value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    /// Test the statistics of a known sequence of time steps.
+    #[test]
+    fn test_timing_stats_known_sequence() {
+        let mut stats = TimingStats::<f32>::new();
+        assert_eq!(0, stats.count);
+        stats.record(0.01);
+        assert_eq!(1, stats.count);
+        assert_eq!((0.01, 0.01, 0.01), (stats.min, stats.max, stats.mean));
+        assert_eq!(0.0, stats.jitter);
+
+        // A late tick and a catch-up tick around a regular loop
+        for dt in [0.01, 0.02, 0.005, 0.01, 0.01] {
+            stats.record(dt);
+        }
+        assert_eq!(6, stats.count);
+        assert_eq!((0.005, 0.02), (stats.min, stats.max));
+        assert!(value_close(0.065 / 6.0, stats.mean));
+        // The changes are 0, 0.01, 0.015, 0.005, and 0
+        assert!(value_close(0.03 / 5.0, stats.jitter));
+        assert_eq!(0.01, stats.last);
+
+        // Non-finite time steps are ignored, while zero is recorded
+        stats.record(f32::NAN);
+        stats.record(f32::INFINITY);
+        assert_eq!(6, stats.count);
+        stats.record(0.0);
+        assert_eq!((7, 0.0), (stats.count, stats.min));
+    }
+
+    /// Test that a regular loop has no jitter, in fixed point.
+    #[test]
+    fn test_timing_stats_fixed() {
+        use fixed::types::I16F16;
+
+        let dt = I16F16::from_num(0.25);
+        let mut stats = TimingStats::new();
+        for _ in 0..100 {
+            stats.record(dt);
+        }
+        assert_eq!(100, stats.count);
+        assert_eq!((dt, dt, dt), (stats.min, stats.max, stats.mean));
+        assert_eq!(I16F16::ZERO, stats.jitter);
+    }
+}
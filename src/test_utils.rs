@@ -2,6 +2,10 @@
 
 //! This module contains utilities for testing.
 
+use crate::pid::PidTerms;
+use crate::Number;
+use fixed::types::I16F16;
+
 /// A constant defining the tolerance within which floating-point values
 /// are considered close enough to be equal.
 pub const TEST_TOLERANCE: f32 = 1e-5;
@@ -65,3 +69,460 @@ pub fn vector_not_close(target: (f32, f32, f32), value: (f32, f32, f32)) -> bool
         && value_not_close(target.1, value.1)
         && value_not_close(target.2, value.2)
 }
+
+/// A number type that the generic test harness can exercise.
+pub trait TestNumber: Number + core::fmt::Debug {
+    /// The tolerance within which values of this type are considered equal.
+    const TOLERANCE: f32;
+
+    /// Converts an `f32` test value into this type.
+    fn from_f32(value: f32) -> Self;
+
+    /// Converts a value of this type into an `f32` for comparison.
+    fn to_f32(self) -> f32;
+}
+
+impl TestNumber for f32 {
+    const TOLERANCE: f32 = TEST_TOLERANCE;
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+}
+
+impl TestNumber for f64 {
+    const TOLERANCE: f32 = TEST_TOLERANCE;
+
+    fn from_f32(value: f32) -> Self {
+        value as f64
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+}
+
+impl TestNumber for I16F16 {
+    /// A few steps of the 16 bit fractional resolution.
+    const TOLERANCE: f32 = 1e-3;
+
+    fn from_f32(value: f32) -> Self {
+        I16F16::from_num(value)
+    }
+
+    fn to_f32(self) -> f32 {
+        self.to_num()
+    }
+}
+
+/// Checks if a generic number is close enough to an `f32` target to be
+/// considered equal, using the tolerance of the number type.
+///
+/// # Arguments
+/// * `target` - The target value.
+/// * `value` - The value to compare against the target.
+///
+/// # Returns
+/// `true` if the absolute difference between `target` and `value` is less than
+/// the tolerance of `T`, otherwise `false`.
+pub fn number_close<T: TestNumber>(target: f32, value: T) -> bool {
+    (target - value.to_f32()).abs() < T::TOLERANCE
+}
+
+/// Names of the (roll, pitch, yaw) axes for assertion messages.
+const AXES: [&str; 3] = ["roll", "pitch", "yaw"];
+
+/// Checks if a generic number is close enough to a target of the same type
+/// to be considered equal, using the tolerance of the number type.
+fn same_close<T: TestNumber>(target: T, value: T) -> bool {
+    (target.to_f32() - value.to_f32()).abs() < T::TOLERANCE
+}
+
+/// Asserts that each axis of a (roll, pitch, yaw) tuple, like a control output,
+/// is close enough to the target to be considered equal.
+///
+/// # Arguments
+/// * `target` - The target tuple.
+/// * `value` - The tuple to compare against the target.
+///
+/// # Panics
+/// Panics naming the first axis that is not close as per the tolerance of `T`.
+#[track_caller]
+pub fn tuple_close<T: TestNumber>(target: (T, T, T), value: (T, T, T)) {
+    let targets = [target.0, target.1, target.2];
+    let values = [value.0, value.1, value.2];
+    for ((axis, target), value) in AXES.iter().zip(targets).zip(values) {
+        assert!(
+            same_close(target, value),
+            "{} differs: expected {:?}, got {:?}",
+            axis,
+            target,
+            value
+        );
+    }
+}
+
+/// Asserts that each term of the (roll, pitch, yaw) PID terms is close enough
+/// to the target to be considered equal.
+///
+/// # Arguments
+/// * `target` - The target terms.
+/// * `value` - The terms to compare against the target.
+///
+/// # Panics
+/// Panics naming the axis and term of the first term that is not close as per
+/// the tolerance of `T`.
+#[track_caller]
+pub fn terms_close<T: TestNumber>(target: [PidTerms<T>; 3], value: [PidTerms<T>; 3]) {
+    for ((axis, target), value) in AXES.iter().zip(target).zip(value) {
+        let terms = [
+            ("proportional", target.proportional, value.proportional),
+            ("integral", target.integral, value.integral),
+            ("derivative", target.derivative, value.derivative),
+            ("feed_forward", target.feed_forward, value.feed_forward),
+        ];
+        for (term, target, value) in terms {
+            assert!(
+                same_close(target, value),
+                "{} {} differs: expected {:?}, got {:?}",
+                axis,
+                term,
+                target,
+                value
+            );
+        }
+    }
+}
+
+/// A simulated vehicle for closed-loop tests. Each axis has a first-order rate
+/// response to the control output, and the attitude integrates the rate.
+/// The rate approaches `gain * output` with the time constant `tau`, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plant {
+    /// Attitude as (roll, pitch, yaw).
+    pub attitude: (f32, f32, f32),
+    /// Rate as (roll rate, pitch rate, yaw rate), as measured by a gyroscope.
+    pub rate: (f32, f32, f32),
+    /// Steady state rate per unit of control output.
+    pub gain: f32,
+    /// Time constant of the rate response, in seconds.
+    pub tau: f32,
+}
+
+impl Plant {
+    /// Creates a plant at rest at a zero attitude.
+    pub fn new(gain: f32, tau: f32) -> Self {
+        Self {
+            attitude: (0.0, 0.0, 0.0),
+            rate: (0.0, 0.0, 0.0),
+            gain,
+            tau,
+        }
+    }
+
+    /// Advances the plant by `dt` seconds with the control output applied,
+    /// using a backward Euler rate step, stable for any `dt`, and a trapezoidal
+    /// attitude step.
+    pub fn step(&mut self, output: (f32, f32, f32), dt: f32) {
+        let alpha = dt / (self.tau + dt);
+        let axis = |attitude: &mut f32, rate: &mut f32, output: f32| {
+            let previous = *rate;
+            *rate += alpha * (self.gain * output - *rate);
+            *attitude += 0.5 * (previous + *rate) * dt;
+        };
+        axis(&mut self.attitude.0, &mut self.rate.0, output.0);
+        axis(&mut self.attitude.1, &mut self.rate.1, output.1);
+        axis(&mut self.attitude.2, &mut self.rate.2, output.2);
+    }
+}
+
+/// The step response of one axis, recorded tick by tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepResponse {
+    /// Target value of the response.
+    pub target: f32,
+    /// Value at the start of the response.
+    pub start: f32,
+    /// Largest excursion past the target, as a fraction of the step size.
+    pub overshoot: f32,
+    /// The first tick after which the value stays within tolerance of the target,
+    /// or `None` if it has not settled.
+    pub settling_tick: Option<usize>,
+    tolerance: f32,
+    ticks: usize,
+}
+
+impl StepResponse {
+    /// Starts recording a step from `start` to `target`, settled within `tolerance`.
+    pub fn new(start: f32, target: f32, tolerance: f32) -> Self {
+        Self {
+            target,
+            start,
+            overshoot: 0.0,
+            settling_tick: None,
+            tolerance,
+            ticks: 0,
+        }
+    }
+
+    /// Records the value of the next tick.
+    pub fn record(&mut self, value: f32) {
+        let step = self.target - self.start;
+        if step != 0.0 {
+            self.overshoot = self.overshoot.max((value - self.target) / step);
+        }
+        if (value - self.target).abs() <= self.tolerance {
+            self.settling_tick.get_or_insert(self.ticks);
+        } else {
+            self.settling_tick = None;
+        }
+        self.ticks += 1;
+    }
+}
+
+/// Returns the step of a trajectory, from its first value to the setpoint,
+/// or `None` for an empty trajectory.
+#[cfg(feature = "std")]
+fn trajectory_step<T: TestNumber>(trajectory: &[T], set_point: T) -> Option<(f32, f32)> {
+    let start = trajectory.first()?.to_f32();
+    Some((start, set_point.to_f32() - start))
+}
+
+/// Computes the overshoot of a step response as a percentage of the step size.
+/// The step runs from the first value of the trajectory to the setpoint.
+///
+/// # Arguments
+/// * `trajectory` - The value of each tick, starting before the response.
+/// * `set_point` - The target of the step.
+///
+/// # Returns
+/// The largest excursion past the setpoint in percent, or zero if the
+/// trajectory never passes the setpoint or the step size is zero.
+#[cfg(feature = "std")]
+pub fn overshoot_percent<T: TestNumber>(trajectory: &[T], set_point: T) -> f32 {
+    let (_, step) = match trajectory_step(trajectory, set_point) {
+        Some((start, step)) if step != 0.0 => (start, step),
+        _ => return 0.0,
+    };
+    let target = set_point.to_f32();
+    trajectory
+        .iter()
+        .map(|value| 100.0 * (value.to_f32() - target) / step)
+        .fold(0.0, f32::max)
+}
+
+/// Computes the rise time of a step response, the number of ticks from
+/// first reaching 10 percent to first reaching 90 percent of the step.
+///
+/// # Arguments
+/// * `trajectory` - The value of each tick, starting before the response.
+/// * `set_point` - The target of the step.
+///
+/// # Returns
+/// The rise time in ticks, or `None` if the trajectory never reaches 90 percent
+/// of the step or the step size is zero.
+#[cfg(feature = "std")]
+pub fn rise_time<T: TestNumber>(trajectory: &[T], set_point: T) -> Option<usize> {
+    let (start, step) = trajectory_step(trajectory, set_point)?;
+    if step == 0.0 {
+        return None;
+    }
+    let reached = |fraction: f32| {
+        trajectory
+            .iter()
+            .position(|value| fraction <= (value.to_f32() - start) / step)
+    };
+    Some(reached(0.9)? - reached(0.1)?)
+}
+
+/// Computes the settling time of a step response, the first tick after which
+/// the trajectory stays within a tolerance band around the setpoint.
+///
+/// # Arguments
+/// * `trajectory` - The value of each tick, starting before the response.
+/// * `set_point` - The target of the step.
+/// * `tolerance` - The half width of the band around the setpoint.
+///
+/// # Returns
+/// The settling time in ticks, or `None` if the last value is outside the band.
+#[cfg(feature = "std")]
+pub fn settling_time<T: TestNumber>(
+    trajectory: &[T],
+    set_point: T,
+    tolerance: f32,
+) -> Option<usize> {
+    let target = set_point.to_f32();
+    let outside = |value: &T| tolerance < (value.to_f32() - target).abs();
+    match trajectory.iter().rposition(outside) {
+        Some(last) if last + 1 == trajectory.len() => None,
+        Some(last) => Some(last + 1),
+        None if trajectory.is_empty() => None,
+        None => Some(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that close tuples pass for every number type.
+    #[test]
+    fn test_tuple_close_passes() {
+        tuple_close((1.0f32, -2.0, 3.0), (1.0, -2.0 + 1e-6, 3.0));
+        tuple_close((1.0f64, -2.0, 3.0), (1.0, -2.0, 3.0 - 1e-6));
+        let n = <I16F16 as TestNumber>::from_f32;
+        tuple_close((n(1.0), n(-2.0), n(3.0)), (n(1.0), n(-2.0005), n(3.0)));
+    }
+
+    /// Test that a roll difference is reported.
+    #[test]
+    #[should_panic(expected = "roll differs")]
+    fn test_tuple_close_fails_roll() {
+        tuple_close((1.0f32, -2.0, 3.0), (1.1, -2.0, 3.0));
+    }
+
+    /// Test that a pitch difference is reported.
+    #[test]
+    #[should_panic(expected = "pitch differs")]
+    fn test_tuple_close_fails_pitch() {
+        tuple_close((1.0f32, -2.0, 3.0), (1.0, f32::NAN, 3.0));
+    }
+
+    /// Test that a yaw difference is reported.
+    #[test]
+    #[should_panic(expected = "yaw differs")]
+    fn test_tuple_close_fails_yaw() {
+        tuple_close((1.0f32, -2.0, 3.0), (1.0, -2.0, 3.001));
+    }
+
+    /// Terms with a distinct value in every field.
+    fn terms() -> [PidTerms<f32>; 3] {
+        let axis = |offset: f32| PidTerms {
+            proportional: offset + 1.0,
+            integral: offset + 2.0,
+            derivative: offset + 3.0,
+            feed_forward: offset + 4.0,
+        };
+        [axis(0.0), axis(10.0), axis(20.0)]
+    }
+
+    /// Test that close terms pass.
+    #[test]
+    fn test_terms_close_passes() {
+        let mut value = terms();
+        value[1].derivative += 1e-6;
+        terms_close(terms(), value);
+    }
+
+    /// Test that a roll term difference is reported with its term.
+    #[test]
+    #[should_panic(expected = "roll proportional differs")]
+    fn test_terms_close_fails_roll() {
+        let mut value = terms();
+        value[0].proportional = 0.0;
+        terms_close(terms(), value);
+    }
+
+    /// Test that a pitch term difference is reported with its term.
+    #[test]
+    #[should_panic(expected = "pitch integral differs")]
+    fn test_terms_close_fails_pitch() {
+        let mut value = terms();
+        value[1].integral = -12.0;
+        terms_close(terms(), value);
+    }
+
+    /// Test that a yaw term difference is reported with its term.
+    #[test]
+    #[should_panic(expected = "yaw feed_forward differs")]
+    fn test_terms_close_fails_yaw() {
+        let mut value = terms();
+        value[2].feed_forward = 0.0;
+        terms_close(terms(), value);
+    }
+
+    /// Test that a constant output drives the plant rate to `gain * output`.
+    #[test]
+    fn test_plant_step() {
+        let mut plant = Plant::new(100.0, 0.05);
+        for _ in 0..100 {
+            plant.step((1.0, -0.5, 0.0), 0.01);
+        }
+        assert!((plant.rate.0 - 100.0).abs() < 1e-3);
+        assert!((plant.rate.1 + 50.0).abs() < 1e-3);
+        assert_eq!(0.0, plant.rate.2);
+
+        // A step of one time constant covers half of the rate change
+        let mut plant = Plant::new(100.0, 0.05);
+        plant.step((1.0, 0.0, 0.0), 0.05);
+        assert!(value_close(50.0, plant.rate.0));
+        assert!(value_close(1.25, plant.attitude.0));
+    }
+
+    /// Test that the step response records overshoot and settling.
+    #[test]
+    fn test_step_response() {
+        let mut response = StepResponse::new(0.0, 10.0, 0.5);
+        for value in [5.0, 9.0, 12.0, 9.8, 10.2, 10.0] {
+            response.record(value);
+        }
+        assert!(value_close(0.2, response.overshoot));
+        assert_eq!(Some(3), response.settling_tick);
+
+        response.record(11.0);
+        assert_eq!(None, response.settling_tick);
+    }
+
+    /// Test the step metrics against an underdamped trajectory.
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_step_metrics_underdamped() {
+        let trajectory = vec![
+            0.0f32, 2.0, 5.0, 9.0, 12.5, 11.0, 9.5, 10.3, 9.9, 10.1, 10.0,
+        ];
+        assert!(value_close(25.0, overshoot_percent(&trajectory, 10.0)));
+        assert_eq!(Some(2), rise_time(&trajectory, 10.0));
+        assert_eq!(Some(6), settling_time(&trajectory, 10.0, 0.5));
+        assert_eq!(Some(8), settling_time(&trajectory, 10.0, 0.2));
+        assert_eq!(None, settling_time(&trajectory[..8], 10.0, 0.2));
+    }
+
+    /// Test the step metrics against an overdamped step down, in fixed point.
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_step_metrics_overdamped() {
+        let n = <I16F16 as TestNumber>::from_f32;
+        let trajectory: Vec<I16F16> = [4.0, 3.0, 2.0, 1.0, 0.5, 0.25, 0.125]
+            .into_iter()
+            .map(n)
+            .collect();
+        assert_eq!(0.0, overshoot_percent(&trajectory, n(0.0)));
+        assert_eq!(Some(4), rise_time(&trajectory, n(0.0)));
+        assert_eq!(Some(5), settling_time(&trajectory, n(0.0), 0.3));
+    }
+
+    /// Test the step metrics of degenerate trajectories.
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_step_metrics_degenerate() {
+        let empty: Vec<f32> = Vec::new();
+        assert_eq!(0.0, overshoot_percent(&empty, 1.0));
+        assert_eq!(None, rise_time(&empty, 1.0));
+        assert_eq!(None, settling_time(&empty, 1.0, 0.1));
+
+        // A trajectory that starts at the setpoint has no step
+        let settled = vec![1.0f32, 1.05, 1.0];
+        assert_eq!(0.0, overshoot_percent(&settled, 1.0));
+        assert_eq!(None, rise_time(&settled, 1.0));
+        assert_eq!(Some(0), settling_time(&settled, 1.0, 0.1));
+
+        // A trajectory that stalls short of the setpoint never rises
+        let stalled = vec![0.0f32, 0.5, 0.8, 0.8];
+        assert_eq!(None, rise_time(&stalled, 1.0));
+        assert_eq!(None, settling_time(&stalled, 1.0, 0.1));
+    }
+}
@@ -0,0 +1,54 @@
+// tests/features.rs
+
+//! Build test confirming the optional `defmt` integration is entirely compiled out.
+//! The library is checked with and without the feature, and the dependency tree
+//! without the feature must not contain `defmt`, so `no_std` and no-alloc builds
+//! are unaffected.
+
+use std::process::Command;
+
+/// Runs cargo in the package directory and returns its standard output,
+/// asserting that the command succeeds.
+fn cargo(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("cargo should run");
+    assert!(
+        output.status.success(),
+        "cargo {} failed:\n{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// Test that the library builds with and without the `defmt` feature.
+#[test]
+fn test_features_defmt_builds() {
+    // A separate target directory avoids waiting on the lock of the running test build.
+    let target_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/target/features");
+    cargo(&["check", "--lib", "--target-dir", target_dir]);
+    cargo(&[
+        "check",
+        "--lib",
+        "--features",
+        "defmt",
+        "--target-dir",
+        target_dir,
+    ]);
+}
+
+/// Test that `defmt` is only a dependency with the feature enabled.
+#[test]
+fn test_features_defmt_dependency() {
+    let tree = |features: &[&str]| {
+        let mut args = vec!["tree", "--edges", "normal", "--prefix", "none"];
+        args.extend_from_slice(features);
+        cargo(&args)
+    };
+    assert!(!tree(&[]).contains("defmt"));
+    assert!(!tree(&["--features", "alloc,serde"]).contains("defmt"));
+    assert!(tree(&["--features", "defmt"]).contains("defmt"));
+}
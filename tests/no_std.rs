@@ -0,0 +1,101 @@
+// tests/no_std.rs
+
+//! Compile test confirming the stabilizers are usable from a `no_std` crate
+//! without allocation. Every value lives on the stack, so this only builds
+//! if the public interface is free of `std` and `alloc` types.
+//! The bare-metal build of the library itself is checked in CI with
+//! `cargo build --lib --target thumbv7em-none-eabi`.
+
+#![no_std]
+
+use free_flight_stabilization::{
+    Angle2FullStabilizer, Angle2Stabilizer, Angle3Stabilizer, AngleFullStabilizer, AngleStabilizer,
+    ArmingStabilizer, CascadeBlendingConfig, DropoutBehavior, DropoutStabilizer, FlightStabilizer,
+    FlightStabilizerConfig, MixedStabilizer, Mixer, NaxisConfig, NaxisStabilizer,
+    OscillationStabilizer, PlantModel, RampedStabilizer, RateStabilizer, ScheduledStabilizer,
+    SettlingStabilizer, SmithPredictorStabilizer, SplitAxisStabilizer, Telemetry, TelemetryHook,
+    TelemetryStabilizer, TimestampedStabilizer,
+};
+
+/// Runs a single control step with a neutral attitude.
+fn step<T: FlightStabilizer<f32>>(stabilizer: &mut T) -> (f32, f32, f32) {
+    let set_point = (0.0, 0.0, 0.0);
+    let imu_attitude = (0.0, 0.0, 0.0);
+    let gyro_rate = (0.0, 0.0, 0.0);
+    stabilizer.control(set_point, imu_attitude, gyro_rate, 0.01, false)
+}
+
+/// Test that every stabilizer runs without `std` or allocation.
+#[test]
+fn test_no_std_stabilizers() {
+    let config = FlightStabilizerConfig::<f32>::new();
+    let blending_config = CascadeBlendingConfig::<f32, 2>::default();
+    let full_blending_config = CascadeBlendingConfig::<f32, 3>::default();
+
+    let mut angle = AngleStabilizer::with_config(config);
+    let mut angle_full = AngleFullStabilizer::with_config(config);
+    let mut angle2 = Angle2Stabilizer::with_config(config, config, blending_config);
+    let mut angle2_full = Angle2FullStabilizer::with_config(config, config, full_blending_config);
+    let mut angle3 = Angle3Stabilizer::with_config(
+        config,
+        config,
+        config,
+        full_blending_config,
+        full_blending_config,
+    );
+    let mut rate = RateStabilizer::with_config(config);
+    let mut scheduled = ScheduledStabilizer::with_config(config, config);
+    let mut ramped = RampedStabilizer::new(AngleStabilizer::with_config(config), 1.0);
+    let mut arming = ArmingStabilizer::new(AngleStabilizer::with_config(config), 1.0);
+    arming.arm();
+    let mut split = SplitAxisStabilizer::new(
+        AngleStabilizer::with_config(config),
+        RateStabilizer::with_config(config),
+    );
+    let mut timestamped = TimestampedStabilizer::new(AngleStabilizer::with_config(config));
+    let mut mixed = MixedStabilizer::new(
+        AngleStabilizer::with_config(config),
+        Mixer::new([[1.0, 1.0, -1.0]; 4]),
+    );
+    let hook: TelemetryHook<f32> = |telemetry: &[Telemetry<f32>; 3]| {
+        assert_eq!(0.0, telemetry[0].output);
+    };
+    let mut telemetry = TelemetryStabilizer::new(AngleStabilizer::with_config(config), hook);
+    let mut oscillation =
+        OscillationStabilizer::<_, _, 16>::new(AngleStabilizer::with_config(config), 4);
+    let mut dropout =
+        DropoutStabilizer::new(AngleStabilizer::with_config(config), DropoutBehavior::Hold);
+    let mut settling =
+        SettlingStabilizer::<_, _, 16>::new(AngleStabilizer::with_config(config), 0.1);
+    let model = PlantModel {
+        gain: 1000.0,
+        time_constant: 0.05,
+    };
+    let mut smith =
+        SmithPredictorStabilizer::<_, _, 20>::new(AngleStabilizer::with_config(config), [model; 3]);
+
+    assert_eq!((0.0, 0.0, 0.0), step(&mut angle));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut angle_full));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut angle2));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut angle2_full));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut angle3));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut rate));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut scheduled));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut ramped));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut arming));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut split));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut timestamped));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut mixed));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut telemetry));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut oscillation));
+    assert_eq!([false; 3], oscillation.is_oscillating());
+    assert_eq!((0.0, 0.0, 0.0), step(&mut dropout));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut smith));
+    assert_eq!((0.0, 0.0, 0.0), step(&mut settling));
+
+    let mut naxis = NaxisStabilizer::with_config(NaxisConfig::<f32, 4>::new());
+    assert_eq!(
+        [0.0; 4],
+        naxis.control([0.0; 4], [0.0; 4], [0.0; 4], 0.01, false)
+    );
+}